@@ -19,6 +19,7 @@ export interface ActionPrototype {
   id: ActionPrototypeId;
   name: string;
   displayName: string;
+  priority: number;
 }
 
 export interface NewAction {
@@ -820,6 +820,10 @@ export const useComponentsStore = (forceChangeSetId?: ChangeSetId) => {
                 destination: { nodeId: string; socketId: string };
                 source: { nodeId: string; socketId: string };
               };
+              edgeId: EdgeId;
+              fromSocketId: SocketId;
+              toSocketId: SocketId;
+              replacedExisting: boolean;
               forceChangesetPk?: string;
             }>({
               method: "post",
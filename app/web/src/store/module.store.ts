@@ -413,6 +413,12 @@ export const useModuleStore = () => {
                 }
               },
             },
+            {
+              eventType: "ModuleRemoved",
+              callback: async () => {
+                await this.LOAD_LOCAL_MODULES();
+              },
+            },
             {
               eventType: "AsyncFinish",
               callback: async ({ id }: { id: string }) => {
@@ -158,9 +158,39 @@ export type WsEventPayloadMap = {
     componentId: string;
     changeSetPk: string;
   };
+  ConnectionCreated: {
+    edgeId: string;
+    fromSocketId: string;
+    toSocketId: string;
+    replacedExisting: boolean;
+    changeSetPk: string;
+  };
   ModuleImported: {
     schemaVariantIds: string[];
   };
+  ModuleRemoved: {
+    installedPkgId: string;
+    schemaVariantIds: string[];
+  };
+  PackageImported: {
+    installedPkgId: string | null;
+    name: string;
+    kind: "module" | "workspaceBackup";
+    stats: {
+      funcs: number;
+      schemas: number;
+      schemaVariants: number;
+      components: number;
+      edges: number;
+    };
+  };
+  PkgImportProgress: {
+    moduleName: string;
+    name: string;
+    phase: "funcs" | "schemas" | "components" | "edges";
+    current: number;
+    total: number;
+  };
   WorkspaceImportBeginApprovalProcess: {
     workspacePk: WorkspacePk;
     userPk: UserId;
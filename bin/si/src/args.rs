@@ -1,4 +1,5 @@
 use clap::{builder::PossibleValuesParser, Parser, Subcommand};
+use std::path::PathBuf;
 use std::str::FromStr;
 use strum::{Display, EnumString, EnumVariantNames};
 
@@ -90,6 +91,15 @@ pub(crate) struct Args {
 
 #[derive(Debug, Subcommand)]
 pub(crate) enum Commands {
+    #[command(flatten)]
+    Launcher(LauncherCommands),
+    /// Checks a package for problems before it is published
+    Validate(ValidateArgs),
+}
+
+/// The commands that manage a System Initiative installation through a container engine.
+#[derive(Debug, Subcommand)]
+pub(crate) enum LauncherCommands {
     /// Checks that the system is setup correctly to run System Initiative
     Check(CheckArgs),
     /// Installs the necessary components to run System Initiative
@@ -145,7 +155,11 @@ pub(crate) struct StatusArgs {
 }
 
 #[derive(Debug, clap::Args)]
-pub(crate) struct StartArgs {}
+pub(crate) struct StartArgs {
+    /// Print the status of each image layer while downloading containers
+    #[clap(long, short)]
+    pub verbose: bool,
+}
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct RestartArgs {}
@@ -173,6 +187,12 @@ pub(crate) struct UpdateArgs {
     pub binary: bool,
 }
 
+#[derive(Debug, clap::Args)]
+pub(crate) struct ValidateArgs {
+    /// The path to the package to check
+    pub path: PathBuf,
+}
+
 #[derive(Debug, clap::Args)]
 pub(crate) struct InstallArgs {
     /// Skip the system check as part of the install command
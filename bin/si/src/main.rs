@@ -1,11 +1,12 @@
-use crate::args::{Commands, Engine};
+use crate::args::{Args, Commands, Engine, LauncherCommands};
 use color_eyre::Result;
 use si_cli::engine::docker_engine::DockerEngine;
 use si_cli::engine::podman_engine::PodmanEngine;
 use si_cli::state::AppState;
+use std::process::ExitCode;
 use std::sync::Arc;
 use telemetry_application::prelude::*;
-use tokio::sync::oneshot::Sender;
+use tokio::sync::oneshot::{Receiver, Sender};
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 mod args;
@@ -13,7 +14,7 @@ mod args;
 static VERSION: &str = include_str!("version.txt");
 
 #[tokio::main(flavor = "multi_thread", worker_threads = 10)]
-async fn main() -> Result<()> {
+async fn main() -> Result<ExitCode> {
     let shutdown_token = CancellationToken::new();
     let task_tracker = TaskTracker::new();
 
@@ -27,6 +28,39 @@ async fn main() -> Result<()> {
         .build()?;
     let _telemetry = telemetry_application::init(config, &task_tracker, shutdown_token.clone())?;
     let args = args::parse();
+
+    task_tracker.close();
+
+    let (exit_code, ph_done_receiver) = match &args.command {
+        Commands::Launcher(command) => (ExitCode::SUCCESS, Some(launch(&args, command).await?)),
+        // Checking a package needs neither a container engine nor a running System Initiative
+        Commands::Validate(validate_args) => {
+            let report = si_cli::cmd::validate_pkg(&validate_args.path).await?;
+            print!("{report}");
+            (ExitCode::from(report.exit_code()), None)
+        }
+    };
+
+    // TODO(fnichol): this will eventually go into the signal handler code but at the moment in
+    // si's case, this doesn't really exist anywhere. At this moment in the program however, the
+    // command to run has shut down so it's an appropriate time to cancel other remaining tasks and
+    // wait on their graceful shutdowns
+    {
+        shutdown_token.cancel();
+        task_tracker.wait().await;
+    }
+
+    if let Some(ph_done_receiver) = ph_done_receiver {
+        if let Err(e) = ph_done_receiver.await {
+            println!("{}", e)
+        }
+    }
+    Ok(exit_code)
+}
+
+/// Runs a command against the System Initiative installation, returning a receiver that resolves
+/// once the queued Posthog events have been flushed.
+async fn launch(args: &Args, command: &LauncherCommands) -> Result<Receiver<()>> {
     let mode = args.mode();
     let is_preview = args.is_preview;
     let mut engine = args.engine();
@@ -92,7 +126,7 @@ async fn main() -> Result<()> {
     #[allow(clippy::disallowed_methods)]
     let auth_api_host = std::env::var("AUTH_API").ok();
 
-    if !matches!(args.command, Commands::Update(_)) {
+    if !matches!(command, LauncherCommands::Update(_)) {
         match state.find(current_version, auth_api_host.as_deref()).await {
             Ok(update) => {
                 if update.si.is_some() {
@@ -114,34 +148,32 @@ async fn main() -> Result<()> {
         println!("Preview mode... System Initiative would have taken the following actions");
     }
 
-    task_tracker.close();
-
-    match args.command {
-        Commands::Install(_args) => {
-            state.install().await?;
+    match command {
+        LauncherCommands::Install(_args) => {
+            state.install(false).await?;
         }
-        Commands::Check(_args) => {
+        LauncherCommands::Check(_args) => {
             state.check(false).await?;
         }
-        Commands::Launch(args) => {
+        LauncherCommands::Launch(args) => {
             state.launch(args.metrics).await?;
         }
-        Commands::Start(_args) => {
-            state.start().await?;
+        LauncherCommands::Start(args) => {
+            state.start(args.verbose).await?;
         }
-        Commands::Configure(args) => {
+        LauncherCommands::Configure(args) => {
             state.configure(args.force_reconfigure).await?;
         }
-        Commands::Delete(args) => {
+        LauncherCommands::Delete(args) => {
             state.delete(args.keep_images).await?;
         }
-        Commands::Restart(_args) => {
+        LauncherCommands::Restart(_args) => {
             state.restart().await?;
         }
-        Commands::Stop(_args) => {
+        LauncherCommands::Stop(_args) => {
             state.stop().await?;
         }
-        Commands::Update(args) => {
+        LauncherCommands::Update(args) => {
             state
                 .update(
                     current_version,
@@ -151,28 +183,14 @@ async fn main() -> Result<()> {
                 )
                 .await?;
         }
-        Commands::Status(args) => {
+        LauncherCommands::Status(args) => {
             state.status(args.show_logs, args.log_lines).await?;
-        } // Commands::Report(_args) => {
+        } // LauncherCommands::Report(_args) => {
           //     state.report().await?;
           // }
     }
 
-    drop(state);
-
-    // TODO(fnichol): this will eventually go into the signal handler code but at the moment in
-    // si's case, this doesn't really exist anywhere. At this moment in the program however, the
-    // command to run has shut down so it's an appropriate time to cancel other remaining tasks and
-    // wait on their graceful shutdowns
-    {
-        shutdown_token.cancel();
-        task_tracker.wait().await;
-    }
-
-    if let Err(e) = ph_done_receiver.await {
-        println!("{}", e)
-    }
-    Ok(())
+    Ok(ph_done_receiver)
 }
 
 async fn wait_for_posthog_flush(done_sender: Sender<()>, sender: si_posthog::PosthogSender) {
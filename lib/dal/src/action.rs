@@ -193,8 +193,10 @@ impl Action {
                 .cloned()
                 .unwrap_or_default();
             let mut actions_by_kind: HashMap<ActionKind, Vec<Action>> = HashMap::new();
+            let mut priorities: HashMap<ActionId, i32> = HashMap::new();
             for action in actions {
                 let prototype = action.prototype(ctx).await?;
+                priorities.insert(*action.id(), prototype.priority());
                 actions_by_kind
                     .entry(*prototype.kind())
                     .or_default()
@@ -213,15 +215,20 @@ impl Action {
 
             // Figure out internal dependencies for actions of this component
             //
-            //
-            // Note (FIXME/TODO): we assume actions of the same kind in the same component
-            // are parallelizable, we should have some way to enable serialization and infer
-            // order since they may be dependent on eachother, but there is nothing exposed about it
+            // Actions of the same kind in the same component run in order of their prototype's
+            // priority (highest first); the ones with the same priority are parallelizable
             for (kind, actions) in &actions_by_kind {
                 for action in actions {
+                    let priority = priorities.get(action.id()).copied().unwrap_or_default();
+                    let higher_priority_ids = actions
+                        .iter()
+                        .filter(|a| priorities.get(a.id()).copied().unwrap_or_default() > priority)
+                        .map(|a| *a.id());
                     actions_graph
                         .entry(*action.id())
-                        .or_insert_with(|| (*kind, Vec::new()));
+                        .or_insert_with(|| (*kind, Vec::new()))
+                        .1
+                        .extend(higher_priority_ids);
 
                     // Action kind order is Initial Deletion -> Creation -> Others -> Final Deletion
                     // Initial deletions happen if there is a resource and a create action, so it deletes before creating
@@ -31,6 +31,7 @@ pub struct ActionPrototypeView {
     id: ActionPrototypeId,
     name: String,
     display_name: Option<String>,
+    priority: i32,
 }
 
 impl ActionPrototypeView {
@@ -55,6 +56,7 @@ impl ActionPrototypeView {
                 ToOwned::to_owned,
             ),
             display_name,
+            priority: prototype.priority,
         })
     }
 }
@@ -188,6 +190,10 @@ pub struct ActionPrototype {
     kind: ActionKind,
     name: Option<String>,
     schema_variant_id: SchemaVariantId,
+    /// Actions of the same kind on the same component run from the highest priority to the
+    /// lowest. Actions with the same priority may run in parallel.
+    #[serde(default)]
+    priority: i32,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -294,6 +300,21 @@ impl ActionPrototype {
         Ok(standard_model::objects_from_rows(rows)?)
     }
 
+    /// Finds the [`ActionPrototypes`](ActionPrototype) of the given [`ActionKind`] for the
+    /// [`SchemaVariant`](crate::SchemaVariant) of the given [`Component`].
+    pub async fn find_for_component_and_kind(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        kind: ActionKind,
+    ) -> ActionPrototypeResult<Vec<Self>> {
+        let schema_variant_id = Component::schema_variant_id(ctx, component_id)
+            .await
+            .map_err(|e| ActionPrototypeError::Component(e.to_string()))?;
+
+        Self::find_for_context_and_kind(ctx, kind, ActionPrototypeContext { schema_variant_id })
+            .await
+    }
+
     pub async fn find_for_func(
         ctx: &DalContext,
         func_id: FuncId,
@@ -339,6 +360,7 @@ impl ActionPrototype {
     standard_model_accessor!(name, Option<String>, ActionPrototypeResult);
     standard_model_accessor!(func_id, Pk(FuncId), ActionPrototypeResult);
     standard_model_accessor!(kind, Enum(ActionKind), ActionPrototypeResult);
+    standard_model_accessor!(priority, i32, ActionPrototypeResult);
 
     pub async fn set_kind_checked(
         &mut self,
@@ -368,12 +390,60 @@ impl ActionPrototype {
         context
     }
 
+    /// Runs the action against the [`ComponentView`] of the given [`Component`], storing the
+    /// result as its resource.
     pub async fn run(
         &self,
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> ActionPrototypeResult<Option<ActionRunResult>> {
         let component_view = ComponentView::new(ctx, component_id).await?;
+        let view_hash = self.view_hash(&component_view)?;
+        self.run_with_view(ctx, component_id, component_view, view_hash)
+            .await
+    }
+
+    /// Like [`Self::run`], except that a refresh action is not executed (and `None` is returned)
+    /// if the component's attribute values have not changed since its last refresh.
+    pub async fn run_if_changed(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ActionPrototypeResult<Option<ActionRunResult>> {
+        let component_view = ComponentView::new(ctx, component_id).await?;
+        let view_hash = self.view_hash(&component_view)?;
+
+        if let Some(view_hash) = &view_hash {
+            let component = Component::get_by_id(ctx, &component_id)
+                .await?
+                .ok_or(ActionPrototypeError::ComponentNotFound(component_id))?;
+            if component.resource_view_hash() == Some(view_hash.as_str()) {
+                debug!(%component_id, "component unchanged since last refresh, skipping");
+                return Ok(None);
+            }
+        }
+
+        self.run_with_view(ctx, component_id, component_view, view_hash)
+            .await
+    }
+
+    /// The hash of the view a refresh action runs against, recorded with the resource so that
+    /// [`Self::run_if_changed`] can tell when nothing changed since the last refresh.
+    fn view_hash(&self, component_view: &ComponentView) -> ActionPrototypeResult<Option<String>> {
+        Ok(if *self.kind() == ActionKind::Refresh {
+            Some(component_view_hash(component_view)?)
+        } else {
+            None
+        })
+    }
+
+    async fn run_with_view(
+        &self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+        component_view: ComponentView,
+        view_hash: Option<String>,
+    ) -> ActionPrototypeResult<Option<ActionRunResult>> {
         let deleted_ctx = ctx.clone_with_delete_visibility();
         let before = before_funcs_for_component(&deleted_ctx, &component_id).await?;
 
@@ -424,9 +494,33 @@ impl ActionPrototype {
                         .await?;
                 }
 
+                if view_hash.is_some() {
+                    component
+                        .set_resource_view_hash(deleted_ctx, view_hash)
+                        .await
+                        .map_err(|e| ActionPrototypeError::Component(e.to_string()))?;
+                }
+
                 Some(run_result)
             }
             None => None,
         })
     }
 }
+
+/// Hashes the parts of a [`ComponentView`] a refresh depends on. The resource (and the values
+/// derived from it) are left out, since the refresh itself rewrites them.
+fn component_view_hash(component_view: &ComponentView) -> ActionPrototypeResult<String> {
+    let inputs: serde_json::Map<String, serde_json::Value> = ["si", "domain", "secrets"]
+        .into_iter()
+        .filter_map(|key| {
+            component_view
+                .properties
+                .get(key)
+                .map(|value| (key.to_owned(), value.clone()))
+        })
+        .collect();
+    let bytes = serde_json::to_vec(&(component_view.kind, inputs))?;
+
+    Ok(blake3::hash(&bytes).to_hex().to_string())
+}
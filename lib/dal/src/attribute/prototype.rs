@@ -147,6 +147,37 @@ pub struct AttributePrototype {
     pub key: Option<String>,
 }
 
+/// Where a func is bound as an attribute function, as returned by
+/// [`AttributePrototype::find_bindings_for_func()`].
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributePrototypeFuncBinding {
+    pub attribute_prototype_id: AttributePrototypeId,
+    pub schema_variant_id: SchemaVariantId,
+    /// Set if the binding only applies to this component rather than to the whole variant
+    pub component_id: Option<ComponentId>,
+    pub target: AttributePrototypeFuncBindingTarget,
+}
+
+/// What an [`AttributePrototypeFuncBinding`] sets the value of.
+#[remain::sorted]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum AttributePrototypeFuncBindingTarget {
+    #[serde(rename_all = "camelCase")]
+    InputSocket {
+        internal_provider_id: InternalProviderId,
+        socket_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    OutputSocket {
+        external_provider_id: ExternalProviderId,
+        socket_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Prop { prop_id: PropId, path: String },
+}
+
 /// This object is used for
 /// [`AttributePrototype::list_by_head_from_external_provider_use_with_tail()`].
 #[derive(Serialize, Deserialize, Debug)]
@@ -881,6 +912,63 @@ impl AttributePrototype {
         Ok(result)
     }
 
+    /// Lists every place the [`Func`] is bound as an attribute function, across all schema
+    /// variants and components. Prototypes of implicit
+    /// [`InternalProviders`](crate::InternalProvider) are left out, since they only expose the
+    /// value of their prop and are never bound by users.
+    pub async fn find_bindings_for_func(
+        ctx: &DalContext,
+        func_id: FuncId,
+    ) -> AttributePrototypeResult<Vec<AttributePrototypeFuncBinding>> {
+        let mut bindings = vec![];
+
+        for prototype in Self::find_for_func(ctx, &func_id).await? {
+            let (schema_variant_id, target) = if prototype.context.prop_id().is_some() {
+                let prop = prototype.prop(ctx).await?;
+                (
+                    prop.schema_variant_id(),
+                    AttributePrototypeFuncBindingTarget::Prop {
+                        prop_id: *prop.id(),
+                        path: prop.path().with_replaced_sep("/"),
+                    },
+                )
+            } else if prototype.context.external_provider_id().is_some() {
+                let external_provider = prototype.external_provider(ctx).await?;
+                (
+                    *external_provider.schema_variant_id(),
+                    AttributePrototypeFuncBindingTarget::OutputSocket {
+                        external_provider_id: *external_provider.id(),
+                        socket_name: external_provider.name().to_owned(),
+                    },
+                )
+            } else if prototype.context.internal_provider_id().is_some() {
+                let internal_provider = prototype.internal_provider(ctx).await?;
+                if internal_provider.is_internal_consumer() {
+                    continue;
+                }
+                (
+                    *internal_provider.schema_variant_id(),
+                    AttributePrototypeFuncBindingTarget::InputSocket {
+                        internal_provider_id: *internal_provider.id(),
+                        socket_name: internal_provider.name().to_owned(),
+                    },
+                )
+            } else {
+                continue;
+            };
+
+            let component_id = prototype.context.component_id();
+            bindings.push(AttributePrototypeFuncBinding {
+                attribute_prototype_id: *prototype.id(),
+                schema_variant_id,
+                component_id: component_id.is_some().then_some(component_id),
+                target,
+            });
+        }
+
+        Ok(bindings)
+    }
+
     pub async fn find_for_context_and_key(
         ctx: &DalContext,
         context: AttributeContext,
@@ -40,6 +40,7 @@
 use serde::{Deserialize, Serialize};
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use si_pkg::AttributeValuePath;
 use std::collections::HashMap;
 use telemetry::prelude::*;
 use thiserror::Error;
@@ -62,12 +63,14 @@ use crate::{
     impl_standard_model,
     job::definition::DependentValuesUpdate,
     pk,
+    prop::PropPath,
     standard_model::{self, TypeHint},
     standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
     AttributeContextError, AttributePrototypeArgumentError, Component, ComponentId, DalContext,
-    Func, FuncBinding, FuncError, HistoryEventError, IndexMap, InternalProvider,
-    InternalProviderId, Prop, PropError, PropId, PropKind, StandardModel, StandardModelError,
-    Tenancy, Timestamp, TransactionsError, Visibility, WsEventError,
+    ExternalProvider, ExternalProviderId, Func, FuncBinding, FuncError, HistoryEventError,
+    IndexMap, InternalProvider, InternalProviderId, Prop, PropError, PropId, PropKind,
+    SchemaVariantId, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
+    Visibility, WsEventError,
 };
 
 pub mod view;
@@ -465,6 +468,151 @@ impl AttributeValue {
         Ok(standard_model::option_object_from_row(row)?)
     }
 
+    /// Resolve an [`AttributeValuePath`] from a package into the [`AttributeValue`] it refers to
+    /// for the given [`Component`] of the given [`SchemaVariant`](crate::SchemaVariant).
+    ///
+    /// For prop paths, the `key` selects an entry of a map and the `index` an element of an
+    /// array, with `path` being the path of the map's or array's item prop. Values nested under a
+    /// map entry or array element cannot be resolved since the path does not carry the keys of
+    /// their ancestors.
+    ///
+    /// Returns `None` if the prop, socket or value does not exist.
+    pub async fn resolve_path(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        schema_variant_id: SchemaVariantId,
+        path: &AttributeValuePath,
+    ) -> AttributeValueResult<Option<Self>> {
+        match path {
+            AttributeValuePath::Prop { path, key, index } => {
+                let prop = match Prop::find_prop_by_path_opt(
+                    ctx,
+                    schema_variant_id,
+                    &PropPath::from(path),
+                )
+                .await
+                .map_err(Box::new)?
+                {
+                    Some(prop) => prop,
+                    None => return Ok(None),
+                };
+
+                // Walk down from the root prop to the parent of the prop we are resolving,
+                // finding each ancestor's value under the value of its own parent.
+                let mut ancestors = vec![];
+                let mut current = prop.parent_prop(ctx).await.map_err(Box::new)?;
+                while let Some(ancestor) = current {
+                    current = ancestor.parent_prop(ctx).await.map_err(Box::new)?;
+                    ancestors.push(ancestor);
+                }
+
+                // Under a map or array above the immediate parent, the value of an ancestor
+                // depends on the key or index of an entry that the path does not carry
+                if ancestors
+                    .iter()
+                    .skip(1)
+                    .any(|ancestor| matches!(ancestor.kind(), PropKind::Map | PropKind::Array))
+                {
+                    return Ok(None);
+                }
+
+                let mut parent_value: Option<Self> = None;
+                for ancestor in ancestors.iter().rev() {
+                    parent_value = match Self::find_with_parent_and_key_for_context(
+                        ctx,
+                        parent_value.as_ref().map(|value| *value.id()),
+                        None,
+                        AttributeReadContext::default_with_prop_and_component_id(
+                            *ancestor.id(),
+                            Some(component_id),
+                        ),
+                    )
+                    .await?
+                    {
+                        Some(value) => Some(value),
+                        None => return Ok(None),
+                    };
+                }
+
+                if let Some(index) = index {
+                    let element_id = parent_value
+                        .as_ref()
+                        .and_then(|parent| parent.index_map())
+                        .and_then(|index_map| {
+                            usize::try_from(*index)
+                                .ok()
+                                .and_then(|index| index_map.order().get(index).copied())
+                        });
+
+                    return Ok(match element_id {
+                        Some(element_id) => Self::get_by_id(ctx, &element_id).await?,
+                        None => None,
+                    });
+                }
+
+                Self::find_with_parent_and_key_for_context(
+                    ctx,
+                    parent_value.as_ref().map(|value| *value.id()),
+                    key.to_owned(),
+                    AttributeReadContext::default_with_prop_and_component_id(
+                        *prop.id(),
+                        Some(component_id),
+                    ),
+                )
+                .await
+            }
+            AttributeValuePath::InputSocket(name) => {
+                let internal_provider =
+                    match InternalProvider::find_explicit_for_schema_variant_and_name(
+                        ctx,
+                        schema_variant_id,
+                        name,
+                    )
+                    .await
+                    .map_err(|e| AttributeValueError::InternalProvider(e.to_string()))?
+                    {
+                        Some(internal_provider) => internal_provider,
+                        None => return Ok(None),
+                    };
+
+                Self::find_for_context(
+                    ctx,
+                    AttributeReadContext {
+                        prop_id: Some(PropId::NONE),
+                        internal_provider_id: Some(*internal_provider.id()),
+                        external_provider_id: Some(ExternalProviderId::NONE),
+                        component_id: Some(component_id),
+                    },
+                )
+                .await
+            }
+            AttributeValuePath::OutputSocket(name) => {
+                let external_provider = match ExternalProvider::find_for_schema_variant_and_name(
+                    ctx,
+                    schema_variant_id,
+                    name,
+                )
+                .await
+                .map_err(|e| AttributeValueError::ExternalProvider(e.to_string()))?
+                {
+                    Some(external_provider) => external_provider,
+                    None => return Ok(None),
+                };
+
+                Self::find_for_context(
+                    ctx,
+                    AttributeReadContext {
+                        prop_id: Some(PropId::NONE),
+                        internal_provider_id: Some(InternalProviderId::NONE),
+                        external_provider_id: Some(*external_provider.id()),
+                        component_id: Some(component_id),
+                    },
+                )
+                .await
+            }
+        }
+    }
+
     /// List [`AttributeValues`](crate::AttributeValue) for a provided
     /// [`AttributeReadContext`](crate::AttributeReadContext).
     ///
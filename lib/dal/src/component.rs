@@ -33,11 +33,11 @@ use crate::{
     standard_model, standard_model_accessor, standard_model_belongs_to, standard_model_has_many,
     ActionPrototypeError, AttributeContext, AttributeContextBuilderError, AttributeContextError,
     AttributePrototype, AttributePrototypeArgumentError, AttributePrototypeError,
-    AttributePrototypeId, AttributeReadContext, ComponentType, DalContext, EdgeError,
-    ExternalProviderError, FixError, FixId, Func, FuncBackendKind, FuncError, HistoryActor,
-    HistoryEventError, IndexMap, Node, NodeError, PropError, RootPropChild, Schema, SchemaError,
-    SchemaId, Socket, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
-    UserPk, Visibility, WorkspaceError, WsEvent, WsEventResult, WsPayload,
+    AttributePrototypeId, AttributeReadContext, AttributeView, ComponentType, DalContext,
+    EdgeError, ExternalProviderError, FixError, FixId, Func, FuncBackendKind, FuncError,
+    HistoryActor, HistoryEventError, IndexMap, Node, NodeError, PropError, RootPropChild, Schema,
+    SchemaError, SchemaId, Socket, StandardModel, StandardModelError, Tenancy, Timestamp,
+    TransactionsError, UserPk, Visibility, WorkspaceError, WsEvent, WsEventResult, WsPayload,
 };
 use crate::{AttributeValueId, QualificationError};
 use crate::{Edge, FixResolverError, NodeKind};
@@ -183,6 +183,24 @@ pub enum ComponentError {
 
 pub type ComponentResult<T> = Result<T, ComponentError>;
 
+/// Writes a resolved value over its place in a value skeleton, keeping the keys of the skeleton
+/// that have no resolved value.
+fn overlay_resolved_value(skeleton: &mut serde_json::Value, resolved: serde_json::Value) {
+    match (skeleton, resolved) {
+        (serde_json::Value::Object(skeleton), serde_json::Value::Object(resolved)) => {
+            for (key, value) in resolved {
+                match skeleton.get_mut(&key) {
+                    Some(existing) => overlay_resolved_value(existing, value),
+                    None => {
+                        skeleton.insert(key, value);
+                    }
+                }
+            }
+        }
+        (skeleton, resolved) => *skeleton = resolved,
+    }
+}
+
 const FIND_FOR_NODE: &str = include_str!("queries/component/find_for_node.sql");
 const FIND_SI_CHILD_PROP_ATTRIBUTE_VALUE: &str =
     include_str!("queries/component/find_si_child_attribute_value.sql");
@@ -242,6 +260,10 @@ pub struct Component {
     deletion_user_pk: Option<UserPk>,
     needs_destroy: bool,
     hidden: bool,
+    /// Hash of the [`ComponentView`] the last refresh [`action`](crate::ActionPrototype) ran
+    /// against, used to skip refreshes when nothing has changed.
+    #[serde(default)]
+    resource_view_hash: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -350,6 +372,7 @@ impl Component {
     standard_model_accessor!(kind, Enum(ComponentKind), ComponentResult);
     standard_model_accessor!(needs_destroy, bool, ComponentResult);
     standard_model_accessor!(hidden, bool, ComponentResult);
+    standard_model_accessor!(resource_view_hash, Option<String>, ComponentResult);
     standard_model_accessor!(deletion_user_pk, Option<Pk(UserPk)>, ComponentResult);
 
     standard_model_belongs_to!(
@@ -667,6 +690,43 @@ impl Component {
         Ok(object_from_row(row)?)
     }
 
+    /// Resolves every [`AttributeValue`](crate::AttributeValue) under "/root/domain" for the given
+    /// [`Component`](Self) into a single JSON object, including the outputs of attribute
+    /// functions. The result has the shape of the "domain" key of
+    /// [`SchemaVariant::build_empty_domain_skeleton()`], with props that have no value left as
+    /// they are in the skeleton.
+    ///
+    /// Unlike the value of the implicit [`InternalProvider`](crate::InternalProvider) for
+    /// "/root/domain", this reads the attribute values themselves, so it shows what an import or
+    /// a function actually wrote even if dependent values have not been updated yet.
+    pub async fn materialize_domain(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<serde_json::Value> {
+        let schema_variant_id = Self::schema_variant_id(ctx, component_id).await?;
+        let skeleton = SchemaVariant::build_empty_domain_skeleton(ctx, schema_variant_id).await?;
+        let mut domain = skeleton
+            .get(RootPropChild::Domain.as_str())
+            .cloned()
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        let domain_value = Self::root_prop_child_attribute_value_for_component(
+            ctx,
+            component_id,
+            RootPropChild::Domain,
+        )
+        .await?;
+        let view_context = AttributeReadContext {
+            prop_id: None,
+            ..AttributeReadContext::from(domain_value.context)
+        };
+        let view = AttributeView::new(ctx, view_context, Some(*domain_value.id())).await?;
+
+        overlay_resolved_value(&mut domain, view.value().clone());
+
+        Ok(domain)
+    }
+
     /// List the connected input [`Sockets`](crate::Socket) for a given [`ComponentId`](Self) and
     /// [`AttributeValueId`](crate::AttributeValue) whose [`context`](crate::AttributeContext)'s
     /// least specific field corresponding to a [`PropId`](crate::Prop). In other words, this is
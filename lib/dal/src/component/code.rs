@@ -20,6 +20,50 @@ struct CodeGenerationEntry {
     pub message: Option<String>,
 }
 
+impl CodeGenerationEntry {
+    fn code_view(&self) -> ComponentResult<Option<CodeView>> {
+        // When a new code gen function is craeted the code/format entries will not yet be
+        // set, so just ignore them here. Function return value type checking should ensure that
+        // the executed function does not unset these itself.
+        let (format, code) = match (&self.format, &self.code) {
+            (Some(format), Some(code)) => (format, code),
+            _ => return Ok(None),
+        };
+
+        let language = if format.is_empty() {
+            CodeLanguage::Unknown
+        } else {
+            CodeLanguage::try_from(format.to_owned())?
+        };
+
+        // NOTE(nick): we may need to determine how we handle empty code generation or
+        // generation in progress. Maybe we never need to? Just re-run?
+        let code = if code.is_empty() {
+            None
+        } else {
+            Some(code.clone())
+        };
+
+        Ok(Some(CodeView::new(language, code, self.message.clone())))
+    }
+}
+
+/// A [`CodeView`](crate::CodeView) whose code may have been cut short, returned by
+/// [`Component::list_code_generated_paged()`].
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PagedCodeView {
+    /// The key of the entry in "/root/code", used to fetch the full entry with
+    /// [`Component::get_code_entry()`].
+    pub key: String,
+    #[serde(flatten)]
+    pub code_view: CodeView,
+    /// Whether the code was cut short.
+    pub truncated: bool,
+    /// The size of the full code, in bytes.
+    pub total_size: usize,
+}
+
 impl Component {
     /// List all [`CodeViews`](crate::CodeView) for based on the "code generation"
     /// [`leaves`](crate::schema::variant::leaves) for a given [`ComponentId`](Self).
@@ -27,6 +71,138 @@ impl Component {
         ctx: &DalContext,
         component_id: ComponentId,
     ) -> ComponentResult<(Vec<CodeView>, bool)> {
+        let code_map = match Self::code_generation_entries(ctx, component_id).await? {
+            Some(code_map) => code_map,
+            None => return Ok((vec![], false)),
+        };
+
+        let mut code_views: Vec<CodeView> = Vec::new();
+        for entry in code_map.values() {
+            if let Some(code_view) = entry.code_view()? {
+                code_views.push(code_view);
+            }
+        }
+        Ok((code_views, true))
+    }
+
+    /// Re-executes the "code generation" [`leaves`](crate::schema::variant::leaves) of a given
+    /// [`ComponentId`](Self) and returns the fresh [`CodeViews`](crate::CodeView). This brings
+    /// "/root/code" up to date right away when a code generation func has been edited, instead
+    /// of waiting for a dependent values update. Publishes [`WsEvent::code_generated()`] on
+    /// commit.
+    pub async fn regenerate_code(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<CodeView>> {
+        for attribute_value_id in
+            Self::all_code_generation_attribute_values_for_component(ctx, component_id).await?
+        {
+            let mut attribute_value = AttributeValue::get_by_id(ctx, &attribute_value_id)
+                .await?
+                .ok_or(AttributeValueError::NotFound(
+                    attribute_value_id,
+                    *ctx.visibility(),
+                ))?;
+            attribute_value.update_from_prototype_function(ctx).await?;
+        }
+
+        // The "/root/code" map is read through its implicit internal provider, which has to
+        // emit the regenerated entries.
+        let mut code_map_attribute_value =
+            Self::code_map_implicit_internal_provider_attribute_value(ctx, component_id).await?;
+        code_map_attribute_value
+            .update_from_prototype_function(ctx)
+            .await?;
+
+        WsEvent::code_generated(ctx, component_id)
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+
+        let (code_views, _) = Self::list_code_generated(ctx, component_id).await?;
+        Ok(code_views)
+    }
+
+    /// Like [`Self::list_code_generated()`], but the code of every view is cut down to at most
+    /// `max_bytes_per_entry` bytes. Views are sorted by key, and entries that were cut short can
+    /// be fetched whole with [`Self::get_code_entry()`].
+    pub async fn list_code_generated_paged(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        max_bytes_per_entry: usize,
+    ) -> ComponentResult<(Vec<PagedCodeView>, bool)> {
+        let code_map = match Self::code_generation_entries(ctx, component_id).await? {
+            Some(code_map) => code_map,
+            None => return Ok((vec![], false)),
+        };
+
+        let mut code_views: Vec<PagedCodeView> = Vec::new();
+        for (key, entry) in code_map {
+            let mut code_view = match entry.code_view()? {
+                Some(code_view) => code_view,
+                None => continue,
+            };
+
+            let total_size = code_view.code.as_ref().map(String::len).unwrap_or_default();
+            let truncated = total_size > max_bytes_per_entry;
+            if let (true, Some(code)) = (truncated, code_view.code.as_mut()) {
+                // Don't cut a character in half
+                let mut end = max_bytes_per_entry;
+                while !code.is_char_boundary(end) {
+                    end -= 1;
+                }
+                code.truncate(end);
+            }
+
+            code_views.push(PagedCodeView {
+                key,
+                code_view,
+                truncated,
+                total_size,
+            });
+        }
+        code_views.sort_by(|a, b| a.key.cmp(&b.key));
+
+        Ok((code_views, true))
+    }
+
+    /// Returns the full [`CodeView`](crate::CodeView) for the "code generation" entry at `key`,
+    /// if the [`Component`] has one.
+    pub async fn get_code_entry(
+        ctx: &DalContext,
+        component_id: ComponentId,
+        key: &str,
+    ) -> ComponentResult<Option<CodeView>> {
+        match Self::code_generation_entries(ctx, component_id)
+            .await?
+            .and_then(|mut code_map| code_map.remove(key))
+        {
+            Some(entry) => entry.code_view(),
+            None => Ok(None),
+        }
+    }
+
+    /// Reads the "/root/code" map of a given [`ComponentId`](Self), if it has been populated.
+    async fn code_generation_entries(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Option<HashMap<String, CodeGenerationEntry>>> {
+        let code_map_attribute_value =
+            Self::code_map_implicit_internal_provider_attribute_value(ctx, component_id).await?;
+
+        Ok(match code_map_attribute_value.get_value(ctx).await? {
+            Some(code_map_value) => Some(serde_json::from_value(code_map_value)?),
+            None => None,
+        })
+    }
+
+    /// Finds the [`AttributeValue`] of the implicit
+    /// [`InternalProvider`](crate::InternalProvider) for the "/root/code" map of a given
+    /// [`ComponentId`](Self).
+    async fn code_map_implicit_internal_provider_attribute_value(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<AttributeValue> {
         let component = Self::get_by_id(ctx, &component_id)
             .await?
             .ok_or(ComponentError::NotFound(component_id))?;
@@ -35,8 +211,7 @@ impl Component {
             .await?
             .ok_or(ComponentError::NoSchemaVariant(component_id))?;
 
-        // Prepare to assemble code views and access the "/root/code" prop tree.
-        let mut code_views: Vec<CodeView> = Vec::new();
+        // Access the "/root/code" prop tree.
         let code_map_implicit_internal_provider =
             SchemaVariant::find_root_child_implicit_internal_provider(
                 ctx,
@@ -49,53 +224,13 @@ impl Component {
             component_id: Some(component_id),
             ..AttributeReadContext::default()
         };
-        let code_map_attribute_value =
+        Ok(
             AttributeValue::find_for_context(ctx, code_map_attribute_read_context)
                 .await?
                 .ok_or(AttributeValueError::NotFoundForReadContext(
                     code_map_attribute_read_context,
-                ))?;
-        let maybe_code_map_value = code_map_attribute_value.get_value(ctx).await?;
-
-        // If the map has been populated, we need to see if there are code views to generate.
-        if let Some(code_map_value) = maybe_code_map_value {
-            let code_map: HashMap<String, CodeGenerationEntry> =
-                serde_json::from_value(code_map_value)?;
-
-            for entry in code_map.values() {
-                // When a new code gen function is craeted the code/format entries will not yet be
-                // set, so just ignore them in the loop here. Function return value type checking
-                // should ensure that the executed function does not unset these itself.
-                if entry.format.is_none() || entry.code.is_none() {
-                    continue;
-                }
-
-                // Safe unwraps because of the above check
-                let format = entry.format.as_ref().unwrap();
-                let code = entry.code.as_ref().unwrap();
-
-                let language = if format.is_empty() {
-                    CodeLanguage::Unknown
-                } else {
-                    CodeLanguage::try_from(format.to_owned())?
-                };
-
-                // NOTE(nick): we may need to determine how we handle empty code generation or
-                // generation in progress. Maybe we never need to? Just re-run?
-                let code = if code.is_empty() {
-                    None
-                } else {
-                    Some(code.clone())
-                };
-
-                let message = entry.message.clone();
-
-                code_views.push(CodeView::new(language, code, message));
-            }
-        } else {
-            return Ok((vec![], false));
-        }
-        Ok((code_views, true))
+                ))?,
+        )
     }
 
     // TODO(nick): big query potential.
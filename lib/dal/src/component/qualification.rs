@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use crate::attribute::value::AttributeValue;
 use crate::attribute::value::AttributeValueError;
 use crate::component::ComponentResult;
+use crate::job::definition::DependentValuesUpdate;
 use crate::qualification::{QualificationSubCheckStatus, QualificationView};
 use crate::schema::SchemaVariant;
 use crate::ws_event::WsEvent;
@@ -132,4 +133,67 @@ impl Component {
 
         Ok(results)
     }
+
+    /// Re-executes the qualification functions for the [`Component`] and returns the resulting
+    /// [`QualificationViews`](QualificationView), as [`Self::list_qualifications()`] would.
+    pub async fn run_qualifications(
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> ComponentResult<Vec<QualificationView>> {
+        let component = Self::get_by_id(ctx, &component_id)
+            .await?
+            .ok_or(ComponentError::NotFound(component_id))?;
+        let schema_variant = component
+            .schema_variant(ctx)
+            .await?
+            .ok_or(ComponentError::NoSchemaVariant(component_id))?;
+
+        let qualification_map_implicit_internal_provider =
+            SchemaVariant::find_root_child_implicit_internal_provider(
+                ctx,
+                *schema_variant.id(),
+                RootPropChild::Qualification,
+            )
+            .await?;
+
+        let prop_qualification_map_attribute_read_context = AttributeReadContext {
+            prop_id: Some(*qualification_map_implicit_internal_provider.prop_id()),
+            component_id: Some(component_id),
+            ..AttributeReadContext::default()
+        };
+        let prop_qualification_map_attribute_value =
+            AttributeValue::find_for_context(ctx, prop_qualification_map_attribute_read_context)
+                .await?
+                .ok_or(AttributeValueError::NotFoundForReadContext(
+                    prop_qualification_map_attribute_read_context,
+                ))?;
+
+        // Only the entries specific to this component are executed, since the schema variant
+        // level ones are not computed from the component's values.
+        let mut updated_attribute_value_ids = vec![];
+        for mut entry_attribute_value in prop_qualification_map_attribute_value
+            .child_attribute_values(ctx)
+            .await?
+        {
+            if entry_attribute_value.context.is_component_unset() {
+                continue;
+            }
+
+            entry_attribute_value
+                .update_from_prototype_function(ctx)
+                .await?;
+            updated_attribute_value_ids.push(*entry_attribute_value.id());
+        }
+
+        if !updated_attribute_value_ids.is_empty() {
+            ctx.enqueue_job(DependentValuesUpdate::new(
+                ctx.access_builder(),
+                *ctx.visibility(),
+                updated_attribute_value_ids,
+            ))
+            .await?;
+        }
+
+        Self::list_qualifications(ctx, component_id).await
+    }
 }
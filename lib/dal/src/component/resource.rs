@@ -58,6 +58,29 @@ impl Component {
         Ok(result)
     }
 
+    /// Lists the [`Components`](Component) that have no resource, i.e. whose
+    /// "/root/resource/payload" is null or empty. Restoring a workspace backup does not
+    /// provision resources, so these are the components that still need their create action
+    /// run.
+    pub async fn list_missing_resource(ctx: &DalContext) -> ComponentResult<Vec<ComponentId>> {
+        let mut missing = vec![];
+        for component in Self::list(ctx).await? {
+            let payload = component.resource(ctx).await?.payload;
+            let is_missing = match payload {
+                None | Some(Value::Null) => true,
+                Some(Value::String(payload)) => payload.is_empty(),
+                Some(Value::Array(payload)) => payload.is_empty(),
+                Some(Value::Object(payload)) => payload.is_empty(),
+                Some(Value::Bool(_) | Value::Number(_)) => false,
+            };
+            if is_missing {
+                missing.push(component.id);
+            }
+        }
+
+        Ok(missing)
+    }
+
     pub async fn resource_attribute_value_by_id(
         ctx: &DalContext,
         component_id: ComponentId,
@@ -789,6 +789,23 @@ impl Transactions {
         &self.nats_txn
     }
 
+    /// Returns how many jobs and NATS messages are pending in these transactions, so that the
+    /// ones queued after it can be dropped with [`Self::discard_pending_since`].
+    pub(crate) async fn pending_mark(&self) -> (usize, usize) {
+        (
+            self.job_queue.size().await,
+            self.nats_txn.pending_len().await,
+        )
+    }
+
+    /// Drops the jobs and NATS messages queued since `mark` was taken with
+    /// [`Self::pending_mark`], e.g. when rolling back to a savepoint, since neither is part of
+    /// the PostgreSQL transaction.
+    pub(crate) async fn discard_pending_since(&self, (jobs, messages): (usize, usize)) {
+        self.job_queue.truncate(jobs).await;
+        self.nats_txn.truncate_pending(messages).await;
+    }
+
     /// Consumes all inner transactions, committing all changes made within them, and returns
     /// underlying connections.
     #[instrument(
@@ -5,8 +5,8 @@ use crate::diagram::DiagramResult;
 use crate::edge::{Edge, EdgeId, EdgeKind};
 use crate::socket::{SocketEdgeKind, SocketId};
 use crate::{
-    node::NodeId, Component, ComponentError, DalContext, DiagramError, Socket, SocketArity,
-    StandardModel, User,
+    node::NodeId, ChangeSetPk, Component, ComponentError, DalContext, DiagramError, Socket,
+    SocketArity, StandardModel, User, WsEvent, WsEventResult, WsPayload,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
@@ -37,6 +37,29 @@ impl Connection {
         to_socket_id: SocketId,
         edge_kind: EdgeKind,
     ) -> DiagramResult<Self> {
+        let (connection, _) = Self::new_with_replaced_edges(
+            ctx,
+            from_node_id,
+            from_socket_id,
+            to_node_id,
+            to_socket_id,
+            edge_kind,
+        )
+        .await?;
+        Ok(connection)
+    }
+
+    /// Creates a [`Connection`] like [`Self::new`], also returning the ids of the [`Edges`](Edge)
+    /// it replaced. Connecting to a _to_ [`Socket`] with an arity of one replaces the connections
+    /// it already had.
+    pub async fn new_with_replaced_edges(
+        ctx: &DalContext,
+        from_node_id: NodeId,
+        from_socket_id: SocketId,
+        to_node_id: NodeId,
+        to_socket_id: SocketId,
+        edge_kind: EdgeKind,
+    ) -> DiagramResult<(Self, Vec<EdgeId>)> {
         let from_component = Component::find_for_node(ctx, from_node_id)
             .await?
             .ok_or(ComponentError::NotFoundForNode(from_node_id))?;
@@ -59,7 +82,7 @@ impl Connection {
             let same_nodes =
                 edge.tail_node_id() == from_node_id && edge.head_node_id() == to_node_id;
             if same_sockets && same_nodes {
-                return Ok(Connection::from_edge(edge));
+                return Ok((Connection::from_edge(edge), vec![]));
             }
         }
 
@@ -73,6 +96,7 @@ impl Connection {
             to_socket.arity(),
         );
 
+        let mut replaced_edge_ids = vec![];
         if *to_socket.arity() == SocketArity::One {
             // Removes all connections for origin node since we are replacing it
 
@@ -81,6 +105,7 @@ impl Connection {
                 .filter(|edge| edge.head_socket_id() == to_socket_id);
 
             for replaced_edge in replaced_edges {
+                replaced_edge_ids.push(*replaced_edge.id());
                 for edge in &edges {
                     if edge.tail_node_id() == replaced_edge.tail_node_id()
                         || edge.head_node_id() == replaced_edge.tail_node_id()
@@ -108,7 +133,7 @@ impl Connection {
         )
         .await?;
 
-        Ok(Connection::from_edge(&edge))
+        Ok((Connection::from_edge(&edge), replaced_edge_ids))
     }
 
     pub async fn new_to_parent(
@@ -173,3 +198,33 @@ impl Connection {
         Ok(())
     }
 }
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionCreatedPayload {
+    edge_id: EdgeId,
+    from_socket_id: SocketId,
+    to_socket_id: SocketId,
+    replaced_existing: bool,
+    change_set_pk: ChangeSetPk,
+}
+
+impl WsEvent {
+    pub async fn connection_created(
+        ctx: &DalContext,
+        connection: &Connection,
+        replaced_existing: bool,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ConnectionCreated(ConnectionCreatedPayload {
+                edge_id: connection.id,
+                from_socket_id: connection.source.socket_id,
+                to_socket_id: connection.destination.socket_id,
+                replaced_existing,
+                change_set_pk: ctx.visibility().change_set_pk,
+            }),
+        )
+        .await
+    }
+}
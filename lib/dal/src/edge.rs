@@ -11,7 +11,7 @@ use thiserror::Error;
 use crate::func::argument::FuncArgumentError;
 use crate::job::definition::DependentValuesUpdate;
 use crate::node::NodeId;
-use crate::socket::SocketError;
+use crate::socket::{SocketEdgeKind, SocketError};
 use crate::standard_model::objects_from_rows;
 use crate::{
     diagram, impl_standard_model, pk, socket::SocketId, standard_model, standard_model_accessor,
@@ -846,6 +846,105 @@ impl Edge {
         Ok(())
     }
 
+    /// Links `socket` of the aggregation frame `frame_id` to the member `member_id` of the frame,
+    /// as is done when a component is put into an aggregation frame, and enqueues a
+    /// [`DependentValuesUpdate`] for the provider value on the receiving end of the link.
+    pub async fn connect_aggregation_frame_socket(
+        ctx: &DalContext,
+        socket: &Socket,
+        frame_id: ComponentId,
+        frame_node_id: NodeId,
+        member_id: ComponentId,
+        member_node_id: NodeId,
+    ) -> EdgeResult<Self> {
+        let (edge, attribute_value_context) = match *socket.edge_kind() {
+            SocketEdgeKind::ConfigurationInput => {
+                let provider = InternalProvider::find_explicit_for_socket(ctx, *socket.id())
+                    .await?
+                    .ok_or(EdgeError::InternalProviderNotFoundForSocket(*socket.id()))?;
+
+                Self::connect_internal_providers_for_components(
+                    ctx,
+                    *provider.id(),
+                    member_id,
+                    frame_id,
+                )
+                .await?;
+
+                let edge = Self::new(
+                    ctx,
+                    EdgeKind::Configuration,
+                    member_node_id,
+                    VertexObjectKind::Configuration,
+                    EdgeObjectId::from(member_id),
+                    *socket.id(),
+                    frame_node_id,
+                    VertexObjectKind::Configuration,
+                    EdgeObjectId::from(frame_id),
+                    *socket.id(),
+                )
+                .await?;
+
+                (
+                    edge,
+                    AttributeReadContext {
+                        component_id: Some(frame_id),
+                        internal_provider_id: Some(*provider.id()),
+                        ..Default::default()
+                    },
+                )
+            }
+            SocketEdgeKind::ConfigurationOutput => {
+                let provider = ExternalProvider::find_for_socket(ctx, *socket.id())
+                    .await?
+                    .ok_or(EdgeError::ExternalProviderNotFoundForSocket(*socket.id()))?;
+
+                Self::connect_external_providers_for_components(
+                    ctx,
+                    *provider.id(),
+                    frame_id,
+                    member_id,
+                )
+                .await?;
+
+                let edge = Self::new(
+                    ctx,
+                    EdgeKind::Configuration,
+                    frame_node_id,
+                    VertexObjectKind::Configuration,
+                    EdgeObjectId::from(frame_id),
+                    *socket.id(),
+                    member_node_id,
+                    VertexObjectKind::Configuration,
+                    EdgeObjectId::from(member_id),
+                    *socket.id(),
+                )
+                .await?;
+
+                (
+                    edge,
+                    AttributeReadContext {
+                        component_id: Some(member_id),
+                        external_provider_id: Some(*provider.id()),
+                        ..Default::default()
+                    },
+                )
+            }
+        };
+
+        let attribute_value = AttributeValue::find_for_context(ctx, attribute_value_context)
+            .await?
+            .ok_or(EdgeError::AttributeValueNotFound)?;
+        ctx.enqueue_job(DependentValuesUpdate::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            vec![*attribute_value.id()],
+        ))
+        .await?;
+
+        Ok(edge)
+    }
+
     pub fn head_component_id(&self) -> ComponentId {
         self.head_object_id().into()
     }
@@ -116,6 +116,8 @@ pub struct Func {
     handler: Option<String>,
     code_base64: Option<String>,
     code_sha256: String,
+    /// The unique id this func had in the package it was imported from, if any.
+    source_unique_id: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -280,6 +282,7 @@ impl Func {
     standard_model_accessor!(handler, Option<String>, FuncResult);
     standard_model_accessor!(code_base64, Option<String>, FuncResult);
     standard_model_accessor_ro!(code_sha256, String);
+    standard_model_accessor!(source_unique_id, Option<String>, FuncResult);
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
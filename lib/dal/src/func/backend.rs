@@ -90,6 +90,50 @@ pub enum FuncBackendKind {
     Validation,
 }
 
+impl FuncBackendKind {
+    /// The [`FuncBackendResponseTypes`](FuncBackendResponseType) a [`Func`](crate::Func) of this
+    /// kind may declare.
+    pub fn valid_response_types(&self) -> &'static [FuncBackendResponseType] {
+        use FuncBackendResponseType as R;
+
+        match self {
+            Self::Array => &[R::Array],
+            Self::Boolean => &[R::Boolean],
+            Self::Diff => &[R::Boolean, R::Json, R::Object],
+            Self::Identity => &[R::Identity],
+            Self::Integer => &[R::Integer],
+            Self::JsAction => &[R::Action],
+            Self::JsAttribute => &[
+                R::Array,
+                R::Boolean,
+                R::CodeGeneration,
+                R::Identity,
+                R::Integer,
+                R::Json,
+                R::Map,
+                R::Object,
+                R::Qualification,
+                R::String,
+                R::Unset,
+                R::Void,
+            ],
+            Self::JsAuthentication => &[R::Void],
+            Self::JsReconciliation => &[R::Reconciliation],
+            Self::JsSchemaVariantDefinition => &[R::SchemaVariantDefinition],
+            Self::JsValidation => &[R::Validation],
+            Self::Map => &[R::Map],
+            Self::Object => &[R::Object],
+            Self::String => &[R::String],
+            Self::Unset => &[R::Unset],
+            Self::Validation => &[R::Validation],
+        }
+    }
+
+    pub fn is_valid_response_type(&self, response_type: FuncBackendResponseType) -> bool {
+        self.valid_response_types().contains(&response_type)
+    }
+}
+
 #[remain::sorted]
 #[derive(
     Deserialize,
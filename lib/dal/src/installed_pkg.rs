@@ -6,8 +6,8 @@ use thiserror::Error;
 
 use crate::{
     impl_standard_model, pk, standard_model, standard_model_accessor, DalContext,
-    HistoryEventError, StandardModel, StandardModelError, Tenancy, Timestamp, TransactionsError,
-    Visibility,
+    HistoryEventError, SchemaVariantId, StandardModel, StandardModelError, Tenancy, Timestamp,
+    TransactionsError, Visibility,
 };
 
 pub mod asset;
@@ -54,6 +54,9 @@ pub struct InstalledPkg {
     id: InstalledPkgId,
     name: String,
     root_hash: String,
+    /// The version of the package, which is only recorded for packages installed since it was
+    /// added
+    version: Option<String>,
     #[serde(flatten)]
     tenancy: Tenancy,
     #[serde(flatten)]
@@ -94,8 +97,54 @@ impl InstalledPkg {
 
     standard_model_accessor!(name, String, InstalledPkgResult);
     standard_model_accessor!(root_hash, String, InstalledPkgResult);
+    standard_model_accessor!(version, Option<String>, InstalledPkgResult);
 
     pub async fn find_by_hash(ctx: &DalContext, hash: &str) -> InstalledPkgResult<Option<Self>> {
         Ok(Self::find_by_attr(ctx, "root_hash", &hash).await?.pop())
     }
+
+    /// Finds every installation of a package with the given name, one per installed version.
+    pub async fn find_by_name(ctx: &DalContext, name: &str) -> InstalledPkgResult<Vec<Self>> {
+        Ok(Self::find_by_attr(ctx, "name", &name).await?)
+    }
+
+    /// Finds the most recently installed package with the given name.
+    pub async fn find_latest_by_name(
+        ctx: &DalContext,
+        name: &str,
+    ) -> InstalledPkgResult<Option<Self>> {
+        Ok(Self::find_by_name(ctx, name)
+            .await?
+            .into_iter()
+            .max_by_key(|installed_pkg| (installed_pkg.timestamp().created_at, installed_pkg.id)))
+    }
+
+    /// Lists the schema variants installed by the given version of the package with the given
+    /// name, or none if that version is not installed.
+    pub async fn variants_for_version(
+        ctx: &DalContext,
+        name: &str,
+        version: &str,
+    ) -> InstalledPkgResult<Vec<SchemaVariantId>> {
+        let installed_pkg = match Self::find_by_name(ctx, name)
+            .await?
+            .into_iter()
+            .filter(|installed_pkg| installed_pkg.version() == Some(version))
+            .max_by_key(|installed_pkg| (installed_pkg.timestamp().created_at, installed_pkg.id))
+        {
+            Some(installed_pkg) => installed_pkg,
+            None => return Ok(vec![]),
+        };
+
+        let mut variant_ids = vec![];
+        for asset in InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg.id).await? {
+            if let InstalledPkgAssetTyped::SchemaVariant { id, .. } =
+                InstalledPkgAssetTyped::from(&asset)
+            {
+                variant_ids.push(id);
+            }
+        }
+
+        Ok(variant_ids)
+    }
 }
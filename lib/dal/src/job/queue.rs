@@ -36,6 +36,10 @@ impl JobQueue {
         self.queue.lock().await.len()
     }
 
+    pub async fn truncate(&self, len: usize) {
+        self.queue.lock().await.truncate(len);
+    }
+
     pub async fn drain(&self) -> Vec<Box<dyn JobProducer + Send + Sync>> {
         self.queue.lock().await.drain(0..).collect()
     }
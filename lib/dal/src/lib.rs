@@ -29,7 +29,8 @@ pub use attribute::{
         AttributePrototypeArgumentResult,
     },
     prototype::{
-        AttributePrototype, AttributePrototypeError, AttributePrototypeId, AttributePrototypeResult,
+        AttributePrototype, AttributePrototypeError, AttributePrototypeFuncBinding,
+        AttributePrototypeFuncBindingTarget, AttributePrototypeId, AttributePrototypeResult,
     },
     value::{
         AttributeValue, AttributeValueError, AttributeValueId, AttributeValuePayload,
@@ -68,7 +69,7 @@ pub use jwt_key::JwtPublicSigningKey;
 pub use key_pair::{KeyPair, KeyPairError, KeyPairResult, PublicKey};
 pub use label_list::{LabelEntry, LabelList, LabelListError};
 pub use node::NodeId;
-pub use node::{Node, NodeError, NodeKind};
+pub use node::{Node, NodeError, NodeGeometry, NodeKind};
 pub use node_menu::NodeMenuError;
 pub use prop::{Prop, PropError, PropId, PropKind, PropPk, PropResult};
 pub use prototype_context::HasPrototypeContext;
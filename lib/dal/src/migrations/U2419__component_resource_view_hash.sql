@@ -0,0 +1 @@
+ALTER TABLE components ADD COLUMN resource_view_hash TEXT;
@@ -0,0 +1 @@
+ALTER TABLE funcs ADD COLUMN source_unique_id TEXT;
@@ -0,0 +1 @@
+ALTER TABLE action_prototypes ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE nodes ADD COLUMN layouts jsonb NOT NULL DEFAULT '{}'::jsonb;
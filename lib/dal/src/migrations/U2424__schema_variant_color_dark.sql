@@ -0,0 +1 @@
+ALTER TABLE schema_variants ADD COLUMN default_color_dark TEXT;
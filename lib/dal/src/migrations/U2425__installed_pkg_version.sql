@@ -0,0 +1 @@
+ALTER TABLE installed_pkgs ADD COLUMN version TEXT;
@@ -11,8 +11,9 @@ use crate::edge::EdgeKind;
 use crate::standard_model::objects_from_rows;
 use crate::{
     diagram, impl_standard_model, pk, schema::variant::SchemaVariantError, standard_model,
-    standard_model_accessor, standard_model_belongs_to, Component, ComponentId, HistoryEventError,
-    StandardModel, StandardModelError, Tenancy, Timestamp, Visibility,
+    standard_model::TypeHint, standard_model_accessor, standard_model_belongs_to, Component,
+    ComponentId, HistoryEvent, HistoryEventError, StandardModel, StandardModelError, Tenancy,
+    Timestamp, Visibility,
 };
 use crate::{DalContext, Edge, SchemaError, TransactionsError};
 
@@ -80,6 +81,15 @@ pub enum NodeKind {
     Configuration,
 }
 
+/// The position and size of a [`Node`](Node) in a named layout of the diagram.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeGeometry {
+    pub x: String,
+    pub y: String,
+    pub width: Option<String>,
+    pub height: Option<String>,
+}
+
 /// A mathematical node that can be used to create [`Edges`](crate::Edge).
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Node {
@@ -96,6 +106,10 @@ pub struct Node {
     y: String,
     width: Option<String>,
     height: Option<String>,
+    /// Geometry of the node in layouts other than the default one, keyed by layout name. The
+    /// default layout is the `x`, `y`, `width` and `height` of the node itself.
+    #[serde(default)]
+    layouts: HashMap<String, NodeGeometry>,
 }
 
 impl_standard_model! {
@@ -306,4 +320,68 @@ impl Node {
         .map_err(|e| NodeError::SummaryDiagram(e.to_string()))?;
         Ok(())
     }
+
+    pub fn layouts(&self) -> &HashMap<String, NodeGeometry> {
+        &self.layouts
+    }
+
+    /// Returns the geometry of the node in the given layout, or [`None`] if it has never been
+    /// placed in it.
+    pub fn geometry_for_layout(&self, layout: &str) -> Option<&NodeGeometry> {
+        self.layouts.get(layout)
+    }
+
+    /// Sets the geometry of the node in the given layout. Without a layout, this is the same as
+    /// [`Self::set_geometry`].
+    pub async fn set_geometry_for_layout(
+        &mut self,
+        ctx: &DalContext,
+        layout: Option<&str>,
+        x: impl AsRef<str>,
+        y: impl AsRef<str>,
+        width: Option<impl AsRef<str>>,
+        height: Option<impl AsRef<str>>,
+    ) -> NodeResult<()> {
+        let layout = match layout {
+            Some(layout) => layout,
+            None => return self.set_geometry(ctx, x, y, width, height).await,
+        };
+
+        let mut layouts = self.layouts.clone();
+        layouts.insert(
+            layout.to_owned(),
+            NodeGeometry {
+                x: x.as_ref().to_owned(),
+                y: y.as_ref().to_owned(),
+                width: width.as_ref().map(|val| val.as_ref().to_owned()),
+                height: height.as_ref().map(|val| val.as_ref().to_owned()),
+            },
+        );
+
+        let value = serde_json::to_value(&layouts)?;
+        let updated_at = standard_model::update(
+            ctx,
+            Self::table_name(),
+            "layouts",
+            self.id(),
+            &value,
+            TypeHint::JsonB,
+        )
+        .await?;
+        let _history_event = HistoryEvent::new(
+            ctx,
+            &Self::history_event_label(vec!["updated"]),
+            &Self::history_event_message("updated"),
+            &serde_json::json![{
+                "pk": self.pk,
+                "field": "layouts",
+                "value": &value,
+            }],
+        )
+        .await?;
+        self.timestamp.updated_at = updated_at;
+        self.layouts = layouts;
+
+        Ok(())
+    }
 }
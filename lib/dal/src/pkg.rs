@@ -2,45 +2,63 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use si_data_pg::{PgError, SqlState};
 use thiserror::Error;
 use url::ParseError;
 
-pub use export::{get_component_type, PkgExporter};
+pub use export::{
+    get_component_type, PkgExporter, TEMPORARY_EXPORTER_CREATED_BY, TEMPORARY_EXPORTER_DESCRIPTION,
+    TEMPORARY_EXPORTER_NAME, TEMPORARY_EXPORTER_VERSION,
+};
 pub use import::{
-    attach_resource_payload_to_value, import_pkg, import_pkg_from_pkg, ImportAttributeSkip,
-    ImportEdgeSkip, ImportOptions, ImportSkips,
+    apply_skipped_attr_funcs, attach_resource_payload_to_value, check_map_item_props,
+    conflicting_action_prototypes, import_pkg, import_pkg_from_pkg, preview_schema_upgrade,
+    ComponentNamePolicy, DbRetryPolicy, FuncNameConflictPolicy, ImportAttrFuncSkip,
+    ImportAttributeSkip, ImportCheckpoint, ImportCheckpointChangeSet, ImportCheckpointComponent,
+    ImportEdgeSkip, ImportOptions, ImportOptionsBuilder, ImportPhase, ImportPlan, ImportResult,
+    ImportSkips, ImportStats, ImportedDefaultValues, SecretsPolicy, UpgradePreview,
+    IMPORT_SKIPS_VERSION,
 };
-use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SpecError};
+pub use integrity::{verify_installed_pkg_integrity, IntegrityReport};
+pub use ndjson::import_ndjson;
+pub use remove::{remove_pkg, RemovedPkgSummary};
+use si_pkg::{FuncSpecBackendKind, FuncSpecBackendResponseType, SiPkgError, SiPkgKind, SpecError};
 
 use crate::authentication_prototype::AuthenticationPrototypeError;
 use crate::{
-    component::view::debug::ComponentDebugViewError,
+    component::view::{debug::ComponentDebugViewError, ComponentViewError},
     func::{
         argument::{FuncArgumentError, FuncArgumentId},
         binding::FuncBindingError,
     },
-    installed_pkg::InstalledPkgError,
+    installed_pkg::{InstalledPkgError, InstalledPkgId},
     prop_tree::PropTreeError,
+    property_editor::schema::WidgetKind,
     schema::variant::definition::{SchemaVariantDefinitionError, SchemaVariantDefinitionId},
     socket::{SocketEdgeKind, SocketError},
-    ActionPrototypeError, AttributeContextBuilderError, AttributePrototypeArgumentError,
-    AttributePrototypeArgumentId, AttributePrototypeError, AttributePrototypeId,
-    AttributeReadContext, AttributeValueError, ChangeSetError, ChangeSetPk, ComponentError,
-    ComponentId, DalContext, EdgeError, ExternalProviderError, ExternalProviderId, FuncBackendKind,
-    FuncBackendResponseType, FuncBindingReturnValueError, FuncError, FuncId, InternalProviderError,
-    InternalProviderId, NodeError, PropError, PropId, PropKind, SchemaError, SchemaId,
-    SchemaVariantError, SchemaVariantId, StandardModelError, UserPk, WorkspaceError, WorkspacePk,
-    WsEvent, WsEventResult, WsPayload,
+    ActionError, ActionPrototypeError, AttributeContextBuilderError,
+    AttributePrototypeArgumentError, AttributePrototypeArgumentId, AttributePrototypeError,
+    AttributePrototypeId, AttributeReadContext, AttributeValueError, ChangeSetError, ChangeSetPk,
+    ComponentError, ComponentId, DalContext, EdgeError, ExternalProviderError, ExternalProviderId,
+    FuncBackendKind, FuncBackendResponseType, FuncBindingReturnValueError, FuncError, FuncId,
+    InternalProviderError, InternalProviderId, NodeError, PropError, PropId, PropKind, SchemaError,
+    SchemaId, SchemaVariantError, SchemaVariantId, StandardModelError, TransactionsError, UserPk,
+    WorkspaceError, WorkspacePk, WsEvent, WsEventError, WsEventResult, WsPayload,
 };
 
 mod export;
 mod import;
+mod integrity;
+mod ndjson;
+mod remove;
 
 #[remain::sorted]
 #[derive(Debug, Error)]
 pub enum PkgError {
     #[error("Action creation error: {0}")]
     Action(#[from] ActionPrototypeError),
+    #[error("action enqueue error: {0}")]
+    ActionEnqueue(#[from] ActionError),
     #[error(transparent)]
     AttributeContextBuilder(#[from] AttributeContextBuilderError),
     #[error("attribute function for context {0:?} has key {1} but is not setting a prop value")]
@@ -75,10 +93,16 @@ pub enum PkgError {
     AttributeValueWithKeyOrIndexButNoParent,
     #[error("Auth func creation error: {0}")]
     AuthFunc(#[from] AuthenticationPrototypeError),
+    #[error("workspace backup has {components} components, more than the limit of {limit}")]
+    BackupTooLarge { components: usize, limit: usize },
+    #[error("import cancelled")]
+    Cancelled,
     #[error(transparent)]
     ChangeSet(#[from] ChangeSetError),
     #[error("change set {0} not found")]
     ChangeSetNotFound(ChangeSetPk),
+    #[error("workspace backup has no change set named \"{0}\"")]
+    ChangeSetNotInBackup(String),
     #[error(transparent)]
     Component(#[from] ComponentError),
     #[error(transparent)]
@@ -95,8 +119,12 @@ pub enum PkgError {
     ComponentMissingSchemaVariant(String, String),
     #[error("could not update find schema {0} with variant {1} for package component {2}")]
     ComponentMissingUpdateSchemaVariant(String, String, String),
+    #[error("a component named {0} already exists")]
+    ComponentNameConflict(String),
     #[error("component spec has no position")]
     ComponentSpecMissingPosition,
+    #[error(transparent)]
+    ComponentView(#[from] ComponentViewError),
     #[error("map item prop {0} has both custom key prototypes and custom prop only prototype")]
     ConflictingMapKeyPrototypes(PropId),
     #[error("expected data on an SiPkg node, but none found: {0}")]
@@ -121,10 +149,22 @@ pub enum PkgError {
     FuncBindingReturnValue(#[from] FuncBindingReturnValueError),
     #[error(transparent)]
     FuncExecution(#[from] crate::func::execution::FuncExecutionError),
+    #[error("A func named {0} already exists")]
+    FuncNameConflict(String),
+    #[error("func not found: {0}")]
+    FuncNotFound(FuncId),
+    #[error("package hash {actual} does not match the expected hash {expected}")]
+    HashMismatch { expected: String, actual: String },
+    #[error("Import checkpoint is for package {0} but package {1} was provided")]
+    ImportCheckpointMismatch(String, String),
     #[error("Installed func id {0} does not exist")]
     InstalledFuncMissing(FuncId),
     #[error(transparent)]
     InstalledPkg(#[from] InstalledPkgError),
+    #[error("Installed package {0} cannot be removed while these components use it: {1:?}")]
+    InstalledPkgInUse(InstalledPkgId, Vec<ComponentId>),
+    #[error("Installed package {0} does not exist")]
+    InstalledPkgNotFound(InstalledPkgId),
     #[error("Installed schema id {0} does not exist")]
     InstalledSchemaMissing(SchemaId),
     #[error("Installed schema variant definition {0} does not exist")]
@@ -135,12 +175,26 @@ pub enum PkgError {
     InternalProvider(#[from] InternalProviderError),
     #[error("Missing Prop {1} for InternalProvider {1}")]
     InternalProviderMissingProp(InternalProviderId, PropId),
+    #[error("Func {func} has backend kind {kind} which cannot return {response_type}")]
+    InvalidFuncKindResponsePair {
+        func: String,
+        kind: FuncBackendKind,
+        response_type: FuncBackendResponseType,
+    },
+    #[error("Func {func} has a link that is not an http(s) URL: {link}")]
+    InvalidFuncLink { func: String, link: String },
     #[error("Leaf Function {0} has invalid argument {1}")]
     InvalidLeafArgument(FuncId, String),
-    #[error("json pointer {1} not found in {0:?}")]
-    JsonPointerNotFound(serde_json::Value, String),
-    #[error("json value is not an object: {0:?}")]
-    JsonValueIsNotAnObject(serde_json::Value),
+    #[error("Line {0} of the NDJSON backup is not a valid record: {1}")]
+    InvalidNdjsonRecord(usize, String),
+    #[error("Prop {prop} has a doc link that is not an http(s) URL: {link}")]
+    InvalidPropDocLink { prop: String, link: String },
+    #[error("Prop {prop} has widget options that do not fit its {kind} widget")]
+    InvalidWidgetOptions { prop: String, kind: WidgetKind },
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Map prop {0} must have exactly one item prop, found {1}")]
+    MapPropItemPropCount(String, usize),
     #[error("Missing AttributePrototype {0} for explicit InternalProvider {1}")]
     MissingAttributePrototypeForInputSocket(AttributePrototypeId, InternalProviderId),
     #[error("Missing AttributePrototype {0} for ExternalProvider {1}")]
@@ -173,18 +227,24 @@ pub enum PkgError {
     MissingItemPropForMapProp(PropId),
     #[error("Cannot find installed prop {0}")]
     MissingProp(PropId),
-    #[error("Cannot find root prop for variant {0}")]
-    MissingRootProp(SchemaVariantId),
     #[error("Cannot find schema_variant_definition {0}")]
     MissingSchemaVariantDefinition(SchemaVariantId),
     #[error("Cannot find socket with name {0} for edge kind {1}")]
     MissingSocketName(String, SocketEdgeKind),
     #[error("Unique id missing for node in workspace backup: {0}")]
     MissingUniqueIdForNode(String),
+    #[error("NDJSON backup has a {0} record for change set {1} before its change set record")]
+    NdjsonMissingChangeSet(&'static str, String),
+    #[error("NDJSON backup does not start with a metadata record")]
+    NdjsonMissingMetadata,
+    #[error("NDJSON backup has a variant record for schema {0} before its schema record")]
+    NdjsonMissingSchema(String),
     #[error(transparent)]
     Node(#[from] NodeError),
     #[error("Package with that hash already installed: {0}")]
     PackageAlreadyInstalled(String),
+    #[error("pg error: {0}")]
+    Pg(#[from] PgError),
     #[error(transparent)]
     Pkg(#[from] SiPkgError),
     #[error(transparent)]
@@ -205,6 +265,8 @@ pub enum PkgError {
     SchemaVariantDefinition(#[from] SchemaVariantDefinitionError),
     #[error("schema variant not found: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error("secret definition prop {0} collides with an existing prop of the same name")]
+    SecretDefinitionCollision(String),
     #[error("json serialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
     #[error(transparent)]
@@ -215,12 +277,29 @@ pub enum PkgError {
     StandardModelMissingBelongsTo(&'static str, &'static str, String),
     #[error("standard model relationship {0} found multiple belongs_to for {1} with id {2}")]
     StandardModelMultipleBelongsTo(&'static str, &'static str, String),
+    #[error("transactions error: {0}")]
+    Transactions(#[from] TransactionsError),
     #[error(transparent)]
     UlidDecode(#[from] ulid::DecodeError),
     #[error("unable to export component: {0}")]
     UnableToExportComponent(ComponentId),
+    #[error("func argument {func_argument} of attribute prototype {prototype_id} for schema variant {schema_variant_id} takes output socket {socket_name} as input, but only props and input sockets are supported")]
+    UnsupportedOutputSocketInput {
+        func_argument: String,
+        socket_name: String,
+        prototype_id: AttributePrototypeId,
+        schema_variant_id: SchemaVariantId,
+    },
+    #[error("schema {0} is an upgrade of an installed builtin, but builtin schema upgrades are not enabled")]
+    UpgradeRequiresFlag(String),
     #[error(transparent)]
     UrlParse(#[from] ParseError),
+    #[error("could not finalize variant {variant} of schema {schema}: {source}")]
+    VariantFinalizeFailed {
+        schema: String,
+        variant: String,
+        source: SchemaVariantError,
+    },
     #[error(transparent)]
     Workspace(#[from] WorkspaceError),
     #[error("Cannot find default change set \"{0}\" in workspace backup")]
@@ -231,6 +310,8 @@ pub enum PkgError {
     WorkspaceNotFound(WorkspacePk),
     #[error("Workspace backup missing workspace pk")]
     WorkspacePkNotInBackup,
+    #[error("ws event error: {0}")]
+    WsEvent(#[from] WsEventError),
 }
 
 impl PkgError {
@@ -241,6 +322,21 @@ impl PkgError {
     fn prop_spec_children_invalid(message: impl Into<String>) -> Self {
         Self::PropSpecChildrenInvalid(message.into())
     }
+
+    /// Whether the error was caused by a deadlock, which is expected to go away if the failed
+    /// step is retried. Serialization failures are not transient here: the snapshot of the
+    /// whole transaction is stale after one, so rolling back to a savepoint cannot fix it.
+    pub fn is_transient_db_error(&self) -> bool {
+        let mut source: Option<&(dyn std::error::Error + 'static)> = Some(self);
+        while let Some(err) = source {
+            if let Some(PgError::Pg(pg_err)) = err.downcast_ref::<PgError>() {
+                return pg_err.code() == Some(&SqlState::T_R_DEADLOCK_DETECTED);
+            }
+            source = err.source();
+        }
+
+        false
+    }
 }
 
 pub type PkgResult<T> = Result<T, PkgError>;
@@ -390,6 +486,32 @@ pub struct ModuleImportedPayload {
     schema_variant_ids: Vec<SchemaVariantId>,
 }
 
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleRemovedPayload {
+    installed_pkg_id: InstalledPkgId,
+    schema_variant_ids: Vec<SchemaVariantId>,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageImportedPayload {
+    installed_pkg_id: Option<InstalledPkgId>,
+    name: String,
+    kind: SiPkgKind,
+    stats: ImportStats,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PkgImportProgressPayload {
+    module_name: String,
+    name: String,
+    phase: ImportPhase,
+    current: usize,
+    total: usize,
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceImportPayload {
@@ -441,6 +563,68 @@ impl WsEvent {
         .await
     }
 
+    /// Published when the removal of an installed package is committed, with the schema variants
+    /// it deleted.
+    pub async fn module_removed(
+        ctx: &DalContext,
+        installed_pkg_id: InstalledPkgId,
+        schema_variant_ids: Vec<SchemaVariantId>,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::ModuleRemoved(ModuleRemovedPayload {
+                installed_pkg_id,
+                schema_variant_ids,
+            }),
+        )
+        .await
+    }
+
+    /// Published when a package import is committed, with counts of what it installed.
+    /// `installed_pkg_id` is `None` for imports that did not record the package as installed.
+    pub async fn package_imported(
+        ctx: &DalContext,
+        installed_pkg_id: Option<InstalledPkgId>,
+        name: impl Into<String>,
+        kind: SiPkgKind,
+        stats: ImportStats,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::PackageImported(PackageImportedPayload {
+                installed_pkg_id,
+                name: name.into(),
+                kind,
+                stats,
+            }),
+        )
+        .await
+    }
+
+    /// Published on commit for each func, schema, component and edge of module `module_name`
+    /// that is imported, so that a large import can show how far along it is. `name` is the
+    /// thing that was imported in `phase`, and `current` of `total` things have been imported.
+    pub async fn pkg_import_progress(
+        ctx: &DalContext,
+        module_name: impl Into<String>,
+        name: impl Into<String>,
+        phase: ImportPhase,
+        current: usize,
+        total: usize,
+    ) -> WsEventResult<Self> {
+        WsEvent::new(
+            ctx,
+            WsPayload::PkgImportProgress(PkgImportProgressPayload {
+                module_name: module_name.into(),
+                name: name.into(),
+                phase,
+                current,
+                total,
+            }),
+        )
+        .await
+    }
+
     pub async fn workspace_imported(
         ctx: &DalContext,
         workspace_pk: Option<WorkspacePk>,
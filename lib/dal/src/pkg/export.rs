@@ -16,6 +16,7 @@ use telemetry::prelude::*;
 
 use crate::authentication_prototype::{AuthenticationPrototype, AuthenticationPrototypeContext};
 use crate::{
+    actor_view::ActorView,
     component::view::{AttributeDebugView, ComponentDebugView},
     edge::EdgeKind,
     func::{argument::FuncArgument, intrinsics::IntrinsicFunc},
@@ -33,6 +34,13 @@ use crate::{
 
 use super::{PkgError, PkgResult};
 
+/// Identity of the exporter the importer uses internally to snapshot components (for example
+/// while upgrading a schema). Packages built by it are never installed.
+pub const TEMPORARY_EXPORTER_NAME: &str = "temporary";
+pub const TEMPORARY_EXPORTER_CREATED_BY: &str = "SystemInit";
+pub const TEMPORARY_EXPORTER_VERSION: &str = "1.0";
+pub const TEMPORARY_EXPORTER_DESCRIPTION: &str = "Temporary pkg created to update schemas";
+
 type FuncSpecMap = super::ChangeSetThingMap<FuncId, FuncSpec>;
 type VariantSpecMap = super::ChangeSetThingMap<SchemaVariantId, SchemaVariantSpec>;
 type ComponentMap = super::ChangeSetThingMap<ComponentId, ComponentSpec>;
@@ -115,6 +123,50 @@ impl PkgExporter {
         }
     }
 
+    /// Creates the workspace exporter used by the importer to snapshot existing components
+    /// before re-importing them.
+    pub fn new_temporary_exporter() -> Self {
+        Self::new_workspace_exporter(
+            TEMPORARY_EXPORTER_NAME,
+            TEMPORARY_EXPORTER_CREATED_BY,
+            TEMPORARY_EXPORTER_VERSION,
+            TEMPORARY_EXPORTER_DESCRIPTION,
+        )
+    }
+
+    /// Exports the given funcs, along with their arguments, as a [`SiPkgKind::Module`] package
+    /// with no schemas, so a set of funcs can be shared as a library. The package is created by
+    /// the actor of the [`DalContext`].
+    pub async fn export_funcs_as_module(
+        ctx: &DalContext,
+        func_ids: &[FuncId],
+        name: impl Into<String>,
+        version: impl Into<String>,
+    ) -> PkgResult<SiPkg> {
+        let created_by = match ActorView::from_history_actor(ctx, *ctx.history_actor()).await? {
+            ActorView::User { label, email, .. } => email.unwrap_or(label),
+            ActorView::System { label } => label,
+        };
+        let exporter = Self::new_module_exporter(name, version, None::<String>, created_by, vec![]);
+
+        let mut pkg_spec_builder = PkgSpec::builder();
+        pkg_spec_builder
+            .name(&exporter.name)
+            .kind(exporter.kind)
+            .version(&exporter.version)
+            .created_by(&exporter.created_by);
+
+        for func_id in func_ids {
+            let func = Func::get_by_id(ctx, func_id)
+                .await?
+                .ok_or(PkgError::FuncNotFound(*func_id))?;
+            let (func_spec, _) = exporter.export_func(ctx, None, &func).await?;
+            pkg_spec_builder.func(func_spec);
+        }
+
+        Ok(SiPkg::load_from_spec(pkg_spec_builder.build()?)?)
+    }
+
     pub async fn export_as_bytes(&mut self, ctx: &DalContext) -> PkgResult<Vec<u8>> {
         match self.kind {
             SiPkgKind::Module => info!("Building module package"),
@@ -256,9 +308,15 @@ impl PkgExporter {
             if let Some(color_str) = variant.color(ctx).await? {
                 data_builder.color(color_str);
             };
+            if let Some(color_dark) = variant.default_color_dark() {
+                data_builder.color_dark(color_dark);
+            }
             if let Some(link) = variant.link() {
                 data_builder.try_link(link)?;
             }
+            if let Some(description) = schema_variant_definition.description() {
+                data_builder.description(description);
+            }
 
             data_builder.component_type(get_component_type(ctx, variant).await?);
 
@@ -790,7 +848,9 @@ impl PkgExporter {
             let prop_id = tree_node.prop_id;
             let mut builder = PropSpec::builder();
 
-            if !change_set_matches(change_set_pk, tree_node.visibility_change_set_pk) {
+            let in_change_set =
+                change_set_matches(change_set_pk, tree_node.visibility_change_set_pk);
+            if !in_change_set {
                 builder.has_data(false);
             }
 
@@ -820,6 +880,14 @@ impl PkgExporter {
                 builder.documentation(documentation.as_str());
             }
 
+            // Setting a validation format marks the prop as having data, which is only true if
+            // the prop was changed in the change set being exported
+            if let Some(validation_format) = tree_node.validation_format {
+                builder
+                    .validation_format(validation_format)
+                    .has_data(in_change_set);
+            }
+
             traversal_stack.push(TraversalStackEntry {
                 builder,
                 prop_id,
@@ -1298,6 +1366,12 @@ impl PkgExporter {
             }
 
             for edge in Edge::list(ctx).await? {
+                // Aggregation frames link their sockets to their members with edges from a
+                // socket to itself. Those are restored from the frame edge on import.
+                if edge.head_socket_id() == edge.tail_socket_id() {
+                    continue;
+                }
+
                 let to_component_spec = self
                     .component_map
                     .get(
@@ -1602,6 +1676,11 @@ impl PkgExporter {
     }
 
     pub async fn export(&mut self, ctx: &DalContext) -> PkgResult<SiPkg> {
+        Ok(SiPkg::load_from_spec(self.export_as_spec(ctx).await?)?)
+    }
+
+    /// Exports the package as a [`PkgSpec`], without building the package itself.
+    pub async fn export_as_spec(&mut self, ctx: &DalContext) -> PkgResult<PkgSpec> {
         let mut pkg_spec_builder = PkgSpec::builder();
         pkg_spec_builder
             .name(&self.name)
@@ -1667,10 +1746,42 @@ impl PkgExporter {
             }
         }
 
-        let spec = pkg_spec_builder.build()?;
-        let pkg = SiPkg::load_from_spec(spec)?;
+        Ok(pkg_spec_builder.build()?)
+    }
+}
+
+impl SchemaVariant {
+    /// Exports the [`SchemaVariant`] back into a normalized [`SchemaVariantSpec`], with unique
+    /// ids stripped and props, sockets and funcs sorted, so that it can be compared for equality
+    /// with the [canonicalized](SchemaVariantSpec::canonicalize) variant spec of an incoming
+    /// package.
+    pub async fn canonical_spec(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> PkgResult<SchemaVariantSpec> {
+        let variant = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(PkgError::SchemaVariantNotFound(schema_variant_id))?;
+
+        let mut exporter = PkgExporter::new_module_exporter(
+            variant.name(),
+            TEMPORARY_EXPORTER_VERSION,
+            None::<String>,
+            TEMPORARY_EXPORTER_CREATED_BY,
+            vec![],
+        );
+
+        for func in Self::all_funcs(ctx, schema_variant_id).await? {
+            let (func_spec, _) = exporter.export_func(ctx, None, &func).await?;
+            exporter
+                .func_map
+                .insert(ChangeSetPk::NONE, *func.id(), func_spec);
+        }
+
+        let mut variant_spec = exporter.export_variant(ctx, None, &variant).await?;
+        variant_spec.canonicalize();
 
-        Ok(pkg)
+        Ok(variant_spec)
     }
 }
 
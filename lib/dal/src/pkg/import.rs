@@ -1,39 +1,44 @@
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
+    future::Future,
     path::Path,
     str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
 };
 
-use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use url::Url;
 
 use si_pkg::{
-    AttrFuncInputSpec, AttributeValuePath, AttributeValueSpec, ComponentSpec, ComponentSpecVariant,
-    EdgeSpec, EdgeSpecKind, FuncArgumentSpec, FuncSpec, FuncSpecData, SchemaVariantSpecPropRoot,
-    SiPkg, SiPkgActionFunc, SiPkgAttrFuncInputView, SiPkgAuthFunc, SiPkgComponent, SiPkgEdge,
-    SiPkgError, SiPkgFunc, SiPkgKind, SiPkgLeafFunction, SiPkgMetadata, SiPkgProp, SiPkgPropData,
-    SiPkgSchema, SiPkgSchemaData, SiPkgSchemaVariant, SiPkgSocket, SiPkgSocketData, SocketSpecKind,
+    func_dependencies_of_schema, AttrFuncInputSpec, AttributeValuePath, AttributeValueSpec,
+    ComponentSpec, ComponentSpecVariant, EdgeSpec, EdgeSpecKind, FuncArgumentSpec, FuncSpec,
+    FuncSpecData, PropSpec, SchemaVariantSpecPropRoot, SiPkg, SiPkgActionFunc,
+    SiPkgAttrFuncInputView, SiPkgAuthFunc, SiPkgComponent, SiPkgEdge, SiPkgError, SiPkgFunc,
+    SiPkgKind, SiPkgLeafFunction, SiPkgMetadata, SiPkgProp, SiPkgPropData, SiPkgSchema,
+    SiPkgSchemaData, SiPkgSchemaVariant, SiPkgSocket,
 };
 use telemetry::prelude::*;
 
 use crate::authentication_prototype::{AuthenticationPrototype, AuthenticationPrototypeContext};
 use crate::{
     component::ComponentKind,
-    edge::EdgeKind,
+    edge::{EdgeId, EdgeKind},
     func::{
         self,
         argument::{FuncArgumentError, FuncArgumentKind},
         backend::js_action::ActionRunResult,
-        binding::FuncBinding,
-        binding_return_value::FuncBindingReturnValue,
     },
     installed_pkg::{
         InstalledPkg, InstalledPkgAsset, InstalledPkgAssetKind, InstalledPkgAssetTyped,
         InstalledPkgId,
     },
+    job::definition::DependentValuesUpdate,
     pkg::PkgExporter,
     prop::PropPath,
+    property_editor::{schema::WidgetKind, SelectWidgetOption},
     schema::{
         variant::{
             definition::{SchemaVariantDefinition, SchemaVariantDefinitionJson},
@@ -41,15 +46,16 @@ use crate::{
         },
         SchemaUiMenu,
     },
-    socket::SocketEdgeKind,
-    ActionKind, ActionPrototype, ActionPrototypeContext, AttributeContext, AttributeContextBuilder,
-    AttributePrototype, AttributePrototypeArgument, AttributePrototypeId, AttributeReadContext,
-    AttributeValue, AttributeValueError, ChangeSet, ChangeSetPk, Component, ComponentError,
-    ComponentId, DalContext, Edge, EdgeError, ExternalProvider, ExternalProviderId, Func,
-    FuncArgument, FuncError, FuncId, InternalProvider, InternalProviderError, InternalProviderId,
-    LeafKind, Node, NodeError, Prop, PropId, PropKind, Schema, SchemaId, SchemaVariant,
-    SchemaVariantError, SchemaVariantId, Socket, StandardModel, Tenancy, UserPk, Workspace,
-    WorkspacePk,
+    socket::{SocketEdgeKind, SocketKind},
+    Action, ActionKind, ActionPrototype, ActionPrototypeContext, AttributeContext,
+    AttributeContextBuilder, AttributePrototype, AttributePrototypeArgument, AttributePrototypeId,
+    AttributeReadContext, AttributeValue, AttributeValueError, AttributeValueId, ChangeSet,
+    ChangeSetPk, Component, ComponentError, ComponentId, ComponentType, ComponentView, DalContext,
+    Edge, EdgeError, ExternalProvider, ExternalProviderId, Func, FuncArgument, FuncBackendKind,
+    FuncBackendResponseType, FuncError, FuncId, InternalProvider, InternalProviderError,
+    InternalProviderId, LeafKind, Node, NodeError, Prop, PropId, PropKind, Schema, SchemaId,
+    SchemaVariant, SchemaVariantError, SchemaVariantId, Socket, StandardModel, Tenancy, UserPk,
+    Workspace, WorkspacePk, WsEvent,
 };
 
 use super::{PkgError, PkgResult};
@@ -64,12 +70,18 @@ enum Thing {
     Func(Func),
     FuncArgument(FuncArgument),
     Schema(Schema),
+    /// A func left out by [`ImportOptions::funcs`]. Looking it up fails like looking up a func
+    /// that is not in the package.
+    SkippedFunc,
     SchemaVariant(SchemaVariant),
     Socket(Box<(Socket, Option<InternalProvider>, Option<ExternalProvider>)>),
 }
 
 type ThingMap = super::ChangeSetThingMap<String, Thing>;
 
+/// The default values a package set on props during an import, keyed by the path of the prop.
+pub type ImportedDefaultValues = Vec<(PropPath, serde_json::Value)>;
+
 #[derive(Clone, Debug, Default)]
 pub struct ImportOptions {
     pub schemas: Option<Vec<String>>,
@@ -80,9 +92,652 @@ pub struct ImportOptions {
     /// If set to `true` then we will set the functions to a builtin
     /// in the UI. They will be marked as such.
     pub is_builtin: bool,
+    /// Resume a workspace backup import from the [`ImportCheckpoint`] returned by a previously
+    /// interrupted import of the same package. Change sets recorded in the checkpoint will not be
+    /// imported a second time, and the components it records are reused by later change sets.
+    pub resume_from: Option<ImportCheckpoint>,
+    /// If set, a workspace backup import will stop after importing this many change sets and
+    /// return the checkpoint to resume from in [`ImportResult::interrupted`]. This allows very
+    /// large backups to be imported incrementally: the caller commits after each interruption
+    /// and resumes in a new transaction. An error is returned as is, and since the transaction
+    /// it happened in is rolled back, the import has to start over.
+    pub max_change_sets: Option<usize>,
+    /// What to do when a func in the package has the same name as an existing, non-builtin func
+    pub func_name_conflict: FuncNameConflictPolicy,
+    /// If set to `true`, a `null` component attribute value for a scalar prop is skipped as an
+    /// [`ImportAttributeSkip::KindMismatch`] instead of bypassing type checking.
+    pub strict_type_checking: bool,
+    /// If set to `true`, every component created by the import that has no resource will have
+    /// its `Create` action enqueued in the change set it was imported into, so that applying the
+    /// change set provisions it. Actions cannot be enqueued on head, so components imported
+    /// directly into head are left alone.
+    pub enqueue_create_actions: bool,
+    /// If set to `true`, the qualifications of every component imported by a workspace backup
+    /// import are run once the components and edges have been imported, so that their results
+    /// are available right away. A qualification that fails to run does not fail the import. It
+    /// is recorded in the `qualification_errors` of the [`ImportSkips`] instead.
+    pub run_qualifications_after_import: bool,
+    /// Checked between each func, schema, component and edge imported. Once cancelled, the
+    /// import stops with [`PkgError::Cancelled`] so that the caller rolls back its transaction
+    /// instead of committing a half-applied import, e.g. when the server is shutting down.
+    pub cancellation_token: Option<CancellationToken>,
+    /// What to do when a component created by the import has the same name as a component that
+    /// already exists in the change set being imported into
+    pub component_name_policy: ComponentNamePolicy,
+    /// How the values of a workspace backup's component secrets (the attributes under
+    /// "/root/secrets") are handled. They are never written into the workspace being imported
+    /// into.
+    pub secrets_policy: SecretsPolicy,
+    /// Socket names used by the edges of a workspace backup, mapped to the names of the sockets
+    /// they should connect to instead. When a socket still cannot be found by name, a socket whose
+    /// name only differs by case is used.
+    pub socket_name_remap: HashMap<String, String>,
+    /// Func links and prop doc links must be http(s) URLs. If set to `true`, any other link is
+    /// dropped with a warning instead of failing the import with [`PkgError::InvalidFuncLink`]
+    /// or [`PkgError::InvalidPropDocLink`].
+    pub strip_invalid_links: bool,
+    /// If set, a workspace backup with more components than this, counted across all of its
+    /// change sets, is rejected with [`PkgError::BackupTooLarge`] before anything is restored.
+    pub max_components: Option<usize>,
+    /// If set to `true`, the schema variants created by the import are finalized together once
+    /// every schema in the change set has been imported, instead of as each one is imported.
+    /// Everything that needs a finalized variant (sockets, action, leaf and attribute funcs,
+    /// default values) is imported along with it.
+    pub defer_finalize: bool,
+    /// If set to `true`, the component attribute values written by the import do not each
+    /// trigger a dependent values update. A single update for all of them runs once every
+    /// component and edge of the change set has been imported, which is much faster for large
+    /// workspace backups.
+    pub defer_dependent_updates: bool,
+    /// If set, the positions of the components in a workspace backup are written into the
+    /// diagram layout with this name instead of into the default layout.
+    pub target_layout: Option<String>,
+    /// If set, import steps that are safe to repeat are retried when they are picked as the
+    /// victim of a deadlock with a concurrent import. Only edges are retried: creating funcs,
+    /// schemas and components queues jobs and events outside of the database transaction,
+    /// which a retry would duplicate.
+    pub db_retry: Option<DbRetryPolicy>,
+    /// If set to `true`, the props of the schema variants created by the import are not wired up
+    /// to their attribute functions and keep the intrinsic prototypes they were created with.
+    /// Wiring up attribute functions is the most expensive part of importing a variant, so this
+    /// speeds up partial imports. Action, authentication and leaf funcs are still imported. The
+    /// skipped functions are returned as [`ImportAttrFuncSkip`]s, which can be wired up later
+    /// with [`apply_skipped_attr_funcs`]. Schemas upgraded in place are not affected.
+    pub skip_attribute_funcs: bool,
+    /// If set, a warning with the schema's name and the number of variants and props it has is
+    /// logged for each schema that takes longer than this many milliseconds to import, so that
+    /// the schemas dominating the time of a large import can be found.
+    pub slow_schema_warn_ms: Option<u64>,
+    /// If set, a workspace backup import restores the default change set, which every other
+    /// change set is based on, and the change set with this name, but none of the others. A
+    /// backup without a change set of this name is rejected with
+    /// [`PkgError::ChangeSetNotInBackup`] before anything is restored.
+    pub only_change_set: Option<String>,
+    /// If set to `true`, schema variants created before "/root/resource_value" was added to
+    /// every variant get the missing prop tree, and the resource payload is wired up to it, when
+    /// they are imported again. Otherwise the props under it are skipped with a warning.
+    pub repair_legacy_variants: bool,
+    /// If set, components and edges created by the import are attributed to this user, instead
+    /// of the actor of the context or the creation user recorded in the package, so that a
+    /// restore shows who triggered it.
+    pub as_user: Option<UserPk>,
+    /// If set to `true`, nothing is imported. Instead, the import runs and is rolled back, and
+    /// what it did is returned as an [`ImportPlan`], so that an import into a production
+    /// workspace can be vetted first.
+    pub dry_run: bool,
+    /// If set to `true`, a func that cannot be created (e.g. its data is missing or its backend
+    /// kind does not fit its response type) does not fail the import. It is recorded in the
+    /// `func_errors` of the [`ImportSkips`] instead, and the schemas that refer to it are skipped
+    /// and recorded in the `schema_skips`.
+    pub continue_on_func_error: bool,
+    /// If set, the name of every component created by a workspace backup import is prefixed
+    /// with this. It only relabels the restored components, e.g. to tell them apart from those
+    /// created after the restore: the import still clears the workspace first, so importing the
+    /// same backup again replaces the components instead of adding prefixed copies. Components
+    /// are still matched to their edges by unique id, and components re-imported by a schema
+    /// upgrade keep their names.
+    pub component_name_prefix: Option<String>,
+    /// If set, a workspace backup import only restores the components whose name or unique id
+    /// is in this list. Edges to the other components are skipped and reported as
+    /// [`ImportEdgeSkip::FilteredComponent`].
+    pub components: Option<Vec<String>>,
+    /// If set to `true`, a workspace backup import does not restore any edges.
+    pub skip_edges: bool,
+    /// If set, only the funcs with these unique ids are imported, along with every intrinsic
+    /// func. Anything else in the package that refers to one of the other funcs fails to import
+    /// with [`PkgError::MissingFuncUniqueId`].
+    pub funcs: Option<Vec<String>>,
+}
+
+impl ImportOptions {
+    pub fn builder() -> ImportOptionsBuilder {
+        ImportOptionsBuilder::default()
+    }
+
+    /// Returns true if the component is restored according to [`ImportOptions::components`].
+    fn includes_component(&self, name: &str, unique_id: &str) -> bool {
+        match &self.components {
+            Some(components) => components
+                .iter()
+                .any(|component| component == name || component == unique_id),
+            None => true,
+        }
+    }
+
+    /// Returns true if the func is imported according to [`ImportOptions::funcs`].
+    fn includes_func(&self, func_spec: &SiPkgFunc<'_>) -> bool {
+        match &self.funcs {
+            Some(funcs) => {
+                func::is_intrinsic(func_spec.name())
+                    || funcs
+                        .iter()
+                        .any(|unique_id| unique_id == func_spec.unique_id())
+            }
+            None => true,
+        }
+    }
+
+    fn check_cancelled(&self) -> PkgResult<()> {
+        match &self.cancellation_token {
+            Some(token) if token.is_cancelled() => Err(PkgError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A builder for [`ImportOptions`]. Any option that is not set keeps its default value.
+#[derive(Clone, Debug, Default)]
+pub struct ImportOptionsBuilder {
+    options: ImportOptions,
+}
+
+impl ImportOptionsBuilder {
+    pub fn schemas(&mut self, schemas: Vec<String>) -> &mut Self {
+        self.options.schemas = Some(schemas);
+        self
+    }
+
+    pub fn skip_import_funcs(&mut self, skip_import_funcs: HashMap<String, Func>) -> &mut Self {
+        self.options.skip_import_funcs = Some(skip_import_funcs);
+        self
+    }
+
+    pub fn no_record(&mut self, no_record: bool) -> &mut Self {
+        self.options.no_record = no_record;
+        self
+    }
+
+    pub fn is_builtin(&mut self, is_builtin: bool) -> &mut Self {
+        self.options.is_builtin = is_builtin;
+        self
+    }
+
+    pub fn resume_from(&mut self, checkpoint: ImportCheckpoint) -> &mut Self {
+        self.options.resume_from = Some(checkpoint);
+        self
+    }
+
+    pub fn max_change_sets(&mut self, max_change_sets: usize) -> &mut Self {
+        self.options.max_change_sets = Some(max_change_sets);
+        self
+    }
+
+    pub fn func_name_conflict(&mut self, policy: FuncNameConflictPolicy) -> &mut Self {
+        self.options.func_name_conflict = policy;
+        self
+    }
+
+    pub fn strict_type_checking(&mut self, strict_type_checking: bool) -> &mut Self {
+        self.options.strict_type_checking = strict_type_checking;
+        self
+    }
+
+    pub fn enqueue_create_actions(&mut self, enqueue_create_actions: bool) -> &mut Self {
+        self.options.enqueue_create_actions = enqueue_create_actions;
+        self
+    }
+
+    pub fn run_qualifications_after_import(
+        &mut self,
+        run_qualifications_after_import: bool,
+    ) -> &mut Self {
+        self.options.run_qualifications_after_import = run_qualifications_after_import;
+        self
+    }
+
+    pub fn cancellation_token(&mut self, cancellation_token: CancellationToken) -> &mut Self {
+        self.options.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    pub fn component_name_policy(&mut self, policy: ComponentNamePolicy) -> &mut Self {
+        self.options.component_name_policy = policy;
+        self
+    }
+
+    pub fn secrets_policy(&mut self, policy: SecretsPolicy) -> &mut Self {
+        self.options.secrets_policy = policy;
+        self
+    }
+
+    pub fn socket_name_remap(&mut self, socket_name_remap: HashMap<String, String>) -> &mut Self {
+        self.options.socket_name_remap = socket_name_remap;
+        self
+    }
+
+    pub fn strip_invalid_links(&mut self, strip_invalid_links: bool) -> &mut Self {
+        self.options.strip_invalid_links = strip_invalid_links;
+        self
+    }
+
+    pub fn max_components(&mut self, max_components: usize) -> &mut Self {
+        self.options.max_components = Some(max_components);
+        self
+    }
+
+    pub fn defer_finalize(&mut self, defer_finalize: bool) -> &mut Self {
+        self.options.defer_finalize = defer_finalize;
+        self
+    }
+
+    pub fn defer_dependent_updates(&mut self, defer_dependent_updates: bool) -> &mut Self {
+        self.options.defer_dependent_updates = defer_dependent_updates;
+        self
+    }
+
+    pub fn target_layout(&mut self, target_layout: impl Into<String>) -> &mut Self {
+        self.options.target_layout = Some(target_layout.into());
+        self
+    }
+
+    pub fn db_retry(&mut self, db_retry: DbRetryPolicy) -> &mut Self {
+        self.options.db_retry = Some(db_retry);
+        self
+    }
+
+    pub fn skip_attribute_funcs(&mut self, skip_attribute_funcs: bool) -> &mut Self {
+        self.options.skip_attribute_funcs = skip_attribute_funcs;
+        self
+    }
+
+    pub fn slow_schema_warn_ms(&mut self, slow_schema_warn_ms: u64) -> &mut Self {
+        self.options.slow_schema_warn_ms = Some(slow_schema_warn_ms);
+        self
+    }
+
+    pub fn only_change_set(&mut self, only_change_set: impl Into<String>) -> &mut Self {
+        self.options.only_change_set = Some(only_change_set.into());
+        self
+    }
+
+    pub fn repair_legacy_variants(&mut self, repair_legacy_variants: bool) -> &mut Self {
+        self.options.repair_legacy_variants = repair_legacy_variants;
+        self
+    }
+
+    pub fn as_user(&mut self, user_pk: UserPk) -> &mut Self {
+        self.options.as_user = Some(user_pk);
+        self
+    }
+
+    pub fn dry_run(&mut self, dry_run: bool) -> &mut Self {
+        self.options.dry_run = dry_run;
+        self
+    }
+
+    pub fn continue_on_func_error(&mut self, continue_on_func_error: bool) -> &mut Self {
+        self.options.continue_on_func_error = continue_on_func_error;
+        self
+    }
+
+    pub fn component_name_prefix(&mut self, component_name_prefix: impl Into<String>) -> &mut Self {
+        self.options.component_name_prefix = Some(component_name_prefix.into());
+        self
+    }
+
+    pub fn components(&mut self, components: Vec<String>) -> &mut Self {
+        self.options.components = Some(components);
+        self
+    }
+
+    pub fn skip_edges(&mut self, skip_edges: bool) -> &mut Self {
+        self.options.skip_edges = skip_edges;
+        self
+    }
+
+    pub fn funcs(&mut self, funcs: Vec<String>) -> &mut Self {
+        self.options.funcs = Some(funcs);
+        self
+    }
+
+    pub fn build(&self) -> ImportOptions {
+        self.options.clone()
+    }
+}
+
+/// How the importer resolves a package func whose name is already taken by a non-builtin func
+/// visible in the change set being imported into.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FuncNameConflictPolicy {
+    /// Create the func anyway, leaving two funcs with the same name
+    #[default]
+    Allow,
+    /// Fail the import with [`PkgError::FuncNameConflict`]
+    Error,
+    /// Append a numeric suffix to the name of the imported func until it is unique
+    Suffix,
+}
+
+/// How the importer names a package component whose name is already used by a component in the
+/// change set being imported into. A workspace backup import starts from a cleared workspace, so
+/// there the collisions are between the backup's own components, e.g. a component created in a
+/// change set with the same name as a component on head.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ComponentNamePolicy {
+    /// Create the component with the name from the package, leaving two components with the
+    /// same name
+    #[default]
+    AsIs,
+    /// Fail the import with [`PkgError::ComponentNameConflict`]
+    Error,
+    /// Append a numeric suffix, e.g. `web (2)`, to the name of the imported component until it
+    /// is unique
+    SuffixOnCollision,
+}
+
+/// How the importer handles the secret values of the components in a workspace backup. Secret
+/// values refer to secrets of the workspace the backup was taken from, so they are always dropped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SecretsPolicy {
+    /// Drop the secret values and report each one as an
+    /// [`ImportAttributeSkip::SecretNeedsReinjection`], so the user knows to enter them again
+    #[default]
+    RequireReinjection,
+    /// Drop the secret values without reporting them
+    Skip,
+}
+
+/// How [`ImportOptions::db_retry`] retries import steps that fail with a transient database
+/// error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DbRetryPolicy {
+    /// How many times a step is retried after its first attempt
+    pub max_retries: u32,
+    /// How long to wait before the first retry. The wait doubles with each retry after it.
+    pub initial_backoff: Duration,
+}
+
+impl Default for DbRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(50),
+        }
+    }
+}
+
+/// Whether [`with_savepoint`] keeps what its step did.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SavepointEnd {
+    /// Release the savepoint if the step succeeds, and roll back to it if the step fails
+    ReleaseOnSuccess,
+    /// Roll back to the savepoint whether the step succeeds or not
+    AlwaysRollBack,
+}
+
+/// Runs `step` inside the savepoint `name`, so that a failed step can be rolled back and leave
+/// the transaction usable by the rest of the import. A failed statement aborts the whole
+/// transaction otherwise. The jobs and WsEvents queued by `step` are not part of the PostgreSQL
+/// transaction, so they are dropped as well when the savepoint is rolled back. The outer result
+/// is an error only if the savepoint itself fails.
+async fn with_savepoint<T>(
+    ctx: &DalContext,
+    name: &str,
+    end: SavepointEnd,
+    step: impl Future<Output = PkgResult<T>>,
+) -> PkgResult<PkgResult<T>> {
+    ctx.txns()
+        .await?
+        .pg()
+        .batch_execute(&format!("SAVEPOINT {name}"))
+        .await?;
+    let mark = ctx.txns().await?.pending_mark().await;
+
+    let result = step.await;
+
+    if result.is_ok() && end == SavepointEnd::ReleaseOnSuccess {
+        ctx.txns()
+            .await?
+            .pg()
+            .batch_execute(&format!("RELEASE SAVEPOINT {name}"))
+            .await?;
+    } else {
+        let txns = ctx.txns().await?;
+        txns.pg()
+            .batch_execute(&format!("ROLLBACK TO SAVEPOINT {name}"))
+            .await?;
+        txns.discard_pending_since(mark).await;
+    }
+
+    Ok(result)
+}
+
+/// Runs the attempts of an import step under a [`DbRetryPolicy`]. Each attempt is run with
+/// [`with_savepoint`], since a failed statement aborts the whole transaction otherwise.
+struct DbRetry {
+    policy: Option<DbRetryPolicy>,
+    retries: u32,
+}
+
+impl DbRetry {
+    const SAVEPOINT: &'static str = "pkg_import_retry";
+
+    fn new(policy: Option<DbRetryPolicy>) -> Self {
+        Self { policy, retries: 0 }
+    }
+
+    /// Makes one attempt at the step. Without a policy, the step runs without a savepoint.
+    async fn attempt<T>(
+        &self,
+        ctx: &DalContext,
+        step: impl Future<Output = PkgResult<T>>,
+    ) -> PkgResult<PkgResult<T>> {
+        if self.policy.is_some() {
+            with_savepoint(ctx, Self::SAVEPOINT, SavepointEnd::ReleaseOnSuccess, step).await
+        } else {
+            Ok(step.await)
+        }
+    }
+
+    /// Returns `true` if the attempt failed with a transient database error and should be made
+    /// again, after waiting to retry.
+    async fn should_retry<T>(&mut self, result: &PkgResult<T>) -> bool {
+        let policy = match self.policy {
+            Some(policy) => policy,
+            None => return false,
+        };
+
+        match result {
+            Ok(_) => false,
+            Err(err) if err.is_transient_db_error() && self.retries < policy.max_retries => {
+                let backoff = policy
+                    .initial_backoff
+                    .saturating_mul(2u32.saturating_pow(self.retries));
+                warn!(
+                    error = %err,
+                    retry = self.retries + 1,
+                    ?backoff,
+                    "retrying import step after transient database error"
+                );
+                tokio::time::sleep(backoff).await;
+                self.retries += 1;
+
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+/// Counts of what an import installed, published with [`WsEvent::package_imported()`] when the
+/// import is committed. Funcs and schemas that were already installed are not counted, while
+/// components and edges are counted for every change set they were imported into.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportStats {
+    pub funcs: usize,
+    pub schemas: usize,
+    pub schema_variants: usize,
+    pub components: usize,
+    pub edges: usize,
+}
+
+/// The phases of an import, reported with each [`WsEvent::pkg_import_progress()`].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportPhase {
+    Funcs,
+    Schemas,
+    Components,
+    Edges,
+}
+
+/// Counts the funcs, schemas, components and edges of a change set as they are imported, and
+/// publishes a [`WsEvent::pkg_import_progress()`] for each of them.
+#[derive(Debug)]
+struct ImportProgress<'a> {
+    module_name: &'a str,
+    current: usize,
+    total: usize,
+}
+
+impl<'a> ImportProgress<'a> {
+    fn new(module_name: &'a str, total: usize) -> Self {
+        Self {
+            module_name,
+            current: 0,
+            total,
+        }
+    }
+
+    /// Counts things that are not imported, e.g. because they were filtered out or imported
+    /// before an interrupted import was resumed, without publishing anything for them.
+    fn skip(&mut self, count: usize) {
+        self.current += count;
+    }
+
+    async fn advance(&mut self, ctx: &DalContext, name: &str, phase: ImportPhase) -> PkgResult<()> {
+        self.current += 1;
+
+        // Imports run outside of a workspace when builtins are installed, and then nobody is
+        // listening for the event
+        if ctx.tenancy().workspace_pk().is_some() {
+            WsEvent::pkg_import_progress(
+                ctx,
+                self.module_name,
+                name,
+                phase,
+                self.current,
+                self.total,
+            )
+            .await?
+            .publish_on_commit(ctx)
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A record of the progress made by a workspace backup import. It is returned in
+/// [`ImportResult::interrupted`] and can be passed back in via [`ImportOptions::resume_from`]
+/// to continue the import with the next change set. The checkpoint only describes work done in
+/// the transaction of the interrupted import, so that transaction must be committed before
+/// resuming.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCheckpoint {
+    pub root_hash: String,
+    pub installed_pkg_id: Option<InstalledPkgId>,
+    /// The change sets that have been imported in full, in the order they were imported
+    pub completed_change_sets: Vec<ImportCheckpointChangeSet>,
+    /// Every component created so far, so that later change sets (and edges) can find them
+    pub components: Vec<ImportCheckpointComponent>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCheckpointChangeSet {
+    pub name: String,
+    pub change_set_pk: ChangeSetPk,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportCheckpointComponent {
+    pub change_set_pk: ChangeSetPk,
+    pub unique_id: String,
+    pub component_id: ComponentId,
+}
+
+impl ImportCheckpoint {
+    fn new(root_hash: impl Into<String>, installed_pkg_id: Option<InstalledPkgId>) -> Self {
+        Self {
+            root_hash: root_hash.into(),
+            installed_pkg_id,
+            ..Default::default()
+        }
+    }
+
+    fn completed_change_set(&self, name: &str) -> Option<&ImportCheckpointChangeSet> {
+        self.completed_change_sets.iter().find(|cs| cs.name == name)
+    }
+
+    fn complete_change_set(&mut self, name: impl Into<String>, change_set_pk: ChangeSetPk) {
+        self.completed_change_sets.push(ImportCheckpointChangeSet {
+            name: name.into(),
+            change_set_pk,
+        });
+    }
+
+    /// Puts every component recorded by this checkpoint into the thing map, so that the
+    /// importer treats them as existing components instead of creating them again
+    async fn restore_components(
+        &self,
+        ctx: &DalContext,
+        thing_map: &mut ThingMap,
+    ) -> PkgResult<()> {
+        for recorded in &self.components {
+            let ctx = if recorded.change_set_pk == ChangeSetPk::NONE {
+                ctx.clone_with_new_visibility(ctx.visibility().to_head())
+            } else {
+                ctx.clone_with_new_visibility(
+                    ctx.visibility().to_change_set(recorded.change_set_pk),
+                )
+            };
+
+            let component = Component::get_by_id(&ctx, &recorded.component_id)
+                .await?
+                .ok_or(ComponentError::NotFound(recorded.component_id))?;
+            let node = component
+                .node(&ctx)
+                .await?
+                .pop()
+                .ok_or(PkgError::ComponentMissingNode(recorded.component_id))?;
+
+            thing_map.insert(
+                recorded.change_set_pk,
+                recorded.unique_id.to_owned(),
+                Thing::Component((component, node)),
+            );
+        }
+
+        Ok(())
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all, fields(change_set.pk = %change_set_pk))]
 async fn import_change_set(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
@@ -95,44 +750,66 @@ async fn import_change_set(
     thing_map: &mut ThingMap,
     options: &ImportOptions,
     override_builtin_schema_feature_flag: bool,
+    checkpoint: &mut ImportCheckpoint,
+    stats: &mut ImportStats,
+    func_errors: &mut Vec<(String, String)>,
+    schema_skips: &mut Vec<(String, String)>,
+    qualification_errors: &mut Vec<(String, String)>,
+    mut plan: Option<&mut ImportPlan>,
 ) -> PkgResult<(
     Vec<SchemaVariantId>,
     Vec<(String, Vec<ImportAttributeSkip>)>,
     Vec<ImportEdgeSkip>,
+    Vec<ImportAttrFuncSkip>,
+    ImportedDefaultValues,
 )> {
+    let mut progress = ImportProgress::new(
+        metadata.name(),
+        funcs.len() + schemas.len() + components.len() + edges.len(),
+    );
+
+    let mut existing_funcs = ExistingFuncCache::load(ctx, funcs).await?;
     for func_spec in funcs {
-        // This is a hack because the hash of the intrinsics has changed from the version in the
-        // packages. We also apply this to si:resourcePayloadToValue since it should be an
-        // intrinsic but is only in our packages
-        let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
-        if func::is_intrinsic(func_spec.name())
-            || special_case_funcs.contains(&func_spec.name())
-            || func_spec.is_from_builtin().unwrap_or(false)
-        {
+        options.check_cancelled()?;
+
+        if !options.includes_func(func_spec) {
+            // Shadows any func with the same unique id imported earlier, so that the things
+            // referring to it fail to import instead of silently using an old version
+            thing_map.insert(
+                change_set_pk,
+                func_spec.unique_id().to_owned(),
+                Thing::SkippedFunc,
+            );
+            progress.skip(1);
+            continue;
+        }
+
+        // A func imported earlier is updated in place by `import_func`, any other is created
+        let updates_existing = matches!(
+            thing_map.get(change_set_pk, &func_spec.unique_id().to_owned()),
+            Some(Thing::Func(_))
+        );
+
+        if updates_func_by_name(func_spec) {
             let hash = func_spec.hash();
             let func_spec: SiPkgFunc<'_> = func_spec.clone();
             let func_spec: FuncSpec = func_spec.try_into()?;
             if let (Some(mut func), Some(data)) = (
-                Func::find_by_name(ctx, &func_spec.name).await?,
+                existing_funcs.func_by_name(&func_spec.name),
                 &func_spec.data,
             ) {
-                func.set_description(ctx, data.description.clone()).await?;
-                func.set_display_name(ctx, data.display_name.clone())
-                    .await?;
-                func.set_handler(ctx, Some(data.handler.clone())).await?;
-                func.set_link(ctx, data.link.clone()).await?;
-                func.set_hidden(ctx, data.hidden).await?;
-                func.set_backend_kind(ctx, data.backend_kind).await?;
-                func.set_backend_response_type(ctx, data.response_type)
-                    .await?;
-                func.set_code_base64(ctx, Some(data.code_base64.clone()))
-                    .await?;
+                update_func(ctx, &mut func, data, options.strip_invalid_links).await?;
+                existing_funcs.add_named(&func);
 
                 thing_map.insert(
                     change_set_pk,
                     func_spec.unique_id.to_owned(),
                     Thing::Func(func.to_owned()),
                 );
+                stats.funcs += 1;
+                if let Some(plan) = plan.as_deref_mut() {
+                    plan.record_func(&func_spec.name, true);
+                }
             } else if let Some(func) = import_func(
                 ctx,
                 change_set_pk,
@@ -141,14 +818,23 @@ async fn import_change_set(
                 installed_pkg_id,
                 thing_map,
                 options.is_builtin,
+                options.func_name_conflict,
+                options.strip_invalid_links,
+                options.continue_on_func_error.then_some(&mut *func_errors),
+                &mut existing_funcs,
             )
             .await?
             {
+                existing_funcs.add_named(&func);
                 let args = func_spec.arguments;
 
                 if !args.is_empty() {
                     import_func_arguments(ctx, change_set_pk, *func.id(), &args, thing_map).await?;
                 }
+                stats.funcs += 1;
+                if let Some(plan) = plan.as_deref_mut() {
+                    plan.record_func(&func_spec.name, updates_existing);
+                }
             }
         } else {
             let hash = func_spec.hash();
@@ -170,6 +856,7 @@ async fn import_change_set(
                         ),
                     )
                     .await?;
+                    existing_funcs.add_installed(hash.to_string(), func);
                 }
 
                 // We're not going to import this func but we need it in the map for lookups later
@@ -189,6 +876,10 @@ async fn import_change_set(
                     installed_pkg_id,
                     thing_map,
                     options.is_builtin,
+                    options.func_name_conflict,
+                    options.strip_invalid_links,
+                    options.continue_on_func_error.then_some(&mut *func_errors),
+                    &mut existing_funcs,
                 )
                 .await?
             };
@@ -199,11 +890,20 @@ async fn import_change_set(
                 if !args.is_empty() {
                     import_func_arguments(ctx, change_set_pk, *func.id(), &args, thing_map).await?;
                 }
+                stats.funcs += 1;
+                if let Some(plan) = plan.as_deref_mut() {
+                    plan.record_func(&func_spec.name, updates_existing);
+                }
             }
         };
+
+        progress
+            .advance(ctx, func_spec.name(), ImportPhase::Funcs)
+            .await?;
     }
 
     let mut installed_schema_variant_ids = vec![];
+    let mut default_values = vec![];
 
     let mut schemas_to_upgrade = Vec::new();
     let mut schemas_to_create = Vec::new();
@@ -214,192 +914,238 @@ async fn import_change_set(
     let update_even_if_not_builtin = false;
 
     'spec: for schema_spec in schemas {
+        options.check_cancelled()?;
+
         match &options.schemas {
             None => {}
             Some(schemas) => {
                 if !schemas.contains(&schema_spec.name().to_string().to_lowercase()) {
+                    progress.skip(1);
                     continue;
                 }
             }
         }
 
+        // Importing a schema that refers to a func that could not be imported would fail
+        if !func_errors.is_empty() {
+            let func_dependencies = func_dependencies_of_schema(&schema_spec.to_spec().await?);
+            if let Some((func_unique_id, _)) = func_errors
+                .iter()
+                .find(|(unique_id, _)| func_dependencies.contains(unique_id))
+            {
+                warn!(
+                    schema.name = schema_spec.name(),
+                    func.unique_id = %func_unique_id,
+                    "skipping schema that refers to a func that could not be imported"
+                );
+                schema_skips.push((
+                    schema_spec.name().to_owned(),
+                    format!("refers to func {func_unique_id}, which could not be imported"),
+                ));
+                progress.skip(1);
+                continue;
+            }
+        }
+
         if (update_even_if_not_builtin || options.is_builtin)
             && override_builtin_schema_feature_flag
         {
-            for schema in Schema::find_by_attr(ctx, "name", &schema_spec.name()).await? {
-                for variant_spec in &schema_spec.variants()? {
-                    for variant in schema.variants(ctx).await? {
-                        if variant.name() != variant_spec.name() {
-                            continue;
-                        }
-
-                        let should_update = if let Some(pkg_created_at) = variant.pkg_created_at() {
-                            metadata
-                                .created_at()
-                                .signed_duration_since(pkg_created_at)
-                                .num_seconds()
-                                > 0
-                        } else {
-                            true
-                        };
-
-                        if should_update
-                            && (update_even_if_not_builtin || variant.is_builtin(ctx).await?)
-                        {
-                            schemas_to_upgrade.push((schema_spec, schema, variant));
-                            continue 'spec;
-                        }
-                    }
-                }
+            if let Some((schema, variant)) =
+                find_schema_to_upgrade(ctx, schema_spec, metadata, update_even_if_not_builtin)
+                    .await?
+            {
+                schemas_to_upgrade.push((schema_spec, schema, variant));
+                continue 'spec;
             }
         }
 
+        // Without the flag this would install a second schema alongside the builtin it was
+        // meant to replace
+        if options.is_builtin
+            && !override_builtin_schema_feature_flag
+            && find_schema_to_upgrade(ctx, schema_spec, metadata, false)
+                .await?
+                .is_some()
+        {
+            return Err(PkgError::UpgradeRequiresFlag(schema_spec.name().to_owned()));
+        }
+
         schemas_to_create.push(schema_spec);
     }
 
+    const UPGRADE_SAVEPOINT: &str = "pkg_import_upgrade";
     let has_upgrade = !schemas_to_upgrade.is_empty();
     let mut exported_components = Vec::new();
-    let mut exporter = PkgExporter::new_workspace_exporter(
-        "temporary",
-        "SystemInit".to_owned(),
-        "1.0",
-        "Temporary pkg created to update schemas",
-    );
+    let mut exporter = PkgExporter::new_temporary_exporter();
     for (schema_spec, mut schema, mut schema_variant) in schemas_to_upgrade {
+        options.check_cancelled()?;
+
         info!(
-            "upgrading schema '{}:{}' from {}",
-            schema_spec.name(),
-            schema_variant.name(),
-            metadata.name(),
+            schema.name = schema_spec.name(),
+            schema_variant.name = schema_variant.name(),
+            "upgrading schema"
         );
-        for mut component in Component::list_for_schema(ctx, *schema.id()).await? {
-            let component_spec = ComponentSpecVariant::UpdateVariant {
-                schema_name: schema.name().to_owned(),
-                variant_name: schema_variant.name().to_owned(),
-            };
-            let (comp_spec, func_specs, head_func_specs) =
-                if let Some((comp_spec, func_specs, head_func_specs)) = exporter
-                    .export_component(ctx, Some(change_set_pk), &component, component_spec)
-                    .await?
-                {
-                    (comp_spec, func_specs, head_func_specs)
-                } else {
-                    continue;
-                };
-
-            let mut edges = Vec::new();
-            for mut edge in Edge::list_for_component(ctx, *component.id()).await? {
-                let from_component = if edge.head_component_id() == *component.id() {
-                    component.clone()
-                } else {
-                    Component::find_for_node(ctx, edge.head_node_id())
-                        .await
-                        .map_err(|err| EdgeError::Component(err.to_string()))?
-                        .ok_or(NodeError::ComponentIsNone)?
-                };
-                let from_component_spec = ComponentSpecVariant::UpdateVariant {
-                    schema_name: from_component
-                        .schema(ctx)
-                        .await?
-                        .ok_or_else(|| ComponentError::NoSchema(*from_component.id()))?
-                        .name()
-                        .to_owned(),
-                    variant_name: from_component
-                        .schema_variant(ctx)
-                        .await?
-                        .ok_or(ComponentError::NoSchemaVariant(*from_component.id()))?
-                        .name()
-                        .to_owned(),
+        if let Some(plan) = plan.as_deref_mut() {
+            let preview = preview_upgrade(ctx, schema_spec, &schema, &schema_variant).await?;
+            plan.components_to_delete
+                .extend(preview.components.iter().copied());
+            plan.schemas_to_upgrade.push(preview);
+        }
+        // The schema and its components are deleted before the new variant is imported, so if
+        // any of it fails, it is all rolled back to leave the schema and its components intact
+        let upgraded = async {
+            for mut component in Component::list_for_schema(ctx, *schema.id()).await? {
+                let component_spec = ComponentSpecVariant::UpdateVariant {
+                    schema_name: schema.name().to_owned(),
+                    variant_name: schema_variant.name().to_owned(),
                 };
-                let from_component_spec = if let Some((comp_spec, _func_specs, _head_func_specs)) =
-                    exporter
-                        .export_component(
-                            ctx,
-                            Some(change_set_pk),
-                            &from_component,
-                            from_component_spec,
-                        )
+                let (comp_spec, func_specs, head_func_specs) =
+                    if let Some((comp_spec, func_specs, head_func_specs)) = exporter
+                        .export_component(ctx, Some(change_set_pk), &component, component_spec)
                         .await?
-                {
-                    comp_spec
-                } else {
-                    continue;
-                };
+                    {
+                        (comp_spec, func_specs, head_func_specs)
+                    } else {
+                        continue;
+                    };
 
-                let to_component = if edge.tail_component_id() == *component.id() {
-                    component.clone()
-                } else {
-                    Component::find_for_node(ctx, edge.tail_node_id())
-                        .await
-                        .map_err(|err| EdgeError::Component(err.to_string()))?
-                        .ok_or(NodeError::ComponentIsNone)?
-                };
-                let to_component_spec = ComponentSpecVariant::UpdateVariant {
-                    schema_name: to_component
-                        .schema(ctx)
-                        .await?
-                        .ok_or_else(|| ComponentError::NoSchema(*to_component.id()))?
-                        .name()
-                        .to_owned(),
-                    variant_name: to_component
-                        .schema_variant(ctx)
-                        .await?
-                        .ok_or(ComponentError::NoSchemaVariant(*to_component.id()))?
-                        .name()
-                        .to_owned(),
-                };
-                let to_component_spec = if let Some((comp_spec, _func_specs, _head_func_specs)) =
-                    exporter
-                        .export_component(
-                            ctx,
-                            Some(change_set_pk),
-                            &to_component,
-                            to_component_spec,
-                        )
-                        .await?
-                {
-                    comp_spec
-                } else {
-                    continue;
-                };
+                let mut edges = Vec::new();
+                for mut edge in Edge::list_for_component(ctx, *component.id()).await? {
+                    let from_component = if edge.head_component_id() == *component.id() {
+                        component.clone()
+                    } else {
+                        Component::find_for_node(ctx, edge.head_node_id())
+                            .await
+                            .map_err(|err| EdgeError::Component(err.to_string()))?
+                            .ok_or(NodeError::ComponentIsNone)?
+                    };
+                    let from_component_spec = ComponentSpecVariant::UpdateVariant {
+                        schema_name: from_component
+                            .schema(ctx)
+                            .await?
+                            .ok_or_else(|| ComponentError::NoSchema(*from_component.id()))?
+                            .name()
+                            .to_owned(),
+                        variant_name: from_component
+                            .schema_variant(ctx)
+                            .await?
+                            .ok_or(ComponentError::NoSchemaVariant(*from_component.id()))?
+                            .name()
+                            .to_owned(),
+                    };
+                    let from_component_spec =
+                        if let Some((comp_spec, _func_specs, _head_func_specs)) = exporter
+                            .export_component(
+                                ctx,
+                                Some(change_set_pk),
+                                &from_component,
+                                from_component_spec,
+                            )
+                            .await?
+                        {
+                            comp_spec
+                        } else {
+                            continue;
+                        };
 
-                let edge_spec = exporter
-                    .export_edge(ctx, &edge, &to_component_spec, &from_component_spec)
-                    .await?;
-                edges.push(edge_spec);
+                    let to_component = if edge.tail_component_id() == *component.id() {
+                        component.clone()
+                    } else {
+                        Component::find_for_node(ctx, edge.tail_node_id())
+                            .await
+                            .map_err(|err| EdgeError::Component(err.to_string()))?
+                            .ok_or(NodeError::ComponentIsNone)?
+                    };
+                    let to_component_spec = ComponentSpecVariant::UpdateVariant {
+                        schema_name: to_component
+                            .schema(ctx)
+                            .await?
+                            .ok_or_else(|| ComponentError::NoSchema(*to_component.id()))?
+                            .name()
+                            .to_owned(),
+                        variant_name: to_component
+                            .schema_variant(ctx)
+                            .await?
+                            .ok_or(ComponentError::NoSchemaVariant(*to_component.id()))?
+                            .name()
+                            .to_owned(),
+                    };
+                    let to_component_spec =
+                        if let Some((comp_spec, _func_specs, _head_func_specs)) = exporter
+                            .export_component(
+                                ctx,
+                                Some(change_set_pk),
+                                &to_component,
+                                to_component_spec,
+                            )
+                            .await?
+                        {
+                            comp_spec
+                        } else {
+                            continue;
+                        };
 
-                edge.delete_by_id(ctx).await?;
-            }
+                    let edge_spec = exporter
+                        .export_edge(ctx, &edge, &to_component_spec, &from_component_spec)
+                        .await?;
+                    edges.push(edge_spec);
 
-            let ctx_with_deleted = &ctx.clone_with_delete_visibility();
-            component.set_hidden(ctx, true).await?;
-            component.delete_by_id(ctx).await?;
-            component.set_needs_destroy(ctx_with_deleted, false).await?;
-            component
-                .set_resource_raw(ctx_with_deleted, ActionRunResult::default(), false)
-                .await?;
-            exported_components.push((comp_spec, func_specs, head_func_specs, edges));
-        }
+                    edge.delete_by_id(ctx).await?;
+                }
 
-        if let Some(mut definition) =
-            SchemaVariantDefinition::get_by_schema_variant_id(ctx, schema_variant.id()).await?
-        {
-            definition.delete_by_id(ctx).await?;
-        }
-        schema_variant.delete_by_id(ctx).await?;
-        schema.delete_by_id(ctx).await?;
+                let ctx_with_deleted = &ctx.clone_with_delete_visibility();
+                component.set_hidden(ctx, true).await?;
+                component.delete_by_id(ctx).await?;
+                component.set_needs_destroy(ctx_with_deleted, false).await?;
+                component
+                    .set_resource_raw(ctx_with_deleted, ActionRunResult::default(), false)
+                    .await?;
+                exported_components.push((comp_spec, func_specs, head_func_specs, edges));
+            }
+
+            if let Some(mut definition) =
+                SchemaVariantDefinition::get_by_schema_variant_id(ctx, schema_variant.id()).await?
+            {
+                definition.delete_by_id(ctx).await?;
+            }
+            schema_variant.delete_by_id(ctx).await?;
+            schema.delete_by_id(ctx).await?;
 
-        let (_, schema_variant_ids) = import_schema(
+            let started = Instant::now();
+            let imported = import_schema(
+                ctx,
+                change_set_pk,
+                schema_spec,
+                installed_pkg_id,
+                thing_map,
+                metadata,
+                options,
+                // Components are restored onto the upgraded variant right away, so it has to be
+                // finalized now
+                None,
+            )
+            .await?;
+            Ok::<_, PkgError>((imported, started.elapsed()))
+        };
+        let ((schema_id, schema_variant_ids, schema_default_values), elapsed) = with_savepoint(
             ctx,
-            change_set_pk,
-            schema_spec,
-            installed_pkg_id,
-            thing_map,
-            metadata,
+            UPGRADE_SAVEPOINT,
+            SavepointEnd::ReleaseOnSuccess,
+            upgraded,
         )
-        .await?;
+        .await??;
+        warn_if_slow_schema(schema_spec, elapsed, options).await?;
 
+        if schema_id.is_some() {
+            stats.schemas += 1;
+        }
+        stats.schema_variants += schema_variant_ids.len();
         installed_schema_variant_ids.extend(schema_variant_ids);
+        default_values.extend(schema_default_values);
+
+        progress
+            .advance(ctx, schema_spec.name(), ImportPhase::Schemas)
+            .await?;
     }
 
     if has_upgrade {
@@ -408,14 +1154,55 @@ async fn import_change_set(
         }
     }
 
+    // Components are restored onto the upgraded variants as they were, under the same names
+    let restore_options = ImportOptions {
+        component_name_policy: ComponentNamePolicy::AsIs,
+        defer_dependent_updates: false,
+        target_layout: None,
+        component_name_prefix: None,
+        ..options.clone()
+    };
+    let mut socket_cache = EdgeSocketCache::default();
     for (comp_spec, _func_specs, _head_func_specs, edges) in exported_components {
-        let _skips = import_component(ctx, change_set_pk, comp_spec, thing_map, true).await?;
+        let _skips = import_component(
+            ctx,
+            change_set_pk,
+            comp_spec,
+            thing_map,
+            &restore_options,
+            true,
+            &mut ComponentImportState::default(),
+        )
+        .await?;
         for edge in edges {
-            let _skips = import_edge(ctx, change_set_pk, &edge, thing_map).await?;
+            let _skips = import_edge(
+                ctx,
+                change_set_pk,
+                &edge,
+                thing_map,
+                &mut socket_cache,
+                &options.socket_name_remap,
+                options.as_user,
+            )
+            .await?;
         }
     }
 
+    // Schemas that are already installed are reused, so the plan only lists the ones that are
+    // not among these
+    let existing_schemas: HashSet<SchemaId> = if plan.is_some() {
+        Schema::list(ctx)
+            .await?
+            .iter()
+            .map(|schema| *schema.id())
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    let mut deferred_finalizes = vec![];
     for schema_spec in schemas_to_create {
+        options.check_cancelled()?;
+
         match &options.schemas {
             None => {}
             Some(schemas) => {
@@ -425,61 +1212,295 @@ async fn import_change_set(
             }
         }
 
-        info!(
-            "installing schema '{}' from {}",
-            schema_spec.name(),
-            metadata.name(),
-        );
+        info!(schema.name = schema_spec.name(), "installing schema");
 
-        let (_, schema_variant_ids) = import_schema(
+        let started = Instant::now();
+        let (schema_id, schema_variant_ids, schema_default_values) = import_schema(
             ctx,
             change_set_pk,
             schema_spec,
             installed_pkg_id,
             thing_map,
             metadata,
+            options,
+            // Skipped attribute funcs are recorded by the deferred finalizes below
+            (options.defer_finalize || options.skip_attribute_funcs)
+                .then_some(&mut deferred_finalizes),
         )
         .await?;
+        warn_if_slow_schema(schema_spec, started.elapsed(), options).await?;
 
+        if let Some(schema_id) = schema_id {
+            stats.schemas += 1;
+            if let Some(plan) = plan.as_deref_mut() {
+                if !existing_schemas.contains(&schema_id) {
+                    plan.schemas_to_create.push(schema_spec.name().to_owned());
+                }
+            }
+        }
+        stats.schema_variants += schema_variant_ids.len();
         installed_schema_variant_ids.extend(schema_variant_ids);
+        default_values.extend(schema_default_values);
+
+        progress
+            .advance(ctx, schema_spec.name(), ImportPhase::Schemas)
+            .await?;
+    }
+
+    let mut attr_func_skips = vec![];
+    for pending in deferred_finalizes {
+        options.check_cancelled()?;
+
+        default_values.extend(
+            finalize_imported_schema_variant(
+                ctx,
+                change_set_pk,
+                pending,
+                thing_map,
+                options.skip_attribute_funcs.then_some(&mut attr_func_skips),
+            )
+            .await?,
+        );
     }
 
-    println!("Finished Imports: {}", Utc::now());
+    debug!(
+        funcs = stats.funcs,
+        schemas = stats.schemas,
+        "finished importing funcs and schemas"
+    );
 
     let mut component_attribute_skips = vec![];
+    let mut components_to_create = vec![];
+    let mut components_to_qualify = vec![];
+    // Listing the names for every imported component would be quadratic, so list them once per
+    // change set and keep them up to date as components are created
+    let mut component_state = ComponentImportState {
+        names: if options.component_name_policy == ComponentNamePolicy::AsIs {
+            HashSet::new()
+        } else {
+            existing_component_names(ctx).await?
+        },
+        ..Default::default()
+    };
+    let filtered_components: HashSet<&str> = components
+        .iter()
+        .filter(|component_spec| {
+            !options.includes_component(component_spec.name(), component_spec.unique_id())
+        })
+        .map(|component_spec| component_spec.unique_id())
+        .collect();
     for component_spec in components {
+        options.check_cancelled()?;
+
+        if filtered_components.contains(component_spec.unique_id()) {
+            progress.skip(1);
+            continue;
+        }
+
         let component_spec: SiPkgComponent<'_> = component_spec.clone();
         let name = component_spec.name().to_owned();
-        let skips = import_component(
+        let mut component_spec: ComponentSpec = component_spec.try_into()?;
+        let secret_skips = strip_secret_values(&mut component_spec, options.secrets_policy);
+        let unique_id = component_spec.unique_id.to_owned();
+        let needs_qualify = !component_spec.deleted;
+        let is_new = !component_spec.deleted
+            && !matches!(
+                thing_map.get(change_set_pk, &unique_id),
+                Some(Thing::Component(_))
+            );
+        let needs_create = is_new && !component_spec_has_resource(&component_spec);
+        let mut skips = import_component(
             ctx,
             change_set_pk,
-            component_spec.try_into()?,
+            component_spec,
             thing_map,
+            options,
             false,
+            &mut component_state,
         )
         .await?;
+        skips.extend(secret_skips);
+        progress
+            .advance(ctx, &name, ImportPhase::Components)
+            .await?;
         if !skips.is_empty() {
-            component_attribute_skips.push((name, skips));
+            component_attribute_skips.push((name.clone(), skips));
+        }
+
+        if let Some(Thing::Component((component, _))) = thing_map.get(change_set_pk, &unique_id) {
+            stats.components += 1;
+            if let (Some(plan), true) = (plan.as_deref_mut(), is_new) {
+                plan.components_to_create.push(component.name(ctx).await?);
+            }
+            if needs_create {
+                components_to_create.push(*component.id());
+            }
+            if needs_qualify {
+                components_to_qualify.push((name, *component.id()));
+            }
+            if !checkpoint.components.iter().any(|recorded| {
+                recorded.change_set_pk == change_set_pk && recorded.unique_id == unique_id
+            }) {
+                checkpoint.components.push(ImportCheckpointComponent {
+                    change_set_pk,
+                    unique_id,
+                    component_id: *component.id(),
+                });
+            }
         }
     }
 
+    // Dense graphs connect many edges to the same sockets, so list the sockets and existing
+    // edges of every component the imported edges touch up front instead of per edge
+    let mut socket_cache = EdgeSocketCache::default();
+    let edges_to_import: &[SiPkgEdge<'_>] = if options.skip_edges { &[] } else { edges };
+    socket_cache
+        .preload(ctx, change_set_pk, edges_to_import, thing_map)
+        .await?;
+    debug!(
+        edges = edges_to_import.len(),
+        components = socket_cache.len(),
+        "resolved edge sockets and existing edges"
+    );
+
     let mut edge_skips = vec![];
-    for edge_spec in edges {
+    for edge_spec in edges_to_import {
+        options.check_cancelled()?;
+
         let edge_spec: SiPkgEdge<'_> = edge_spec.clone();
-        if let Some(skip) =
-            import_edge(ctx, change_set_pk, &edge_spec.try_into()?, thing_map).await?
+        let edge_spec: EdgeSpec = edge_spec.try_into()?;
+
+        if let Some(unique_id) = [
+            &edge_spec.from_component_unique_id,
+            &edge_spec.to_component_unique_id,
+        ]
+        .into_iter()
+        .find(|unique_id| filtered_components.contains(unique_id.as_str()))
         {
-            edge_skips.push(skip);
+            edge_skips.push(ImportEdgeSkip::FilteredComponent {
+                unique_id: unique_id.to_owned(),
+            });
+            progress.skip(1);
+            continue;
+        }
+
+        // Importing an edge only records it in the thing map once it succeeds, and an edge that
+        // already exists is reused, so it is safe to repeat
+        let mut retry = DbRetry::new(options.db_retry);
+        let skip = loop {
+            let result = retry
+                .attempt(
+                    ctx,
+                    import_edge(
+                        ctx,
+                        change_set_pk,
+                        &edge_spec,
+                        thing_map,
+                        &mut socket_cache,
+                        &options.socket_name_remap,
+                        options.as_user,
+                    ),
+                )
+                .await?;
+            if !retry.should_retry(&result).await {
+                break result?;
+            }
+        };
+        match skip {
+            Some(skip) => edge_skips.push(skip),
+            None => {
+                stats.edges += 1;
+                if let (Some(plan), false) = (plan.as_deref_mut(), edge_spec.deleted) {
+                    plan.edges_to_create.push(edge_spec.unique_id.to_owned());
+                }
+            }
         }
+        progress
+            .advance(ctx, &edge_spec.unique_id, ImportPhase::Edges)
+            .await?;
+    }
+
+    if !component_state.deferred_dependent_values.is_empty() {
+        ctx.enqueue_job(DependentValuesUpdate::new(
+            ctx.access_builder(),
+            *ctx.visibility(),
+            component_state.deferred_dependent_values,
+        ))
+        .await?;
+    }
+
+    if options.enqueue_create_actions {
+        enqueue_create_actions(ctx, &components_to_create).await?;
+    }
+
+    if options.run_qualifications_after_import {
+        qualification_errors.extend(run_qualifications(ctx, &components_to_qualify).await);
     }
 
     Ok((
         installed_schema_variant_ids,
         component_attribute_skips,
         edge_skips,
+        attr_func_skips,
+        default_values,
     ))
 }
 
+/// Returns `true` if the component in the package carries a resource payload, i.e. it already
+/// exists in the real world.
+fn component_spec_has_resource(component_spec: &ComponentSpec) -> bool {
+    let resource_path = PropPath::new(["root", "resource"]).to_string();
+    component_spec
+        .attributes
+        .iter()
+        .filter(|attribute| attribute.path.path() == resource_path)
+        .filter_map(|attribute| attribute.implicit_value.clone())
+        .filter_map(|value| serde_json::from_value::<ActionRunResult>(value).ok())
+        .any(|result| result.payload.is_some())
+}
+
+/// Enqueues the `Create` action of every given component.
+async fn enqueue_create_actions(ctx: &DalContext, component_ids: &[ComponentId]) -> PkgResult<()> {
+    if ctx.visibility().is_head() {
+        if !component_ids.is_empty() {
+            warn!("cannot enqueue create actions for components imported into head, skipping");
+        }
+        return Ok(());
+    }
+
+    for component_id in component_ids {
+        for prototype in
+            ActionPrototype::find_for_component_and_kind(ctx, *component_id, ActionKind::Create)
+                .await?
+        {
+            Action::new(ctx, *prototype.id(), *component_id).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the qualifications of every given component, returning the name of each component whose
+/// qualifications could not be run along with the error.
+async fn run_qualifications(
+    ctx: &DalContext,
+    components: &[(String, ComponentId)],
+) -> Vec<(String, String)> {
+    let mut errors = vec![];
+    for (name, component_id) in components {
+        if let Err(err) = Component::run_qualifications(ctx, *component_id).await {
+            warn!(
+                component.name = name,
+                error = %err,
+                "could not run qualifications for imported component"
+            );
+            errors.push((name.to_owned(), err.to_string()));
+        }
+    }
+
+    errors
+}
+
 #[derive(Eq, PartialEq, Hash, Debug, Clone)]
 struct ValueCacheKey {
     context: AttributeContext,
@@ -498,86 +1519,273 @@ impl ValueCacheKey {
     }
 }
 
+/// The sockets and edges of the components imported edges connect, listed once per component so
+/// that edges sharing sockets don't look them up again, and so that an edge that already exists
+/// is found without listing the edges of its head component for every imported edge.
+#[derive(Debug, Default)]
+struct EdgeSocketCache {
+    sockets: HashMap<ComponentId, Vec<Socket>>,
+    edges: HashMap<ComponentId, Vec<Edge>>,
+}
+
+impl EdgeSocketCache {
+    /// Lists the sockets of every component connected by `edges` that has already been imported,
+    /// along with the existing edges of the components the edges point to.
+    async fn preload(
+        &mut self,
+        ctx: &DalContext,
+        change_set_pk: ChangeSetPk,
+        edges: &[SiPkgEdge<'_>],
+        thing_map: &ThingMap,
+    ) -> PkgResult<()> {
+        for edge in edges {
+            for unique_id in [
+                edge.to_component_unique_id(),
+                edge.from_component_unique_id(),
+            ] {
+                if let Some(Thing::Component((component, _))) =
+                    thing_map.get(change_set_pk, &unique_id.to_owned())
+                {
+                    self.sockets_for(ctx, *component.id()).await?;
+                }
+            }
+
+            if let Some(Thing::Component((component, _))) =
+                thing_map.get(change_set_pk, &edge.to_component_unique_id().to_owned())
+            {
+                self.edges_for(ctx, *component.id()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The edges of the component, listed on first use.
+    async fn edges_for(
+        &mut self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> PkgResult<&[Edge]> {
+        if !self.edges.contains_key(&component_id) {
+            let edges = Edge::list_for_component(ctx, component_id).await?;
+            self.edges.insert(component_id, edges);
+        }
+
+        Ok(self
+            .edges
+            .get(&component_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default())
+    }
+
+    /// Records an edge created by the import, so that an edge repeated later on is reused.
+    fn add_edge(&mut self, component_id: ComponentId, edge: Edge) {
+        self.edges.entry(component_id).or_default().push(edge);
+    }
+
+    /// Forgets an edge deleted by the import, so that it isn't reused.
+    fn remove_edge(&mut self, edge_id: EdgeId) {
+        for edges in self.edges.values_mut() {
+            edges.retain(|edge| *edge.id() != edge_id);
+        }
+    }
+
+    /// The sockets of the component, listed on first use.
+    async fn sockets_for(
+        &mut self,
+        ctx: &DalContext,
+        component_id: ComponentId,
+    ) -> PkgResult<&[Socket]> {
+        if !self.sockets.contains_key(&component_id) {
+            let sockets = Socket::list_for_component(ctx, component_id).await?;
+            self.sockets.insert(component_id, sockets);
+        }
+
+        Ok(self
+            .sockets
+            .get(&component_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default())
+    }
+
+    fn len(&self) -> usize {
+        self.sockets.len()
+    }
+}
+
+/// Resolves the name of the socket an imported edge connects to on a component: `name` is first
+/// looked up in `socket_name_remap`, and if the component has no socket of that exact name, a
+/// socket whose name only differs by case is used instead.
+fn resolve_edge_socket_name(
+    name: &str,
+    socket_edge_kind: SocketEdgeKind,
+    sockets: &[Socket],
+    socket_name_remap: &HashMap<String, String>,
+) -> String {
+    let name = socket_name_remap
+        .get(name)
+        .map(String::as_str)
+        .unwrap_or(name);
+
+    let sockets: Vec<&Socket> = sockets
+        .iter()
+        .filter(|socket| *socket.edge_kind() == socket_edge_kind)
+        .collect();
+    if sockets.iter().any(|socket| socket.name() == name) {
+        return name.to_owned();
+    }
+
+    sockets
+        .iter()
+        .find(|socket| socket.name().to_lowercase() == name.to_lowercase())
+        .map(|socket| socket.name().to_owned())
+        .unwrap_or_else(|| name.to_owned())
+}
+
+#[instrument(level = "debug", skip_all)]
 async fn import_edge(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
     edge_spec: &EdgeSpec,
     thing_map: &mut ThingMap,
+    socket_cache: &mut EdgeSocketCache,
+    socket_name_remap: &HashMap<String, String>,
+    as_user: Option<UserPk>,
 ) -> PkgResult<Option<ImportEdgeSkip>> {
+    let mut created = false;
     let edge = match thing_map.get(change_set_pk, &edge_spec.unique_id.clone()) {
         Some(Thing::Edge(edge)) => Some(edge.to_owned()),
         _ => {
             if !edge_spec.deleted {
                 let head_component_unique_id = edge_spec.to_component_unique_id.clone();
-                let (_, head_node) = match thing_map.get(change_set_pk, &head_component_unique_id) {
-                    Some(Thing::Component((component, node))) => (component, node),
-                    _ => {
-                        return Err(PkgError::MissingComponentForEdge(
-                            head_component_unique_id,
-                            edge_spec.from_socket_name.clone(),
-                            edge_spec.to_socket_name.clone(),
-                        ));
-                    }
-                };
+                let (head_component, head_node) =
+                    match thing_map.get(change_set_pk, &head_component_unique_id) {
+                        Some(Thing::Component((component, node))) => (component, node),
+                        _ => {
+                            return Err(PkgError::MissingComponentForEdge(
+                                head_component_unique_id,
+                                edge_spec.from_socket_name.clone(),
+                                edge_spec.to_socket_name.clone(),
+                            ));
+                        }
+                    };
 
                 let tail_component_unique_id = edge_spec.from_component_unique_id.clone();
-                let (_, tail_node) = match thing_map.get(change_set_pk, &tail_component_unique_id) {
-                    Some(Thing::Component((component, node))) => (component, node),
-                    _ => {
-                        return Err(PkgError::MissingComponentForEdge(
-                            tail_component_unique_id,
-                            edge_spec.from_socket_name.clone(),
-                            edge_spec.to_socket_name.clone(),
-                        ));
-                    }
-                };
+                let (tail_component, tail_node) =
+                    match thing_map.get(change_set_pk, &tail_component_unique_id) {
+                        Some(Thing::Component((component, node))) => (component, node),
+                        _ => {
+                            return Err(PkgError::MissingComponentForEdge(
+                                tail_component_unique_id,
+                                edge_spec.from_socket_name.clone(),
+                                edge_spec.to_socket_name.clone(),
+                            ));
+                        }
+                    };
 
-                let to_socket = match Socket::find_by_name_for_edge_kind_and_node(
-                    ctx,
+                let head_sockets = socket_cache.sockets_for(ctx, *head_component.id()).await?;
+                let to_socket_name = resolve_edge_socket_name(
                     &edge_spec.to_socket_name,
                     SocketEdgeKind::ConfigurationInput,
-                    *head_node.id(),
-                )
-                .await?
-                {
-                    Some(socket) => socket,
+                    head_sockets,
+                    socket_name_remap,
+                );
+                let to_socket = match head_sockets.iter().find(|socket| {
+                    socket.name() == to_socket_name
+                        && *socket.edge_kind() == SocketEdgeKind::ConfigurationInput
+                }) {
+                    Some(socket) => socket.clone(),
                     None => {
-                        return Ok(Some(ImportEdgeSkip::MissingInputSocket(
-                            edge_spec.to_socket_name.clone(),
-                        )));
+                        return Ok(Some(ImportEdgeSkip::MissingInputSocket {
+                            name: edge_spec.to_socket_name.clone(),
+                        }));
                     }
                 };
 
-                let from_socket = match Socket::find_by_name_for_edge_kind_and_node(
-                    ctx,
+                let tail_sockets = socket_cache.sockets_for(ctx, *tail_component.id()).await?;
+                let from_socket_name = resolve_edge_socket_name(
                     &edge_spec.from_socket_name,
                     SocketEdgeKind::ConfigurationOutput,
-                    *tail_node.id(),
-                )
-                .await?
-                {
-                    Some(socket) => socket,
+                    tail_sockets,
+                    socket_name_remap,
+                );
+                // Several output sockets may share a name, so prefer the one whose connection
+                // annotations fit the input socket
+                let candidates: Vec<&Socket> = tail_sockets
+                    .iter()
+                    .filter(|socket| {
+                        socket.name() == from_socket_name
+                            && *socket.edge_kind() == SocketEdgeKind::ConfigurationOutput
+                    })
+                    .collect();
+                let mut from_socket = candidates.first().copied();
+                for candidate in &candidates {
+                    if candidate.is_annotation_compatible_with(&to_socket)? {
+                        from_socket = Some(*candidate);
+                        break;
+                    }
+                }
+                let from_socket = match from_socket {
+                    Some(socket) => socket.clone(),
                     None => {
-                        return Ok(Some(ImportEdgeSkip::MissingOutputSocket(
-                            edge_spec.from_socket_name.clone(),
-                        )));
+                        return Ok(Some(ImportEdgeSkip::MissingOutputSocket {
+                            name: edge_spec.from_socket_name.clone(),
+                        }));
                     }
                 };
 
-                Some(
-                    Edge::new_for_connection(
+                let edge_kind = match edge_spec.edge_kind {
+                    EdgeSpecKind::Configuration => EdgeKind::Configuration,
+                    EdgeSpecKind::Symbolic => EdgeKind::Symbolic,
+                };
+                // The components may already be connected, e.g. when resuming an import that
+                // was interrupted after this edge was created, so don't connect them twice
+                let existing_edge = socket_cache
+                    .edges_for(ctx, *head_component.id())
+                    .await?
+                    .iter()
+                    .find(|edge| {
+                        edge.kind() == &edge_kind
+                            && edge.head_node_id() == *head_node.id()
+                            && edge.head_socket_id() == *to_socket.id()
+                            && edge.tail_node_id() == *tail_node.id()
+                            && edge.tail_socket_id() == *from_socket.id()
+                    })
+                    .cloned();
+                if existing_edge.is_some() {
+                    existing_edge
+                } else {
+                    let edge = Edge::new_for_connection(
                         ctx,
                         *head_node.id(),
                         *to_socket.id(),
                         *tail_node.id(),
                         *from_socket.id(),
-                        match edge_spec.edge_kind {
-                            EdgeSpecKind::Configuration => EdgeKind::Configuration,
-                            EdgeSpecKind::Symbolic => EdgeKind::Symbolic,
-                        },
+                        edge_kind,
                     )
-                    .await?,
-                )
+                    .await?;
+                    socket_cache.add_edge(*head_component.id(), edge.clone());
+
+                    // Configuration frames link their sockets to their children with regular
+                    // edges, which are part of the package. Aggregation frames don't, so their
+                    // links are rebuilt from the frame edge itself.
+                    if edge_kind == EdgeKind::Symbolic
+                        && to_socket.kind() == &SocketKind::Frame
+                        && head_component.get_type(ctx).await? == ComponentType::AggregationFrame
+                    {
+                        connect_aggregation_frame_member(
+                            ctx,
+                            head_component,
+                            head_node,
+                            tail_component,
+                            tail_node,
+                        )
+                        .await?;
+                    }
+
+                    created = true;
+                    Some(edge)
+                }
             } else {
                 None
             }
@@ -585,9 +1793,10 @@ async fn import_edge(
     };
 
     if let Some(mut edge) = edge {
-        let creation_user_pk = match &edge_spec.creation_user_pk {
-            Some(pk_str) => Some(UserPk::from_str(pk_str)?),
-            None => None,
+        let creation_user_pk = match (as_user, &edge_spec.creation_user_pk) {
+            (Some(user_pk), _) if created => Some(user_pk),
+            (_, Some(pk_str)) => Some(UserPk::from_str(pk_str)?),
+            (_, None) => None,
         };
         if creation_user_pk.as_ref() != edge.creation_user_pk() {
             edge.set_creation_user_pk(ctx, creation_user_pk).await?;
@@ -611,6 +1820,7 @@ async fn import_edge(
             Edge::restore_by_id(ctx, *edge.id()).await?;
         } else if !edge.visibility().is_deleted() && edge_spec.deleted {
             edge.delete_and_propagate(ctx).await?;
+            socket_cache.remove_edge(*edge.id());
         }
 
         thing_map.insert(
@@ -623,13 +1833,64 @@ async fn import_edge(
     Ok(None)
 }
 
-async fn import_component(
+/// Links every input and output socket of an aggregation frame to a member of the frame with
+/// [`Edge::connect_aggregation_frame_socket`], the same way the diagram does when a component is
+/// put into one. These edges go from a frame socket to itself, so they are not exported and have
+/// to be recreated on import.
+async fn connect_aggregation_frame_member(
     ctx: &DalContext,
-    change_set_pk: ChangeSetPk,
+    frame: &Component,
+    frame_node: &Node,
+    member: &Component,
+    member_node: &Node,
+) -> PkgResult<()> {
+    for socket in Socket::list_for_component(ctx, *frame.id()).await? {
+        if socket.kind() == &SocketKind::Frame {
+            continue;
+        }
+
+        Edge::connect_aggregation_frame_socket(
+            ctx,
+            &socket,
+            *frame.id(),
+            *frame_node.id(),
+            *member.id(),
+            *member_node.id(),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// What [`import_component`] keeps track of across the components of a change set.
+#[derive(Debug, Default)]
+struct ComponentImportState {
+    /// The names of the components in the change set, for [`resolve_component_name`]
+    names: HashSet<String>,
+    /// The attribute values written for the components if
+    /// [`ImportOptions::defer_dependent_updates`] is set, for the caller to enqueue a single
+    /// dependent values update for
+    deferred_dependent_values: Vec<AttributeValueId>,
+}
+
+/// Imports a component from a workspace backup, named, positioned and attributed as `options`
+/// say. The resource of the component is only written on head, unless `force_resource_patch`
+/// is set.
+#[instrument(level = "debug", skip_all, fields(component.name = %component_spec.name))]
+async fn import_component(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
     mut component_spec: ComponentSpec,
     thing_map: &mut ThingMap,
+    options: &ImportOptions,
     force_resource_patch: bool,
+    state: &mut ComponentImportState,
 ) -> PkgResult<Vec<ImportAttributeSkip>> {
+    let mut deferred_dependent_values = options
+        .defer_dependent_updates
+        .then_some(&mut state.deferred_dependent_values);
+
     let variant = match &component_spec.variant {
         ComponentSpecVariant::BuiltinVariant {
             schema_name,
@@ -679,14 +1940,39 @@ async fn import_component(
         }
     };
 
+    let mut renamed = false;
+    if let Some(prefix) = &options.component_name_prefix {
+        component_spec.name = format!("{prefix}{}", component_spec.name);
+        renamed = true;
+    }
+
     let (mut component, mut node) =
         match thing_map.get(change_set_pk, &component_spec.unique_id.clone()) {
             Some(Thing::Component((existing_component, node))) => {
                 (existing_component.to_owned(), node.to_owned())
             }
             _ => {
-                let (component, node) =
-                    Component::new(ctx, component_spec.name.clone(), *variant.id()).await?;
+                let name = resolve_component_name(
+                    &component_spec.name,
+                    options.component_name_policy,
+                    &state.names,
+                )?;
+                if name != component_spec.name {
+                    component_spec.name = name;
+                    renamed = true;
+                }
+
+                // Components record their creation user from the actor of the context
+                let as_user_ctx = options
+                    .as_user
+                    .map(|user_pk| ctx.clone_with_new_history_actor(user_pk.into()));
+                let (component, node) = Component::new(
+                    as_user_ctx.as_ref().unwrap_or(ctx),
+                    component_spec.name.clone(),
+                    *variant.id(),
+                )
+                .await?;
+                state.names.insert(component_spec.name.clone());
 
                 thing_map.insert(
                     change_set_pk,
@@ -698,12 +1984,7 @@ async fn import_component(
             }
         };
 
-    let mut exporter = PkgExporter::new_workspace_exporter(
-        "temporary",
-        "SystemInit".to_owned(),
-        "1.0",
-        "Temporary pkg created to update schemas",
-    );
+    let mut exporter = PkgExporter::new_temporary_exporter();
     let new_component_spec = if let Some((comp_spec, _, _)) = exporter
         .export_component(ctx, Some(change_set_pk), &component, component_spec.variant)
         .await?
@@ -717,17 +1998,36 @@ async fn import_component(
         component
             .set_name(ctx, Some(component_spec.name.clone()))
             .await?;
+        state.names.insert(component_spec.name.clone());
     }
 
     let position = component_spec.position;
-    if node.x() != position.x
-        || node.y() != position.y
-        || node.height() != position.height.as_deref()
-        || node.width() != position.width.as_deref()
-    {
-        // Use set_geometry to ensure summary diagram gets updated positioning
-        node.set_geometry(ctx, position.x, position.y, position.width, position.height)
-            .await?;
+    let position_changed = match options.target_layout.as_deref() {
+        Some(layout) => node.geometry_for_layout(layout).map_or(true, |geometry| {
+            geometry.x != position.x
+                || geometry.y != position.y
+                || geometry.height != position.height
+                || geometry.width != position.width
+        }),
+        None => {
+            node.x() != position.x
+                || node.y() != position.y
+                || node.height() != position.height.as_deref()
+                || node.width() != position.width.as_deref()
+        }
+    };
+    if position_changed {
+        // Goes through set_geometry for the default layout to ensure summary diagram gets
+        // updated positioning
+        node.set_geometry_for_layout(
+            ctx,
+            options.target_layout.as_deref(),
+            position.x,
+            position.y,
+            position.width,
+            position.height,
+        )
+        .await?;
     }
 
     let mut value_cache: HashMap<ValueCacheKey, AttributeValue> = HashMap::new();
@@ -745,6 +2045,8 @@ async fn import_component(
             &mut value_cache,
             &mut prop_cache,
             thing_map,
+            options.strict_type_checking,
+            deferred_dependent_values.as_deref_mut(),
         )
         .await?
         {
@@ -762,6 +2064,8 @@ async fn import_component(
             &mut value_cache,
             &mut prop_cache,
             thing_map,
+            options.strict_type_checking,
+            deferred_dependent_values.as_deref_mut(),
         )
         .await?
         {
@@ -771,50 +2075,7 @@ async fn import_component(
 
     let mut resource_value = None;
 
-    let mut default_json = serde_json::json!({});
-    let mut work_queue: VecDeque<Prop> = vec![variant
-        .root_prop(ctx)
-        .await?
-        .ok_or_else(|| PkgError::MissingRootProp(*variant.id()))?]
-    .into_iter()
-    .collect();
-    while let Some(prop) = work_queue.pop_front() {
-        if matches!(prop.kind(), PropKind::Object) {
-            work_queue.extend(prop.child_props(ctx).await?);
-        }
-
-        let path = prop.path();
-        let mut parts = path.as_parts();
-        if parts.len() <= 1 {
-            continue;
-        }
-
-        // Join will convert it into a prepended / if there is anything after it,
-        // otherwise its an empty string
-        parts[0] = "";
-        let parent_path = parts[..parts.len() - 1].join("/");
-        let last_part = parts[parts.len() - 1].to_string();
-
-        if let Some(value) = default_json.pointer_mut(&parent_path) {
-            if let Some(object) = value.as_object_mut() {
-                object.insert(
-                    last_part,
-                    match prop.kind() {
-                        PropKind::String => serde_json::Value::Null,
-                        PropKind::Boolean => serde_json::Value::Null,
-                        PropKind::Integer => serde_json::Value::Null,
-                        PropKind::Array => serde_json::json!([]),
-                        PropKind::Map => serde_json::json!({}),
-                        PropKind::Object => serde_json::json!({}),
-                    },
-                );
-            } else {
-                return Err(PkgError::JsonValueIsNotAnObject(value.clone()));
-            }
-        } else {
-            return Err(PkgError::JsonPointerNotFound(default_json, parent_path));
-        }
-    }
+    let mut default_json = SchemaVariant::build_empty_domain_skeleton(ctx, *variant.id()).await?;
 
     let imported_json = component_spec.attributes[0]
         .implicit_value
@@ -858,6 +2119,8 @@ async fn import_component(
             &mut value_cache,
             &mut prop_cache,
             thing_map,
+            options.strict_type_checking,
+            deferred_dependent_values.as_deref_mut(),
         )
         .await?
         {
@@ -870,14 +2133,44 @@ async fn import_component(
         }
     }
 
+    // Frame edges are restored according to the type of the frame (see `import_edge`), so make
+    // sure the component has the type from the package before any edge is imported
+    if let Some(component_type) = component_spec.attributes[0]
+        .implicit_value
+        .as_ref()
+        .and_then(|root| root.pointer("/si/type"))
+        .and_then(|value| serde_json::from_value::<ComponentType>(value.to_owned()).ok())
+    {
+        if component.get_type(ctx).await? != component_type {
+            component.set_type(ctx, component_type).await?;
+        }
+    }
+
+    // The imported "/root/si/name" attribute carries the name from the package, so put the
+    // resolved name back
+    if renamed {
+        component
+            .set_name(ctx, Some(component_spec.name.clone()))
+            .await?;
+    }
+
     if component_spec.needs_destroy {
         component.set_needs_destroy(ctx, true).await?;
     }
 
     if let Some(resource_value) = resource_value {
         if force_resource_patch || change_set_pk == ChangeSetPk::NONE {
-            if let Ok(result) = serde_json::from_value(resource_value) {
-                component.set_resource(ctx, result).await?;
+            match serde_json::from_value::<ActionRunResult>(resource_value) {
+                Ok(result) => {
+                    component.set_resource(ctx, result).await?;
+                }
+                Err(err) => {
+                    warn!(
+                        component.name = component_spec.name.as_str(),
+                        error = %err,
+                        "resource payload is not a valid action run result, skipping"
+                    );
+                }
             }
         }
     }
@@ -891,6 +2184,101 @@ async fn import_component(
     Ok(skips)
 }
 
+/// Removes every value under "/root/secrets" from a backed up component, so that secrets from the
+/// workspace the backup was taken from are never written into the one being imported into.
+/// Returns a skip for each secret that had a value, if `secrets_policy` asks for them.
+fn strip_secret_values(
+    component_spec: &mut ComponentSpec,
+    secrets_policy: SecretsPolicy,
+) -> Vec<ImportAttributeSkip> {
+    let secrets_path = PropPath::new(["root", "secrets"]);
+    let mut secret_paths = vec![];
+
+    component_spec.attributes.retain(|attribute| {
+        let path = match &attribute.path {
+            AttributeValuePath::Prop { path, .. } => PropPath::from(path),
+            AttributeValuePath::InputSocket(_) | AttributeValuePath::OutputSocket(_) => {
+                return true;
+            }
+        };
+        if !path.is_descendant_of(&secrets_path) {
+            return true;
+        }
+
+        let has_value = [&attribute.value, &attribute.implicit_value]
+            .into_iter()
+            .flatten()
+            .any(|value| !value.is_null() && !value.is_object());
+        if path != secrets_path && has_value && !secret_paths.contains(&path) {
+            secret_paths.push(path);
+        }
+
+        false
+    });
+
+    // The root attribute carries the whole value tree of the component, secrets included
+    if let Some(serde_json::Value::Object(root)) = component_spec
+        .attributes
+        .first_mut()
+        .and_then(|attribute| attribute.implicit_value.as_mut())
+    {
+        if let Some(serde_json::Value::Object(secrets)) = root.remove("secrets") {
+            for (name, value) in secrets {
+                let path = secrets_path.join(&PropPath::new([name]));
+                if !value.is_null() && !secret_paths.contains(&path) {
+                    secret_paths.push(path);
+                }
+            }
+        }
+    }
+
+    match secrets_policy {
+        SecretsPolicy::RequireReinjection => secret_paths
+            .into_iter()
+            .map(|path| ImportAttributeSkip::SecretNeedsReinjection { path })
+            .collect(),
+        SecretsPolicy::Skip => vec![],
+    }
+}
+
+/// Lists the names of the components in the change set of `ctx`, for
+/// [`resolve_component_name`].
+async fn existing_component_names(ctx: &DalContext) -> PkgResult<HashSet<String>> {
+    let mut names = HashSet::new();
+    for component in Component::list(ctx).await? {
+        names.insert(component.name(ctx).await?);
+    }
+
+    Ok(names)
+}
+
+/// Picks the name for a component created by an import, according to `component_name_policy`
+/// and the names of the components that already exist.
+fn resolve_component_name(
+    name: &str,
+    component_name_policy: ComponentNamePolicy,
+    existing_names: &HashSet<String>,
+) -> PkgResult<String> {
+    if component_name_policy == ComponentNamePolicy::AsIs || !existing_names.contains(name) {
+        return Ok(name.to_owned());
+    }
+
+    match component_name_policy {
+        ComponentNamePolicy::AsIs => Ok(name.to_owned()),
+        ComponentNamePolicy::Error => Err(PkgError::ComponentNameConflict(name.to_owned())),
+        ComponentNamePolicy::SuffixOnCollision => {
+            let mut suffix = 2;
+            loop {
+                let candidate = format!("{name} ({suffix})");
+                if !existing_names.contains(&candidate) {
+                    return Ok(candidate);
+                }
+                suffix += 1;
+            }
+        }
+    }
+}
+
 fn get_prop_kind_for_value(value: Option<&serde_json::Value>) -> Option<PropKind> {
     match value {
         Some(serde_json::Value::Array(_)) => Some(PropKind::Array),
@@ -913,6 +2301,8 @@ async fn import_component_attribute(
     value_cache: &mut HashMap<ValueCacheKey, AttributeValue>,
     prop_cache: &mut HashMap<String, Option<Prop>>,
     thing_map: &mut ThingMap,
+    strict_type_checking: bool,
+    deferred_dependent_values: Option<&mut Vec<AttributeValueId>>,
 ) -> PkgResult<Option<ImportAttributeSkip>> {
     match &attribute.path {
         AttributeValuePath::Prop { path, key, index } => {
@@ -935,10 +2325,6 @@ async fn import_component_attribute(
                 }
             };
 
-            struct ParentData {
-                attribute_value: Option<AttributeValue>,
-            }
-
             match prop {
                 Some(prop) => {
                     // Do not write attributes for the resource or props under the resource tree if
@@ -965,60 +2351,74 @@ async fn import_component_attribute(
                             if prop.path() != PropPath::new(["root", "resource", "payload"]) {
                                 return Ok(Some(ImportAttributeSkip::KindMismatch {
                                     path: PropPath::from(path),
-                                    expected_kind,
+                                    expected_kind: Some(expected_kind),
                                     variant_kind: *prop.kind(),
                                 }));
                             }
                         }
+                    } else if strict_type_checking
+                        && attribute.value == Some(serde_json::Value::Null)
+                        && matches!(
+                            prop.kind(),
+                            PropKind::Boolean | PropKind::Integer | PropKind::String
+                        )
+                        && prop.path() != PropPath::new(["root", "resource", "payload"])
+                    {
+                        return Ok(Some(ImportAttributeSkip::KindMismatch {
+                            path: PropPath::from(path),
+                            expected_kind: None,
+                            variant_kind: *prop.kind(),
+                        }));
                     }
 
                     if index.is_some() || key.is_some() {
                         return Ok(None);
                     }
 
-                    let parent_data =
-                        if let Some(AttributeValuePath::Prop { path, .. }) = &attribute.parent_path
-                        {
+                    // Parents are imported before their children, so the parent value is
+                    // usually cached and the value is found with a single query. The path is
+                    // only resolved from the root prop when the parent is not cached.
+                    let cached_parent_av_id = match &attribute.parent_path {
+                        Some(AttributeValuePath::Prop { path, .. }) => {
                             let parent_prop = prop_cache.get(path).and_then(|p| p.as_ref()).ok_or(
                                 PkgError::AttributeValueParentPropNotFound(path.to_owned()),
                             )?;
 
-                            let parent_value_cache_key =
-                                ValueCacheKey::new(*component.id(), *parent_prop.id());
+                            value_cache
+                                .get(&ValueCacheKey::new(*component.id(), *parent_prop.id()))
+                                .map(|parent_av| Some(*parent_av.id()))
+                        }
+                        _ => Some(None),
+                    };
 
-                            let parent_av = match value_cache.get(&parent_value_cache_key) {
-                                Some(parent_av) => parent_av.to_owned(),
-                                // If we don't have a parent in the cache it means we're under a map or
-                                // array and currently we don't support custom attribute functions at
-                                // that depth
-                                None => return Ok(None),
+                    let maybe_av = match cached_parent_av_id {
+                        Some(parent_av_id) => {
+                            let context = AttributeReadContext {
+                                prop_id: Some(*prop.id()),
+                                internal_provider_id: Some(InternalProviderId::NONE),
+                                external_provider_id: Some(ExternalProviderId::NONE),
+                                component_id: Some(*component.id()),
                             };
 
-                            ParentData {
-                                attribute_value: Some(parent_av.to_owned()),
-                            }
-                        } else {
-                            ParentData {
-                                attribute_value: None,
-                            }
-                        };
-
-                    let context = AttributeReadContext {
-                        prop_id: Some(*prop.id()),
-                        internal_provider_id: Some(InternalProviderId::NONE),
-                        external_provider_id: Some(ExternalProviderId::NONE),
-                        component_id: Some(*component.id()),
+                            AttributeValue::find_with_parent_and_key_for_context(
+                                ctx,
+                                parent_av_id,
+                                key.to_owned(),
+                                context,
+                            )
+                            .await?
+                        }
+                        None => {
+                            AttributeValue::resolve_path(
+                                ctx,
+                                *component.id(),
+                                *variant.id(),
+                                &attribute.path,
+                            )
+                            .await?
+                        }
                     };
 
-                    let parent_av_id = parent_data.attribute_value.as_ref().map(|av| *av.id());
-                    let maybe_av = AttributeValue::find_with_parent_and_key_for_context(
-                        ctx,
-                        parent_av_id,
-                        key.to_owned(),
-                        context,
-                    )
-                    .await?;
-
                     let mut updated_av = match maybe_av {
                         Some(av) => {
                             // Write the entire root implicit value, which will write all child
@@ -1036,17 +2436,16 @@ async fn import_component_attribute(
                                     .set_component_id(*component.id())
                                     .to_context_unchecked();
 
-                                let (_, new_av_id) = AttributeValue::update_for_context(
+                                let new_av_id = update_attribute_value(
                                     ctx,
                                     *av.id(),
-                                    None,
                                     context,
                                     if attribute.implicit_value.is_some() {
                                         attribute.implicit_value.clone()
                                     } else {
                                         attribute.value.clone()
                                     },
-                                    None,
+                                    deferred_dependent_values,
                                 )
                                 .await?;
 
@@ -1080,7 +2479,9 @@ async fn import_component_attribute(
                 }
                 None => {
                     // collect missing props and log them
-                    return Ok(Some(ImportAttributeSkip::MissingProp(PropPath::from(path))));
+                    return Ok(Some(ImportAttributeSkip::MissingProp {
+                        path: PropPath::from(path),
+                    }));
                 }
             }
         }
@@ -1092,6 +2493,46 @@ async fn import_component_attribute(
     Ok(None)
 }
 
+/// Writes `value` to the attribute value and returns the id of the value that was written. If
+/// `deferred_dependent_values` is given, the id is pushed to it instead of enqueueing a dependent
+/// values update.
+async fn update_attribute_value(
+    ctx: &DalContext,
+    attribute_value_id: AttributeValueId,
+    context: AttributeContext,
+    value: Option<serde_json::Value>,
+    deferred_dependent_values: Option<&mut Vec<AttributeValueId>>,
+) -> PkgResult<AttributeValueId> {
+    Ok(match deferred_dependent_values {
+        Some(deferred_dependent_values) => {
+            let (_, new_av_id) =
+                AttributeValue::update_for_context_without_propagating_dependent_values(
+                    ctx,
+                    attribute_value_id,
+                    None,
+                    context,
+                    value,
+                    None,
+                )
+                .await?;
+            deferred_dependent_values.push(new_av_id);
+            new_av_id
+        }
+        None => {
+            let (_, new_av_id) = AttributeValue::update_for_context(
+                ctx,
+                attribute_value_id,
+                None,
+                context,
+                value,
+                None,
+            )
+            .await?;
+            new_av_id
+        }
+    })
+}
+
 async fn get_ip_for_input(
     ctx: &DalContext,
     schema_variant_id: SchemaVariantId,
@@ -1110,7 +2551,7 @@ async fn get_ip_for_input(
                 None => return Ok(None),
             };
 
-            let ip = InternalProvider::find_for_prop(ctx, *input_source_prop.id())
+            let ip = InternalProvider::find_or_create_for_prop(ctx, *input_source_prop.id())
                 .await?
                 .ok_or(PkgError::MissingInternalProviderForProp(
                     *input_source_prop.id(),
@@ -1239,212 +2680,996 @@ async fn update_prototype(
     Ok(())
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+/// Finds the installed [`Schema`] and [`SchemaVariant`] that importing `schema_spec` would
+/// replace, if any. A variant is only upgraded when the package is newer than the one it was
+/// created from and, unless `update_even_if_not_builtin` is set, when the variant is a builtin.
+async fn find_schema_to_upgrade(
+    ctx: &DalContext,
+    schema_spec: &SiPkgSchema<'_>,
+    metadata: &SiPkgMetadata,
+    update_even_if_not_builtin: bool,
+) -> PkgResult<Option<(Schema, SchemaVariant)>> {
+    for schema in Schema::find_by_attr(ctx, "name", &schema_spec.name()).await? {
+        for variant_spec in &schema_spec.variants()? {
+            for variant in schema.variants(ctx).await? {
+                if variant.name() != variant_spec.name() {
+                    continue;
+                }
+
+                let should_update = if let Some(pkg_created_at) = variant.pkg_created_at() {
+                    metadata
+                        .created_at()
+                        .signed_duration_since(pkg_created_at)
+                        .num_seconds()
+                        > 0
+                } else {
+                    true
+                };
+
+                if should_update && (update_even_if_not_builtin || variant.is_builtin(ctx).await?) {
+                    return Ok(Some((schema, variant)));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// What upgrading an installed schema to `schema_spec` would do, as reported by
+/// [`preview_schema_upgrade`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradePreview {
+    pub schema_name: String,
+    /// The name of the variant that would be replaced, or `None` if nothing would be upgraded.
+    pub variant_name: Option<String>,
+    /// Components that would be deleted and re-created from the new variant.
+    pub components: Vec<ComponentId>,
+    /// Edges that would be deleted and re-established between the re-created components.
+    pub edges: Vec<EdgeId>,
+    /// Domain props of the current variant that do not exist in the new one.
+    pub removed_props: Vec<PropPath>,
+    /// Whether any component would lose attribute values because their props were removed.
+    pub data_loss: bool,
+}
+
+/// Reports what importing `schema_spec` as a schema upgrade would change, without mutating
+/// anything. Unlike the upgrade itself this considers any installed schema with the same name,
+/// builtin or not, so that upgrades can be vetted before they are run.
+#[instrument(level = "info", skip_all, fields(schema.name = schema_spec.name()))]
+pub async fn preview_schema_upgrade(
+    ctx: &DalContext,
+    schema_spec: SiPkgSchema<'_>,
+    metadata: &SiPkgMetadata,
+) -> PkgResult<UpgradePreview> {
+    match find_schema_to_upgrade(ctx, &schema_spec, metadata, true).await? {
+        Some((schema, variant)) => preview_upgrade(ctx, &schema_spec, &schema, &variant).await,
+        None => Ok(UpgradePreview {
+            schema_name: schema_spec.name().to_owned(),
+            ..Default::default()
+        }),
+    }
+}
+
+/// Reports what replacing `variant` of `schema` with the variant in `schema_spec` would change.
+async fn preview_upgrade(
+    ctx: &DalContext,
+    schema_spec: &SiPkgSchema<'_>,
+    schema: &Schema,
+    variant: &SchemaVariant,
+) -> PkgResult<UpgradePreview> {
+    let mut preview = UpgradePreview {
+        schema_name: schema_spec.name().to_owned(),
+        variant_name: Some(variant.name().to_owned()),
+        ..Default::default()
+    };
+
+    let mut edge_ids = HashSet::new();
+    for component in Component::list_for_schema(ctx, *schema.id()).await? {
+        for edge in Edge::list_for_component(ctx, *component.id()).await? {
+            if edge_ids.insert(*edge.id()) {
+                preview.edges.push(*edge.id());
+            }
+        }
+        preview.components.push(*component.id());
+    }
+
+    let mut spec_prop_paths = HashSet::new();
+    for variant_spec in schema_spec.to_spec().await?.variants {
+        if variant_spec.name == variant.name() {
+            collect_prop_paths(
+                &variant_spec.domain,
+                PropPath::new(["root"]),
+                &mut spec_prop_paths,
+            );
+        }
+    }
+
+    let domain_path = PropPath::new(["root", "domain"]);
+    for prop in SchemaVariant::all_props(ctx, *variant.id()).await? {
+        let path = prop.path();
+        if path.is_descendant_of(&domain_path) && !spec_prop_paths.contains(path.as_str()) {
+            preview.removed_props.push(path);
+        }
+    }
+    for component_id in &preview.components {
+        let properties = ComponentView::new(ctx, *component_id).await?.properties;
+        if preview
+            .removed_props
+            .iter()
+            .any(|path| has_value_at_prop_path(&properties, path))
+        {
+            preview.data_loss = true;
+            break;
+        }
+    }
+
+    Ok(preview)
+}
+
+/// Whether the [`ComponentView`] `properties` hold a value at `path`. Empty objects, maps and
+/// arrays do not count, since components have those for their container props regardless.
+fn has_value_at_prop_path(properties: &serde_json::Value, path: &PropPath) -> bool {
+    let pointer: String = path
+        .as_parts()
+        .iter()
+        .skip(1)
+        .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+        .collect();
+
+    match properties.pointer(&pointer) {
+        None | Some(serde_json::Value::Null) => false,
+        Some(serde_json::Value::Object(entries)) => !entries.is_empty(),
+        Some(serde_json::Value::Array(items)) => !items.is_empty(),
+        Some(_) => true,
+    }
+}
+
+fn collect_prop_paths(prop_spec: &PropSpec, parent_path: PropPath, paths: &mut HashSet<String>) {
+    let (name, children) = match prop_spec {
+        PropSpec::Array {
+            name, type_prop, ..
+        }
+        | PropSpec::Map {
+            name, type_prop, ..
+        } => (name, vec![type_prop.as_ref()]),
+        PropSpec::Object { name, entries, .. } => (name, entries.iter().collect()),
+        PropSpec::Boolean { name, .. }
+        | PropSpec::Number { name, .. }
+        | PropSpec::String { name, .. } => (name, vec![]),
+    };
+
+    let path = parent_path.join(&PropPath::new([name]));
+    for child in children {
+        collect_prop_paths(child, path.clone(), paths);
+    }
+    paths.insert(path.as_str().to_owned());
+}
+
+/// The version of the serialized [`ImportSkips`] format. Bump this when the shape of the skips
+/// changes in a way clients need to know about.
+///
+/// Version 2 made the `expectedKind` of a `kindMismatch` skip nullable.
+/// Version 3 added the `secretNeedsReinjection` attribute skip.
+/// Version 4 added the `attrFuncSkips`.
+/// Version 5 added the `funcErrors` and `schemaSkips`.
+/// Version 6 added the `filteredComponent` edge skip.
+/// Version 7 added the `qualificationErrors`.
+pub const IMPORT_SKIPS_VERSION: u32 = 7;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImportSkips {
+    /// Skips serialized before the format was versioned deserialize as version `0`.
+    #[serde(default)]
+    pub version: u32,
     pub change_set_pk: ChangeSetPk,
     pub edge_skips: Vec<ImportEdgeSkip>,
     pub attribute_skips: Vec<(String, Vec<ImportAttributeSkip>)>,
+    /// Attribute functions left unwired because of [`ImportOptions::skip_attribute_funcs`]
+    #[serde(default)]
+    pub attr_func_skips: Vec<ImportAttrFuncSkip>,
+    /// The unique ids of the funcs that could not be imported because of
+    /// [`ImportOptions::continue_on_func_error`], with the error for each
+    #[serde(default)]
+    pub func_errors: Vec<(String, String)>,
+    /// The names of the schemas that were not imported because they refer to one of the
+    /// `func_errors`, with the reason for each
+    #[serde(default)]
+    pub schema_skips: Vec<(String, String)>,
+    /// The names of the components whose qualifications could not be run because of
+    /// [`ImportOptions::run_qualifications_after_import`], with the error for each
+    #[serde(default)]
+    pub qualification_errors: Vec<(String, String)>,
+}
+
+/// An attribute function that [`ImportOptions::skip_attribute_funcs`] did not wire up to the prop
+/// at `path`. It carries everything needed to wire it up later with
+/// [`apply_skipped_attr_funcs`].
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportAttrFuncSkip {
+    pub schema_variant_id: SchemaVariantId,
+    pub path: PropPath,
+    pub func_id: FuncId,
+    /// The map key the function sets the value of, for functions set on a single map item
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub inputs: Vec<AttrFuncInputSpec>,
 }
 
 #[remain::sorted]
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ImportAttributeSkip {
     #[serde(rename_all = "camelCase")]
     KindMismatch {
         path: PropPath,
-        expected_kind: PropKind,
+        /// The kind of the imported value, or `None` if the value was `null`
+        expected_kind: Option<PropKind>,
         variant_kind: PropKind,
     },
-    MissingInputSocket(String),
-    MissingOutputSocket(String),
-    MissingProp(PropPath),
+    MissingInputSocket {
+        name: String,
+    },
+    MissingOutputSocket {
+        name: String,
+    },
+    MissingProp {
+        path: PropPath,
+    },
+    /// The component had a value for this secret in the backup. It was not imported and has to
+    /// be entered again.
+    SecretNeedsReinjection {
+        path: PropPath,
+    },
+    /// A skip kind this version does not know about, serialized by a newer version.
+    #[serde(other)]
+    Unknown,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[remain::sorted]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ImportEdgeSkip {
-    MissingInputSocket(String),
-    MissingOutputSocket(String),
+    /// The edge connects a component left out by [`ImportOptions::components`]
+    #[serde(rename_all = "camelCase")]
+    FilteredComponent {
+        unique_id: String,
+    },
+    MissingInputSocket {
+        name: String,
+    },
+    MissingOutputSocket {
+        name: String,
+    },
+    /// A skip kind this version does not know about, serialized by a newer version.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ImportSkips {
+    /// Returns true if nothing was skipped.
+    pub fn is_empty(&self) -> bool {
+        self.edge_skips.is_empty()
+            && self.attr_func_skips.is_empty()
+            && self.func_errors.is_empty()
+            && self.schema_skips.is_empty()
+            && self.qualification_errors.is_empty()
+            && self
+                .attribute_skips
+                .iter()
+                .all(|(_, attribute_skips)| attribute_skips.is_empty())
+    }
+
+    /// A human readable, multi-line summary of everything that was skipped, suitable for
+    /// printing to a terminal.
+    pub fn summary(&self) -> String {
+        let attribute_skip_count: usize = self
+            .attribute_skips
+            .iter()
+            .map(|(_, attribute_skips)| attribute_skips.len())
+            .sum();
+
+        let mut summary = format!(
+            "change set {}: skipped {} edge(s) and {} attribute(s)",
+            self.change_set_pk,
+            self.edge_skips.len(),
+            attribute_skip_count,
+        );
+        for edge_skip in &self.edge_skips {
+            summary.push_str(&format!("\n  edge: {edge_skip}"));
+        }
+        for (component_name, attribute_skips) in &self.attribute_skips {
+            for attribute_skip in attribute_skips {
+                summary.push_str(&format!("\n  {component_name}: {attribute_skip}"));
+            }
+        }
+        for attr_func_skip in &self.attr_func_skips {
+            summary.push_str(&format!("\n  attribute func: {attr_func_skip}"));
+        }
+        for (func_unique_id, error) in &self.func_errors {
+            summary.push_str(&format!("\n  func {func_unique_id}: {error}"));
+        }
+        for (schema_name, reason) in &self.schema_skips {
+            summary.push_str(&format!("\n  schema {schema_name}: {reason}"));
+        }
+        for (component_name, error) in &self.qualification_errors {
+            summary.push_str(&format!("\n  qualifications of {component_name}: {error}"));
+        }
+
+        summary
+    }
 }
 
+impl std::fmt::Display for ImportAttributeSkip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KindMismatch {
+                path,
+                expected_kind,
+                variant_kind,
+            } => {
+                let expected_kind = expected_kind
+                    .map(|kind| kind.to_string())
+                    .unwrap_or_else(|| "null".to_owned());
+                write!(
+                    f,
+                    "{}: prop kind {variant_kind} does not match imported value kind {expected_kind}",
+                    path.with_replaced_sep("/"),
+                )
+            }
+            Self::MissingInputSocket { name } => write!(f, "missing input socket {name}"),
+            Self::MissingOutputSocket { name } => write!(f, "missing output socket {name}"),
+            Self::MissingProp { path } => {
+                write!(f, "missing prop {}", path.with_replaced_sep("/"))
+            }
+            Self::SecretNeedsReinjection { path } => {
+                write!(
+                    f,
+                    "secret {} must be entered again",
+                    path.with_replaced_sep("/")
+                )
+            }
+            Self::Unknown => write!(f, "unknown skip"),
+        }
+    }
+}
+
+impl std::fmt::Display for ImportAttrFuncSkip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "func {} not set on {}",
+            self.func_id,
+            self.path.with_replaced_sep("/")
+        )?;
+        if let Some(key) = &self.key {
+            write!(f, " for key {key}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ImportEdgeSkip {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FilteredComponent { unique_id } => {
+                write!(f, "component {unique_id} was filtered out")
+            }
+            Self::MissingInputSocket { name } => write!(f, "missing input socket {name}"),
+            Self::MissingOutputSocket { name } => write!(f, "missing output socket {name}"),
+            Self::Unknown => write!(f, "unknown skip"),
+        }
+    }
+}
+
+#[instrument(
+    name = "pkg.import",
+    level = "info",
+    skip_all,
+    fields(
+        pkg.name = Empty,
+        pkg.hash = Empty,
+        pkg.kind = Empty,
+    )
+)]
 pub async fn import_pkg_from_pkg(
     ctx: &DalContext,
     pkg: &SiPkg,
     options: Option<ImportOptions>,
     override_builtin_schema_feature_flag: bool,
-) -> PkgResult<(
-    Option<InstalledPkgId>,
-    Vec<SchemaVariantId>,
-    Option<Vec<ImportSkips>>,
-)> {
+) -> PkgResult<ImportResult> {
     // We have to write the installed_pkg row first, so that we have an id, and rely on transaction
-    // semantics to remove the row if anything in the installation process fails
+    // semantics to remove the row if anything in the installation process fails. Nothing below
+    // may commit, or a failed import would leave the row behind.
     let root_hash = pkg.hash()?.to_string();
 
-    let options = options.unwrap_or_default();
+    let options = options.unwrap_or_default();
+
+    let metadata = pkg.metadata()?;
+
+    let span = Span::current();
+    span.record("pkg.name", metadata.name());
+    span.record("pkg.hash", root_hash.as_str());
+    span.record("pkg.kind", metadata.kind().as_ref());
+
+    if let (SiPkgKind::WorkspaceBackup, Some(limit)) = (metadata.kind(), options.max_components) {
+        let mut components = 0;
+        for change_set in pkg.change_sets()? {
+            components += change_set.components()?.len();
+        }
+        if components > limit {
+            return Err(PkgError::BackupTooLarge { components, limit });
+        }
+    }
+
+    if let (SiPkgKind::WorkspaceBackup, Some(only_change_set)) =
+        (metadata.kind(), &options.only_change_set)
+    {
+        if !pkg
+            .change_sets()?
+            .iter()
+            .any(|change_set| change_set.name() == only_change_set)
+        {
+            return Err(PkgError::ChangeSetNotInBackup(only_change_set.to_owned()));
+        }
+    }
+
+    let installed_pkg_id = match &options.resume_from {
+        Some(resume_from) => {
+            if resume_from.root_hash != root_hash {
+                return Err(PkgError::ImportCheckpointMismatch(
+                    resume_from.root_hash.to_owned(),
+                    root_hash,
+                ));
+            }
+
+            resume_from.installed_pkg_id
+        }
+        None => {
+            if InstalledPkg::find_by_hash(ctx, &root_hash).await?.is_some() {
+                return Err(PkgError::PackageAlreadyInstalled(root_hash));
+            }
+
+            if options.no_record || options.dry_run {
+                None
+            } else {
+                let mut installed_pkg =
+                    InstalledPkg::new(ctx, metadata.name(), pkg.hash()?.to_string()).await?;
+                installed_pkg
+                    .set_version(ctx, Some(metadata.version().to_owned()))
+                    .await?;
+                Some(*installed_pkg.id())
+            }
+        }
+    };
+
+    if options.dry_run {
+        let mut plan = ImportPlan::default();
+        with_savepoint(
+            ctx,
+            "pkg_import_dry_run",
+            SavepointEnd::AlwaysRollBack,
+            import_pkg_contents(
+                ctx,
+                pkg,
+                &metadata,
+                root_hash,
+                installed_pkg_id,
+                &options,
+                override_builtin_schema_feature_flag,
+                Some(&mut plan),
+            ),
+        )
+        .await??;
+        return Ok(ImportResult {
+            plan: Some(plan),
+            ..Default::default()
+        });
+    }
+
+    import_pkg_contents(
+        ctx,
+        pkg,
+        &metadata,
+        root_hash,
+        installed_pkg_id,
+        &options,
+        override_builtin_schema_feature_flag,
+        None,
+    )
+    .await
+}
+
+/// Imports the contents of `pkg` once [`import_pkg_from_pkg`] has checked it can be imported. If
+/// `plan` is set, what the import does is recorded in it.
+#[allow(clippy::too_many_arguments)]
+async fn import_pkg_contents(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    metadata: &SiPkgMetadata,
+    root_hash: String,
+    installed_pkg_id: Option<InstalledPkgId>,
+    options: &ImportOptions,
+    override_builtin_schema_feature_flag: bool,
+    mut plan: Option<&mut ImportPlan>,
+) -> PkgResult<ImportResult> {
+    let mut change_set_things = ThingMap::new();
+    let mut stats = ImportStats::default();
+
+    let imported = match metadata.kind() {
+        SiPkgKind::Module => {
+            let mut func_errors = vec![];
+            let mut schema_skips = vec![];
+            let (installed_schema_variant_ids, _, _, attr_func_skips, default_values) =
+                import_change_set(
+                    ctx,
+                    ctx.visibility().change_set_pk,
+                    metadata,
+                    &pkg.funcs()?,
+                    &pkg.schemas()?,
+                    &[],
+                    &[],
+                    installed_pkg_id,
+                    &mut change_set_things,
+                    options,
+                    override_builtin_schema_feature_flag,
+                    &mut ImportCheckpoint::new(root_hash, installed_pkg_id),
+                    &mut stats,
+                    &mut func_errors,
+                    &mut schema_skips,
+                    &mut vec![],
+                    plan,
+                )
+                .await?;
+
+            let import_skips = ImportSkips {
+                version: IMPORT_SKIPS_VERSION,
+                change_set_pk: ctx.visibility().change_set_pk,
+                edge_skips: vec![],
+                attribute_skips: vec![],
+                attr_func_skips,
+                func_errors,
+                schema_skips,
+                qualification_errors: vec![],
+            };
+
+            ImportResult {
+                installed_pkg_id,
+                schema_variant_ids: installed_schema_variant_ids,
+                skips: if import_skips.is_empty() {
+                    None
+                } else {
+                    Some(vec![import_skips])
+                },
+                default_values,
+                plan: None,
+            }
+        }
+        SiPkgKind::WorkspaceBackup => {
+            let mut ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
+
+            let workspace_pk = WorkspacePk::from_str(
+                metadata
+                    .workspace_pk()
+                    .ok_or(PkgError::WorkspacePkNotInBackup)?,
+            )?;
+            let workspace_name = metadata
+                .workspace_name()
+                .ok_or(PkgError::WorkspaceNameNotInBackup)?;
+
+            // A resumed import continues in the workspace the interrupted import already set up
+            if options.resume_from.is_none() {
+                if let Some(plan) = plan.as_deref_mut() {
+                    let ctx = ctx.clone_with_new_tenancy(Tenancy::new(workspace_pk));
+                    for component in Component::list(&ctx).await? {
+                        plan.components_to_delete.push(*component.id());
+                    }
+                }
+                Workspace::clear_or_create_workspace(&mut ctx, workspace_pk, workspace_name)
+                    .await?;
+            }
+
+            ctx.update_tenancy(Tenancy::new(workspace_pk));
+
+            let mut checkpoint = options
+                .resume_from
+                .clone()
+                .unwrap_or_else(|| ImportCheckpoint::new(root_hash, installed_pkg_id));
+
+            match import_workspace_backup(
+                &ctx,
+                pkg,
+                metadata,
+                installed_pkg_id,
+                &mut change_set_things,
+                options,
+                override_builtin_schema_feature_flag,
+                &mut checkpoint,
+                &mut stats,
+                plan.as_deref_mut(),
+            )
+            .await?
+            {
+                Some(import_skips) => {
+                    if let Some(plan) = plan {
+                        plan.record_skips(&import_skips);
+                    }
+
+                    ImportResult {
+                        skips: if import_skips.is_empty() {
+                            None
+                        } else {
+                            Some(import_skips)
+                        },
+                        ..Default::default()
+                    }
+                }
+                None => ImportResult {
+                    interrupted: Some(checkpoint),
+                    ..Default::default()
+                },
+            }
+        }
+    };
+
+    // Imports run outside of a workspace when builtins are installed, and then nobody is
+    // listening for the event
+    if ctx.tenancy().workspace_pk().is_some() {
+        WsEvent::package_imported(
+            ctx,
+            installed_pkg_id,
+            metadata.name(),
+            metadata.kind(),
+            stats,
+        )
+        .await?
+        .publish_on_commit(ctx)
+        .await?;
+    }
+
+    Ok(imported)
+}
+
+/// Whether the func is matched to an existing func by name and updated in place, rather than
+/// found by hash like every other func.
+fn updates_func_by_name(func_spec: &SiPkgFunc<'_>) -> bool {
+    // This is a hack because the hash of the intrinsics has changed from the version in the
+    // packages. We also apply this to si:resourcePayloadToValue since it should be an
+    // intrinsic but is only in our packages
+    let special_case_funcs = ["si:resourcePayloadToValue", "si:normalizeToArray"];
+    func::is_intrinsic(func_spec.name())
+        || special_case_funcs.contains(&func_spec.name())
+        || func_spec.is_from_builtin().unwrap_or(false)
+}
+
+/// What an import did, returned by [`import_pkg_from_pkg`].
+#[derive(Clone, Debug, Default)]
+pub struct ImportResult {
+    /// The installed package record, unless [`ImportOptions::no_record`] or
+    /// [`ImportOptions::dry_run`] is set or the package is a workspace backup
+    pub installed_pkg_id: Option<InstalledPkgId>,
+    /// The schema variants installed by a module
+    pub schema_variant_ids: Vec<SchemaVariantId>,
+    /// What could not be imported, per change set, if anything was skipped
+    pub skips: Option<Vec<ImportSkips>>,
+    /// The default values the package set on props
+    pub default_values: ImportedDefaultValues,
+    /// What the import would change, if [`ImportOptions::dry_run`] is set
+    pub plan: Option<ImportPlan>,
+    /// Set if a workspace backup import stopped at [`ImportOptions::max_change_sets`] before
+    /// importing every change set, with the checkpoint to resume the import from
+    pub interrupted: Option<ImportCheckpoint>,
+}
+
+/// What an import would change, returned instead of importing anything when
+/// [`ImportOptions::dry_run`] is set. The import runs as usual inside a savepoint that is always
+/// rolled back, and records what it does here as it goes.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPlan {
+    /// Schemas that are not installed yet, by name
+    pub schemas_to_create: Vec<String>,
+    /// Installed schemas that would be upgraded, with the components and edges each upgrade
+    /// would delete and re-create
+    pub schemas_to_upgrade: Vec<UpgradePreview>,
+    /// Components that would be created, by the name they would be given
+    pub components_to_create: Vec<String>,
+    /// Existing components that would be deleted, either by a schema upgrade or because the
+    /// workspace a backup is restored into is cleared first
+    pub components_to_delete: Vec<ComponentId>,
+    /// The unique ids of the edges that would be created
+    pub edges_to_create: Vec<String>,
+    /// Funcs that would be created, by name. Funcs already installed by hash are reused and are
+    /// in neither this list nor [`funcs_to_update`](Self::funcs_to_update)
+    pub funcs_to_create: Vec<String>,
+    /// Existing funcs that would be updated in place, by name
+    pub funcs_to_update: Vec<String>,
+    /// The attributes that would be skipped, by component name
+    pub attribute_skips: Vec<(String, Vec<ImportAttributeSkip>)>,
+    /// The edges that would be skipped
+    pub edge_skips: Vec<ImportEdgeSkip>,
+}
+
+impl ImportPlan {
+    fn record_func(&mut self, name: &str, updates_existing: bool) {
+        let funcs = if updates_existing {
+            &mut self.funcs_to_update
+        } else {
+            &mut self.funcs_to_create
+        };
+        if !funcs.iter().any(|func| func == name) {
+            funcs.push(name.to_owned());
+        }
+    }
+
+    fn record_skips(&mut self, import_skips: &[ImportSkips]) {
+        for skips in import_skips {
+            self.attribute_skips
+                .extend(skips.attribute_skips.iter().cloned());
+            self.edge_skips.extend(skips.edge_skips.iter().cloned());
+        }
+    }
+}
+
+/// Imports every change set in a workspace backup, recording progress in `checkpoint` as it goes.
+/// Returns `None` if the import stopped early because [`ImportOptions::max_change_sets`] was
+/// reached.
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all)]
+async fn import_workspace_backup(
+    ctx: &DalContext,
+    pkg: &SiPkg,
+    metadata: &SiPkgMetadata,
+    installed_pkg_id: Option<InstalledPkgId>,
+    change_set_things: &mut ThingMap,
+    options: &ImportOptions,
+    override_builtin_schema_feature_flag: bool,
+    checkpoint: &mut ImportCheckpoint,
+    stats: &mut ImportStats,
+    mut plan: Option<&mut ImportPlan>,
+) -> PkgResult<Option<Vec<ImportSkips>>> {
+    let mut import_skips = vec![];
+
+    let default_change_set_name = metadata.default_change_set().unwrap_or("head");
 
-    if InstalledPkg::find_by_hash(ctx, &root_hash).await?.is_some() {
-        return Err(PkgError::PackageAlreadyInstalled(root_hash));
-    }
+    let change_sets = pkg.change_sets()?;
+    let default_change_set = change_sets
+        .iter()
+        .find(|cs| cs.name() == default_change_set_name)
+        .ok_or(PkgError::WorkspaceBackupNoDefaultChangeSet(
+            default_change_set_name.into(),
+        ))?;
 
-    let metadata = pkg.metadata()?;
+    checkpoint
+        .restore_components(ctx, change_set_things)
+        .await?;
+
+    let mut imported_change_sets = 0;
 
-    let installed_pkg_id = if options.no_record {
-        None
+    if checkpoint
+        .completed_change_set(default_change_set_name)
+        .is_some()
+    {
+        // The default change set was imported in full, but later change sets fall back to the
+        // things it created, so we import its funcs and schemas again. Both are found by hash
+        // via their installed package assets, so this does not create them twice.
+        import_change_set(
+            ctx,
+            ChangeSetPk::NONE,
+            metadata,
+            &default_change_set.funcs()?,
+            &default_change_set.schemas()?,
+            &[],
+            &[],
+            installed_pkg_id,
+            change_set_things,
+            options,
+            override_builtin_schema_feature_flag,
+            checkpoint,
+            stats,
+            &mut vec![],
+            &mut vec![],
+            &mut vec![],
+            plan.as_deref_mut(),
+        )
+        .await?;
     } else {
-        Some(
-            *InstalledPkg::new(ctx, metadata.name(), pkg.hash()?.to_string())
-                .await?
-                .id(),
+        let mut func_errors = vec![];
+        let mut schema_skips = vec![];
+        let mut qualification_errors = vec![];
+        let (_, attribute_skips, edge_skips, attr_func_skips, _) = import_change_set(
+            ctx,
+            ChangeSetPk::NONE,
+            metadata,
+            &default_change_set.funcs()?,
+            &default_change_set.schemas()?,
+            &default_change_set.components()?,
+            &default_change_set.edges()?,
+            installed_pkg_id,
+            change_set_things,
+            options,
+            override_builtin_schema_feature_flag,
+            checkpoint,
+            stats,
+            &mut func_errors,
+            &mut schema_skips,
+            &mut qualification_errors,
+            plan.as_deref_mut(),
         )
-    };
-
-    let mut change_set_things = ThingMap::new();
+        .await?;
 
-    match metadata.kind() {
-        SiPkgKind::Module => {
-            let (installed_schema_variant_ids, _, _) = import_change_set(
-                ctx,
-                ctx.visibility().change_set_pk,
-                &metadata,
-                &pkg.funcs()?,
-                &pkg.schemas()?,
-                &[],
-                &[],
-                installed_pkg_id,
-                &mut change_set_things,
-                &options,
-                override_builtin_schema_feature_flag,
-            )
-            .await?;
+        checkpoint.complete_change_set(default_change_set_name, ChangeSetPk::NONE);
+        imported_change_sets += 1;
+
+        import_skips.push(ImportSkips {
+            version: IMPORT_SKIPS_VERSION,
+            change_set_pk: ChangeSetPk::NONE,
+            attribute_skips,
+            edge_skips,
+            attr_func_skips,
+            func_errors,
+            schema_skips,
+            qualification_errors,
+        });
+    }
 
-            Ok((installed_pkg_id, installed_schema_variant_ids, None))
+    for change_set in change_sets {
+        if change_set.name() == default_change_set_name
+            || checkpoint.completed_change_set(change_set.name()).is_some()
+        {
+            continue;
         }
-        SiPkgKind::WorkspaceBackup => {
-            let mut ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
-
-            let mut import_skips = vec![];
-
-            let workspace_pk = WorkspacePk::from_str(
-                metadata
-                    .workspace_pk()
-                    .ok_or(PkgError::WorkspacePkNotInBackup)?,
-            )?;
-            let workspace_name = metadata
-                .workspace_name()
-                .ok_or(PkgError::WorkspaceNameNotInBackup)?;
-            let default_change_set_name = metadata.default_change_set().unwrap_or("head");
-
-            Workspace::clear_or_create_workspace(&mut ctx, workspace_pk, workspace_name).await?;
-
-            ctx.update_tenancy(Tenancy::new(workspace_pk));
 
-            let change_sets = pkg.change_sets()?;
-            let default_change_set = change_sets
-                .iter()
-                .find(|cs| cs.name() == default_change_set_name)
-                .ok_or(PkgError::WorkspaceBackupNoDefaultChangeSet(
-                    default_change_set_name.into(),
-                ))?;
+        if let Some(only_change_set) = &options.only_change_set {
+            if change_set.name() != only_change_set {
+                continue;
+            }
+        }
 
-            let (_, attribute_skips, edge_skips) = import_change_set(
-                &ctx,
-                ChangeSetPk::NONE,
-                &metadata,
-                &default_change_set.funcs()?,
-                &default_change_set.schemas()?,
-                &default_change_set.components()?,
-                &default_change_set.edges()?,
-                installed_pkg_id,
-                &mut change_set_things,
-                &options,
-                override_builtin_schema_feature_flag,
-            )
-            .await?;
+        if options
+            .max_change_sets
+            .map(|max| imported_change_sets >= max)
+            .unwrap_or(false)
+        {
+            return Ok(None);
+        }
 
-            import_skips.push(ImportSkips {
-                change_set_pk: ChangeSetPk::NONE,
-                attribute_skips,
-                edge_skips,
-            });
+        // Revert to head to create new change set
+        let change_set_pk = {
+            let ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
+            ChangeSet::new(&ctx, change_set.name(), None).await?.pk
+        };
 
-            for change_set in change_sets {
-                if change_set.name() == default_change_set_name {
-                    continue;
-                }
+        // Switch to new change set visibility
+        let ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set_pk));
 
-                // Revert to head to create new change set
-                let ctx = ctx.clone_with_new_visibility(ctx.visibility().to_head());
-                let new_cs = ChangeSet::new(&ctx, change_set.name(), None).await?;
-                // Switch to new change set visibility
-                let ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_cs.pk));
-
-                let (_, attribute_skips, edge_skips) = import_change_set(
-                    &ctx,
-                    new_cs.pk,
-                    &metadata,
-                    &change_set.funcs()?,
-                    &change_set.schemas()?,
-                    &change_set.components()?,
-                    &change_set.edges()?,
-                    installed_pkg_id,
-                    &mut change_set_things,
-                    &options,
-                    override_builtin_schema_feature_flag,
-                )
-                .await?;
+        let mut func_errors = vec![];
+        let mut schema_skips = vec![];
+        let mut qualification_errors = vec![];
+        let (_, attribute_skips, edge_skips, attr_func_skips, _) = import_change_set(
+            &ctx,
+            change_set_pk,
+            metadata,
+            &change_set.funcs()?,
+            &change_set.schemas()?,
+            &change_set.components()?,
+            &change_set.edges()?,
+            installed_pkg_id,
+            change_set_things,
+            options,
+            override_builtin_schema_feature_flag,
+            checkpoint,
+            stats,
+            &mut func_errors,
+            &mut schema_skips,
+            &mut qualification_errors,
+            plan.as_deref_mut(),
+        )
+        .await?;
 
-                import_skips.push(ImportSkips {
-                    change_set_pk: new_cs.pk,
-                    attribute_skips,
-                    edge_skips,
-                });
-            }
+        checkpoint.complete_change_set(change_set.name(), change_set_pk);
+        imported_change_sets += 1;
 
-            Ok((
-                None,
-                vec![],
-                if import_skips.is_empty() {
-                    None
-                } else {
-                    Some(import_skips)
-                },
-            ))
-        }
+        import_skips.push(ImportSkips {
+            version: IMPORT_SKIPS_VERSION,
+            change_set_pk,
+            attribute_skips,
+            edge_skips,
+            attr_func_skips,
+            func_errors,
+            schema_skips,
+            qualification_errors,
+        });
     }
+
+    Ok(Some(import_skips))
 }
 
+/// Imports the package at `pkg_file_path`. If `expected_hash` is set, the package is refused
+/// with [`PkgError::HashMismatch`] before anything is imported unless its root hash matches, so
+/// that a pipeline can pin the exact package it expects to install.
+#[instrument(level = "info", skip_all, fields(pkg.path = ?pkg_file_path.as_ref()))]
 pub async fn import_pkg(
     ctx: &DalContext,
     pkg_file_path: impl AsRef<Path>,
+    expected_hash: Option<String>,
     override_builtin_schema_feature_flag: bool,
-) -> PkgResult<SiPkg> {
-    println!("Importing package from {:?}", pkg_file_path.as_ref());
+) -> PkgResult<(SiPkg, Option<Vec<ImportSkips>>)> {
+    info!("importing package from file");
     let pkg = SiPkg::load_from_file(&pkg_file_path).await?;
 
-    import_pkg_from_pkg(ctx, &pkg, None, override_builtin_schema_feature_flag).await?;
+    if let Some(expected) = expected_hash {
+        let actual = pkg.hash()?.to_string();
+        if actual != expected {
+            return Err(PkgError::HashMismatch { expected, actual });
+        }
+    }
+
+    let import_skips = import_pkg_from_pkg(ctx, &pkg, None, override_builtin_schema_feature_flag)
+        .await?
+        .skips;
+
+    for skips in import_skips.iter().flatten() {
+        if !skips.is_empty() {
+            warn!("import skipped data: {}", skips.summary());
+        }
+    }
 
-    Ok(pkg)
+    Ok((pkg, import_skips))
 }
 
-async fn create_func(ctx: &DalContext, func_spec: &FuncSpec) -> PkgResult<Func> {
-    let name = func_spec.name.clone();
+/// Finds the first name of the form `{name}-{n}` that is not used by any func.
+async fn unique_func_name(ctx: &DalContext, name: &str) -> PkgResult<String> {
+    let mut suffix = 2;
+    loop {
+        let candidate = format!("{name}-{suffix}");
+        if Func::find_by_name(ctx, &candidate).await?.is_none() {
+            return Ok(candidate);
+        }
+        suffix += 1;
+    }
+}
 
+async fn create_func(
+    ctx: &DalContext,
+    func_spec: &FuncSpec,
+    func_name_conflict: FuncNameConflictPolicy,
+    strip_invalid_links: bool,
+) -> PkgResult<Func> {
     let func_spec_data = func_spec
         .data
         .clone()
-        .ok_or_else(|| PkgError::DataNotFound(name.clone()))?;
+        .ok_or_else(|| PkgError::DataNotFound(func_spec.name.clone()))?;
+
+    let mut name_taken = false;
+    if func_name_conflict != FuncNameConflictPolicy::Allow {
+        for existing_func in Func::find_by_attr(ctx, "name", &func_spec.name).await? {
+            if !existing_func.builtin() && !existing_func.is_builtin(ctx).await? {
+                name_taken = true;
+                break;
+            }
+        }
+    }
 
-    // How to handle name conflicts?
-    let mut func = Func::new(
-        ctx,
-        name,
-        func_spec_data.backend_kind.into(),
-        func_spec_data.response_type.into(),
-    )
-    .await?;
+    let name = match (name_taken, func_name_conflict) {
+        (true, FuncNameConflictPolicy::Error) => {
+            return Err(PkgError::FuncNameConflict(func_spec.name.clone()));
+        }
+        (true, FuncNameConflictPolicy::Suffix) => unique_func_name(ctx, &func_spec.name).await?,
+        _ => func_spec.name.clone(),
+    };
+
+    let (backend_kind, response_type) = validate_func_kind_response_pair(func_spec_data)?;
+    let link = import_link(func_spec_data.link.as_ref(), strip_invalid_links, |link| {
+        PkgError::InvalidFuncLink {
+            func: func_spec_data.name.clone(),
+            link,
+        }
+    })?;
+    let mut func = Func::new(ctx, name, backend_kind, response_type).await?;
 
     func.set_display_name(ctx, func_spec_data.display_name.clone())
         .await?;
@@ -1455,36 +3680,232 @@ async fn create_func(ctx: &DalContext, func_spec: &FuncSpec) -> PkgResult<Func>
     func.set_handler(ctx, Some(func_spec_data.handler.clone()))
         .await?;
     func.set_hidden(ctx, func_spec_data.hidden).await?;
-    func.set_link(ctx, func_spec_data.link.map(|l| l.to_string()))
-        .await?;
+    func.set_link(ctx, link).await?;
 
     Ok(func)
 }
 
+/// Creates the func with [`with_savepoint`], so that if creating it fails the func is rolled back
+/// and the transaction can still be used by the rest of the import. The outer result is an error
+/// only if the savepoint itself fails.
+async fn create_func_or_roll_back(
+    ctx: &DalContext,
+    func_spec: &FuncSpec,
+    func_name_conflict: FuncNameConflictPolicy,
+    strip_invalid_links: bool,
+) -> PkgResult<PkgResult<Func>> {
+    with_savepoint(
+        ctx,
+        "pkg_import_func",
+        SavepointEnd::ReleaseOnSuccess,
+        create_func(ctx, func_spec, func_name_conflict, strip_invalid_links),
+    )
+    .await
+}
+
+fn validate_func_kind_response_pair(
+    func_spec_data: &FuncSpecData,
+) -> PkgResult<(FuncBackendKind, FuncBackendResponseType)> {
+    let kind: FuncBackendKind = func_spec_data.backend_kind.into();
+    let response_type: FuncBackendResponseType = func_spec_data.response_type.into();
+
+    if !kind.is_valid_response_type(response_type) {
+        return Err(PkgError::InvalidFuncKindResponsePair {
+            func: func_spec_data.name.clone(),
+            kind,
+            response_type,
+        });
+    }
+
+    Ok((kind, response_type))
+}
+
+/// Updates an existing func from a func spec, only writing the fields that differ, since each
+/// write is a query and a history event.
 async fn update_func(
     ctx: &DalContext,
     func: &mut Func,
     func_spec_data: &FuncSpecData,
+    strip_invalid_links: bool,
 ) -> PkgResult<()> {
-    func.set_name(ctx, func_spec_data.name.clone()).await?;
-    func.set_backend_kind(ctx, func_spec_data.backend_kind)
-        .await?;
-    func.set_backend_response_type(ctx, func_spec_data.response_type)
-        .await?;
-    func.set_display_name(ctx, func_spec_data.display_name.clone())
-        .await?;
-    func.set_code_base64(ctx, Some(func_spec_data.code_base64.clone()))
-        .await?;
-    func.set_description(ctx, func_spec_data.description.clone())
-        .await?;
-    func.set_handler(ctx, Some(func_spec_data.handler.clone()))
-        .await?;
-    func.set_hidden(ctx, func_spec_data.hidden).await?;
-    func.set_link(ctx, func_spec_data.link.clone()).await?;
+    let (backend_kind, response_type) = validate_func_kind_response_pair(func_spec_data)?;
+    let link = import_link(func_spec_data.link.as_ref(), strip_invalid_links, |link| {
+        PkgError::InvalidFuncLink {
+            func: func_spec_data.name.clone(),
+            link,
+        }
+    })?;
+
+    if func.name() != func_spec_data.name {
+        func.set_name(ctx, func_spec_data.name.clone()).await?;
+    }
+    if *func.backend_kind() != backend_kind {
+        func.set_backend_kind(ctx, backend_kind).await?;
+    }
+    if *func.backend_response_type() != response_type {
+        func.set_backend_response_type(ctx, response_type).await?;
+    }
+    if func.display_name() != func_spec_data.display_name.as_deref() {
+        func.set_display_name(ctx, func_spec_data.display_name.clone())
+            .await?;
+    }
+    if func.code_base64() != Some(func_spec_data.code_base64.as_str()) {
+        func.set_code_base64(ctx, Some(func_spec_data.code_base64.clone()))
+            .await?;
+    }
+    if func.description() != func_spec_data.description.as_deref() {
+        func.set_description(ctx, func_spec_data.description.clone())
+            .await?;
+    }
+    if func.handler() != Some(func_spec_data.handler.as_str()) {
+        func.set_handler(ctx, Some(func_spec_data.handler.clone()))
+            .await?;
+    }
+    if func.hidden() != func_spec_data.hidden {
+        func.set_hidden(ctx, func_spec_data.hidden).await?;
+    }
+    if func.link() != link.as_deref() {
+        func.set_link(ctx, link).await?;
+    }
+
+    Ok(())
+}
+
+/// Finds the installed func that `func_spec` refers to, by its unique id or else by its name. A
+/// func with the name that was not installed from a package is not considered.
+async fn find_installed_func(ctx: &DalContext, func_spec: &FuncSpec) -> PkgResult<Option<Func>> {
+    let mut candidates = Func::find_by_attr(ctx, "source_unique_id", &func_spec.unique_id).await?;
+    candidates.extend(Func::find_by_name(ctx, &func_spec.name).await?);
+
+    for func in candidates {
+        if !installed_func_assets(ctx, *func.id()).await?.is_empty() {
+            return Ok(Some(func));
+        }
+    }
+
+    Ok(None)
+}
+
+async fn installed_func_assets(
+    ctx: &DalContext,
+    func_id: FuncId,
+) -> PkgResult<Vec<InstalledPkgAsset>> {
+    Ok(
+        InstalledPkgAsset::find_by_attr(ctx, "asset_id", &func_id.to_string())
+            .await?
+            .into_iter()
+            .filter(|asset| matches!(asset.asset_kind(), InstalledPkgAssetKind::Func))
+            .collect(),
+    )
+}
+
+/// Deletes an installed func along with the records of its installation, so that installing it
+/// again later creates it anew instead of finding the deleted func by hash.
+async fn delete_installed_func(ctx: &DalContext, func: &mut Func) -> PkgResult<()> {
+    for mut asset in installed_func_assets(ctx, *func.id()).await? {
+        asset.delete_by_id(ctx).await?;
+    }
+    func.delete_by_id(ctx).await?;
 
     Ok(())
 }
 
+/// The funcs that a change set's funcs may already exist as, loaded with one query per kind of
+/// lookup before the funcs are imported instead of with a few queries per func: the funcs installed
+/// from a func spec with the same hash, and the funcs with the names of the funcs
+/// [`updates_func_by_name`]. The import keeps it up to date as it installs and deletes funcs.
+#[derive(Debug, Default)]
+struct ExistingFuncCache {
+    installed: HashMap<String, FuncId>,
+    funcs: HashMap<FuncId, Func>,
+    by_name: HashMap<String, Func>,
+}
+
+impl ExistingFuncCache {
+    async fn load(ctx: &DalContext, funcs: &[SiPkgFunc<'_>]) -> PkgResult<Self> {
+        let mut cache = Self::default();
+
+        let hashes: Vec<String> = funcs
+            .iter()
+            .map(|func_spec| func_spec.hash().to_string())
+            .collect();
+        if !hashes.is_empty() {
+            let hashes: Vec<&String> = hashes.iter().collect();
+            for asset in InstalledPkgAsset::find_by_attr_in(ctx, "asset_hash", &hashes).await? {
+                if !matches!(asset.asset_kind(), InstalledPkgAssetKind::Func) {
+                    continue;
+                }
+                if let InstalledPkgAssetTyped::Func { id, .. } = asset.as_installed_func()? {
+                    cache.installed.insert(asset.asset_hash().to_owned(), id);
+                }
+            }
+        }
+
+        let func_ids: Vec<&FuncId> = cache.installed.values().collect();
+        if !func_ids.is_empty() {
+            for func in Func::find_by_attr_in(ctx, "id", &func_ids).await? {
+                cache.funcs.insert(*func.id(), func);
+            }
+        }
+
+        let names: Vec<String> = funcs
+            .iter()
+            .filter(|func_spec| updates_func_by_name(func_spec))
+            .map(|func_spec| func_spec.name().to_owned())
+            .collect();
+        if !names.is_empty() {
+            let names: Vec<&String> = names.iter().collect();
+            for func in Func::find_by_attr_in(ctx, "name", &names).await? {
+                cache.by_name.insert(func.name().to_owned(), func);
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// The func installed from a func spec with this hash, if any.
+    fn installed_func(&self, hash: &str) -> PkgResult<Option<Func>> {
+        match self.installed.get(hash) {
+            Some(func_id) => match self.funcs.get(func_id) {
+                Some(func) => Ok(Some(func.to_owned())),
+                None => Err(PkgError::InstalledFuncMissing(*func_id)),
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// The func with this name, if any.
+    fn func_by_name(&self, name: &str) -> Option<Func> {
+        self.by_name.get(name).cloned()
+    }
+
+    /// Records a func installed by the import, so that a func spec with the same hash imported
+    /// later on reuses it.
+    fn add_installed(&mut self, hash: String, func: &Func) {
+        self.installed.insert(hash, *func.id());
+        self.funcs.insert(*func.id(), func.to_owned());
+    }
+
+    /// Records a func created or updated by name, so that a func with the same name imported
+    /// later on finds it as it is now.
+    fn add_named(&mut self, func: &Func) {
+        self.by_name.insert(func.name().to_owned(), func.to_owned());
+    }
+
+    /// Forgets a func deleted by the import. Its installation records are only forgotten if they
+    /// were deleted along with it.
+    fn remove(&mut self, func_id: FuncId, installation_deleted: bool) {
+        if installation_deleted {
+            self.installed
+                .retain(|_, installed_func_id| *installed_func_id != func_id);
+        }
+        self.funcs.remove(&func_id);
+        self.by_name.retain(|_, func| *func.id() != func_id);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all, fields(func.name = %func_spec.name))]
 async fn import_func(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
@@ -1493,47 +3914,57 @@ async fn import_func(
     installed_pkg_id: Option<InstalledPkgId>,
     thing_map: &mut ThingMap,
     is_builtin: bool,
+    func_name_conflict: FuncNameConflictPolicy,
+    strip_invalid_links: bool,
+    func_errors: Option<&mut Vec<(String, String)>>,
+    existing_funcs: &mut ExistingFuncCache,
 ) -> PkgResult<Option<Func>> {
-    let mut func = {
-        let existing_func = InstalledPkgAsset::list_for_kind_and_hash(
-            ctx,
-            InstalledPkgAssetKind::Func,
-            &hash.map_or_else(String::new, |h| h.to_string()),
-        )
-        .await?
-        .pop();
+    // Deleting a func changes the hash of its spec, so an installed func that the package
+    // deletes is not found by hash below
+    if func_spec.deleted {
+        if let Some(mut func) = find_installed_func(ctx, func_spec).await? {
+            delete_installed_func(ctx, &mut func).await?;
+            existing_funcs.remove(*func.id(), true);
+            return Ok(None);
+        }
+    }
 
-        if let Some(installed_func_record) = existing_func {
-            match installed_func_record.as_installed_func()? {
-                InstalledPkgAssetTyped::Func { id, .. } => match Func::get_by_id(ctx, &id).await? {
-                    Some(mut func) => {
-                        if is_builtin {
-                            func.set_builtin(ctx, true).await?
-                        }
+    let mut func = {
+        let installed_func =
+            existing_funcs.installed_func(&hash.map_or_else(String::new, |h| h.to_string()))?;
+
+        if let Some(mut func) = installed_func {
+            // The func is already installed, but this package deletes it, so it must not survive
+            // the import
+            if func_spec.deleted {
+                delete_installed_func(ctx, &mut func).await?;
+                existing_funcs.remove(*func.id(), true);
+                return Ok(None);
+            }
 
-                        if let (Some(installed_pkg_id), Some(hash)) = (installed_pkg_id, hash) {
-                            InstalledPkgAsset::new(
-                                ctx,
-                                InstalledPkgAssetTyped::new_for_func(
-                                    *func.id(),
-                                    installed_pkg_id,
-                                    hash.to_string(),
-                                ),
-                            )
-                            .await?;
-                        }
+            if is_builtin {
+                func.set_builtin(ctx, true).await?
+            }
 
-                        thing_map.insert(
-                            change_set_pk,
-                            func_spec.unique_id.clone(),
-                            Thing::Func(func.to_owned()),
-                        );
-                        None
-                    }
-                    None => return Err(PkgError::InstalledFuncMissing(id)),
-                },
-                _ => unreachable!(),
+            if let (Some(installed_pkg_id), Some(hash)) = (installed_pkg_id, hash) {
+                InstalledPkgAsset::new(
+                    ctx,
+                    InstalledPkgAssetTyped::new_for_func(
+                        *func.id(),
+                        installed_pkg_id,
+                        hash.to_string(),
+                    ),
+                )
+                .await?;
+                existing_funcs.add_installed(hash.to_string(), &func);
             }
+
+            thing_map.insert(
+                change_set_pk,
+                func_spec.unique_id.clone(),
+                Thing::Func(func.to_owned()),
+            );
+            None
         } else {
             let existing_func = thing_map.get(change_set_pk, &func_spec.unique_id.clone());
 
@@ -1543,11 +3974,12 @@ async fn import_func(
 
                     if func_spec.deleted {
                         existing_func.delete_by_id(ctx).await?;
+                        existing_funcs.remove(*existing_func.id(), false);
 
                         None
                     } else {
                         if let Some(data) = &func_spec.data {
-                            update_func(ctx, &mut existing_func, data).await?;
+                            update_func(ctx, &mut existing_func, data, strip_invalid_links).await?;
                         }
 
                         Some(existing_func)
@@ -1559,7 +3991,39 @@ async fn import_func(
                         // deleted only in a change set. Do nothing
                         None
                     } else {
-                        Some(create_func(ctx, func_spec).await?)
+                        match func_errors {
+                            Some(func_errors) => {
+                                match create_func_or_roll_back(
+                                    ctx,
+                                    func_spec,
+                                    func_name_conflict,
+                                    strip_invalid_links,
+                                )
+                                .await?
+                                {
+                                    Ok(func) => Some(func),
+                                    Err(err) => {
+                                        warn!(
+                                            func.unique_id = %func_spec.unique_id,
+                                            error = %err,
+                                            "could not import func, continuing without it"
+                                        );
+                                        func_errors
+                                            .push((func_spec.unique_id.clone(), err.to_string()));
+                                        return Ok(None);
+                                    }
+                                }
+                            }
+                            None => Some(
+                                create_func(
+                                    ctx,
+                                    func_spec,
+                                    func_name_conflict,
+                                    strip_invalid_links,
+                                )
+                                .await?,
+                            ),
+                        }
                     }
                 }
             }
@@ -1571,6 +4035,11 @@ async fn import_func(
             func.set_builtin(ctx, true).await?
         }
 
+        if func.source_unique_id() != Some(func_spec.unique_id.as_str()) {
+            func.set_source_unique_id(ctx, Some(func_spec.unique_id.clone()))
+                .await?;
+        }
+
         if let (Some(installed_pkg_id), Some(hash)) = (installed_pkg_id, hash) {
             InstalledPkgAsset::new(
                 ctx,
@@ -1581,6 +4050,7 @@ async fn import_func(
                 ),
             )
             .await?;
+            existing_funcs.add_installed(hash.to_string(), func);
         }
 
         thing_map.insert(
@@ -1705,28 +4175,84 @@ async fn update_schema(
             .await?;
     }
 
-    if let Some(mut ui_menu) = schema.ui_menus(ctx).await?.pop() {
-        if let Some(category_name) = schema_spec_data.category_name() {
-            if category_name != ui_menu.name() {
-                ui_menu.set_name(ctx, category_name).await?;
-            }
-            if schema_spec_data.category() != ui_menu.category() {
-                ui_menu.set_name(ctx, schema_spec_data.category()).await?;
-            }
+    if let Some(mut ui_menu) = schema.ui_menus(ctx).await?.pop() {
+        if let Some(category_name) = schema_spec_data.category_name() {
+            if category_name != ui_menu.name() {
+                ui_menu.set_name(ctx, category_name).await?;
+            }
+            if schema_spec_data.category() != ui_menu.category() {
+                ui_menu.set_name(ctx, schema_spec_data.category()).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Logs a warning if importing the schema took longer than [`ImportOptions::slow_schema_warn_ms`].
+async fn warn_if_slow_schema(
+    schema_spec: &SiPkgSchema<'_>,
+    elapsed: Duration,
+    options: &ImportOptions,
+) -> PkgResult<()> {
+    let threshold = match options.slow_schema_warn_ms {
+        Some(threshold_ms) => Duration::from_millis(threshold_ms),
+        None => return Ok(()),
+    };
+    if elapsed <= threshold {
+        return Ok(());
+    }
+
+    let variants = schema_spec.variants()?;
+    let props = AtomicUsize::new(0);
+    for variant in &variants {
+        for prop_root in [
+            SchemaVariantSpecPropRoot::Domain,
+            SchemaVariantSpecPropRoot::Secrets,
+        ] {
+            variant
+                .visit_prop_tree(prop_root, count_prop, None, &props)
+                .await?;
         }
     }
 
+    warn!(
+        schema.name = schema_spec.name(),
+        elapsed_ms = elapsed.as_millis() as u64,
+        threshold_ms = threshold.as_millis() as u64,
+        variants = variants.len(),
+        props = props.into_inner(),
+        "slow schema import"
+    );
+
     Ok(())
 }
 
-async fn import_schema(
+async fn count_prop(
+    _spec: SiPkgProp<'_>,
+    _parent: Option<()>,
+    props: &AtomicUsize,
+) -> PkgResult<Option<()>> {
+    props.fetch_add(1, Ordering::Relaxed);
+    Ok(None)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all, fields(schema.name = schema_spec.name()))]
+async fn import_schema<'a>(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
-    schema_spec: &SiPkgSchema<'_>,
+    schema_spec: &SiPkgSchema<'a>,
     installed_pkg_id: Option<InstalledPkgId>,
     thing_map: &mut ThingMap,
     metadata: &SiPkgMetadata,
-) -> PkgResult<(Option<SchemaId>, Vec<SchemaVariantId>)> {
+    options: &ImportOptions,
+    mut deferred_finalizes: Option<&mut Vec<PendingVariantFinalize<'a>>>,
+) -> PkgResult<(
+    Option<SchemaId>,
+    Vec<SchemaVariantId>,
+    ImportedDefaultValues,
+)> {
     let hash = schema_spec.hash().to_string();
     let schema = {
         let existing_schema =
@@ -1804,8 +4330,9 @@ async fn import_schema(
         }
 
         let mut installed_schema_variant_ids = vec![];
+        let mut default_values = vec![];
         for variant_spec in &schema_spec.variants()? {
-            let variant = import_schema_variant(
+            let (variant, variant_default_values) = import_schema_variant(
                 ctx,
                 change_set_pk,
                 &mut schema,
@@ -1813,8 +4340,11 @@ async fn import_schema(
                 installed_pkg_id,
                 thing_map,
                 metadata,
+                options,
+                deferred_finalizes.as_deref_mut(),
             )
             .await?;
+            default_values.extend(variant_default_values);
 
             if let Some(variant) = variant {
                 installed_schema_variant_ids.push(*variant.id());
@@ -1834,27 +4364,33 @@ async fn import_schema(
                     )
                     .await?;
 
-                    if let Thing::Func(asset_func) =
-                        thing_map
-                            .get(change_set_pk, &func_unique_id)
-                            .ok_or(PkgError::MissingFuncUniqueId(func_unique_id.to_string()))?
-                    {
-                        create_schema_variant_definition(
-                            ctx,
-                            schema_spec.clone(),
-                            installed_pkg_id,
-                            *variant.id(),
-                            asset_func,
-                        )
-                        .await?;
+                    match thing_map.get(change_set_pk, &func_unique_id) {
+                        Some(Thing::Func(asset_func)) => {
+                            create_schema_variant_definition(
+                                ctx,
+                                schema_spec.clone(),
+                                installed_pkg_id,
+                                *variant.id(),
+                                asset_func,
+                            )
+                            .await?;
+                        }
+                        Some(Thing::SkippedFunc) | None => {
+                            return Err(PkgError::MissingFuncUniqueId(func_unique_id));
+                        }
+                        Some(_) => {}
                     }
                 }
             }
         }
 
-        Ok((Some(*schema.id()), installed_schema_variant_ids))
+        Ok((
+            Some(*schema.id()),
+            installed_schema_variant_ids,
+            default_values,
+        ))
     } else {
-        Ok((None, vec![]))
+        Ok((None, vec![], vec![]))
     }
 }
 
@@ -1912,11 +4448,11 @@ async fn create_schema_variant_definition(
         None => {
             let maybe_schema_variant_definition =
                 SchemaVariantDefinition::get_by_func_id(ctx, *asset_func.id()).await?;
+            let spec = schema_spec.to_spec().await?;
+            let metadata = SchemaVariantDefinitionJson::metadata_from_spec(spec)?;
+
             let mut schema_variant_definition = match maybe_schema_variant_definition {
                 None => {
-                    let spec = schema_spec.to_spec().await?;
-                    let metadata = SchemaVariantDefinitionJson::metadata_from_spec(spec)?;
-
                     let mut svd = SchemaVariantDefinition::new(
                         ctx,
                         metadata.name,
@@ -1934,7 +4470,25 @@ async fn create_schema_variant_definition(
 
                     svd
                 }
-                Some(schema_variant_definition) => schema_variant_definition,
+                Some(mut schema_variant_definition) => {
+                    if metadata.description.is_some()
+                        && schema_variant_definition.description()
+                            != metadata.description.as_deref()
+                    {
+                        schema_variant_definition
+                            .set_description(ctx, metadata.description)
+                            .await?;
+                    }
+                    if metadata.link.is_some()
+                        && schema_variant_definition.link() != metadata.link.as_deref()
+                    {
+                        schema_variant_definition
+                            .set_link(ctx, metadata.link)
+                            .await?;
+                    }
+
+                    schema_variant_definition
+                }
             };
 
             schema_variant_definition
@@ -1980,26 +4534,18 @@ struct AttrFuncInfo {
     inputs: Vec<SiPkgAttrFuncInputView>,
 }
 
-#[remain::sorted]
+/// A default value to set once the schema variant is finalized, already checked with
+/// [`PropKind::coerce_default`]
 #[derive(Clone, Debug)]
-enum DefaultValueInfo {
-    Boolean {
-        prop_id: PropId,
-        default_value: bool,
-    },
-    Number {
-        prop_id: PropId,
-        default_value: i64,
-    },
-    String {
-        prop_id: PropId,
-        default_value: String,
-    },
+struct DefaultValueInfo {
+    prop_id: PropId,
+    default_value: serde_json::Value,
 }
 
 struct PropVisitContext<'a> {
     pub ctx: &'a DalContext,
     pub schema_variant_id: SchemaVariantId,
+    pub strip_invalid_links: bool,
     pub attr_funcs: Mutex<Vec<AttrFuncInfo>>,
     pub default_values: Mutex<Vec<DefaultValueInfo>>,
     pub map_key_funcs: Mutex<Vec<(String, AttrFuncInfo)>>,
@@ -2035,118 +4581,88 @@ async fn import_leaf_function(
     Ok(())
 }
 
-// TODO: cache this so we don't fetch it for every socket
-async fn get_identity_func(
-    ctx: &DalContext,
-) -> PkgResult<(Func, FuncBinding, FuncBindingReturnValue, FuncArgument)> {
-    let func_name = "si:identity";
-    let func_argument_name = "identity";
-    let func: Func = Func::find_by_name(ctx, func_name)
-        .await?
-        .ok_or_else(|| FuncError::NotFoundByName(func_name.to_string()))?;
-
-    let func_id = *func.id();
-    let (func_binding, func_binding_return_value) = FuncBinding::create_and_execute(
-        ctx,
-        serde_json::json![{ "identity": null }],
-        func_id,
-        vec![],
-    )
-    .await?;
-    let func_argument = FuncArgument::find_by_name_for_func(ctx, func_argument_name, func_id)
-        .await?
-        .ok_or_else(|| {
-            PkgError::MissingIntrinsicFuncArgument(
-                func_name.to_string(),
-                func_argument_name.to_string(),
-            )
-        })?;
-
-    Ok((func, func_binding, func_binding_return_value, func_argument))
+fn find_imported_socket(
+    change_set_pk: ChangeSetPk,
+    socket_spec: &SiPkgSocket<'_>,
+    thing_map: &ThingMap,
+) -> Option<(Socket, Option<InternalProvider>, Option<ExternalProvider>)> {
+    // prop trees, including sockets and providers, are created whole cloth, so should not have
+    // differences in change sets (currently)
+    match socket_spec
+        .unique_id()
+        .and_then(|unique_id| thing_map.get(change_set_pk, &unique_id.to_owned()))
+    {
+        Some(Thing::Socket(socket_box)) => Some((
+            socket_box.0.to_owned(),
+            socket_box.1.to_owned(),
+            socket_box.2.to_owned(),
+        )),
+        _ => None,
+    }
 }
 
-async fn create_socket(
+async fn import_sockets(
     ctx: &DalContext,
-    data: &SiPkgSocketData,
+    change_set_pk: ChangeSetPk,
+    socket_specs: Vec<SiPkgSocket<'_>>,
     schema_id: SchemaId,
     schema_variant_id: SchemaVariantId,
-) -> PkgResult<(Socket, Option<InternalProvider>, Option<ExternalProvider>)> {
-    let (identity_func, identity_func_binding, identity_fbrv, _) = get_identity_func(ctx).await?;
-
-    let (mut socket, ip, ep) = match data.kind() {
-        SocketSpecKind::Input => {
-            let (ip, socket) = InternalProvider::new_explicit_with_socket(
-                ctx,
-                schema_variant_id,
-                data.name(),
-                *identity_func.id(),
-                *identity_func_binding.id(),
-                *identity_fbrv.id(),
-                data.connection_annotations(),
-                data.arity().into(),
-                false,
-            )
-            .await?;
-
-            (socket, Some(ip), None)
+    default_connection_annotations: Option<&str>,
+    thing_map: &mut ThingMap,
+) -> PkgResult<()> {
+    let mut missing_socket_data = vec![];
+    for socket_spec in &socket_specs {
+        if find_imported_socket(change_set_pk, socket_spec, thing_map).is_none() {
+            let mut socket_data = socket_spec
+                .data()
+                .ok_or(PkgError::DataNotFound(socket_spec.name().into()))?
+                .to_owned();
+            if let Some(default_connection_annotations) = default_connection_annotations {
+                socket_data.inherit_connection_annotations(default_connection_annotations);
+            }
+            missing_socket_data.push(socket_data);
         }
-        SocketSpecKind::Output => {
-            let (ep, socket) = ExternalProvider::new_with_socket(
-                ctx,
-                schema_id,
-                schema_variant_id,
-                data.name(),
-                None,
-                *identity_func.id(),
-                *identity_func_binding.id(),
-                *identity_fbrv.id(),
-                data.connection_annotations(),
-                data.arity().into(),
-                false,
-            )
-            .await?;
+    }
 
-            (socket, None, Some(ep))
-        }
-    };
+    let mut created_sockets = SchemaVariant::create_sockets_batch(
+        ctx,
+        schema_variant_id,
+        schema_id,
+        &missing_socket_data,
+    )
+    .await?
+    .into_iter();
+
+    for socket_spec in socket_specs {
+        let socket = match find_imported_socket(change_set_pk, &socket_spec, thing_map) {
+            Some(socket) => socket,
+            None => created_sockets
+                .next()
+                .ok_or(PkgError::DataNotFound(socket_spec.name().into()))?,
+        };
 
-    socket.set_ui_hidden(ctx, data.ui_hidden()).await?;
+        import_socket(
+            ctx,
+            change_set_pk,
+            socket_spec,
+            socket,
+            schema_variant_id,
+            thing_map,
+        )
+        .await?;
+    }
 
-    Ok((socket, ip, ep))
+    Ok(())
 }
 
 async fn import_socket(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
     socket_spec: SiPkgSocket<'_>,
-    schema_id: SchemaId,
+    (socket, ip, ep): (Socket, Option<InternalProvider>, Option<ExternalProvider>),
     schema_variant_id: SchemaVariantId,
     thing_map: &mut ThingMap,
 ) -> PkgResult<()> {
-    let (socket, ip, ep) = {
-        match socket_spec
-            .unique_id()
-            .and_then(|unique_id| thing_map.get(change_set_pk, &unique_id.to_owned()))
-        {
-            Some(Thing::Socket(socket_box)) => {
-                (
-                    socket_box.0.to_owned(),
-                    socket_box.1.to_owned(),
-                    socket_box.2.to_owned(),
-                )
-                // prop trees, including sockets and providers, are created whole cloth, so
-                // should not have differences in change sets (currently)
-            }
-            _ => {
-                let data = socket_spec
-                    .data()
-                    .ok_or(PkgError::DataNotFound(socket_spec.name().into()))?;
-
-                create_socket(ctx, data, schema_id, schema_variant_id).await?
-            }
-        }
-    };
-
     if let Some(unique_id) = socket_spec.unique_id() {
         thing_map.insert(
             change_set_pk,
@@ -2261,6 +4777,48 @@ async fn update_authentication_prototype(
     Ok(())
 }
 
+/// Ensures every map prop of the schema variant has exactly one item prop. A map without one
+/// is a package authoring bug, which would otherwise only surface when attribute funcs are
+/// wired up to the map's items.
+pub async fn check_map_item_props(
+    ctx: &DalContext,
+    schema_variant_id: SchemaVariantId,
+) -> PkgResult<()> {
+    for prop in SchemaVariant::all_props(ctx, schema_variant_id).await? {
+        if !matches!(prop.kind(), PropKind::Map) {
+            continue;
+        }
+
+        let item_prop_count = prop.child_props(ctx).await?.len();
+        if item_prop_count != 1 {
+            return Err(PkgError::MapPropItemPropCount(
+                prop.path().with_replaced_sep("/"),
+                item_prop_count,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the action prototypes for `func_id` on schema variants other than `schema_variant_id`
+/// whose kind differs from `kind`. A func reused across variants normally performs the same
+/// kind of action everywhere, so these usually point at a mis-wired package.
+pub async fn conflicting_action_prototypes(
+    ctx: &DalContext,
+    func_id: FuncId,
+    schema_variant_id: SchemaVariantId,
+    kind: ActionKind,
+) -> PkgResult<Vec<ActionPrototype>> {
+    Ok(ActionPrototype::find_for_func(ctx, func_id)
+        .await?
+        .into_iter()
+        .filter(|prototype| {
+            prototype.schema_variant_id() != schema_variant_id && *prototype.kind() != kind
+        })
+        .collect())
+}
+
 async fn import_action_func(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
@@ -2268,60 +4826,77 @@ async fn import_action_func(
     schema_variant_id: SchemaVariantId,
     thing_map: &ThingMap,
 ) -> PkgResult<Option<ActionPrototype>> {
-    let prototype =
-        match thing_map.get(change_set_pk, &action_func_spec.func_unique_id().to_owned()) {
-            Some(Thing::Func(func)) => {
-                let func_id = *func.id();
-
-                if let Some(unique_id) = action_func_spec.unique_id() {
-                    match thing_map.get(change_set_pk, &unique_id.to_owned()) {
-                        Some(Thing::ActionPrototype(prototype)) => {
-                            let mut prototype = prototype.to_owned();
-
-                            if action_func_spec.deleted() {
-                                prototype.delete_by_id(ctx).await?;
-                            } else {
-                                update_action_prototype(
+    let prototype = match thing_map
+        .get(change_set_pk, &action_func_spec.func_unique_id().to_owned())
+    {
+        Some(Thing::Func(func)) => {
+            let func_id = *func.id();
+
+            if !action_func_spec.deleted() {
+                let kind: ActionKind = action_func_spec.kind().into();
+                for conflicting in
+                    conflicting_action_prototypes(ctx, func_id, schema_variant_id, kind).await?
+                {
+                    warn!(
+                        func.name = func.name(),
+                        action.kind = %kind,
+                        schema_variant.id = %schema_variant_id,
+                        conflicting.kind = %conflicting.kind(),
+                        conflicting.schema_variant.id = %conflicting.schema_variant_id(),
+                        "action func is attached to another schema variant as a different kind of action"
+                    );
+                }
+            }
+
+            if let Some(unique_id) = action_func_spec.unique_id() {
+                match thing_map.get(change_set_pk, &unique_id.to_owned()) {
+                    Some(Thing::ActionPrototype(prototype)) => {
+                        let mut prototype = prototype.to_owned();
+
+                        if action_func_spec.deleted() {
+                            prototype.delete_by_id(ctx).await?;
+                        } else {
+                            update_action_prototype(
+                                ctx,
+                                &mut prototype,
+                                action_func_spec,
+                                func_id,
+                                schema_variant_id,
+                            )
+                            .await?;
+                        }
+
+                        Some(prototype)
+                    }
+                    _ => {
+                        if action_func_spec.deleted() {
+                            None
+                        } else {
+                            Some(
+                                create_action_prototype(
                                     ctx,
-                                    &mut prototype,
                                     action_func_spec,
                                     func_id,
                                     schema_variant_id,
                                 )
-                                .await?;
-                            }
-
-                            Some(prototype)
-                        }
-                        _ => {
-                            if action_func_spec.deleted() {
-                                None
-                            } else {
-                                Some(
-                                    create_action_prototype(
-                                        ctx,
-                                        action_func_spec,
-                                        func_id,
-                                        schema_variant_id,
-                                    )
-                                    .await?,
-                                )
-                            }
+                                .await?,
+                            )
                         }
                     }
-                } else {
-                    Some(
-                        create_action_prototype(ctx, action_func_spec, func_id, schema_variant_id)
-                            .await?,
-                    )
                 }
+            } else {
+                Some(
+                    create_action_prototype(ctx, action_func_spec, func_id, schema_variant_id)
+                        .await?,
+                )
             }
-            _ => {
-                return Err(PkgError::MissingFuncUniqueId(
-                    action_func_spec.func_unique_id().into(),
-                ));
-            }
-        };
+        }
+        _ => {
+            return Err(PkgError::MissingFuncUniqueId(
+                action_func_spec.func_unique_id().into(),
+            ));
+        }
+    };
 
     Ok(prototype)
 }
@@ -2414,10 +4989,12 @@ async fn create_props(
     prop_root: SchemaVariantSpecPropRoot,
     prop_root_prop_id: PropId,
     schema_variant_id: SchemaVariantId,
+    strip_invalid_links: bool,
 ) -> PkgResult<CreatePropsSideEffects> {
     let context = PropVisitContext {
         ctx,
         schema_variant_id,
+        strip_invalid_links,
         attr_funcs: Mutex::new(vec![]),
         default_values: Mutex::new(vec![]),
         map_key_funcs: Mutex::new(vec![]),
@@ -2436,6 +5013,39 @@ async fn create_props(
     })
 }
 
+/// Ensures none of the top level props of the variant's secret definition share a name with an
+/// existing child of the given parent props.
+async fn check_secret_definition_collisions(
+    ctx: &DalContext,
+    variant_spec: &SiPkgSchemaVariant<'_>,
+    parent_prop_ids: &[PropId],
+) -> PkgResult<()> {
+    let secret_definition_props = match variant_spec.to_spec().await?.secret_definition {
+        Some(PropSpec::Object { entries, .. }) => entries,
+        _ => return Ok(()),
+    };
+
+    let mut existing_names = HashSet::new();
+    for parent_prop_id in parent_prop_ids {
+        let parent_prop = Prop::get_by_id(ctx, parent_prop_id)
+            .await?
+            .ok_or(PkgError::MissingProp(*parent_prop_id))?;
+        for child_prop in parent_prop.child_props(ctx).await? {
+            existing_names.insert(child_prop.name().to_owned());
+        }
+    }
+
+    for prop_spec in &secret_definition_props {
+        if existing_names.contains(prop_spec.name()) {
+            return Err(PkgError::SecretDefinitionCollision(
+                prop_spec.name().to_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 async fn update_schema_variant(
     ctx: &DalContext,
     schema_variant: &mut SchemaVariant,
@@ -2459,15 +5069,19 @@ async fn update_schema_variant(
     Ok(())
 }
 
-async fn import_schema_variant(
+#[allow(clippy::too_many_arguments)]
+#[instrument(level = "debug", skip_all, fields(schema_variant.name = variant_spec.name()))]
+async fn import_schema_variant<'a>(
     ctx: &DalContext,
     change_set_pk: ChangeSetPk,
     schema: &mut Schema,
-    variant_spec: &SiPkgSchemaVariant<'_>,
+    variant_spec: &SiPkgSchemaVariant<'a>,
     installed_pkg_id: Option<InstalledPkgId>,
     thing_map: &mut ThingMap,
     metadata: &SiPkgMetadata,
-) -> PkgResult<Option<SchemaVariant>> {
+    options: &ImportOptions,
+    deferred_finalizes: Option<&mut Vec<PendingVariantFinalize<'a>>>,
+) -> PkgResult<(Option<SchemaVariant>, ImportedDefaultValues)> {
     let hash = variant_spec.hash().to_string();
     let mut schema_variant = {
         let existing_schema_variant = InstalledPkgAsset::list_for_kind_and_hash(
@@ -2527,6 +5141,7 @@ async fn import_schema_variant(
         }
     };
 
+    let mut default_values = vec![];
     if let Some(schema_variant) = schema_variant.as_mut() {
         if let Some(installed_pkg_id) = installed_pkg_id {
             InstalledPkgAsset::new(
@@ -2559,6 +5174,22 @@ async fn import_schema_variant(
                     schema_variant.set_color(ctx, spec_color.to_owned()).await?;
                 }
             }
+
+            if let Some(spec_color_dark) = data.color_dark() {
+                if schema_variant.default_color_dark() != Some(spec_color_dark) {
+                    schema_variant
+                        .set_default_color_dark(ctx, Some(spec_color_dark.to_owned()))
+                        .await?;
+                }
+            }
+
+            if let Some(spec_link) = data.link() {
+                if schema_variant.link() != Some(spec_link.as_str()) {
+                    schema_variant
+                        .set_link(ctx, Some(spec_link.to_string()))
+                        .await?;
+                }
+            }
         }
 
         let mut side_effects = CreatePropsSideEffects::default();
@@ -2576,6 +5207,7 @@ async fn import_schema_variant(
                 SchemaVariantSpecPropRoot::Domain,
                 domain_prop_id,
                 *schema_variant.id(),
+                options.strip_invalid_links,
             )
             .await?,
         );
@@ -2593,11 +5225,19 @@ async fn import_schema_variant(
                 SchemaVariantSpecPropRoot::Secrets,
                 secrets_prop_id,
                 *schema_variant.id(),
+                options.strip_invalid_links,
             )
             .await?,
         );
 
         if !variant_spec.secret_definitions()?.is_empty() {
+            check_secret_definition_collisions(
+                ctx,
+                variant_spec,
+                &[domain_prop_id, secrets_prop_id],
+            )
+            .await?;
+
             let secret_definition_prop_id = *Prop::new_without_ui_optionals(
                 ctx,
                 "secret_definition",
@@ -2615,11 +5255,23 @@ async fn import_schema_variant(
                     SchemaVariantSpecPropRoot::SecretDefinition,
                     secret_definition_prop_id,
                     *schema_variant.id(),
+                    options.strip_invalid_links,
                 )
                 .await?,
             );
         }
 
+        if options.repair_legacy_variants
+            && SchemaVariant::ensure_resource_value_prop(ctx, *schema_variant.id()).await?
+        {
+            // The resource payload is attached to the new prop when the variant is finalized,
+            // unless the package brings its own resource value function
+            info!(
+                schema_variant.name = variant_spec.name(),
+                "created missing /root/resource_value prop"
+            );
+        }
+
         match schema_variant
             .find_prop(ctx, &["root", "resource_value"])
             .await
@@ -2632,6 +5284,7 @@ async fn import_schema_variant(
                         SchemaVariantSpecPropRoot::ResourceValue,
                         *resource_value_prop.id(),
                         *schema_variant.id(),
+                        options.strip_invalid_links,
                     )
                     .await?,
                 );
@@ -2642,167 +5295,389 @@ async fn import_schema_variant(
             Err(err) => Err(err)?,
         };
 
-        if let Some(data) = variant_spec.data() {
-            schema_variant
-                .finalize(ctx, Some(data.component_type().into()))
-                .await?;
+        check_map_item_props(ctx, *schema_variant.id()).await?;
+
+        let pending = PendingVariantFinalize {
+            schema_id: *schema.id(),
+            schema_name: schema.name().to_owned(),
+            schema_variant: schema_variant.to_owned(),
+            variant_spec: variant_spec.to_owned(),
+            side_effects,
+        };
+        match deferred_finalizes {
+            Some(deferred_finalizes) => deferred_finalizes.push(pending),
+            None => default_values.extend(
+                finalize_imported_schema_variant(ctx, change_set_pk, pending, thing_map, None)
+                    .await?,
+            ),
+        }
+    }
+
+    Ok((schema_variant, default_values))
+}
+
+/// The part of a schema variant import that needs the variant to be finalized, which
+/// [`ImportOptions::defer_finalize`] postpones until every schema in the change set is imported.
+struct PendingVariantFinalize<'a> {
+    schema_id: SchemaId,
+    schema_name: String,
+    schema_variant: SchemaVariant,
+    variant_spec: SiPkgSchemaVariant<'a>,
+    side_effects: CreatePropsSideEffects,
+}
+
+/// Finalizes a schema variant created by the import, then imports everything that depends on the
+/// finalized variant: funcs, sockets, default values and attribute functions. If
+/// `attr_func_skips` is given, the attribute functions of the variant's props are recorded there
+/// instead of being imported.
+async fn finalize_imported_schema_variant(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
+    pending: PendingVariantFinalize<'_>,
+    thing_map: &mut ThingMap,
+    attr_func_skips: Option<&mut Vec<ImportAttrFuncSkip>>,
+) -> PkgResult<ImportedDefaultValues> {
+    let PendingVariantFinalize {
+        schema_id,
+        schema_name,
+        mut schema_variant,
+        variant_spec,
+        side_effects,
+    } = pending;
+    let mut default_values = vec![];
+
+    if let Some(data) = variant_spec.data() {
+        schema_variant
+            .finalize(ctx, Some(data.component_type().into()))
+            .await
+            .map_err(|source| PkgError::VariantFinalizeFailed {
+                schema: schema_name.to_owned(),
+                variant: variant_spec.name().to_owned(),
+                source,
+            })?;
+    }
+
+    for action_func in &variant_spec.action_funcs()? {
+        let prototype = import_action_func(
+            ctx,
+            change_set_pk,
+            action_func,
+            *schema_variant.id(),
+            thing_map,
+        )
+        .await?;
+
+        if let (Some(prototype), Some(unique_id)) = (prototype, action_func.unique_id()) {
+            thing_map.insert(
+                change_set_pk,
+                unique_id.to_owned(),
+                Thing::ActionPrototype(prototype),
+            );
+        }
+    }
+
+    for func in &variant_spec.auth_funcs()? {
+        let prototype =
+            import_auth_func(ctx, change_set_pk, func, *schema_variant.id(), thing_map).await?;
+
+        if let (Some(prototype), Some(unique_id)) = (prototype, func.unique_id()) {
+            thing_map.insert(
+                change_set_pk,
+                unique_id.to_owned(),
+                Thing::AuthPrototype(prototype),
+            );
+        }
+    }
+
+    for leaf_func in variant_spec.leaf_functions()? {
+        import_leaf_function(
+            ctx,
+            change_set_pk,
+            leaf_func,
+            *schema_variant.id(),
+            thing_map,
+        )
+        .await?;
+    }
+
+    import_sockets(
+        ctx,
+        change_set_pk,
+        variant_spec.sockets()?,
+        schema_id,
+        *schema_variant.id(),
+        variant_spec
+            .data()
+            .and_then(|data| data.default_connection_annotations()),
+        thing_map,
+    )
+    .await?;
+
+    // Default values must be set before attribute functions are configured so they don't
+    // override the prototypes set there
+    for default_value_info in side_effects.default_values {
+        default_values.push(set_default_value(ctx, default_value_info).await?);
+    }
+
+    // Set a default name value for all name props, this ensures region has a name before
+    // the function is executed
+    {
+        let name_prop = schema_variant
+            .find_prop(ctx, &["root", "si", "name"])
+            .await?;
+        let name_default_value_info = DefaultValueInfo {
+            prop_id: *name_prop.id(),
+            default_value: serde_json::json!(schema_name.to_lowercase()),
+        };
+
+        set_default_value(ctx, name_default_value_info).await?;
+    }
+
+    for si_prop_func in variant_spec.si_prop_funcs()? {
+        let prop = schema_variant
+            .find_prop(ctx, &si_prop_func.kind().prop_path())
+            .await?;
+        import_attr_func_for_prop(
+            ctx,
+            change_set_pk,
+            *schema_variant.id(),
+            AttrFuncInfo {
+                func_unique_id: si_prop_func.func_unique_id().to_owned(),
+                prop_id: *prop.id(),
+                inputs: si_prop_func
+                    .inputs()?
+                    .iter()
+                    .map(|input| input.to_owned().into())
+                    .collect(),
+            },
+            None,
+            thing_map,
+        )
+        .await?;
+    }
+
+    let mut has_resource_value_func = false;
+    for root_prop_func in variant_spec.root_prop_funcs()? {
+        if root_prop_func.prop() == SchemaVariantSpecPropRoot::ResourceValue {
+            has_resource_value_func = true;
         }
 
-        for action_func in &variant_spec.action_funcs()? {
-            let prototype = import_action_func(
-                ctx,
-                change_set_pk,
-                action_func,
-                *schema_variant.id(),
-                thing_map,
-            )
+        let prop = schema_variant
+            .find_prop(ctx, root_prop_func.prop().path_parts())
             .await?;
+        import_attr_func_for_prop(
+            ctx,
+            change_set_pk,
+            *schema_variant.id(),
+            AttrFuncInfo {
+                func_unique_id: root_prop_func.func_unique_id().to_owned(),
+                prop_id: *prop.id(),
+                inputs: root_prop_func
+                    .inputs()?
+                    .iter()
+                    .map(|input| input.to_owned().into())
+                    .collect(),
+            },
+            None,
+            thing_map,
+        )
+        .await?;
+    }
+    if !has_resource_value_func {
+        attach_resource_payload_to_value(ctx, *schema_variant.id()).await?;
+    }
 
-            if let (Some(prototype), Some(unique_id)) = (prototype, action_func.unique_id()) {
-                thing_map.insert(
-                    change_set_pk,
-                    unique_id.to_owned(),
-                    Thing::ActionPrototype(prototype),
+    let attr_funcs = side_effects
+        .attr_funcs
+        .into_iter()
+        .map(|attr_func| (attr_func, None))
+        .chain(
+            side_effects
+                .map_key_funcs
+                .into_iter()
+                .map(|(key, map_key_func)| (map_key_func, Some(key))),
+        );
+    match attr_func_skips {
+        Some(attr_func_skips) => {
+            for (attr_func, key) in attr_funcs {
+                attr_func_skips.push(
+                    skip_attr_func_for_prop(
+                        ctx,
+                        change_set_pk,
+                        *schema_variant.id(),
+                        attr_func,
+                        key,
+                        thing_map,
+                    )
+                    .await?,
                 );
             }
         }
-
-        for func in &variant_spec.auth_funcs()? {
-            let prototype =
-                import_auth_func(ctx, change_set_pk, func, *schema_variant.id(), thing_map).await?;
-
-            if let (Some(prototype), Some(unique_id)) = (prototype, func.unique_id()) {
-                thing_map.insert(
+        None => {
+            for (attr_func, key) in attr_funcs {
+                import_attr_func_for_prop(
+                    ctx,
                     change_set_pk,
-                    unique_id.to_owned(),
-                    Thing::AuthPrototype(prototype),
-                );
+                    *schema_variant.id(),
+                    attr_func,
+                    key,
+                    thing_map,
+                )
+                .await?;
             }
         }
+    }
 
-        for leaf_func in variant_spec.leaf_functions()? {
-            import_leaf_function(
-                ctx,
-                change_set_pk,
-                leaf_func,
-                *schema_variant.id(),
-                thing_map,
-            )
-            .await?;
-        }
+    Ok(default_values)
+}
 
-        for socket in variant_spec.sockets()? {
-            import_socket(
-                ctx,
-                change_set_pk,
-                socket,
-                *schema.id(),
-                *schema_variant.id(),
-                thing_map,
-            )
-            .await?;
-        }
+/// Wires up attribute functions that were skipped by an import with
+/// [`ImportOptions::skip_attribute_funcs`], as they would have been by the import itself.
+pub async fn apply_skipped_attr_funcs(
+    ctx: &DalContext,
+    attr_func_skips: &[ImportAttrFuncSkip],
+) -> PkgResult<()> {
+    let change_set_pk = ctx.visibility().change_set_pk;
+    let mut thing_map = ThingMap::new();
 
-        // Default values must be set before attribute functions are configured so they don't
-        // override the prototypes set there
-        for default_value_info in side_effects.default_values {
-            set_default_value(ctx, default_value_info).await?;
-        }
+    for attr_func_skip in attr_func_skips {
+        let func = Func::get_by_id(ctx, &attr_func_skip.func_id)
+            .await?
+            .ok_or(FuncError::NotFound(attr_func_skip.func_id))?;
+        let func_unique_id = func.id().to_string();
+        thing_map.insert(change_set_pk, func_unique_id.to_owned(), Thing::Func(func));
 
-        // Set a default name value for all name props, this ensures region has a name before
-        // the function is executed
-        {
-            let name_prop = schema_variant
-                .find_prop(ctx, &["root", "si", "name"])
+        let prop =
+            Prop::find_prop_by_path(ctx, attr_func_skip.schema_variant_id, &attr_func_skip.path)
                 .await?;
-            let name_default_value_info = DefaultValueInfo::String {
-                prop_id: *name_prop.id(),
-                default_value: schema.name().to_lowercase(),
-            };
-
-            set_default_value(ctx, name_default_value_info).await?;
-        }
 
-        for si_prop_func in variant_spec.si_prop_funcs()? {
-            let prop = schema_variant
-                .find_prop(ctx, &si_prop_func.kind().prop_path())
-                .await?;
-            import_attr_func_for_prop(
-                ctx,
-                change_set_pk,
-                *schema_variant.id(),
-                AttrFuncInfo {
-                    func_unique_id: si_prop_func.func_unique_id().to_owned(),
-                    prop_id: *prop.id(),
-                    inputs: si_prop_func
-                        .inputs()?
-                        .iter()
-                        .map(|input| input.to_owned().into())
-                        .collect(),
-                },
-                None,
-                thing_map,
-            )
-            .await?;
-        }
+        import_attr_func_for_prop(
+            ctx,
+            change_set_pk,
+            attr_func_skip.schema_variant_id,
+            AttrFuncInfo {
+                func_unique_id,
+                prop_id: *prop.id(),
+                inputs: attr_func_skip
+                    .inputs
+                    .iter()
+                    .map(attr_func_input_view)
+                    .collect(),
+            },
+            attr_func_skip.key.to_owned(),
+            &mut thing_map,
+        )
+        .await?;
+    }
 
-        let mut has_resource_value_func = false;
-        for root_prop_func in variant_spec.root_prop_funcs()? {
-            if root_prop_func.prop() == SchemaVariantSpecPropRoot::ResourceValue {
-                has_resource_value_func = true;
-            }
+    Ok(())
+}
 
-            let prop = schema_variant
-                .find_prop(ctx, root_prop_func.prop().path_parts())
-                .await?;
-            import_attr_func_for_prop(
-                ctx,
-                change_set_pk,
-                *schema_variant.id(),
-                AttrFuncInfo {
-                    func_unique_id: root_prop_func.func_unique_id().to_owned(),
-                    prop_id: *prop.id(),
-                    inputs: root_prop_func
-                        .inputs()?
-                        .iter()
-                        .map(|input| input.to_owned().into())
-                        .collect(),
-                },
-                None,
-                thing_map,
-            )
-            .await?;
-        }
-        if !has_resource_value_func {
-            attach_resource_payload_to_value(ctx, *schema_variant.id()).await?;
-        }
+/// Records an attribute function as skipped instead of importing it, resolving its func and prop
+/// so that it can still be applied once the package is gone.
+async fn skip_attr_func_for_prop(
+    ctx: &DalContext,
+    change_set_pk: ChangeSetPk,
+    schema_variant_id: SchemaVariantId,
+    AttrFuncInfo {
+        func_unique_id,
+        prop_id,
+        inputs,
+    }: AttrFuncInfo,
+    key: Option<String>,
+    thing_map: &ThingMap,
+) -> PkgResult<ImportAttrFuncSkip> {
+    let func_id = match thing_map.get(change_set_pk, &func_unique_id) {
+        Some(Thing::Func(func)) => *func.id(),
+        _ => return Err(PkgError::MissingFuncUniqueId(func_unique_id)),
+    };
+    let prop = Prop::get_by_id(ctx, &prop_id)
+        .await?
+        .ok_or(PkgError::MissingProp(prop_id))?;
 
-        for attr_func in side_effects.attr_funcs {
-            import_attr_func_for_prop(
-                ctx,
-                change_set_pk,
-                *schema_variant.id(),
-                attr_func,
-                None,
-                thing_map,
-            )
-            .await?;
-        }
+    Ok(ImportAttrFuncSkip {
+        schema_variant_id,
+        path: prop.path(),
+        func_id,
+        key,
+        inputs: inputs.into_iter().map(attr_func_input_spec).collect(),
+    })
+}
 
-        for (key, map_key_func) in side_effects.map_key_funcs {
-            import_attr_func_for_prop(
-                ctx,
-                change_set_pk,
-                *schema_variant.id(),
-                map_key_func,
-                Some(key),
-                thing_map,
-            )
-            .await?;
-        }
+fn attr_func_input_spec(input: SiPkgAttrFuncInputView) -> AttrFuncInputSpec {
+    match input {
+        SiPkgAttrFuncInputView::InputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        } => AttrFuncInputSpec::InputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        },
+        SiPkgAttrFuncInputView::OutputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        } => AttrFuncInputSpec::OutputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        },
+        SiPkgAttrFuncInputView::Prop {
+            name,
+            prop_path,
+            unique_id,
+            deleted,
+        } => AttrFuncInputSpec::Prop {
+            name,
+            prop_path,
+            unique_id,
+            deleted,
+        },
     }
+}
 
-    Ok(schema_variant)
+fn attr_func_input_view(input: &AttrFuncInputSpec) -> SiPkgAttrFuncInputView {
+    match input.to_owned() {
+        AttrFuncInputSpec::InputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        } => SiPkgAttrFuncInputView::InputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        },
+        AttrFuncInputSpec::OutputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        } => SiPkgAttrFuncInputView::OutputSocket {
+            name,
+            socket_name,
+            unique_id,
+            deleted,
+        },
+        AttrFuncInputSpec::Prop {
+            name,
+            prop_path,
+            unique_id,
+            deleted,
+        } => SiPkgAttrFuncInputView::Prop {
+            name,
+            prop_path,
+            unique_id,
+            deleted,
+        },
+    }
 }
 
 pub async fn attach_resource_payload_to_value(
@@ -2832,7 +5707,7 @@ pub async fn attach_resource_payload_to_value(
         )
         .await?;
 
-        InternalProvider::find_for_prop(ctx, *prop.id())
+        InternalProvider::find_or_create_for_prop(ctx, *prop.id())
             .await?
             .ok_or(InternalProviderError::NotFoundForProp(*prop.id()))?
     };
@@ -2881,31 +5756,22 @@ pub async fn attach_resource_payload_to_value(
     Ok(())
 }
 
+/// Sets the default value of a prop, returning the prop's path and the value that was set.
 async fn set_default_value(
     ctx: &DalContext,
     default_value_info: DefaultValueInfo,
-) -> PkgResult<()> {
-    let prop = match &default_value_info {
-        DefaultValueInfo::Number { prop_id, .. }
-        | DefaultValueInfo::String { prop_id, .. }
-        | DefaultValueInfo::Boolean { prop_id, .. } => Prop::get_by_id(ctx, prop_id)
-            .await?
-            .ok_or(PkgError::MissingProp(*prop_id))?,
-    };
+) -> PkgResult<(PropPath, serde_json::Value)> {
+    let DefaultValueInfo {
+        prop_id,
+        default_value,
+    } = default_value_info;
+    let prop = Prop::get_by_id(ctx, &prop_id)
+        .await?
+        .ok_or(PkgError::MissingProp(prop_id))?;
 
-    match default_value_info {
-        DefaultValueInfo::Boolean { default_value, .. } => {
-            prop.set_default_value(ctx, default_value).await?
-        }
-        DefaultValueInfo::Number { default_value, .. } => {
-            prop.set_default_value(ctx, default_value).await?
-        }
-        DefaultValueInfo::String { default_value, .. } => {
-            prop.set_default_value(ctx, default_value).await?
-        }
-    }
+    prop.set_default_value(ctx, &default_value).await?;
 
-    Ok(())
+    Ok((prop.path(), default_value))
 }
 
 async fn import_attr_func_for_prop(
@@ -3071,7 +5937,7 @@ async fn create_attr_proto_arg(
     Ok(match input {
         SiPkgAttrFuncInputView::Prop { prop_path, .. } => {
             let prop = Prop::find_prop_by_path(ctx, schema_variant_id, &prop_path.into()).await?;
-            let prop_ip = InternalProvider::find_for_prop(ctx, *prop.id())
+            let prop_ip = InternalProvider::find_or_create_for_prop(ctx, *prop.id())
                 .await?
                 .ok_or(PkgError::MissingInternalProviderForProp(*prop.id()))?;
 
@@ -3102,9 +5968,15 @@ async fn create_attr_proto_arg(
             )
             .await?
         }
-        _ => {
-            // xxx: make this an error
-            panic!("unsupported taking external provider as input for prop");
+        SiPkgAttrFuncInputView::OutputSocket {
+            name, socket_name, ..
+        } => {
+            return Err(PkgError::UnsupportedOutputSocketInput {
+                func_argument: name.to_owned(),
+                socket_name: socket_name.to_owned(),
+                prototype_id,
+                schema_variant_id,
+            });
         }
     })
 }
@@ -3112,7 +5984,7 @@ async fn create_attr_proto_arg(
 async fn update_attr_proto_arg(
     ctx: &DalContext,
     apa: &mut AttributePrototypeArgument,
-    _prototype_id: AttributePrototypeId,
+    prototype_id: AttributePrototypeId,
     input: &SiPkgAttrFuncInputView,
     func_id: FuncId,
     schema_variant_id: SchemaVariantId,
@@ -3134,7 +6006,7 @@ async fn update_attr_proto_arg(
     match input {
         SiPkgAttrFuncInputView::Prop { prop_path, .. } => {
             let prop = Prop::find_prop_by_path(ctx, schema_variant_id, &prop_path.into()).await?;
-            let prop_ip = InternalProvider::find_for_prop(ctx, *prop.id())
+            let prop_ip = InternalProvider::find_or_create_for_prop(ctx, *prop.id())
                 .await?
                 .ok_or(PkgError::MissingInternalProviderForProp(*prop.id()))?;
 
@@ -3159,7 +6031,16 @@ async fn update_attr_proto_arg(
                     .await?;
             }
         }
-        _ => {}
+        SiPkgAttrFuncInputView::OutputSocket {
+            name, socket_name, ..
+        } => {
+            return Err(PkgError::UnsupportedOutputSocketInput {
+                func_argument: name.to_owned(),
+                socket_name: socket_name.to_owned(),
+                prototype_id,
+                schema_variant_id,
+            });
+        }
     }
 
     Ok(())
@@ -3258,20 +6139,82 @@ fn prop_kind_for_pkg_prop(pkg_prop: &SiPkgProp<'_>) -> PropKind {
     }
 }
 
+/// Returns `link` as a string if it is an http(s) URL, so that packages cannot plant links such
+/// as `javascript:` or `file:` URLs in the docs shown to users. Any other link is dropped with a
+/// warning if `strip_invalid_links` is set, and otherwise fails with the error built by
+/// `invalid_link_err`.
+fn import_link(
+    link: Option<&Url>,
+    strip_invalid_links: bool,
+    invalid_link_err: impl FnOnce(String) -> PkgError,
+) -> PkgResult<Option<String>> {
+    match link {
+        Some(link) if !matches!(link.scheme(), "http" | "https") => {
+            if strip_invalid_links {
+                warn!(%link, "dropping imported link that is not an http(s) URL");
+                Ok(None)
+            } else {
+                Err(invalid_link_err(link.to_string()))
+            }
+        }
+        link => Ok(link.map(ToString::to_string)),
+    }
+}
+
+/// Checks that imported widget options have the shape the [`WidgetKind`] expects: a list of
+/// label/value pairs for the widgets that offer a choice of values, and nothing for the others.
+fn validate_widget_options(
+    prop: &str,
+    kind: WidgetKind,
+    options: Option<&serde_json::Value>,
+) -> PkgResult<()> {
+    let valid = match options {
+        None | Some(serde_json::Value::Null) => true,
+        Some(options) => match kind {
+            WidgetKind::ComboBox | WidgetKind::Map | WidgetKind::Secret | WidgetKind::Select => {
+                serde_json::from_value::<Vec<SelectWidgetOption>>(options.to_owned()).is_ok()
+            }
+            WidgetKind::Array
+            | WidgetKind::Checkbox
+            | WidgetKind::CodeEditor
+            | WidgetKind::Color
+            | WidgetKind::Header
+            | WidgetKind::Password
+            | WidgetKind::Text
+            | WidgetKind::TextArea => {
+                matches!(options.as_array(), Some(options) if options.is_empty())
+            }
+        },
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(PkgError::InvalidWidgetOptions {
+            prop: prop.to_owned(),
+            kind,
+        })
+    }
+}
+
 async fn create_dal_prop(
     ctx: &DalContext,
     data: &SiPkgPropData,
     kind: PropKind,
     schema_variant_id: SchemaVariantId,
     parent_prop_id: Option<PropId>,
+    strip_invalid_links: bool,
 ) -> PkgResult<Prop> {
+    let widget_kind: WidgetKind = (&data.widget_kind).into();
+    validate_widget_options(&data.name, widget_kind, data.widget_options.as_ref())?;
+
     let mut prop = Prop::new(
         ctx,
         &data.name,
         kind,
         schema_variant_id,
         parent_prop_id,
-        Some(((&data.widget_kind).into(), data.widget_options.to_owned())),
+        Some((widget_kind, data.widget_options.to_owned())),
         data.documentation.to_owned(),
         data.validation_format.to_owned(),
     )
@@ -3279,8 +6222,16 @@ async fn create_dal_prop(
     .map_err(SiPkgError::visit_prop)?;
 
     prop.set_hidden(ctx, data.hidden).await?;
-    prop.set_doc_link(ctx, data.doc_link.as_ref().map(|l| l.to_string()))
-        .await?;
+    prop.set_doc_link(
+        ctx,
+        import_link(data.doc_link.as_ref(), strip_invalid_links, |link| {
+            PkgError::InvalidPropDocLink {
+                prop: data.name.clone(),
+                link,
+            }
+        })?,
+    )
+    .await?;
 
     Ok(prop)
 }
@@ -3307,6 +6258,7 @@ async fn create_prop(
                     prop_kind_for_pkg_prop(&spec),
                     ctx.schema_variant_id,
                     parent_prop_info.as_ref().map(|info| info.0.to_owned()),
+                    ctx.strip_invalid_links,
                 )
                 .await?
             }
@@ -3321,49 +6273,26 @@ async fn create_prop(
     // queues up to the outer context via the PropVisitContext, which uses Mutexes for interior
     // mutability (maybe there's a better type for that here?)
 
-    if let Some(data) = spec.data() {
-        if let Some(default_value_info) = match &spec {
-            SiPkgProp::String { .. } => {
-                if let Some(serde_json::Value::String(default_value)) = &data.default_value {
-                    Some(DefaultValueInfo::String {
-                        prop_id,
-                        default_value: default_value.to_owned(),
-                    })
-                } else {
-                    // Raise error here for type mismatch
-                    None
-                }
-            }
-            SiPkgProp::Number { .. } => {
-                if let Some(serde_json::Value::Number(default_value_number)) = &data.default_value {
-                    if default_value_number.is_i64() {
-                        default_value_number
-                            .as_i64()
-                            .map(|dv_i64| DefaultValueInfo::Number {
-                                prop_id,
-                                default_value: dv_i64,
-                            })
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }
-            SiPkgProp::Boolean { .. } => {
-                if let Some(serde_json::Value::Bool(default_value)) = &data.default_value {
-                    Some(DefaultValueInfo::Boolean {
+    if let Some(default_value) = spec.data().and_then(|data| data.default_value.as_ref()) {
+        let kind = prop_kind_for_pkg_prop(&spec);
+        // Default values for complex types are not yet supported in packages
+        if matches!(
+            kind,
+            PropKind::Boolean | PropKind::Integer | PropKind::String
+        ) {
+            match kind.coerce_default(default_value) {
+                Ok(default_value) => {
+                    ctx.default_values.lock().await.push(DefaultValueInfo {
                         prop_id,
-                        default_value: *default_value,
-                    })
-                } else {
-                    None
+                        default_value,
+                    });
                 }
+                Err(err) => warn!(
+                    prop.path = prop.path().with_replaced_sep("/"),
+                    error = %err,
+                    "skipping default value of imported prop"
+                ),
             }
-            // Default values for complex types are not yet supported in packages
-            _ => None,
-        } {
-            ctx.default_values.lock().await.push(default_value_info);
         }
     }
 
@@ -3395,3 +6324,144 @@ async fn create_prop(
 
     Ok(Some((*prop.id(), prop.path())))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(skips: &ImportSkips) -> ImportSkips {
+        let json = serde_json::to_value(skips).expect("serialize skips");
+        serde_json::from_value(json).expect("deserialize skips")
+    }
+
+    #[test]
+    fn import_skips_round_trip() {
+        let skips = ImportSkips {
+            version: IMPORT_SKIPS_VERSION,
+            change_set_pk: ChangeSetPk::NONE,
+            edge_skips: vec![
+                ImportEdgeSkip::FilteredComponent {
+                    unique_id: "component".to_owned(),
+                },
+                ImportEdgeSkip::MissingInputSocket {
+                    name: "input".to_owned(),
+                },
+                ImportEdgeSkip::MissingOutputSocket {
+                    name: "output".to_owned(),
+                },
+            ],
+            attribute_skips: vec![(
+                "component".to_owned(),
+                vec![
+                    ImportAttributeSkip::KindMismatch {
+                        path: PropPath::new(["root", "domain", "name"]),
+                        expected_kind: Some(PropKind::String),
+                        variant_kind: PropKind::Integer,
+                    },
+                    ImportAttributeSkip::MissingInputSocket {
+                        name: "input".to_owned(),
+                    },
+                    ImportAttributeSkip::MissingOutputSocket {
+                        name: "output".to_owned(),
+                    },
+                    ImportAttributeSkip::MissingProp {
+                        path: PropPath::new(["root", "domain", "gone"]),
+                    },
+                    ImportAttributeSkip::SecretNeedsReinjection {
+                        path: PropPath::new(["root", "secrets", "token"]),
+                    },
+                ],
+            )],
+            attr_func_skips: vec![ImportAttrFuncSkip {
+                schema_variant_id: SchemaVariantId::NONE,
+                path: PropPath::new(["root", "domain", "tags"]),
+                func_id: FuncId::NONE,
+                key: Some("Name".to_owned()),
+                inputs: vec![AttrFuncInputSpec::Prop {
+                    name: "identity".to_owned(),
+                    prop_path: PropPath::new(["root", "si", "name"]).to_string(),
+                    unique_id: None,
+                    deleted: false,
+                }],
+            }],
+            func_errors: vec![("test:broken".to_owned(), "data not found".to_owned())],
+            schema_skips: vec![(
+                "broken".to_owned(),
+                "refers to func test:broken, which could not be imported".to_owned(),
+            )],
+            qualification_errors: vec![("component".to_owned(), "func failed".to_owned())],
+        };
+
+        assert_eq!(skips, round_trip(&skips));
+    }
+
+    #[test]
+    fn skips_summary_lists_every_skip() {
+        let skips = ImportSkips {
+            version: IMPORT_SKIPS_VERSION,
+            change_set_pk: ChangeSetPk::NONE,
+            edge_skips: vec![ImportEdgeSkip::MissingOutputSocket {
+                name: "output".to_owned(),
+            }],
+            attribute_skips: vec![(
+                "component".to_owned(),
+                vec![
+                    ImportAttributeSkip::KindMismatch {
+                        path: PropPath::new(["root", "domain", "port"]),
+                        expected_kind: None,
+                        variant_kind: PropKind::Integer,
+                    },
+                    ImportAttributeSkip::MissingProp {
+                        path: PropPath::new(["root", "domain", "gone"]),
+                    },
+                ],
+            )],
+            attr_func_skips: vec![],
+            func_errors: vec![],
+            schema_skips: vec![],
+            qualification_errors: vec![],
+        };
+
+        assert!(!skips.is_empty());
+        assert_eq!(
+            format!(
+                "change set {}: skipped 1 edge(s) and 2 attribute(s)\n  \
+                 edge: missing output socket output\n  \
+                 component: root/domain/port: prop kind integer does not match imported value kind null\n  \
+                 component: missing prop root/domain/gone",
+                ChangeSetPk::NONE,
+            ),
+            skips.summary()
+        );
+
+        let empty = ImportSkips {
+            version: IMPORT_SKIPS_VERSION,
+            change_set_pk: ChangeSetPk::NONE,
+            edge_skips: vec![],
+            attribute_skips: vec![("component".to_owned(), vec![])],
+            attr_func_skips: vec![],
+            func_errors: vec![],
+            schema_skips: vec![],
+            qualification_errors: vec![],
+        };
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn unknown_skip_kinds_deserialize_as_unknown() {
+        let json = serde_json::json!({
+            "changeSetPk": ChangeSetPk::NONE,
+            "edgeSkips": [{ "type": "arityExceeded", "name": "output", "arity": "one" }],
+            "attributeSkips": [["component", [{ "type": "unsupportedDepth", "depth": 12 }]]],
+        });
+
+        let skips: ImportSkips = serde_json::from_value(json).expect("deserialize skips");
+
+        assert_eq!(0, skips.version);
+        assert_eq!(vec![ImportEdgeSkip::Unknown], skips.edge_skips);
+        assert_eq!(
+            vec![("component".to_owned(), vec![ImportAttributeSkip::Unknown])],
+            skips.attribute_skips
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    installed_pkg::{InstalledPkgAsset, InstalledPkgAssetTyped, InstalledPkgId},
+    schema::variant::definition::SchemaVariantDefinition,
+    DalContext, Func, Schema, SchemaVariant, StandardModel,
+};
+
+use super::PkgResult;
+
+/// The result of [`verify_installed_pkg_integrity`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub installed_pkg_id: InstalledPkgId,
+    /// How many asset records the installed package has
+    pub checked_assets: usize,
+    /// Asset records whose func, schema, schema variant or schema variant definition no longer
+    /// exists, e.g. because it was deleted after the package was installed. Importing a package
+    /// whose assets are dangling fails with errors such as
+    /// [`PkgError::InstalledFuncMissing`](super::PkgError::InstalledFuncMissing).
+    pub dangling_assets: Vec<InstalledPkgAssetTyped>,
+}
+
+impl IntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.dangling_assets.is_empty()
+    }
+}
+
+/// Checks that every [`InstalledPkgAsset`] of the installed package still points to something
+/// that exists.
+pub async fn verify_installed_pkg_integrity(
+    ctx: &DalContext,
+    installed_pkg_id: InstalledPkgId,
+) -> PkgResult<IntegrityReport> {
+    let assets = InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg_id).await?;
+    let checked_assets = assets.len();
+
+    let mut dangling_assets = vec![];
+    for asset in &assets {
+        let typed: InstalledPkgAssetTyped = asset.into();
+        let exists = match &typed {
+            InstalledPkgAssetTyped::Func { id, .. } => Func::get_by_id(ctx, id).await?.is_some(),
+            InstalledPkgAssetTyped::Schema { id, .. } => {
+                Schema::get_by_id(ctx, id).await?.is_some()
+            }
+            InstalledPkgAssetTyped::SchemaVariant { id, .. } => {
+                SchemaVariant::get_by_id(ctx, id).await?.is_some()
+            }
+            InstalledPkgAssetTyped::SchemaVariantDefinition { id, .. } => {
+                SchemaVariantDefinition::get_by_id(ctx, id).await?.is_some()
+            }
+        };
+
+        if !exists {
+            dangling_assets.push(typed);
+        }
+    }
+
+    Ok(IntegrityReport {
+        installed_pkg_id,
+        checked_assets,
+        dangling_assets,
+    })
+}
@@ -0,0 +1,251 @@
+use serde::{Deserialize, Serialize};
+use si_pkg::{
+    ChangeSetSpec, ComponentSpec, EdgeSpec, FuncSpec, PkgSpec, SchemaSpec, SchemaVariantSpec, SiPkg,
+};
+use telemetry::prelude::*;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::DalContext;
+
+use super::{import_pkg_from_pkg, ImportOptions, ImportResult, PkgError, PkgExporter, PkgResult};
+
+/// One line of a package exported as newline delimited JSON. The metadata of the package comes
+/// first, and everything in a change set comes after the record for the change set itself.
+/// Schema records have no variants, each variant comes in its own record after its schema.
+#[remain::sorted]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum NdjsonRecord {
+    ChangeSet {
+        spec: ChangeSetSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    Component {
+        change_set: String,
+        spec: ComponentSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    Edge {
+        change_set: String,
+        spec: EdgeSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    Func {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        change_set: Option<String>,
+        spec: FuncSpec,
+    },
+    Metadata {
+        spec: PkgSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    Schema {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        change_set: Option<String>,
+        spec: SchemaSpec,
+    },
+    #[serde(rename_all = "camelCase")]
+    Variant {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        change_set: Option<String>,
+        schema: String,
+        spec: SchemaVariantSpec,
+    },
+}
+
+impl PkgExporter {
+    /// Exports the package as newline delimited JSON, with one record per line for each func,
+    /// schema, schema variant, component and edge. Unlike the binary package, this can be
+    /// searched with line based tools and, if it gets cut short, everything before the last
+    /// line can still be imported with [`import_ndjson`].
+    pub async fn export_ndjson<W>(&mut self, ctx: &DalContext, mut writer: W) -> PkgResult<()>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let spec = self.export_as_spec(ctx).await?;
+
+        for record in records_for_spec(spec) {
+            let mut line = serde_json::to_vec(&record)?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+        }
+        writer.flush().await?;
+
+        Ok(())
+    }
+}
+
+/// Imports a package exported with [`PkgExporter::export_ndjson`]. A last line that is not a
+/// valid record is assumed to have been cut short and is dropped with a warning, so that the
+/// rest of a truncated backup can be recovered.
+pub async fn import_ndjson<R>(
+    ctx: &DalContext,
+    reader: R,
+    options: Option<ImportOptions>,
+    override_builtin_schema_feature_flag: bool,
+) -> PkgResult<ImportResult>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut spec: Option<PkgSpec> = None;
+
+    let mut lines = reader.lines();
+    let mut line_number = 0;
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record: NdjsonRecord = match serde_json::from_str(&line) {
+            Ok(record) => record,
+            Err(err) => {
+                if lines.next_line().await?.is_none() {
+                    warn!(
+                        line_number,
+                        error = %err,
+                        "dropping truncated last line of NDJSON backup"
+                    );
+                    break;
+                }
+                return Err(PkgError::InvalidNdjsonRecord(line_number, err.to_string()));
+            }
+        };
+
+        match spec {
+            Some(ref mut spec) => add_record(spec, record, line_number)?,
+            None => match record {
+                NdjsonRecord::Metadata { spec: metadata } => spec = Some(metadata),
+                _ => return Err(PkgError::NdjsonMissingMetadata),
+            },
+        }
+    }
+
+    let pkg = SiPkg::load_from_spec(spec.ok_or(PkgError::NdjsonMissingMetadata)?)?;
+    import_pkg_from_pkg(ctx, &pkg, options, override_builtin_schema_feature_flag).await
+}
+
+fn records_for_spec(mut spec: PkgSpec) -> Vec<NdjsonRecord> {
+    let funcs = std::mem::take(&mut spec.funcs);
+    let schemas = std::mem::take(&mut spec.schemas);
+    let change_sets = std::mem::take(&mut spec.change_sets);
+
+    let mut records = vec![NdjsonRecord::Metadata { spec }];
+    push_func_and_schema_records(&mut records, None, funcs, schemas);
+
+    for mut change_set in change_sets {
+        let name = change_set.name.to_owned();
+        let funcs = std::mem::take(&mut change_set.funcs);
+        let schemas = std::mem::take(&mut change_set.schemas);
+        let components = std::mem::take(&mut change_set.components);
+        let edges = std::mem::take(&mut change_set.edges);
+
+        records.push(NdjsonRecord::ChangeSet { spec: change_set });
+        push_func_and_schema_records(&mut records, Some(&name), funcs, schemas);
+        records.extend(components.into_iter().map(|spec| NdjsonRecord::Component {
+            change_set: name.to_owned(),
+            spec,
+        }));
+        records.extend(edges.into_iter().map(|spec| NdjsonRecord::Edge {
+            change_set: name.to_owned(),
+            spec,
+        }));
+    }
+
+    records
+}
+
+fn push_func_and_schema_records(
+    records: &mut Vec<NdjsonRecord>,
+    change_set: Option<&str>,
+    funcs: Vec<FuncSpec>,
+    schemas: Vec<SchemaSpec>,
+) {
+    records.extend(funcs.into_iter().map(|spec| NdjsonRecord::Func {
+        change_set: change_set.map(ToOwned::to_owned),
+        spec,
+    }));
+
+    for mut schema in schemas {
+        let variants = std::mem::take(&mut schema.variants);
+        let schema_name = schema.name.to_owned();
+
+        records.push(NdjsonRecord::Schema {
+            change_set: change_set.map(ToOwned::to_owned),
+            spec: schema,
+        });
+        records.extend(variants.into_iter().map(|spec| NdjsonRecord::Variant {
+            change_set: change_set.map(ToOwned::to_owned),
+            schema: schema_name.to_owned(),
+            spec,
+        }));
+    }
+}
+
+fn add_record(spec: &mut PkgSpec, record: NdjsonRecord, line_number: usize) -> PkgResult<()> {
+    match record {
+        NdjsonRecord::ChangeSet { spec: change_set } => spec.change_sets.push(change_set),
+        NdjsonRecord::Component {
+            change_set,
+            spec: component,
+        } => change_set_mut(spec, "component", &change_set)?
+            .components
+            .push(component),
+        NdjsonRecord::Edge {
+            change_set,
+            spec: edge,
+        } => change_set_mut(spec, "edge", &change_set)?.edges.push(edge),
+        NdjsonRecord::Func {
+            change_set,
+            spec: func,
+        } => match change_set {
+            Some(change_set) => change_set_mut(spec, "func", &change_set)?.funcs.push(func),
+            None => spec.funcs.push(func),
+        },
+        NdjsonRecord::Metadata { .. } => {
+            return Err(PkgError::InvalidNdjsonRecord(
+                line_number,
+                "the backup has more than one metadata record".to_owned(),
+            ));
+        }
+        NdjsonRecord::Schema {
+            change_set,
+            spec: schema,
+        } => match change_set {
+            Some(change_set) => change_set_mut(spec, "schema", &change_set)?
+                .schemas
+                .push(schema),
+            None => spec.schemas.push(schema),
+        },
+        NdjsonRecord::Variant {
+            change_set,
+            schema,
+            spec: variant,
+        } => {
+            let schemas = match change_set {
+                Some(change_set) => &mut change_set_mut(spec, "variant", &change_set)?.schemas,
+                None => &mut spec.schemas,
+            };
+            schemas
+                .iter_mut()
+                .rev()
+                .find(|schema_spec| schema_spec.name == schema)
+                .ok_or_else(|| PkgError::NdjsonMissingSchema(schema.to_owned()))?
+                .variants
+                .push(variant);
+        }
+    }
+
+    Ok(())
+}
+
+fn change_set_mut<'a>(
+    spec: &'a mut PkgSpec,
+    record_kind: &'static str,
+    name: &str,
+) -> PkgResult<&'a mut ChangeSetSpec> {
+    spec.change_sets
+        .iter_mut()
+        .find(|change_set| change_set.name == name)
+        .ok_or_else(|| PkgError::NdjsonMissingChangeSet(record_kind, name.to_owned()))
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use telemetry::prelude::*;
+
+use crate::{
+    installed_pkg::{
+        InstalledPkg, InstalledPkgAsset, InstalledPkgAssetKind, InstalledPkgAssetTyped,
+        InstalledPkgId,
+    },
+    schema::variant::definition::{SchemaVariantDefinition, SchemaVariantDefinitionId},
+    Component, ComponentId, DalContext, Func, FuncId, Schema, SchemaId, SchemaVariant,
+    SchemaVariantId, StandardModel,
+};
+
+use super::{PkgError, PkgResult};
+
+/// The result of [`remove_pkg`].
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedPkgSummary {
+    pub installed_pkg_id: InstalledPkgId,
+    pub removed_schemas: Vec<SchemaId>,
+    pub removed_schema_variants: Vec<SchemaVariantId>,
+    pub removed_schema_variant_definitions: Vec<SchemaVariantDefinitionId>,
+    pub removed_funcs: Vec<FuncId>,
+    /// Components of the package's schema variants that were deleted because the removal was
+    /// forced
+    pub removed_components: Vec<ComponentId>,
+    /// Funcs that were kept because another installed package installed the same func
+    pub retained_funcs: Vec<FuncId>,
+}
+
+/// Uninstalls a package, deleting the schemas, schema variants, schema variant definitions and
+/// funcs it installed along with the records of its installation. Funcs that another installed
+/// package installed too, i.e. that have the same hash recorded against a different
+/// [`InstalledPkgId`], are kept.
+///
+/// Fails with [`PkgError::InstalledPkgInUse`] if components exist for any of the package's
+/// schema variants, unless `force` is set, in which case the components are deleted first.
+pub async fn remove_pkg(
+    ctx: &DalContext,
+    installed_pkg_id: InstalledPkgId,
+    force: bool,
+) -> PkgResult<RemovedPkgSummary> {
+    let mut installed_pkg = InstalledPkg::get_by_id(ctx, &installed_pkg_id)
+        .await?
+        .ok_or(PkgError::InstalledPkgNotFound(installed_pkg_id))?;
+    let mut assets = InstalledPkgAsset::list_for_installed_pkg_id(ctx, installed_pkg_id).await?;
+
+    let mut components = vec![];
+    for asset in &assets {
+        let typed: InstalledPkgAssetTyped = asset.into();
+        if let InstalledPkgAssetTyped::SchemaVariant { id, .. } = typed {
+            components.extend(Component::list_for_schema_variant(ctx, id).await?);
+        }
+    }
+    if !components.is_empty() && !force {
+        return Err(PkgError::InstalledPkgInUse(
+            installed_pkg_id,
+            components.iter().map(|component| *component.id()).collect(),
+        ));
+    }
+
+    let mut summary = RemovedPkgSummary {
+        installed_pkg_id,
+        removed_schemas: vec![],
+        removed_schema_variants: vec![],
+        removed_schema_variant_definitions: vec![],
+        removed_funcs: vec![],
+        removed_components: vec![],
+        retained_funcs: vec![],
+    };
+
+    for mut component in components {
+        component.delete_and_propagate(ctx).await?;
+        summary.removed_components.push(*component.id());
+    }
+
+    // Definitions and variants refer to the schemas and funcs, so they go first
+    assets.sort_by_key(|asset| match asset.asset_kind() {
+        InstalledPkgAssetKind::SchemaVariantDefinition => 0,
+        InstalledPkgAssetKind::SchemaVariant => 1,
+        InstalledPkgAssetKind::Schema => 2,
+        InstalledPkgAssetKind::Func => 3,
+    });
+
+    for mut asset in assets {
+        let typed: InstalledPkgAssetTyped = (&asset).into();
+        match typed {
+            InstalledPkgAssetTyped::SchemaVariantDefinition { id, .. } => {
+                if let Some(mut definition) = SchemaVariantDefinition::get_by_id(ctx, &id).await? {
+                    definition.delete_by_id(ctx).await?;
+                    summary.removed_schema_variant_definitions.push(id);
+                }
+            }
+            InstalledPkgAssetTyped::SchemaVariant { id, .. } => {
+                if let Some(mut variant) = SchemaVariant::get_by_id(ctx, &id).await? {
+                    variant.delete_by_id(ctx).await?;
+                    summary.removed_schema_variants.push(id);
+                }
+            }
+            InstalledPkgAssetTyped::Schema { id, .. } => {
+                if let Some(mut schema) = Schema::get_by_id(ctx, &id).await? {
+                    schema.delete_by_id(ctx).await?;
+                    summary.removed_schemas.push(id);
+                }
+            }
+            InstalledPkgAssetTyped::Func { id, hash, .. } => {
+                let shared = InstalledPkgAsset::list_for_kind_and_hash(
+                    ctx,
+                    InstalledPkgAssetKind::Func,
+                    &hash,
+                )
+                .await?
+                .iter()
+                .any(|other| other.installed_pkg_id() != installed_pkg_id);
+
+                if shared {
+                    if !summary.retained_funcs.contains(&id) {
+                        summary.retained_funcs.push(id);
+                    }
+                } else if let Some(mut func) = Func::get_by_id(ctx, &id).await? {
+                    func.delete_by_id(ctx).await?;
+                    summary.removed_funcs.push(id);
+                }
+            }
+        }
+
+        asset.delete_by_id(ctx).await?;
+    }
+
+    installed_pkg.delete_by_id(ctx).await?;
+
+    info!(
+        installed_pkg.id = %installed_pkg_id,
+        removed_funcs = summary.removed_funcs.len(),
+        retained_funcs = summary.retained_funcs.len(),
+        "removed installed package"
+    );
+
+    Ok(summary)
+}
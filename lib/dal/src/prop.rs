@@ -84,6 +84,30 @@ impl PropPath {
 
         true
     }
+
+    /// Returns the path of the parent of this PropPath, or `None` if it has a single segment
+    pub fn parent(&self) -> Option<PropPath> {
+        self.0
+            .rsplit_once(PROP_PATH_SEPARATOR)
+            .map(|(parent, _)| Self(parent.to_owned()))
+    }
+
+    /// Returns the last segment of this PropPath, which is the name of the [`Prop`] it points to
+    pub fn last_segment(&self) -> &str {
+        self.0
+            .rsplit_once(PROP_PATH_SEPARATOR)
+            .map_or(self.0.as_str(), |(_, last)| last)
+    }
+
+    /// Returns this PropPath as a JSON pointer into a value keyed by prop name from the root
+    /// down, such as "/root/domain/name". Segments are escaped, so a prop name containing "/" or
+    /// "~" still points at a single key.
+    pub fn to_json_pointer(&self) -> String {
+        self.as_parts()
+            .into_iter()
+            .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
 }
 
 impl AsRef<str> for PropPath {
@@ -131,6 +155,8 @@ pub enum PropError {
     AttributePrototype(#[from] AttributePrototypeError),
     #[error("AttributeValue error: {0}")]
     AttributeValue(#[from] AttributeValueError),
+    #[error("invalid default value: {0}")]
+    CoerceDefault(#[from] CoerceError),
     #[error("default diff function not found")]
     DefaultDiffFunctionNotFound,
     #[error("expected child prop not found with name {0}")]
@@ -197,6 +223,38 @@ pub enum PropKind {
     String,
 }
 
+impl PropKind {
+    /// Checks that `value` can be the default value of a [`Prop`] of this kind, returning the
+    /// value to store. Values are never converted from one JSON type to another, so the string
+    /// "5" is not a valid default for an integer prop.
+    pub fn coerce_default(
+        &self,
+        value: &serde_json::Value,
+    ) -> Result<serde_json::Value, CoerceError> {
+        match (self, value) {
+            (PropKind::Integer, serde_json::Value::Number(number)) => number
+                .as_i64()
+                .map(serde_json::Value::from)
+                .ok_or_else(|| CoerceError::NotAnInteger(number.to_owned())),
+            (PropKind::Array, serde_json::Value::Array(_))
+            | (PropKind::Boolean, serde_json::Value::Bool(_))
+            | (PropKind::Map | PropKind::Object, serde_json::Value::Object(_))
+            | (PropKind::String, serde_json::Value::String(_)) => Ok(value.to_owned()),
+            (kind, value) => Err(CoerceError::KindMismatch(*kind, value.to_owned())),
+        }
+    }
+}
+
+/// Why a value cannot be the default value of a [`Prop`], see [`PropKind::coerce_default`].
+#[remain::sorted]
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum CoerceError {
+    #[error("{1} is not a valid default value for a prop of kind {0}")]
+    KindMismatch(PropKind, serde_json::Value),
+    #[error("{0} is not an integer that fits in 64 bits")]
+    NotAnInteger(serde_json::Number),
+}
+
 impl From<PropKind> for PropSpecKind {
     fn from(prop: PropKind) -> Self {
         match prop {
@@ -614,6 +672,7 @@ impl Prop {
         let value = serde_json::to_value(value)?;
         match self.kind() {
             PropKind::String | PropKind::Boolean | PropKind::Integer => {
+                let value = self.kind().coerce_default(&value)?;
                 let attribute_read_context = AttributeReadContext::default_with_prop(self.id);
                 let attribute_value = AttributeValue::find_for_context(ctx, attribute_read_context)
                     .await?
@@ -666,3 +725,124 @@ impl Prop {
         self.set_diff_func_id(ctx, Some(*func.id())).await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn coerce_default_integer() {
+        assert_eq!(
+            Ok(serde_json::json!(5)),
+            PropKind::Integer.coerce_default(&serde_json::json!(5))
+        );
+        assert_eq!(
+            Err(CoerceError::KindMismatch(
+                PropKind::Integer,
+                serde_json::json!("5")
+            )),
+            PropKind::Integer.coerce_default(&serde_json::json!("5"))
+        );
+        assert!(matches!(
+            PropKind::Integer.coerce_default(&serde_json::json!(5.5)),
+            Err(CoerceError::NotAnInteger(_))
+        ));
+        assert!(matches!(
+            PropKind::Integer.coerce_default(&serde_json::json!(u64::MAX)),
+            Err(CoerceError::NotAnInteger(_))
+        ));
+    }
+
+    #[test]
+    fn coerce_default_scalars() {
+        assert_eq!(
+            Ok(serde_json::json!("5")),
+            PropKind::String.coerce_default(&serde_json::json!("5"))
+        );
+        assert!(PropKind::String
+            .coerce_default(&serde_json::json!(5))
+            .is_err());
+        assert_eq!(
+            Ok(serde_json::json!(true)),
+            PropKind::Boolean.coerce_default(&serde_json::json!(true))
+        );
+        assert!(PropKind::Boolean
+            .coerce_default(&serde_json::json!("true"))
+            .is_err());
+        assert!(PropKind::String
+            .coerce_default(&serde_json::Value::Null)
+            .is_err());
+    }
+
+    #[test]
+    fn coerce_default_complex() {
+        assert_eq!(
+            Ok(serde_json::json!(["a"])),
+            PropKind::Array.coerce_default(&serde_json::json!(["a"]))
+        );
+        assert_eq!(
+            Ok(serde_json::json!({ "a": 1 })),
+            PropKind::Map.coerce_default(&serde_json::json!({ "a": 1 }))
+        );
+        assert_eq!(
+            Ok(serde_json::json!({})),
+            PropKind::Object.coerce_default(&serde_json::json!({}))
+        );
+        assert!(PropKind::Object
+            .coerce_default(&serde_json::json!([]))
+            .is_err());
+        assert!(PropKind::Array
+            .coerce_default(&serde_json::json!({}))
+            .is_err());
+    }
+
+    #[test]
+    fn parent_of_nested_path() {
+        let path = PropPath::new(["root", "domain", "name"]);
+        assert_eq!(Some(PropPath::new(["root", "domain"])), path.parent());
+        assert_eq!("name", path.last_segment());
+    }
+
+    #[test]
+    fn parent_of_root_path() {
+        let path = PropPath::new(["root"]);
+        assert_eq!(None, path.parent());
+        assert_eq!("root", path.last_segment());
+    }
+
+    #[test]
+    fn parent_of_single_segment_path() {
+        let path = PropPath::new(["domain"]);
+        assert_eq!(None, path.parent());
+        assert_eq!(
+            Some(path.clone()),
+            PropPath::new(["domain", "name"]).parent()
+        );
+    }
+
+    #[test]
+    fn json_pointer_for_paths() {
+        assert_eq!("/root", PropPath::new(["root"]).to_json_pointer());
+        assert_eq!(
+            "/root/domain/name",
+            PropPath::new(["root", "domain", "name"]).to_json_pointer()
+        );
+        assert_eq!(
+            "/root/domain/a~1b~0c",
+            PropPath::new(["root", "domain", "a/b~c"]).to_json_pointer()
+        );
+    }
+
+    #[test]
+    fn json_pointer_resolves_parent() {
+        let value = serde_json::json!({ "root": { "domain": { "a/b": 1 } } });
+        let path = PropPath::new(["root", "domain", "a/b"]);
+        let parent = path.parent().expect("path has a parent");
+        assert_eq!(
+            Some(&serde_json::json!(1)),
+            value
+                .pointer(&parent.to_json_pointer())
+                .and_then(|parent| parent.get(path.last_segment()))
+        );
+    }
+}
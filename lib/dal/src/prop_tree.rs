@@ -6,6 +6,8 @@ use crate::{
 };
 use convert_case::{Case, Casing};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
 use thiserror::Error;
 
 const PROP_TREE_FOR_ALL_SCHEMA_VARIANTS: &str =
@@ -51,6 +53,7 @@ pub struct PropTreeNode {
     pub widget_options: Option<serde_json::Value>,
     pub doc_link: Option<String>,
     pub documentation: Option<String>,
+    pub validation_format: Option<String>,
 }
 
 impl PropTreeNode {
@@ -97,6 +100,80 @@ impl PropTreeNode {
             PropKind::String => "string | null | undefined".into(),
         })
     }
+
+    /// Produces a JSON Schema (draft-07) fragment describing the values this prop accepts.
+    /// Default values are looked up by [`PropId`] in `default_values`, since finding them
+    /// requires the database.
+    pub fn json_schema(
+        &self,
+        default_values: &HashMap<PropId, serde_json::Value>,
+    ) -> PropTreeResult<serde_json::Value> {
+        let mut schema = match self.kind {
+            PropKind::Array => {
+                let array_element = self
+                    .children
+                    .get(0)
+                    .ok_or(PropTreeError::ArrayMissingElementProp(self.prop_id))?;
+
+                json!({ "type": "array", "items": array_element.json_schema(default_values)? })
+            }
+            PropKind::Boolean => json!({ "type": "boolean" }),
+            PropKind::Integer => json!({ "type": "integer" }),
+            PropKind::Object => {
+                let mut properties = serde_json::Map::new();
+                let mut required = vec![];
+                for child in &self.children {
+                    if child.is_required() {
+                        required.push(child.name.clone());
+                    }
+                    properties.insert(child.name.clone(), child.json_schema(default_values)?);
+                }
+
+                let mut object = json!({ "type": "object", "properties": properties });
+                if !required.is_empty() {
+                    object["required"] = json!(required);
+                }
+
+                object
+            }
+            PropKind::Map => {
+                let map_element = self
+                    .children
+                    .get(0)
+                    .ok_or(PropTreeError::MapMissingElementProp(self.prop_id))?;
+
+                json!({
+                    "type": "object",
+                    "additionalProperties": map_element.json_schema(default_values)?,
+                })
+            }
+            PropKind::String => json!({ "type": "string" }),
+        };
+
+        if let Some(documentation) = &self.documentation {
+            schema["description"] = json!(documentation);
+        }
+        if let Some(default_value) = default_values.get(&self.prop_id) {
+            schema["default"] = default_value.clone();
+        }
+
+        Ok(schema)
+    }
+
+    /// A prop is required if its validation format (a serialized Joi description) marks its
+    /// presence as required.
+    fn is_required(&self) -> bool {
+        self.validation_format
+            .as_deref()
+            .and_then(|format| serde_json::from_str::<serde_json::Value>(format).ok())
+            .and_then(|description| {
+                description
+                    .pointer("/flags/presence")
+                    .and_then(|presence| presence.as_str())
+                    .map(|presence| presence == "required")
+            })
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -173,6 +250,7 @@ impl PropTree {
                 widget_options: prop.widget_options().cloned(),
                 doc_link: prop.doc_link().map(|l| l.to_owned()),
                 documentation: prop.documentation().map(|d| d.to_owned()),
+                validation_format: prop.validation_format().map(|v| v.to_owned()),
                 visibility_change_set_pk,
             };
 
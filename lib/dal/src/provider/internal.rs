@@ -85,8 +85,8 @@ use crate::{
     impl_standard_model, pk, standard_model, standard_model_accessor, standard_model_accessor_ro,
     AttributeContextBuilderError, AttributePrototype, AttributePrototypeError,
     AttributePrototypeId, AttributeReadContext, AttributeValueError, AttributeView, DiagramKind,
-    FuncError, FuncId, HistoryEventError, Prop, PropError, StandardModel, StandardModelError,
-    Tenancy, Timestamp, TransactionsError, Visibility,
+    FuncError, FuncId, HistoryEventError, Prop, PropError, PropKind, StandardModel,
+    StandardModelError, Tenancy, Timestamp, TransactionsError, Visibility,
 };
 use crate::{
     standard_model_has_many, AttributeContext, AttributeContextError, AttributeValue, DalContext,
@@ -608,6 +608,33 @@ impl InternalProvider {
         Ok(object_option_from_row_option(row)?)
     }
 
+    /// Finds the implicit [`InternalProvider`] for the [`Prop`](crate::Prop), creating it if the
+    /// prop does not have one yet, e.g. because it was added to its
+    /// [`SchemaVariant`](crate::SchemaVariant) after the variant's implicit providers were
+    /// created. Props that descend from a map or an array never have an implicit provider, so
+    /// `None` is returned for them.
+    pub async fn find_or_create_for_prop(
+        ctx: &DalContext,
+        prop_id: PropId,
+    ) -> InternalProviderResult<Option<Self>> {
+        if let Some(internal_provider) = Self::find_for_prop(ctx, prop_id).await? {
+            return Ok(Some(internal_provider));
+        }
+
+        for ancestor in Prop::all_ancestor_props(ctx, prop_id).await? {
+            if *ancestor.id() != prop_id
+                && matches!(ancestor.kind(), PropKind::Map | PropKind::Array)
+            {
+                return Ok(None);
+            }
+        }
+
+        debug!(%prop_id, "creating missing implicit internal provider for prop");
+        Ok(Some(
+            Self::new_implicit(ctx, prop_id, SchemaVariantId::NONE).await?,
+        ))
+    }
+
     pub async fn by_socket(ctx: &DalContext) -> InternalProviderResult<HashMap<SocketId, Self>> {
         let rows = ctx
             .txns()
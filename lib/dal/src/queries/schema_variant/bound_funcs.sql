@@ -0,0 +1,39 @@
+SELECT DISTINCT bound.kind, bound.func_id
+FROM ((SELECT CASE
+                  WHEN funcs.backend_response_type IN ('CodeGeneration', 'Qualification')
+                      THEN 'leaf'
+                  ELSE 'attribute'
+                  END  AS kind,
+              funcs.id AS func_id
+       FROM attribute_prototypes_v1($1, $2) ap
+                JOIN funcs_v1($1, $2) funcs
+                     ON funcs.id = ap.func_id
+       WHERE (ap.attribute_context_prop_id IN
+              (SELECT id FROM props_v1($1, $2) AS props WHERE props.schema_variant_id = $3)
+           OR ap.attribute_context_internal_provider_id IN
+              (SELECT id FROM internal_providers_v1($1, $2) AS ips WHERE ips.schema_variant_id = $3)
+           OR ap.attribute_context_external_provider_id IN
+              (SELECT id FROM external_providers_v1($1, $2) AS eps WHERE eps.schema_variant_id = $3))
+         -- intrinsics are bound to nearly every prop, so they are left out
+         AND funcs.code_sha256 != '0'
+         -- we don't want component specific functions
+         AND ap.attribute_context_component_id = ident_nil_v1())
+
+      UNION ALL
+
+      (SELECT 'action' AS kind, action_prototypes.func_id
+       FROM action_prototypes_v1($1, $2) action_prototypes
+       WHERE action_prototypes.schema_variant_id = $3)
+
+      UNION ALL
+
+      (SELECT 'auth' AS kind, prototypes.func_id
+       FROM authentication_prototypes_v1($1, $2) prototypes
+       WHERE prototypes.schema_variant_id = $3)
+
+      UNION ALL
+
+      (SELECT 'validation' AS kind, vp.func_id
+       FROM validation_prototypes_v1($1, $2) vp
+       WHERE vp.schema_variant_id = $3)) AS bound
+ORDER BY bound.kind, bound.func_id
@@ -120,6 +120,8 @@ impl_standard_model! {
 }
 
 impl ReconciliationPrototype {
+    /// Creates the prototype for `context`, or updates the func and name of the prototype that
+    /// already exists for that exact context, so calling this repeatedly never duplicates it.
     pub async fn upsert(
         ctx: &DalContext,
         func_id: FuncId,
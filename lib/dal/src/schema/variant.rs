@@ -2,15 +2,18 @@
 //! [`Component`](crate::Component).
 
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use thiserror::Error;
 
 use si_data_nats::NatsError;
 use si_data_pg::PgError;
+use si_pkg::{SiPkgSocketData, SocketSpecKind};
 use telemetry::prelude::*;
 
 use crate::attribute::context::AttributeContextBuilder;
 use crate::func::binding_return_value::FuncBindingReturnValueError;
 use crate::prop::PropPath;
+use crate::prop_tree::{PropTree, PropTreeError, PropTreeNode};
 use crate::provider::internal::InternalProviderError;
 use crate::schema::variant::definition::{SchemaVariantDefinitionError, SchemaVariantDefinitionId};
 use crate::schema::variant::root_prop::component_type::ComponentType;
@@ -25,7 +28,7 @@ use crate::{
     },
     impl_standard_model, pk,
     schema::{RootProp, SchemaError},
-    socket::{Socket, SocketError, SocketId},
+    socket::{Socket, SocketEdgeKind, SocketError, SocketId},
     standard_model::{self, objects_from_rows},
     standard_model_accessor, standard_model_belongs_to, standard_model_many_to_many,
     AttributeContextBuilderError, AttributePrototype, AttributePrototypeArgumentError,
@@ -46,6 +49,7 @@ pub mod root_prop;
 
 const ALL_FUNCS: &str = include_str!("../queries/schema_variant/all_related_funcs.sql");
 const ALL_PROPS: &str = include_str!("../queries/schema_variant/all_props.sql");
+const BOUND_FUNCS: &str = include_str!("../queries/schema_variant/bound_funcs.sql");
 const FIND_ROOT_PROP: &str = include_str!("../queries/schema_variant/find_root_prop.sql");
 const FIND_LEAF_ITEM_PROP: &str = include_str!("../queries/schema_variant/find_leaf_item_prop.sql");
 const FIND_ROOT_CHILD_IMPLICIT_INTERNAL_PROVIDER: &str =
@@ -106,6 +110,10 @@ pub enum SchemaVariantError {
     InternalProvider(#[from] InternalProviderError),
     #[error("must provide valid schema variant, found unset schema variant id")]
     InvalidSchemaVariant,
+    #[error("json pointer {1} not found in {0:?}")]
+    JsonPointerNotFound(serde_json::Value, String),
+    #[error("json value is not an object: {0:?}")]
+    JsonValueIsNotAnObject(serde_json::Value),
     #[error("leaf function response type ({0}) must match leaf kind ({0})")]
     LeafFunctionMismatch(FuncBackendResponseType, LeafKind),
     #[error("leaf function ({0}) must be JsAttribute")]
@@ -141,6 +149,8 @@ pub enum SchemaVariantError {
     PropNotFoundAtPath(SchemaVariantId, String, Visibility),
     #[error("prop not found in cache for name ({0}) and parent prop id ({1})")]
     PropNotFoundInCache(String, PropId),
+    #[error("prop tree error: {0}")]
+    PropTree(#[from] Box<PropTreeError>),
     #[error("reconciliation prototype: {0}")]
     ReconciliationPrototype(#[from] ReconciliationPrototypeError),
     #[error("schema error: {0}")]
@@ -168,6 +178,24 @@ pk!(SchemaVariantId);
 
 type DateTimeUtc = chrono::DateTime<chrono::Utc>;
 
+/// The [`Funcs`](crate::Func) bound to a [`SchemaVariant`], as found by
+/// [`SchemaVariant::bound_funcs`].
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BoundFuncs {
+    /// Funcs of the variant's [`ActionPrototypes`](crate::ActionPrototype)
+    pub actions: Vec<FuncId>,
+    /// Funcs of the variant's authentication prototypes
+    pub auths: Vec<FuncId>,
+    /// Code generation and qualification funcs on the variant's
+    /// [`leaves`](crate::schema::variant::leaves)
+    pub leaves: Vec<FuncId>,
+    /// Funcs of the other attribute prototypes for the variant's props and providers
+    pub attributes: Vec<FuncId>,
+    /// Funcs of the variant's validation prototypes
+    pub validations: Vec<FuncId>,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct SchemaVariant {
     pk: SchemaVariantPk,
@@ -181,6 +209,7 @@ pub struct SchemaVariant {
 
     ui_hidden: bool,
     default_color: Option<String>,
+    default_color_dark: Option<String>,
     name: String,
     /// The [`RootProp`](crate::RootProp) for [`self`](Self).
     root_prop_id: Option<PropId>,
@@ -427,6 +456,7 @@ impl SchemaVariant {
     }
 
     standard_model_accessor!(default_color, Option<String>, SchemaVariantResult);
+    standard_model_accessor!(default_color_dark, Option<String>, SchemaVariantResult);
     standard_model_accessor!(pkg_created_at, Option<DateTimeUtc>, SchemaVariantResult);
     standard_model_accessor!(ui_hidden, bool, SchemaVariantResult);
     standard_model_accessor!(name, String, SchemaVariantResult);
@@ -474,6 +504,15 @@ impl SchemaVariant {
         Ok(color)
     }
 
+    /// The color to use for this variant in dark mode. Falls back to [`Self::color`] when the
+    /// variant has no separate dark color.
+    pub async fn color_dark(&self, ctx: &DalContext) -> SchemaVariantResult<Option<String>> {
+        match self.default_color_dark() {
+            Some(color_dark) => Ok(Some(color_dark.to_owned())),
+            None => self.color(ctx).await,
+        }
+    }
+
     pub async fn set_color(&mut self, ctx: &DalContext, color: String) -> SchemaVariantResult<()> {
         self.set_default_color(ctx, Some(color.clone())).await?;
 
@@ -589,6 +628,253 @@ impl SchemaVariant {
         Ok(objects_from_rows(rows)?)
     }
 
+    /// Find the ids of the [`Funcs`](crate::Func) bound to this schema variant, grouped by how
+    /// they are bound. Like [`Self::all_funcs`], funcs bound to individual components and
+    /// intrinsics are ignored.
+    pub async fn bound_funcs(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<BoundFuncs> {
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                BOUND_FUNCS,
+                &[ctx.tenancy(), ctx.visibility(), &schema_variant_id],
+            )
+            .await?;
+
+        let mut bound_funcs = BoundFuncs::default();
+        for row in rows {
+            let kind: String = row.try_get("kind")?;
+            let func_id: FuncId = row.try_get("func_id")?;
+            match kind.as_str() {
+                "action" => bound_funcs.actions.push(func_id),
+                "auth" => bound_funcs.auths.push(func_id),
+                "leaf" => bound_funcs.leaves.push(func_id),
+                "validation" => bound_funcs.validations.push(func_id),
+                _ => bound_funcs.attributes.push(func_id),
+            }
+        }
+
+        Ok(bound_funcs)
+    }
+
+    /// List every [`Socket`] for a given [`SchemaVariantId`](SchemaVariant), paired with the
+    /// provider backing it. Input sockets come with their explicit
+    /// [`InternalProvider`](crate::InternalProvider) and output sockets come with their
+    /// [`ExternalProvider`](crate::ExternalProvider).
+    pub async fn sockets_with_providers(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<Vec<(Socket, Option<InternalProvider>, Option<ExternalProvider>)>>
+    {
+        let schema_variant = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(schema_variant_id))?;
+
+        let mut sockets_with_providers = vec![];
+        for socket in schema_variant.sockets(ctx).await? {
+            let (internal_provider, external_provider) = match socket.edge_kind() {
+                SocketEdgeKind::ConfigurationInput => (
+                    InternalProvider::find_explicit_for_socket(ctx, *socket.id()).await?,
+                    None,
+                ),
+                SocketEdgeKind::ConfigurationOutput => (
+                    None,
+                    ExternalProvider::find_for_socket(ctx, *socket.id()).await?,
+                ),
+            };
+
+            sockets_with_providers.push((socket, internal_provider, external_provider));
+        }
+
+        Ok(sockets_with_providers)
+    }
+
+    /// Creates a [`Socket`] and the provider backing it for each of the given socket specs,
+    /// returned in the same order. The identity [`Func`] binding shared by every socket is
+    /// resolved once for the whole batch rather than once per socket.
+    pub async fn create_sockets_batch(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+        schema_id: SchemaId,
+        specs: &[SiPkgSocketData],
+    ) -> SchemaVariantResult<Vec<(Socket, Option<InternalProvider>, Option<ExternalProvider>)>>
+    {
+        if specs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let (identity_func, identity_func_binding, identity_func_binding_return_value) =
+            Func::identity_with_binding_and_return_value(ctx).await?;
+
+        let mut sockets = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let (mut socket, internal_provider, external_provider) = match spec.kind() {
+                SocketSpecKind::Input => {
+                    let (internal_provider, socket) = InternalProvider::new_explicit_with_socket(
+                        ctx,
+                        schema_variant_id,
+                        spec.name(),
+                        *identity_func.id(),
+                        *identity_func_binding.id(),
+                        *identity_func_binding_return_value.id(),
+                        spec.connection_annotations(),
+                        spec.arity().into(),
+                        false,
+                    )
+                    .await?;
+
+                    (socket, Some(internal_provider), None)
+                }
+                SocketSpecKind::Output => {
+                    let (external_provider, socket) = ExternalProvider::new_with_socket(
+                        ctx,
+                        schema_id,
+                        schema_variant_id,
+                        spec.name(),
+                        None,
+                        *identity_func.id(),
+                        *identity_func_binding.id(),
+                        *identity_func_binding_return_value.id(),
+                        spec.connection_annotations(),
+                        spec.arity().into(),
+                        false,
+                    )
+                    .await?;
+
+                    (socket, None, Some(external_provider))
+                }
+            };
+
+            socket.set_ui_hidden(ctx, spec.ui_hidden()).await?;
+
+            sockets.push((socket, internal_provider, external_provider));
+        }
+
+        Ok(sockets)
+    }
+
+    /// Exports the "/root/domain" prop tree of a [`SchemaVariant`] as a JSON Schema (draft-07)
+    /// document, so that external tooling can validate domain values before handing them over.
+    /// Default values are included for primitive props that are not inside a map or array, the
+    /// same ones a package export would carry.
+    pub async fn export_json_schema(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<serde_json::Value> {
+        let schema_variant = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(schema_variant_id))?;
+
+        let prop_tree = PropTree::new(ctx, true, Some(vec![schema_variant_id]), None)
+            .await
+            .map_err(Box::new)?;
+        let domain_tree_node = prop_tree
+            .root_props
+            .into_iter()
+            .flat_map(|root| root.children)
+            .find(|tree_node| tree_node.name == "domain" && tree_node.path == "/root/")
+            .ok_or(SchemaVariantError::PropNotFound("/root/domain"))?;
+
+        let mut default_values = HashMap::new();
+        let mut work_queue: VecDeque<(&PropTreeNode, bool)> = domain_tree_node
+            .children
+            .iter()
+            .map(|child| (child, false))
+            .collect();
+        while let Some((tree_node, inside_map_or_array)) = work_queue.pop_front() {
+            match tree_node.kind {
+                PropKind::String | PropKind::Integer | PropKind::Boolean => {
+                    if inside_map_or_array {
+                        continue;
+                    }
+                    let context = AttributeContextBuilder::new()
+                        .set_prop_id(tree_node.prop_id)
+                        .to_context()?;
+                    if let Some(attribute_value) =
+                        AttributeValue::find_for_context(ctx, context.into()).await?
+                    {
+                        if let Some(default_value) = attribute_value.get_value(ctx).await? {
+                            default_values.insert(tree_node.prop_id, default_value);
+                        }
+                    }
+                }
+                PropKind::Object => work_queue.extend(
+                    tree_node
+                        .children
+                        .iter()
+                        .map(|child| (child, inside_map_or_array)),
+                ),
+                PropKind::Array | PropKind::Map => {
+                    work_queue.extend(tree_node.children.iter().map(|child| (child, true)))
+                }
+            }
+        }
+
+        let mut json_schema = domain_tree_node
+            .json_schema(&default_values)
+            .map_err(Box::new)?;
+        json_schema["$schema"] = serde_json::json!("http://json-schema.org/draft-07/schema#");
+        json_schema["title"] = serde_json::json!(schema_variant.name());
+
+        Ok(json_schema)
+    }
+
+    /// Builds the empty value skeleton of a component of this [`SchemaVariant`]: every
+    /// [`Prop`] under "/root" (including everything under "/root/domain") keyed by name, with
+    /// objects and maps as empty objects, arrays as empty arrays and primitives as `null`. The
+    /// children of arrays and maps are not included, since they have no keys or indices yet.
+    pub async fn build_empty_domain_skeleton(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<serde_json::Value> {
+        let mut skeleton = serde_json::json!({ "root": {} });
+        let mut work_queue: VecDeque<Prop> =
+            VecDeque::from([Self::find_root_prop(ctx, schema_variant_id)
+                .await?
+                .ok_or(SchemaVariantError::PropNotFound("/root"))?]);
+        while let Some(prop) = work_queue.pop_front() {
+            if matches!(prop.kind(), PropKind::Object) {
+                work_queue.extend(prop.child_props(ctx).await?);
+            }
+
+            let path = prop.path();
+            let parent_path = match path.parent() {
+                Some(parent_path) => parent_path.to_json_pointer(),
+                None => continue,
+            };
+
+            match skeleton.pointer_mut(&parent_path) {
+                Some(serde_json::Value::Object(object)) => {
+                    object.insert(
+                        path.last_segment().to_owned(),
+                        match prop.kind() {
+                            PropKind::String | PropKind::Boolean | PropKind::Integer => {
+                                serde_json::Value::Null
+                            }
+                            PropKind::Array => serde_json::json!([]),
+                            PropKind::Map | PropKind::Object => serde_json::json!({}),
+                        },
+                    );
+                }
+                Some(value) => {
+                    return Err(SchemaVariantError::JsonValueIsNotAnObject(value.clone()))
+                }
+                None => {
+                    return Err(SchemaVariantError::JsonPointerNotFound(
+                        skeleton,
+                        parent_path,
+                    ))
+                }
+            }
+        }
+
+        Ok(skeleton["root"].take())
+    }
+
     pub async fn upsert_leaf_function(
         ctx: &DalContext,
         schema_variant_id: SchemaVariantId,
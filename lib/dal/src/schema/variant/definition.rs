@@ -569,9 +569,12 @@ impl SchemaVariantDefinitionJson {
             .unwrap_or(SchemaVariantSpecData {
                 name: "v0".into(),
                 color: None,
+                color_dark: None,
                 link: None,
+                description: None,
                 component_type: si_pkg::SchemaVariantSpecComponentType::Component,
                 func_unique_id: "0".into(),
+                default_connection_annotations: None,
             });
 
         let metadata = SchemaVariantDefinitionMetadataJson {
@@ -585,7 +588,7 @@ impl SchemaVariantDefinitionJson {
             component_kind: ComponentKind::Standard,
             component_type: variant_spec_data.component_type.into(),
             link: variant_spec_data.link.as_ref().map(|l| l.to_string()),
-            description: None, // XXX - does this exist?
+            description: variant_spec_data.description.to_owned(),
         };
 
         Ok(metadata)
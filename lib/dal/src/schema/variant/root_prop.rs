@@ -6,7 +6,7 @@ use telemetry::prelude::*;
 
 use crate::property_editor::schema::WidgetKind;
 use crate::{
-    schema::variant::{leaves::LeafKind, SchemaVariantResult},
+    schema::variant::{leaves::LeafKind, SchemaVariantError, SchemaVariantResult},
     DalContext, Func, Prop, PropId, PropKind, ReconciliationPrototype,
     ReconciliationPrototypeContext, SchemaId, SchemaVariant, SchemaVariantId, StandardModel,
 };
@@ -163,6 +163,31 @@ impl SchemaVariant {
         })
     }
 
+    /// Creates the "/root/resource_value" [`Prop`](crate::Prop) tree on a [`SchemaVariant`] that
+    /// was created before the tree was part of the [`RootProp`]. Returns `true` if the tree was
+    /// missing and has been created, in which case a function still has to be attached to it,
+    /// e.g. with [`attach_resource_payload_to_value`](crate::pkg::attach_resource_payload_to_value).
+    pub async fn ensure_resource_value_prop(
+        ctx: &DalContext,
+        schema_variant_id: SchemaVariantId,
+    ) -> SchemaVariantResult<bool> {
+        match Self::find_prop_in_tree(ctx, schema_variant_id, &["root", "resource_value"]).await {
+            Ok(_) => return Ok(false),
+            Err(SchemaVariantError::PropNotFoundAtPath(_, _, _)) => {}
+            Err(err) => return Err(err),
+        }
+
+        let mut schema_variant = Self::get_by_id(ctx, &schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::NotFound(schema_variant_id))?;
+        let root_prop = Self::find_root_prop(ctx, schema_variant_id)
+            .await?
+            .ok_or(SchemaVariantError::PropNotFound("/root"))?;
+        Self::setup_resource_value(ctx, *root_prop.id(), &mut schema_variant).await?;
+
+        Ok(true)
+    }
+
     async fn insert_leaf_props(
         ctx: &DalContext,
         leaf_kind: LeafKind,
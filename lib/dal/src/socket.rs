@@ -37,6 +37,8 @@ pub enum SocketError {
     /// Could not find the [`SchemaVariant`](crate::SchemaVariant) by id.
     #[error("schema variant not found by id: {0}")]
     SchemaVariantNotFound(SchemaVariantId),
+    #[error("error serializing/deserializing json: {0}")]
+    SerdeJson(#[from] serde_json::Error),
     #[error("standard model error: {0}")]
     StandardModel(#[from] StandardModelError),
     #[error("transactions error: {0}")]
@@ -234,6 +236,20 @@ impl Socket {
     standard_model_accessor!(required, bool, SocketResult);
     standard_model_accessor!(ui_hidden, bool, SocketResult);
 
+    /// Parses the JSON-encoded [`connection annotations`](Self::connection_annotations()).
+    pub fn connection_annotation_list(&self) -> SocketResult<Vec<String>> {
+        Ok(serde_json::from_str(&self.connection_annotations)?)
+    }
+
+    /// Returns true if [`Self`] shares at least one connection annotation with `other`.
+    pub fn is_annotation_compatible_with(&self, other: &Socket) -> SocketResult<bool> {
+        let other_annotations = other.connection_annotation_list()?;
+        Ok(self
+            .connection_annotation_list()?
+            .iter()
+            .any(|annotation| other_annotations.contains(annotation)))
+    }
+
     standard_model_many_to_many!(
         lookup_fn: types,
         associate_fn: add_type,
@@ -370,4 +386,42 @@ impl Socket {
             .await?;
         Ok(standard_model::option_object_from_row(maybe_row)?)
     }
+
+    /// Find a [`Socket`] by a provided name for a given [`SocketEdgeKind`] and a given
+    /// [`NodeId`](crate::Node), preferring the one whose connection annotations are compatible
+    /// with `counterpart` when several sockets share the name. Falls back to the first socket
+    /// found if none are compatible.
+    pub async fn find_by_name_for_edge_kind_and_node_compatible_with(
+        ctx: &DalContext,
+        name: impl AsRef<str>,
+        socket_edge_kind: SocketEdgeKind,
+        node_id: NodeId,
+        counterpart: &Socket,
+    ) -> SocketResult<Option<Self>> {
+        let name = name.as_ref();
+        let rows = ctx
+            .txns()
+            .await?
+            .pg()
+            .query(
+                FIND_BY_NAME_FOR_EDGE_KIND_AND_NODE,
+                &[
+                    ctx.tenancy(),
+                    ctx.visibility(),
+                    &name,
+                    &socket_edge_kind.as_ref(),
+                    &node_id,
+                ],
+            )
+            .await?;
+        let mut sockets: Vec<Self> = standard_model::objects_from_rows(rows)?;
+
+        for (index, socket) in sockets.iter().enumerate() {
+            if socket.is_annotation_compatible_with(counterpart)? {
+                return Ok(Some(sockets.swap_remove(index)));
+            }
+        }
+
+        Ok(sockets.into_iter().next())
+    }
 }
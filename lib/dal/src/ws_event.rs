@@ -7,9 +7,11 @@ use ulid::Ulid;
 use crate::action::{ActionAddedPayload, ActionRemovedPayload};
 use crate::change_set::{ChangeSetActorPayload, ChangeSetMergeVotePayload};
 use crate::component::{ComponentCreatedPayload, ComponentUpdatedPayload};
+use crate::diagram::connection::ConnectionCreatedPayload;
 use crate::func::{FuncCreatedPayload, FuncDeletedPayload, FuncRevertedPayload, FuncSavedPayload};
 use crate::pkg::{
-    ImportWorkspaceVotePayload, ModuleImportedPayload, WorkspaceActorPayload,
+    ImportWorkspaceVotePayload, ModuleImportedPayload, ModuleRemovedPayload,
+    PackageImportedPayload, PkgImportProgressPayload, WorkspaceActorPayload,
     WorkspaceExportPayload, WorkspaceImportApprovalActorPayload, WorkspaceImportPayload,
 };
 use crate::schema::variant::definition::{
@@ -73,6 +75,7 @@ pub enum WsPayload {
     CodeGenerated(CodeGeneratedPayload),
     ComponentCreated(ComponentCreatedPayload),
     ComponentUpdated(ComponentUpdatedPayload),
+    ConnectionCreated(ConnectionCreatedPayload),
     Cursor(CursorPayload),
     FixBatchReturn(FixBatchReturn),
     FixReturn(FixReturn),
@@ -83,7 +86,10 @@ pub enum WsPayload {
     ImportWorkspaceVote(ImportWorkspaceVotePayload),
     LogLine(LogLinePayload),
     ModuleImported(ModuleImportedPayload),
+    ModuleRemoved(ModuleRemovedPayload),
     Online(OnlinePayload),
+    PackageImported(PackageImportedPayload),
+    PkgImportProgress(PkgImportProgressPayload),
     ResourceRefreshed(ResourceRefreshedPayload),
     SchemaCreated(SchemaPk),
     SchemaVariantDefinitionCloned(SchemaVariantDefinitionClonedPayload),
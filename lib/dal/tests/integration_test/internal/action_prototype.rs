@@ -1,8 +1,15 @@
 use pretty_assertions_sorted::assert_eq;
 
 use dal::action_prototype::ActionKind;
-use dal::{ActionPrototype, ActionPrototypeContext, DalContext, FuncId};
-use dal_test::test;
+use dal::{
+    Action, ActionPrototype, ActionPrototypeContext, Component, DalContext, FuncId, Schema,
+    StandardModel,
+};
+use dal_test::{
+    test,
+    test_harness::{create_component_for_schema_variant, create_schema, create_schema_variant},
+    DalContextHeadRef,
+};
 
 #[test]
 async fn new(ctx: &DalContext) {
@@ -13,3 +20,133 @@ async fn new(ctx: &DalContext) {
     assert_eq!(*prototype.kind(), ActionKind::Create);
     assert_eq!(prototype.func_id(), FuncId::NONE);
 }
+
+#[test]
+async fn run_skips_unchanged_refresh(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("find starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    let (component, _) = Component::new(ctx, "serenity", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let refresh = ActionPrototype::find_for_context_and_kind(
+        ctx,
+        ActionKind::Refresh,
+        ActionPrototypeContext { schema_variant_id },
+    )
+    .await
+    .expect("find refresh prototypes")
+    .pop()
+    .expect("starfield has a refresh action");
+
+    let first_run = refresh
+        .run_if_changed(ctx, *component.id())
+        .await
+        .expect("run refresh");
+    assert!(first_run.is_some());
+
+    let second_run = refresh
+        .run_if_changed(ctx, *component.id())
+        .await
+        .expect("run refresh again");
+    assert_eq!(None, second_run);
+
+    let forced_run = refresh
+        .run(ctx, *component.id())
+        .await
+        .expect("run refresh without skipping");
+    assert!(forced_run.is_some());
+}
+
+#[test]
+async fn find_for_component_and_kind(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let mut schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+    let context = ActionPrototypeContext {
+        schema_variant_id: *schema_variant.id(),
+    };
+
+    let create = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Create, context)
+        .await
+        .expect("unable to create create prototype");
+    let delete = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Delete, context)
+        .await
+        .expect("unable to create delete prototype");
+
+    let component = create_component_for_schema_variant(ctx, schema_variant.id()).await;
+
+    let found =
+        ActionPrototype::find_for_component_and_kind(ctx, *component.id(), ActionKind::Create)
+            .await
+            .expect("find create prototypes");
+    assert_eq!(vec![create], found);
+
+    let found =
+        ActionPrototype::find_for_component_and_kind(ctx, *component.id(), ActionKind::Delete)
+            .await
+            .expect("find delete prototypes");
+    assert_eq!(vec![delete], found);
+
+    let found =
+        ActionPrototype::find_for_component_and_kind(ctx, *component.id(), ActionKind::Refresh)
+            .await
+            .expect("find refresh prototypes");
+    assert!(found.is_empty());
+}
+
+#[test]
+async fn order_by_priority(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let mut schema_variant = create_schema_variant(ctx, *schema.id()).await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+    let context = ActionPrototypeContext {
+        schema_variant_id: *schema_variant.id(),
+    };
+
+    let mut connect = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Other, context)
+        .await
+        .expect("unable to create connect prototype");
+    connect
+        .set_priority(ctx, 10)
+        .await
+        .expect("unable to set priority");
+    let tag = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Other, context)
+        .await
+        .expect("unable to create tag prototype");
+    let label = ActionPrototype::new(ctx, FuncId::NONE, ActionKind::Other, context)
+        .await
+        .expect("unable to create label prototype");
+    assert_eq!(0, tag.priority());
+
+    let component = create_component_for_schema_variant(ctx, schema_variant.id()).await;
+    let connect_action = Action::new(ctx, *connect.id(), *component.id())
+        .await
+        .expect("unable to create connect action");
+    let tag_action = Action::new(ctx, *tag.id(), *component.id())
+        .await
+        .expect("unable to create tag action");
+    let label_action = Action::new(ctx, *label.id(), *component.id())
+        .await
+        .expect("unable to create label action");
+
+    let bags = Action::order(ctx).await.expect("unable to order actions");
+
+    let connect_bag = bags.get(connect_action.id()).expect("connect is ordered");
+    assert!(connect_bag.parents.is_empty());
+
+    // Lower priority actions wait for the higher priority one, but not for each other
+    let tag_bag = bags.get(tag_action.id()).expect("tag is ordered");
+    assert_eq!(vec![*connect_action.id()], tag_bag.parents);
+    let label_bag = bags.get(label_action.id()).expect("label is ordered");
+    assert_eq!(vec![*connect_action.id()], label_bag.parents);
+}
@@ -2,8 +2,9 @@ use dal::{
     attribute::context::{AttributeContext, AttributeContextBuilder},
     attribute::prototype::AttributePrototype,
     func::{backend::string::FuncBackendStringArgs, binding::FuncBinding},
-    AttributePrototypeError, AttributeValue, Component, ComponentView, DalContext, Func,
-    FuncBackendKind, FuncBackendResponseType, PropKind, Schema, SchemaVariant, StandardModel,
+    AttributePrototypeError, AttributePrototypeFuncBinding, AttributePrototypeFuncBindingTarget,
+    AttributeValue, Component, ComponentView, DalContext, Func, FuncBackendKind,
+    FuncBackendResponseType, PropKind, Schema, SchemaVariant, StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::{
@@ -524,3 +525,65 @@ async fn remove_component_specific(ctx: &DalContext) {
         }
     }
 }
+
+#[test]
+async fn find_bindings_for_func(ctx: &DalContext) {
+    let func = Func::new(
+        ctx,
+        "test:setName",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create func");
+
+    let mut expected = vec![];
+    for schema_name in ["starfield", "fallout"] {
+        let schema = Schema::find_by_name(ctx, schema_name)
+            .await
+            .expect("cannot find schema");
+        let schema_variant_id = *schema
+            .default_schema_variant_id()
+            .expect("schema has no default variant");
+        let schema_variant = SchemaVariant::get_by_id(ctx, &schema_variant_id)
+            .await
+            .expect("cannot get schema variant")
+            .expect("schema variant not found");
+        let name_prop = schema_variant
+            .find_prop(ctx, &["root", "si", "name"])
+            .await
+            .expect("cannot find name prop");
+        let context = AttributeContext::builder()
+            .set_prop_id(*name_prop.id())
+            .to_context()
+            .expect("cannot create context");
+        let mut prototype = AttributePrototype::find_for_context_and_key(ctx, context, &None)
+            .await
+            .expect("cannot find prototypes")
+            .pop()
+            .expect("name prop has no prototype");
+        prototype
+            .set_func_id(ctx, *func.id())
+            .await
+            .expect("cannot set func");
+
+        expected.push(AttributePrototypeFuncBinding {
+            attribute_prototype_id: *prototype.id(),
+            schema_variant_id,
+            component_id: None,
+            target: AttributePrototypeFuncBindingTarget::Prop {
+                prop_id: *name_prop.id(),
+                path: "root/si/name".to_owned(),
+            },
+        });
+    }
+
+    let bindings = AttributePrototype::find_bindings_for_func(ctx, *func.id())
+        .await
+        .expect("cannot find bindings for func");
+
+    assert_eq!(expected.len(), bindings.len());
+    for binding in &expected {
+        assert!(bindings.contains(binding));
+    }
+}
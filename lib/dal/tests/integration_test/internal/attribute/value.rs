@@ -2,14 +2,15 @@ use pretty_assertions_sorted::assert_eq;
 
 use dal::{
     attribute::context::AttributeContextBuilder, component::view::ComponentView, generate_name,
-    AttributeContext, AttributeReadContext, AttributeValue, Component, DalContext, PropKind,
-    StandardModel,
+    prop::PropPath, AttributeContext, AttributeReadContext, AttributeValue, Component, DalContext,
+    PropKind, StandardModel,
 };
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::{
     test,
     test_harness::{create_schema, create_schema_variant_with_root},
 };
+use si_pkg::AttributeValuePath;
 
 #[test]
 async fn update_for_context_simple(ctx: &DalContext) {
@@ -707,3 +708,190 @@ async fn list_payload(ctx: &DalContext) {
     assert_eq!(found_name.replace('"', ""), name);
     assert_eq!(si_name_value, domain_name_value);
 }
+
+#[test]
+async fn resolve_path(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+
+    let name_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "name",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await;
+    let map_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "tags",
+        PropKind::Map,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await;
+    let map_item_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "tag",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*map_prop.id()),
+    )
+    .await;
+    let array_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "ports",
+        PropKind::Array,
+        *schema_variant.id(),
+        Some(root.domain_prop_id),
+    )
+    .await;
+    let array_element_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "port",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*array_prop.id()),
+    )
+    .await;
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    let (component, _) =
+        Component::new_for_default_variant_from_schema(ctx, "Resolved Component", *schema.id())
+            .await
+            .expect("Unable to create component");
+
+    let base_attribute_read_context = AttributeReadContext {
+        prop_id: None,
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+
+    let map_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*map_prop.id()),
+            ..base_attribute_read_context
+        },
+    )
+    .await
+    .expect("cannot get map AttributeValue")
+    .expect("map AttributeValue not found");
+    let map_item_context = AttributeContextBuilder::from(base_attribute_read_context)
+        .set_prop_id(*map_item_prop.id())
+        .to_context()
+        .expect("cannot build write AttributeContext");
+    let map_item_value_id = AttributeValue::insert_for_context(
+        ctx,
+        map_item_context,
+        *map_value.id(),
+        Some(serde_json::json!["systeminit"]),
+        Some("owner".to_string()),
+    )
+    .await
+    .expect("cannot insert map item");
+
+    let array_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*array_prop.id()),
+            ..base_attribute_read_context
+        },
+    )
+    .await
+    .expect("cannot get array AttributeValue")
+    .expect("array AttributeValue not found");
+    let array_element_context = AttributeContextBuilder::from(base_attribute_read_context)
+        .set_prop_id(*array_element_prop.id())
+        .to_context()
+        .expect("cannot build write AttributeContext");
+    let mut array_element_value_ids = vec![];
+    for port in ["80", "443"] {
+        array_element_value_ids.push(
+            AttributeValue::insert_for_context(
+                ctx,
+                array_element_context,
+                *array_value.id(),
+                Some(serde_json::json![port]),
+                None,
+            )
+            .await
+            .expect("cannot insert array element"),
+        );
+    }
+
+    let name_value = AttributeValue::find_for_context(
+        ctx,
+        AttributeReadContext {
+            prop_id: Some(*name_prop.id()),
+            ..base_attribute_read_context
+        },
+    )
+    .await
+    .expect("cannot get name AttributeValue")
+    .expect("name AttributeValue not found");
+
+    let resolve = |path: &[&str], key, index| AttributeValuePath::Prop {
+        path: PropPath::new(path).to_string(),
+        key,
+        index,
+    };
+
+    let resolved = AttributeValue::resolve_path(
+        ctx,
+        *component.id(),
+        *schema_variant.id(),
+        &resolve(&["root", "domain", "name"], None, None),
+    )
+    .await
+    .expect("resolve prop path")
+    .expect("prop path resolves to a value");
+    assert_eq!(name_value.id(), resolved.id());
+
+    let resolved = AttributeValue::resolve_path(
+        ctx,
+        *component.id(),
+        *schema_variant.id(),
+        &resolve(
+            ["root", "domain", "tags", "tag"],
+            Some("owner".to_string()),
+            None,
+        ),
+    )
+    .await
+    .expect("resolve map path")
+    .expect("map path resolves to a value");
+    assert_eq!(map_item_value_id, *resolved.id());
+
+    let resolved = AttributeValue::resolve_path(
+        ctx,
+        *component.id(),
+        *schema_variant.id(),
+        &resolve(&["root", "domain", "ports", "port"], None, Some(1)),
+    )
+    .await
+    .expect("resolve array path")
+    .expect("array path resolves to a value");
+    assert_eq!(array_element_value_ids[1], *resolved.id());
+
+    let missing = AttributeValue::resolve_path(
+        ctx,
+        *component.id(),
+        *schema_variant.id(),
+        &resolve(
+            ["root", "domain", "tags", "tag"],
+            Some("missing".to_string()),
+            None,
+        ),
+    )
+    .await
+    .expect("resolve missing map key");
+    assert!(missing.is_none());
+}
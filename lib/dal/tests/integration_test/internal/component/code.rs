@@ -299,6 +299,102 @@ async fn code_generation_can_handle_string_formats(ctx: &DalContext) {
     assert_eq!(Some("test string".to_string()), code_view.code);
 }
 
+#[test]
+async fn list_code_generated_paged_truncates_oversized_entries(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, _) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+
+    // Create a code prototype that generates a lot of code.
+    let mut func = Func::new(
+        ctx,
+        "test:codeGenerationPlan",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::CodeGeneration,
+    )
+    .await
+    .expect("could not create func");
+    let code = "function generatePlan(input) {
+      return {
+        format: \"string\",
+        code: \"resource\".repeat(1000)
+      };
+    }";
+    func.set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("set code");
+    func.set_handler(ctx, Some("generatePlan"))
+        .await
+        .expect("set handler");
+    let func_argument =
+        FuncArgument::new(ctx, "domain", FuncArgumentKind::Object, None, *func.id())
+            .await
+            .expect("could not create func argument");
+
+    SchemaVariant::add_leaf(
+        ctx,
+        *func.id(),
+        *schema_variant.id(),
+        None,
+        LeafKind::CodeGeneration,
+        vec![LeafInput {
+            location: LeafInputLocation::Domain,
+            func_argument_id: *func_argument.id(),
+        }],
+    )
+    .await
+    .expect("could not add code generation");
+
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new(ctx, "component", *schema_variant.id())
+        .await
+        .expect("cannot create component");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (mut code_views, has_code) =
+        Component::list_code_generated_paged(ctx, *component.id(), 100)
+            .await
+            .expect("could not list paged code generated for component");
+    assert!(has_code);
+    let code_view = code_views.pop().expect("code views are empty");
+    assert!(code_views.is_empty());
+    assert_eq!("test:codeGenerationPlan", code_view.key);
+    assert!(code_view.truncated);
+    assert_eq!(8000, code_view.total_size);
+    assert_eq!(
+        Some("resource".repeat(1000)[..100].to_string()),
+        code_view.code_view.code
+    );
+
+    // The full entry is still available on its own.
+    let full_code_view = Component::get_code_entry(ctx, *component.id(), &code_view.key)
+        .await
+        .expect("could not get code entry")
+        .expect("code entry not found");
+    assert_eq!(CodeLanguage::String, full_code_view.language);
+    assert_eq!(Some("resource".repeat(1000)), full_code_view.code);
+
+    // Entries that fit are left alone.
+    let (code_views, _) = Component::list_code_generated_paged(ctx, *component.id(), 8000)
+        .await
+        .expect("could not list paged code generated for component");
+    assert!(code_views.iter().all(|code_view| !code_view.truncated));
+}
+
 #[test]
 async fn all_code_generation_attribute_values(ctx: &DalContext) {
     // Create two schemas and variants.
@@ -588,3 +684,141 @@ async fn check_results(ctx: &DalContext) {
     assert!(found_kru_one_code_generation_one);
     assert!(found_kru_two_code_generation_one);
 }
+
+#[test]
+async fn regenerate_code_after_editing_code_generation_func(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let schema_variant_id = *schema_variant.id();
+
+    // domain: Object
+    // └─ poop: String
+    let poop_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "poop",
+        PropKind::String,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await;
+    let mut func = Func::new(
+        ctx,
+        "test:codeGeneration",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::CodeGeneration,
+    )
+    .await
+    .expect("could not create func");
+    let code = "function generateYAML(input) {
+      return {
+        format: \"yaml\",
+        code: Object.keys(input.domain).length > 0 ? YAML.stringify(input.domain) : \"\"
+      };
+    }";
+    func.set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("set code");
+    func.set_handler(ctx, Some("generateYAML"))
+        .await
+        .expect("set handler");
+    let func_argument =
+        FuncArgument::new(ctx, "domain", FuncArgumentKind::Object, None, *func.id())
+            .await
+            .expect("could not create func argument");
+    SchemaVariant::add_leaf(
+        ctx,
+        *func.id(),
+        *schema_variant.id(),
+        None,
+        LeafKind::CodeGeneration,
+        vec![LeafInput {
+            location: LeafInputLocation::Domain,
+            func_argument_id: *func_argument.id(),
+        }],
+    )
+    .await
+    .expect("could not add code generation");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new(ctx, "component", *schema_variant.id())
+        .await
+        .expect("cannot create component");
+    let read_context = AttributeReadContext {
+        prop_id: Some(*poop_prop.id()),
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("could not perform find for context")
+        .expect("attribute value not found");
+    let parent_attribute_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .expect("could not perform find parent attribute value")
+        .expect("no parent attribute value found");
+    let context = AttributeContextBuilder::from(read_context)
+        .to_context()
+        .expect("could not convert builder to attribute context");
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_attribute_value.id()),
+        context,
+        Some(serde_json::json!["canoe"]),
+        None,
+    )
+    .await
+    .expect("could not perform update for context");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // Edit the func to generate JSON instead. Nothing it depends on changed, so the code of the
+    // component stays stale until it is regenerated.
+    let code = "function generateJSON(input) {
+      return {
+        format: \"json\",
+        code: JSON.stringify(input.domain)
+      };
+    }";
+    func.set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("set code");
+    func.set_handler(ctx, Some("generateJSON"))
+        .await
+        .expect("set handler");
+
+    let (code_views, _) = Component::list_code_generated(ctx, *component.id())
+        .await
+        .expect("could not list code generated for component");
+    assert_eq!(Some("poop: canoe\n".to_string()), code_views[0].code);
+
+    let mut code_views = Component::regenerate_code(ctx, *component.id())
+        .await
+        .expect("could not regenerate code");
+    let code_view = code_views.pop().expect("code views are empty");
+    assert!(code_views.is_empty());
+    assert_eq!(CodeLanguage::Json, code_view.language);
+    assert_eq!(Some("{\"poop\":\"canoe\"}".to_string()), code_view.code);
+
+    let (mut code_views, _) = Component::list_code_generated(ctx, *component.id())
+        .await
+        .expect("could not list code generated for component");
+    assert_eq!(
+        Some("{\"poop\":\"canoe\"}".to_string()),
+        code_views.pop().expect("code views are empty").code
+    );
+}
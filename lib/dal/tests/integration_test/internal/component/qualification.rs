@@ -182,3 +182,139 @@ async fn add_and_list_qualifications(ctx: &DalContext) {
         QualificationSubCheckStatus::Success,
     );
 }
+
+#[test]
+async fn run_qualifications(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    let schema_variant_id = *schema_variant.id();
+    schema
+        .set_default_schema_variant_id(ctx, Some(schema_variant_id))
+        .await
+        .expect("cannot set default schema variant");
+
+    let poop_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "poop",
+        PropKind::Boolean,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await;
+    let mut qualification_func = Func::new(
+        ctx,
+        "test:qualification",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Qualification,
+    )
+    .await
+    .expect("could not create func");
+    let qualification_func_id = *qualification_func.id();
+    let code = r#"function isQualified(input) {
+        return {
+            result: (input.domain?.poop ?? false) ? 'success' : 'failure',
+            message: "must be present when result is not 'success'",
+        };
+    }"#;
+    qualification_func
+        .set_code_plaintext(ctx, Some(code))
+        .await
+        .expect("set code");
+    qualification_func
+        .set_handler(ctx, Some("isQualified"))
+        .await
+        .expect("set handler");
+    let qualified_func_argument = FuncArgument::new(
+        ctx,
+        "domain",
+        FuncArgumentKind::Object,
+        None,
+        qualification_func_id,
+    )
+    .await
+    .expect("could not create func argument");
+    SchemaVariant::add_leaf(
+        ctx,
+        qualification_func_id,
+        schema_variant_id,
+        None,
+        LeafKind::Qualification,
+        vec![LeafInput {
+            location: LeafInputLocation::Domain,
+            func_argument_id: *qualified_func_argument.id(),
+        }],
+    )
+    .await
+    .expect("could not add qualification");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let (component, _) = Component::new(ctx, "component", schema_variant_id)
+        .await
+        .expect("cannot create component");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let qualifications = Component::run_qualifications(ctx, *component.id())
+        .await
+        .expect("cannot run qualifications");
+    assert_eq!(
+        vec![Some(QualificationSubCheckStatus::Failure)],
+        qualifications
+            .iter()
+            .map(|qualification| qualification.result.as_ref().map(|result| result.status))
+            .collect::<Vec<_>>()
+    );
+
+    // Update the value the qualification depends on and check again.
+    let read_context = AttributeReadContext {
+        prop_id: Some(*poop_prop.id()),
+        component_id: Some(*component.id()),
+        ..AttributeReadContext::default()
+    };
+    let attribute_value = AttributeValue::find_for_context(ctx, read_context)
+        .await
+        .expect("could not perform find for context")
+        .expect("attribute value not found");
+    let parent_attribute_value = attribute_value
+        .parent_attribute_value(ctx)
+        .await
+        .expect("could not perform find parent attribute value")
+        .expect("no parent attribute value found");
+    let context = AttributeContextBuilder::from(read_context)
+        .to_context()
+        .expect("could not convert builder to attribute context");
+    AttributeValue::update_for_context(
+        ctx,
+        *attribute_value.id(),
+        Some(*parent_attribute_value.id()),
+        context,
+        Some(serde_json::json![true]),
+        None,
+    )
+    .await
+    .expect("could not perform update for context");
+
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let qualifications = Component::run_qualifications(ctx, *component.id())
+        .await
+        .expect("cannot run qualifications");
+    assert_eq!(
+        vec![Some(QualificationSubCheckStatus::Success)],
+        qualifications
+            .iter()
+            .map(|qualification| qualification.result.as_ref().map(|result| result.status))
+            .collect::<Vec<_>>()
+    );
+}
@@ -1,5 +1,5 @@
 use dal::func::backend::js_action::ActionRunResult;
-use dal::{ChangeSet, DalContext, ResourceView};
+use dal::{ChangeSet, Component, DalContext, ResourceView};
 use dal_test::helpers::component_bag::ComponentBagger;
 use dal_test::test;
 use pretty_assertions_sorted::assert_eq;
@@ -100,3 +100,48 @@ async fn get_resource(mut octx: DalContext) {
         actual,   // actual
     );
 }
+
+#[test]
+async fn list_missing_resource(mut octx: DalContext) {
+    let ctx = &mut octx;
+
+    let mut bagger = ComponentBagger::new();
+    let provisioned_bag = bagger.create_component(ctx, "vault", "fallout").await;
+    let unprovisioned_bag = bagger.create_component(ctx, "megaton", "fallout").await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut change_set = ChangeSet::get_by_pk(ctx, &ctx.visibility().change_set_pk)
+        .await
+        .expect("could not fetch change set by pk")
+        .expect("no change set found for pk");
+    change_set
+        .apply(ctx)
+        .await
+        .expect("cannot apply change set");
+    provisioned_bag
+        .component(ctx)
+        .await
+        .set_resource(
+            ctx,
+            ActionRunResult {
+                status: Some(ResourceStatus::Ok),
+                payload: Some(serde_json::json![{ "poop": true }]),
+                message: None,
+                logs: vec![],
+                last_synced: Default::default(),
+            },
+        )
+        .await
+        .expect("could not set resource");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let missing = Component::list_missing_resource(ctx)
+        .await
+        .expect("could not list components missing a resource");
+    assert!(missing.contains(&unprovisioned_bag.component_id));
+    assert!(!missing.contains(&provisioned_bag.component_id));
+}
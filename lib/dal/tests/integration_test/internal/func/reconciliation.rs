@@ -1,5 +1,8 @@
-use dal::{DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncBinding, StandardModel};
-use dal_test::test;
+use dal::{
+    ComponentId, DalContext, Func, FuncBackendKind, FuncBackendResponseType, FuncBinding,
+    ReconciliationPrototype, ReconciliationPrototypeContext, SchemaVariant, StandardModel,
+};
+use dal_test::{test, test_harness::create_schema};
 use ulid::Ulid;
 
 #[test]
@@ -88,3 +91,74 @@ async fn run(ctx: &DalContext) {
         }))
     );
 }
+
+#[test]
+async fn upsert_is_idempotent_per_context(ctx: &DalContext) {
+    let reconciliation_func = Func::find_by_attr(ctx, "name", &"si:defaultReconciliation")
+        .await
+        .expect("could not find func")
+        .pop()
+        .expect("default reconciliation func exists");
+
+    let schema = create_schema(ctx).await;
+    // Creating the variant sets up its resource value, which upserts the default prototype.
+    let (schema_variant, _) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+    let variant_context = ReconciliationPrototypeContext::new(*schema_variant.id());
+    let existing = ReconciliationPrototype::find_for_context(ctx, variant_context.clone())
+        .await
+        .expect("could not find prototype")
+        .expect("variant has a reconciliation prototype");
+
+    let upserted = ReconciliationPrototype::upsert(
+        ctx,
+        *reconciliation_func.id(),
+        "Reconciliation",
+        variant_context,
+    )
+    .await
+    .expect("could not upsert prototype");
+    assert_eq!(existing.id(), upserted.id());
+
+    // Two different components must each get their own prototype instead of overwriting one
+    // another.
+    let first_component_context = ReconciliationPrototypeContext::new(ComponentId::generate());
+    let second_component_context = ReconciliationPrototypeContext::new(ComponentId::generate());
+    let first = ReconciliationPrototype::upsert(
+        ctx,
+        *reconciliation_func.id(),
+        "Reconciliation",
+        first_component_context,
+    )
+    .await
+    .expect("could not upsert prototype");
+    let second = ReconciliationPrototype::upsert(
+        ctx,
+        *reconciliation_func.id(),
+        "Reconciliation",
+        second_component_context,
+    )
+    .await
+    .expect("could not upsert prototype");
+    assert_ne!(first.id(), second.id());
+
+    let prototypes = ReconciliationPrototype::list(ctx)
+        .await
+        .expect("could not list prototypes");
+    let variant_prototypes: Vec<_> = prototypes
+        .iter()
+        .filter(|prototype| {
+            prototype.schema_variant_id() == *schema_variant.id()
+                && prototype.component_id() == ComponentId::NONE
+        })
+        .collect();
+    assert_eq!(1, variant_prototypes.len());
+    assert_eq!(
+        1,
+        prototypes
+            .iter()
+            .filter(|prototype| prototype.component_id() == first.component_id())
+            .count()
+    );
+}
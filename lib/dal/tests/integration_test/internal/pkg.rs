@@ -1,24 +1,63 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+};
+
 use base64::{engine::general_purpose, Engine};
+use chrono::{DateTime, Duration, Utc};
 use dal::{
-    func::{argument::FuncArgumentKind, intrinsics::IntrinsicFunc},
+    edge::{EdgeId, EdgeKind},
+    func::{
+        argument::{FuncArgument, FuncArgumentKind},
+        backend::js_action::{ActionRunResult, ResourceStatus},
+        intrinsics::IntrinsicFunc,
+    },
     installed_pkg::*,
     pkg::*,
     prop::PropPath,
-    schema::variant::leaves::LeafKind,
-    ActionKind, ChangeSet, ChangeSetPk, DalContext, Func, InternalProvider, PropKind, Schema,
-    SchemaVariant, StandardModel,
+    property_editor::schema::WidgetKind,
+    qualification::QualificationSubCheckStatus,
+    schema::variant::{definition::SchemaVariantDefinition, leaves::LeafKind},
+    socket::SocketEdgeKind,
+    Action, ActionKind, ActionPrototype, ActionPrototypeContext, AttributeReadContext,
+    AttributeValue, AttributeValueId, ChangeSet, ChangeSetPk, Component, ComponentId,
+    ComponentStatus, ComponentView, Connection, DalContext, Edge, Func, FuncBackendKind,
+    FuncBackendResponseType, HistoryActor, InternalProvider, Node, NodeGeometry, Prop, PropId,
+    PropKind, Schema, SchemaVariant, SchemaVariantError, Socket, StandardModel, UserPk,
 };
 use dal::{BuiltinsResult, ComponentType};
-use dal_test::{connection_annotation_string, test, DalContextHeadRef};
+use dal_test::{
+    connection_annotation_string, helpers::component_bag::ComponentBagger, test,
+    test_harness::create_schema, tracing_subscriber, DalContextHeadRef,
+};
+use futures::StreamExt;
 use si_pkg::{
-    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, FuncArgumentSpec, FuncSpec,
-    FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, LeafFunctionSpec,
-    LeafInputLocation as PkgLeafInputLocation, LeafKind as PkgLeafKind, PkgSpec, PropSpec,
-    PropSpecKind, SchemaSpec, SchemaSpecData, SchemaVariantSpec, SchemaVariantSpecData, SiPkg,
-    SocketSpec, SocketSpecArity, SocketSpecData, SocketSpecKind,
+    ActionFuncSpec, AttrFuncInputSpec, AttrFuncInputSpecKind, AttributeValuePath, FuncArgumentSpec,
+    FuncSpec, FuncSpecBackendKind, FuncSpecBackendResponseType, FuncSpecData, LeafFunctionSpec,
+    LeafInputLocation as PkgLeafInputLocation, LeafKind as PkgLeafKind, PkgSpec, PkgSpecBuilder,
+    PropSpec, PropSpecKind, PropSpecWidgetKind, SchemaSpec, SchemaSpecData, SchemaVariantSpec,
+    SchemaVariantSpecBuilder, SchemaVariantSpecData, SchemaVariantSpecDataBuilder, SiPkg,
+    SiPkgKind, SocketSpec, SocketSpecArity, SocketSpecData, SocketSpecKind,
 };
+use telemetry::tracing::{instrument::WithSubscriber, Level};
+use tokio_util::sync::CancellationToken;
 
 async fn make_stellarfield(ctx: &DalContext) -> BuiltinsResult<()> {
+    import_pkg_from_pkg(
+        ctx,
+        &stellarfield_pkg()?,
+        Some(dal::pkg::ImportOptions {
+            schemas: Some(vec!["stellarfield".into()]),
+            ..Default::default()
+        }),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
+fn stellarfield_pkg() -> BuiltinsResult<SiPkg> {
     let mut stellarfield_builder = PkgSpec::builder();
 
     stellarfield_builder
@@ -311,19 +350,220 @@ async fn make_stellarfield(ctx: &DalContext) -> BuiltinsResult<()> {
         .schema(stellarfield_schema)
         .build()?;
 
-    let stellarfield_pkg = SiPkg::load_from_spec(stellarfield_spec)?;
-    import_pkg_from_pkg(
+    Ok(SiPkg::load_from_spec(stellarfield_spec)?)
+}
+
+async fn prototype_func_name_for_prop(ctx: &DalContext, prop_id: PropId) -> String {
+    let attribute_value = AttributeValue::find_for_context(
         ctx,
-        &stellarfield_pkg,
-        Some(dal::pkg::ImportOptions {
-            schemas: Some(vec!["stellarfield".into()]),
-            ..Default::default()
-        }),
+        AttributeReadContext {
+            prop_id: Some(prop_id),
+            ..AttributeReadContext::default()
+        },
+    )
+    .await
+    .expect("find attribute value")
+    .expect("attribute value exists");
+    let prototype = attribute_value
+        .attribute_prototype(ctx)
+        .await
+        .expect("find attribute prototype")
+        .expect("attribute prototype exists");
+
+    Func::get_by_id(ctx, &prototype.func_id())
+        .await
+        .expect("get func")
+        .expect("func exists")
+        .name()
+        .to_owned()
+}
+
+#[test]
+async fn test_import_skips_attribute_funcs(ctx: &DalContext) {
+    let pkg = stellarfield_pkg().expect("able to build stellarfield");
+    let ImportResult {
+        schema_variant_ids,
+        skips: import_skips,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().skip_attribute_funcs(true).build()),
         true,
     )
-    .await?;
+    .await
+    .expect("able to import stellarfield");
+    let schema_variant_id = *schema_variant_ids
+        .first()
+        .expect("stellarfield variant was imported");
 
-    Ok(())
+    // The props exist, but the ones with attribute functions in the package are still unset
+    let name_prop = Prop::find_prop_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "name"]),
+    )
+    .await
+    .expect("name prop exists");
+    let galaxies_prop = Prop::find_prop_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "universe", "galaxies"]),
+    )
+    .await
+    .expect("galaxies prop exists");
+    assert_eq!(
+        "si:unset",
+        prototype_func_name_for_prop(ctx, *name_prop.id()).await
+    );
+    assert_eq!(
+        "si:unset",
+        prototype_func_name_for_prop(ctx, *galaxies_prop.id()).await
+    );
+
+    // Action funcs are still imported
+    let refresh_prototype = ActionPrototype::find_for_context_and_kind(
+        ctx,
+        ActionKind::Refresh,
+        ActionPrototypeContext { schema_variant_id },
+    )
+    .await
+    .expect("find action prototypes");
+    assert_eq!(1, refresh_prototype.len());
+
+    let import_skips = import_skips.expect("attribute funcs were recorded as skipped");
+    let attr_func_skips: Vec<&ImportAttrFuncSkip> = import_skips
+        .iter()
+        .flat_map(|import_skips| import_skips.attr_func_skips.iter())
+        .collect();
+    let mut skipped_paths: Vec<String> = attr_func_skips
+        .iter()
+        .map(|skip| skip.path.with_replaced_sep("/"))
+        .collect();
+    skipped_paths.sort();
+    assert_eq!(
+        vec![
+            "root/domain/attributes".to_owned(),
+            "root/domain/name".to_owned(),
+            "root/domain/universe/galaxies".to_owned(),
+        ],
+        skipped_paths
+    );
+
+    let attr_func_skips: Vec<ImportAttrFuncSkip> = attr_func_skips.into_iter().cloned().collect();
+    apply_skipped_attr_funcs(ctx, &attr_func_skips)
+        .await
+        .expect("able to apply skipped attribute funcs");
+    assert_eq!(
+        "si:identity",
+        prototype_func_name_for_prop(ctx, *name_prop.id()).await
+    );
+}
+
+#[test]
+async fn test_import_publishes_package_imported_event(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk().expect("ctx has a workspace");
+    let mut events = ctx
+        .nats_conn()
+        .subscribe(format!("si.workspace_pk.{workspace_pk}.event"))
+        .await
+        .expect("able to subscribe to ws events");
+
+    let pkg = stellarfield_pkg().expect("able to build stellarfield");
+    let ImportResult {
+        installed_pkg_id, ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import stellarfield");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let payload = loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), events.next())
+            .await
+            .expect("package imported event was published")
+            .expect("ws event subscription is open");
+        let event: serde_json::Value =
+            serde_json::from_slice(message.payload()).expect("ws event is json");
+        if event["payload"]["kind"] == "PackageImported" {
+            break event["payload"]["data"].clone();
+        }
+    };
+
+    assert_eq!(
+        serde_json::json!(installed_pkg_id),
+        payload["installedPkgId"]
+    );
+    assert_eq!("stellarfield", payload["name"]);
+    assert_eq!("module", payload["kind"]);
+    let stats: ImportStats =
+        serde_json::from_value(payload["stats"].clone()).expect("stats deserialize");
+    assert!(stats.funcs > 0);
+    assert_eq!(1, stats.schemas);
+    assert_eq!(1, stats.schema_variants);
+    assert_eq!(0, stats.components);
+    assert_eq!(0, stats.edges);
+}
+
+#[test]
+async fn test_import_publishes_progress_events(ctx: &DalContext) {
+    let workspace_pk = ctx.tenancy().workspace_pk().expect("ctx has a workspace");
+    let mut events = ctx
+        .nats_conn()
+        .subscribe(format!("si.workspace_pk.{workspace_pk}.event"))
+        .await
+        .expect("able to subscribe to ws events");
+
+    let pkg = stellarfield_pkg().expect("able to build stellarfield");
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import stellarfield");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    // The progress events are queued before the package imported event
+    let mut progress = vec![];
+    loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), events.next())
+            .await
+            .expect("package imported event was published")
+            .expect("ws event subscription is open");
+        let event: serde_json::Value =
+            serde_json::from_slice(message.payload()).expect("ws event is json");
+        match event["payload"]["kind"].as_str() {
+            Some("PkgImportProgress") => progress.push(event["payload"]["data"].clone()),
+            Some("PackageImported") => break,
+            _ => {}
+        }
+    }
+
+    // One event per func and schema, counting up to the total of the whole import
+    let total = pkg.funcs().expect("able to get funcs").len()
+        + pkg.schemas().expect("able to get schemas").len();
+    assert_eq!(total, progress.len());
+    for (index, payload) in progress.iter().enumerate() {
+        assert_eq!("stellarfield", payload["moduleName"]);
+        assert_eq!(serde_json::json!(index + 1), payload["current"]);
+        assert_eq!(serde_json::json!(total), payload["total"]);
+    }
+
+    assert!(progress.iter().any(|payload| {
+        payload["phase"] == "funcs" && payload["name"] == "test:createActionStellarfield"
+    }));
+    let last = progress.last().expect("progress was published");
+    assert_eq!("schemas", last["phase"]);
+    assert_eq!("stellarfield", last["name"]);
+}
+
+/// Exports every change set in the workspace and loads the result back as a package, the way a
+/// workspace backup is read before it is imported.
+async fn export_workspace(ctx: &DalContext) -> SiPkg {
+    let mut exporter =
+        PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar");
+    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
+    SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes")
 }
 
 #[test]
@@ -338,12 +578,7 @@ async fn test_workspace_pkg_export(DalContextHeadRef(ctx): DalContextHeadRef<'_>
         .await
         .expect("able to make stellarfield");
 
-    let mut exporter =
-        PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar");
-
-    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
-
-    let pkg = SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes");
+    let pkg = export_workspace(ctx).await;
     let _spec = pkg.to_spec().await.expect("can convert to spec");
 
     import_pkg_from_pkg(ctx, &pkg, None, true)
@@ -352,460 +587,4819 @@ async fn test_workspace_pkg_export(DalContextHeadRef(ctx): DalContextHeadRef<'_>
 }
 
 #[test]
-async fn test_module_pkg_export(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
-    let generic_frame_id = Schema::find_by_name(ctx, "Generic Frame")
+async fn test_workspace_pkg_import_only_updates_changed_func_fields(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    make_stellarfield(ctx)
         .await
-        .expect("get generic frame")
-        .id()
-        .to_owned();
+        .expect("able to make stellarfield");
 
-    let starfield_id = Schema::find_by_name(ctx, "starfield")
+    let new_change_set = ChangeSet::new(ctx, "cs1", None)
         .await
-        .expect("get starfield")
-        .id()
-        .to_owned();
+        .expect("can create change set");
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
 
-    let fallout_id = Schema::find_by_name(ctx, "fallout")
+    let mut func = Func::find_by_name(&cs_ctx, "test:refreshActionStellarfield")
         .await
-        .expect("get fallout")
-        .id()
-        .to_owned();
+        .expect("find func")
+        .expect("func exists");
+    func.set_description(&cs_ctx, Some("refreshes the stellarfield"))
+        .await
+        .expect("able to set description");
 
-    let schema_ids = vec![generic_frame_id, starfield_id, fallout_id];
+    let pkg = export_workspace(ctx).await;
 
-    let mut exporter = PkgExporter::new_module_exporter(
-        "module",
-        "test-version",
-        None::<String>,
-        "sally@systeminit.com",
-        schema_ids,
-    );
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import workspace");
 
-    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
+    let head_func = Func::find_by_name(ctx, "test:refreshActionStellarfield")
+        .await
+        .expect("find func")
+        .expect("func exists");
+    let imported_change_set = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
+        .into_iter()
+        .find(|cs| cs.name == "cs1" && cs.pk != new_change_set.pk)
+        .expect("imported change set exists");
+    let imported_ctx =
+        ctx.clone_with_new_visibility(ctx.visibility().to_change_set(imported_change_set.pk));
+    let change_set_func = Func::find_by_name(&imported_ctx, "test:refreshActionStellarfield")
+        .await
+        .expect("find func")
+        .expect("func exists");
+    assert_eq!(
+        Some("refreshes the stellarfield"),
+        change_set_func.description()
+    );
 
-    let pkg = SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes");
-    let _spec = pkg.to_spec().await.expect("can convert to spec");
+    // Only the change set import updates the func, and it only changed the description. The
+    // other fields it could have written are never written when the func is created.
+    let row = ctx
+        .txns()
+        .await
+        .expect("get txns")
+        .pg()
+        .query_one(
+            "SELECT count(*) AS count FROM history_events
+             WHERE label = 'function.updated'
+               AND data ->> 'pk' = $1
+               AND data ->> 'field' IN ('name', 'backend_kind', 'backend_response_type')",
+            &[&head_func.pk().to_string()],
+        )
+        .await
+        .expect("count history events");
+    let count: i64 = row.try_get("count").expect("get count");
+    assert_eq!(0, count);
+}
 
-    let new_change_set = ChangeSet::new(ctx, "cs1", None)
+#[test]
+async fn test_workspace_pkg_import_resume(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
         .await
-        .expect("can create change set");
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
 
-    let new_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
-    import_pkg_from_pkg(&new_ctx, &pkg, None, true)
+    Component::new(ctx, "hyperion", schema_variant_id)
         .await
-        .expect("able to import pkg");
+        .expect("create head component");
 
-    let sv_change_sets: Vec<ChangeSetPk> = SchemaVariant::list(&new_ctx)
+    for (change_set_name, component_name) in [("cs1", "constellation"), ("cs2", "frontier")] {
+        let change_set = ChangeSet::new(ctx, change_set_name, None)
+            .await
+            .expect("can create change set");
+        let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+        Component::new(&cs_ctx, component_name, schema_variant_id)
+            .await
+            .expect("create change set component");
+    }
+
+    let pkg = export_workspace(ctx).await;
+
+    // Import one change set per transaction, committing each one before resuming from its
+    // checkpoint, as a caller importing a very large backup would
+    let mut checkpoints = vec![];
+    let mut resume_from = None;
+    loop {
+        let mut options = ImportOptions::builder();
+        options.max_change_sets(1);
+        if let Some(checkpoint) = resume_from.take() {
+            options.resume_from(checkpoint);
+        }
+
+        let result = import_pkg_from_pkg(ctx, &pkg, Some(options.build()), true)
+            .await
+            .expect("able to import workspace");
+        match result.interrupted {
+            Some(checkpoint) => {
+                ctx.blocking_commit().await.expect("unable to commit");
+                checkpoints.push(checkpoint.clone());
+                resume_from = Some(checkpoint);
+            }
+            None => break,
+        }
+    }
+
+    assert_eq!(2, checkpoints.len());
+    assert_eq!(
+        vec!["head"],
+        checkpoints[0]
+            .completed_change_sets
+            .iter()
+            .map(|cs| cs.name.as_str())
+            .collect::<Vec<&str>>()
+    );
+    assert_eq!(1, checkpoints[0].components.len());
+    assert_eq!(2, checkpoints[1].completed_change_sets.len());
+    assert_eq!(2, checkpoints[1].components.len());
+
+    let mut head_names = vec![];
+    for component in Component::list(ctx).await.expect("list head components") {
+        head_names.push(component.name(ctx).await.expect("get component name"));
+    }
+    assert_eq!(
+        1,
+        head_names.iter().filter(|name| *name == "hyperion").count()
+    );
+
+    let change_sets: Vec<ChangeSet> = ChangeSet::list_open(ctx)
         .await
-        .expect("get svs again")
-        .iter()
-        .map(|sv| sv.visibility().change_set_pk)
+        .expect("list open change sets")
+        .into_iter()
+        .filter(|cs| cs.name == "cs1")
         .collect();
+    assert_eq!(1, change_sets.len());
 
-    let installed_variants = sv_change_sets
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_sets[0].pk));
+    let mut cs_names = vec![];
+    for component in Component::list(&cs_ctx)
+        .await
+        .expect("list change set components")
+    {
+        cs_names.push(component.name(&cs_ctx).await.expect("get component name"));
+    }
+    assert_eq!(
+        1,
+        cs_names
+            .iter()
+            .filter(|name| *name == "constellation")
+            .count()
+    );
+
+    let change_sets: Vec<ChangeSet> = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
         .into_iter()
-        .filter(|cspk| *cspk == new_change_set.pk)
-        .collect::<Vec<ChangeSetPk>>();
+        .filter(|cs| cs.name == "cs2")
+        .collect();
+    assert_eq!(1, change_sets.len());
+}
 
-    assert_eq!(3, installed_variants.len());
+#[test]
+async fn test_workspace_pkg_import_only_change_set(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
 
-    let installed_schemas: Vec<Schema> = Schema::list(&new_ctx)
+    Component::new(ctx, "hyperion", schema_variant_id)
         .await
-        .expect("get schemas")
+        .expect("create head component");
+
+    let mut original_pks = vec![];
+    for (change_set_name, component_name) in [("cs1", "constellation"), ("cs2", "frontier")] {
+        let change_set = ChangeSet::new(ctx, change_set_name, None)
+            .await
+            .expect("can create change set");
+        let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+        Component::new(&cs_ctx, component_name, schema_variant_id)
+            .await
+            .expect("create change set component");
+        original_pks.push(change_set.pk);
+    }
+
+    let pkg = export_workspace(ctx).await;
+
+    match import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().only_change_set("cs3").build()),
+        true,
+    )
+    .await
+    {
+        Err(PkgError::ChangeSetNotInBackup(name)) => assert_eq!("cs3", name),
+        other => panic!("expected the missing change set to be rejected, got {other:?}"),
+    }
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().only_change_set("cs2").build()),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let mut head_names = vec![];
+    for component in Component::list(ctx).await.expect("list head components") {
+        head_names.push(component.name(ctx).await.expect("get component name"));
+    }
+    assert!(head_names.contains(&"hyperion".to_owned()));
+
+    let imported_change_sets: Vec<ChangeSet> = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
         .into_iter()
-        .filter(|schema| schema.visibility().change_set_pk == new_change_set.pk)
+        .filter(|cs| !original_pks.contains(&cs.pk))
         .collect();
+    assert_eq!(
+        vec!["cs2"],
+        imported_change_sets
+            .iter()
+            .map(|cs| cs.name.as_str())
+            .collect::<Vec<&str>>()
+    );
 
-    let generic_frame = installed_schemas
-        .iter()
-        .find(|schema| schema.name() == "Generic Frame")
-        .expect("able to find generic frame");
-
-    dbg!(generic_frame
-        .ui_menus(&new_ctx)
+    let cs_ctx =
+        ctx.clone_with_new_visibility(ctx.visibility().to_change_set(imported_change_sets[0].pk));
+    let mut cs_names = vec![];
+    for component in Component::list(&cs_ctx)
         .await
-        .expect("get ui menus for generic frame"));
+        .expect("list change set components")
+    {
+        cs_names.push(component.name(&cs_ctx).await.expect("get component name"));
+    }
+    assert!(cs_names.contains(&"frontier".to_owned()));
+    assert!(!cs_names.contains(&"constellation".to_owned()));
 }
 
 #[test]
-async fn test_install_pkg(ctx: &DalContext) {
-    let qualification_code = "function qualification(_input) { return { result: 'warning', message: 'omit needless words' }; } }";
-    let qualification_b64 = general_purpose::STANDARD_NO_PAD.encode(qualification_code.as_bytes());
+async fn test_workspace_pkg_reimported_edges_are_not_duplicated(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout.node_id,
+        *output_socket.id(),
+        starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
 
-    let qualification_func_spec = FuncSpec::builder()
-        .name("si:qualificationWarning")
-        .unique_id("si:qualificationWarning")
-        .data(
-            FuncSpecData::builder()
-                .name("si:qualificationWarning")
-                .display_name("warning")
-                .description("it warns")
-                .handler("qualification")
-                .code_base64(&qualification_b64)
-                .backend_kind(FuncSpecBackendKind::JsAttribute)
-                .response_type(FuncSpecBackendResponseType::Qualification)
-                .hidden(false)
-                .build()
-                .expect("able to build func data for qual"),
-        )
-        .build()
-        .expect("build qual func spec");
+    let new_change_set = ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
+    bagger
+        .create_component(&cs_ctx, "frontier", "starfield")
+        .await;
 
-    let qualification_spec = LeafFunctionSpec::builder()
-        .func_unique_id(&qualification_func_spec.unique_id)
-        .leaf_kind(PkgLeafKind::Qualification)
-        .inputs(vec![
-            PkgLeafInputLocation::Domain,
-            PkgLeafInputLocation::Code,
-        ])
-        .build()
-        .expect("could not build qual spec");
+    let pkg = export_workspace(ctx).await;
 
-    let scaffold_func_a = "function createAsset() {
-                return new AssetBuilder().build();
-            }";
-    let scaffold_func_spec_a = FuncSpec::builder()
-        .name("si:scaffoldFuncA")
-        .unique_id("si:scaffoldFuncA")
-        .data(
-            FuncSpecData::builder()
-                .name("si:scaffoldFuncA")
-                .code_plaintext(scaffold_func_a)
-                .handler("createAsset")
-                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
-                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
-                .build()
-                .expect("build func data"),
-        )
-        .build()
-        .expect("could not build schema variant definition spec");
+    let mut checkpoint = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().max_change_sets(1).build()),
+        true,
+    )
+    .await
+    .expect("able to import workspace")
+    .interrupted
+    .expect("the import was interrupted");
 
-    let schema_a = SchemaSpec::builder()
-        .name("Tyrone Slothrop")
-        .data(
-            SchemaSpecData::builder()
-                .name("Tyrone Slothrop")
-                .category("Banana Puddings")
-                .ui_hidden(false)
-                .build()
-                .expect("you never did the kenosha kid?"),
-        )
-        .variant(
-            SchemaVariantSpec::builder()
-                .name("Pig Bodine")
-                .data(
-                    SchemaVariantSpecData::builder()
-                        .name("Pig Bodine")
-                        .color("baddad")
-                        .component_type(ComponentType::Component)
-                        .func_unique_id(&scaffold_func_spec_a.unique_id)
-                        .build()
-                        .expect("pig bodine"),
-                )
-                .domain_prop(
-                    PropSpec::builder()
-                        .name("ImpolexG")
-                        .kind(PropSpecKind::String)
-                        .build()
-                        .expect("able to make prop spec"),
-                )
-                .domain_prop(
-                    PropSpec::builder()
-                        .name("TheZone")
-                        .kind(PropSpecKind::String)
-                        .build()
-                        .expect("able to make prop spec"),
-                )
-                .leaf_function(qualification_spec)
-                .build()
-                .expect("able to make schema variant spec"),
-        )
-        .build()
-        .expect("able to make schema spec");
+    // Lose track of head having been imported, so that its components are found again but its
+    // edges are imported a second time
+    checkpoint.completed_change_sets.clear();
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().resume_from(checkpoint).build()),
+        true,
+    )
+    .await
+    .expect("able to resume workspace import");
 
-    let scaffold_func_b = "function createAsset() {
-                return new AssetBuilder().build();
-            }";
-    let scaffold_func_spec_b = FuncSpec::builder()
-        .name("si:scaffoldFuncB")
-        .unique_id("si:scaffoldFuncB")
-        .data(
-            FuncSpecData::builder()
-                .name("si:scaffoldFuncB")
-                .code_plaintext(scaffold_func_b)
-                .handler("createAsset")
-                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
-                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
-                .build()
-                .expect("scaffold b func data"),
+    let mut constellations = 0;
+    let mut edge_count = 0;
+    for component in Component::list(ctx).await.expect("list components") {
+        if component.name(ctx).await.expect("get component name") != "constellation" {
+            continue;
+        }
+        constellations += 1;
+        edge_count += Edge::list_for_component(ctx, *component.id())
+            .await
+            .expect("list edges for component")
+            .iter()
+            .filter(|edge| edge.kind() == &EdgeKind::Configuration)
+            .count();
+    }
+    assert_eq!(1, constellations);
+    assert_eq!(1, edge_count);
+}
+
+#[test]
+async fn test_workspace_pkg_import_retries_transient_edge_errors(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout.node_id,
+        *output_socket.id(),
+        starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let pkg = export_workspace(ctx).await;
+
+    // Fail the first edge insert with a deadlock, as if a concurrent import had locked the same
+    // rows
+    ctx.txns()
+        .await
+        .expect("get txns")
+        .pg()
+        .batch_execute(
+            "CREATE TEMP SEQUENCE import_retry_faults;
+            CREATE FUNCTION pg_temp.fail_first_edge_insert() RETURNS trigger AS $$
+            BEGIN
+                IF nextval('import_retry_faults') = 1 THEN
+                    RAISE EXCEPTION 'injected deadlock'
+                        USING ERRCODE = 'deadlock_detected';
+                END IF;
+                RETURN NEW;
+            END;
+            $$ LANGUAGE plpgsql;
+            CREATE TRIGGER fail_first_edge_insert BEFORE INSERT ON edges
+                FOR EACH ROW EXECUTE FUNCTION pg_temp.fail_first_edge_insert();",
         )
-        .build()
-        .expect("could not build schema variant definition spec");
+        .await
+        .expect("able to inject a transient edge insert failure");
 
-    let schema_b = SchemaSpec::builder()
-        .name("Roger Mexico")
-        .data(
-            SchemaSpecData::builder()
-                .name("Roger Mexico")
-                .ui_hidden(false)
-                .category("Banana Puddings")
-                .build()
-                .expect("roger mexico data"),
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .db_retry(DbRetryPolicy::default())
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace after retrying the edge");
+
+    ctx.txns()
+        .await
+        .expect("get txns")
+        .pg()
+        .batch_execute("DROP TRIGGER fail_first_edge_insert ON edges;")
+        .await
+        .expect("able to remove the injected failure");
+
+    let mut edge_count = 0;
+    for component in Component::list(ctx).await.expect("list components") {
+        if component.name(ctx).await.expect("get component name") != "constellation" {
+            continue;
+        }
+        edge_count += Edge::list_for_component(ctx, *component.id())
+            .await
+            .expect("list edges for component")
+            .iter()
+            .filter(|edge| edge.kind() == &EdgeKind::Configuration)
+            .count();
+    }
+    assert_eq!(1, edge_count);
+}
+
+#[test]
+async fn test_workspace_pkg_import_reuses_existing_edges_on_resume(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    for index in 0..6 {
+        let starfield = bagger
+            .create_component(ctx, &format!("constellation {index}"), "starfield")
+            .await;
+        let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+            ctx,
+            "bethesda",
+            SocketEdgeKind::ConfigurationInput,
+            starfield.node_id,
         )
-        .variant(
-            SchemaVariantSpec::builder()
-                .name("The Light Bulb Conspiracy")
-                .data(
-                    SchemaVariantSpecData::builder()
-                        .name("The Light Bulb Conspiracy")
-                        .color("baddad")
-                        .func_unique_id(&scaffold_func_spec_b.unique_id)
-                        .build()
-                        .expect("light bulb spec data"),
-                )
-                .socket(
-                    SocketSpec::builder()
-                        .name("AC Power")
-                        .data(
-                            SocketSpecData::builder()
-                                .name("AC Power")
-                                .connection_annotations(connection_annotation_string!("ac power"))
-                                .ui_hidden(false)
-                                .kind(SocketSpecKind::Input)
-                                .arity(SocketSpecArity::One)
-                                .build()
-                                .expect("build socket data"),
-                        )
-                        .build()
-                        .expect("able to make input socket"),
-                )
-                .socket(
-                    SocketSpec::builder()
-                        .name("Light")
-                        .data(
-                            SocketSpecData::builder()
-                                .name("Light")
-                                .connection_annotations(connection_annotation_string!("light"))
-                                .kind(SocketSpecKind::Output)
-                                .arity(SocketSpecArity::Many)
-                                .ui_hidden(false)
-                                .build()
-                                .expect("build light socket data"),
-                        )
-                        .build()
-                        .expect("able to make output socket"),
-                )
-                .domain_prop(
-                    PropSpec::builder()
-                        .name("distress_jess")
-                        .kind(PropSpecKind::Number)
-                        .build()
-                        .expect("able to make prop spec"),
-                )
-                .domain_prop(
-                    PropSpec::builder()
-                        .name("sixes_and_sevens")
-                        .kind(PropSpecKind::Number)
-                        .build()
-                        .expect("able to make prop spec"),
-                )
-                .build()
-                .expect("able to make schema variant spec"),
+        .await
+        .expect("could not perform socket find")
+        .expect("could not find socket");
+        Connection::new(
+            ctx,
+            fallout.node_id,
+            *output_socket.id(),
+            starfield.node_id,
+            *input_socket.id(),
+            EdgeKind::Configuration,
         )
-        .build()
-        .expect("able to make schema spec");
+        .await
+        .expect("could not create connection");
+    }
+
+    // A second change set lets the import stop, and be resumed, after head
+    ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+
+    let pkg = export_workspace(ctx).await;
+
+    let mut checkpoint = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().max_change_sets(1).build()),
+        true,
+    )
+    .await
+    .expect("able to import workspace")
+    .interrupted
+    .expect("the import was interrupted");
+    let imported_edges = configuration_edges(ctx).await;
+    assert_eq!(6, imported_edges.len());
+
+    // Import head's edges a second time, over the edges the first import created
+    checkpoint.completed_change_sets.clear();
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().resume_from(checkpoint).build()),
+        true,
+    )
+    .await
+    .expect("able to resume workspace import");
+
+    assert_eq!(imported_edges, configuration_edges(ctx).await);
+}
+
+/// The ids of the configuration edges on head, sorted and without duplicates.
+async fn configuration_edges(ctx: &DalContext) -> Vec<EdgeId> {
+    let mut edge_ids = vec![];
+    for component in Component::list(ctx).await.expect("list components") {
+        edge_ids.extend(
+            Edge::list_for_component(ctx, *component.id())
+                .await
+                .expect("list edges for component")
+                .iter()
+                .filter(|edge| edge.kind() == &EdgeKind::Configuration)
+                .map(|edge| *edge.id()),
+        );
+    }
+    edge_ids.sort();
+    edge_ids.dedup();
+    edge_ids
+}
+
+#[test]
+async fn test_workspace_pkg_import_as_user(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout.node_id,
+        *output_socket.id(),
+        starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let pkg = export_workspace(ctx).await;
+
+    let user_pk = UserPk::generate();
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().as_user(user_pk).build()),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let mut edge_count = 0;
+    for component in Component::list(ctx).await.expect("list components") {
+        let status = ComponentStatus::get_by_id(ctx, component.id())
+            .await
+            .expect("get component status")
+            .expect("component has a status");
+        assert_eq!(HistoryActor::User(user_pk), status.creation().actor);
+
+        for edge in Edge::list_for_component(ctx, *component.id())
+            .await
+            .expect("list edges for component")
+        {
+            assert_eq!(Some(&user_pk), edge.creation_user_pk());
+            edge_count += 1;
+        }
+    }
+    assert!(edge_count > 0);
+}
+
+#[test]
+async fn test_workspace_pkg_import_materializes_computed_domain(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let rads_prop = fallout.find_prop(ctx, &["root", "domain", "rads"]).await;
+    fallout
+        .update_attribute_value_for_prop(ctx, *rads_prop.id(), Some(serde_json::json![1]))
+        .await;
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let pkg = export_workspace(ctx).await;
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import workspace");
+    ctx.blocking_commit()
+        .await
+        .expect("could not commit & run jobs");
+
+    let mut vault_id = None;
+    for component in Component::list(ctx).await.expect("list components") {
+        if component.name(ctx).await.expect("get component name") == "vault" {
+            vault_id = Some(*component.id());
+        }
+    }
+    let vault_id = vault_id.expect("vault was imported");
+
+    // "name" is computed from "/root/si/name" and "active" comes from the prop default, while
+    // "special" was never set and is left as it is in the skeleton
+    assert_eq!(
+        serde_json::json!({
+            "name": "vault",
+            "special": null,
+            "rads": 1,
+            "active": true,
+        }),
+        Component::materialize_domain(ctx, vault_id)
+            .await
+            .expect("able to materialize domain")
+    );
+}
+
+async fn workspace_snapshot(ctx: &DalContext) -> (Vec<(String, serde_json::Value)>, usize) {
+    let mut properties = vec![];
+    for component in Component::list(ctx).await.expect("list components") {
+        let view = ComponentView::new(ctx, *component.id())
+            .await
+            .expect("get component view");
+        properties.push((
+            component.name(ctx).await.expect("get component name"),
+            view.properties,
+        ));
+    }
+    properties.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let edge_count = Edge::list_for_kind(ctx, EdgeKind::Configuration)
+        .await
+        .expect("list edges")
+        .len();
+
+    (properties, edge_count)
+}
+
+#[test]
+async fn test_workspace_ndjson_round_trip(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout.node_id,
+        *output_socket.id(),
+        starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+    ctx.blocking_commit()
+        .await
+        .expect("run dependent values updates");
+
+    let mut ndjson = vec![];
+    PkgExporter::new_workspace_exporter("workspace", "sally@systeminit.com", "foo", "bar")
+        .export_ndjson(ctx, &mut ndjson)
+        .await
+        .expect("able to export");
+    let ndjson = String::from_utf8(ndjson).expect("export is utf-8");
+
+    let kinds: Vec<String> = ndjson
+        .lines()
+        .map(|line| {
+            let record: serde_json::Value = serde_json::from_str(line).expect("line is json");
+            record["kind"]
+                .as_str()
+                .expect("record has a kind")
+                .to_owned()
+        })
+        .collect();
+    assert_eq!(Some("metadata"), kinds.first().map(String::as_str));
+    for kind in [
+        "changeSet",
+        "func",
+        "schema",
+        "variant",
+        "component",
+        "edge",
+    ] {
+        assert!(kinds.iter().any(|k| k == kind), "no {kind} record exported");
+    }
+
+    let exported = workspace_snapshot(ctx).await;
+    assert_eq!(1, exported.1);
+
+    import_ndjson(ctx, ndjson.as_bytes(), None, true)
+        .await
+        .expect("able to import workspace");
+    ctx.blocking_commit()
+        .await
+        .expect("run dependent values updates");
+
+    assert_eq!(exported, workspace_snapshot(ctx).await);
+
+    // The edge is the last record, so cutting the backup short only loses the edge
+    assert_eq!(Some("edge"), kinds.last().map(String::as_str));
+    let truncated = &ndjson[..ndjson.trim_end().len() - 10];
+    import_ndjson(ctx, truncated.as_bytes(), None, true)
+        .await
+        .expect("able to import truncated workspace");
+
+    let (properties, edge_count) = workspace_snapshot(ctx).await;
+    assert_eq!(
+        exported
+            .0
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>(),
+        properties
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<&str>>()
+    );
+    assert_eq!(0, edge_count);
+}
+
+#[test]
+async fn test_module_pkg_export(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let generic_frame_id = Schema::find_by_name(ctx, "Generic Frame")
+        .await
+        .expect("get generic frame")
+        .id()
+        .to_owned();
+
+    let starfield_id = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .id()
+        .to_owned();
+
+    let fallout_id = Schema::find_by_name(ctx, "fallout")
+        .await
+        .expect("get fallout")
+        .id()
+        .to_owned();
+
+    let schema_ids = vec![generic_frame_id, starfield_id, fallout_id];
+
+    let mut exporter = PkgExporter::new_module_exporter(
+        "module",
+        "test-version",
+        None::<String>,
+        "sally@systeminit.com",
+        schema_ids,
+    );
+
+    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
+
+    let pkg = SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes");
+    let _spec = pkg.to_spec().await.expect("can convert to spec");
+
+    let new_change_set = ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+
+    let new_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
+    import_pkg_from_pkg(&new_ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let sv_change_sets: Vec<ChangeSetPk> = SchemaVariant::list(&new_ctx)
+        .await
+        .expect("get svs again")
+        .iter()
+        .map(|sv| sv.visibility().change_set_pk)
+        .collect();
+
+    let installed_variants = sv_change_sets
+        .into_iter()
+        .filter(|cspk| *cspk == new_change_set.pk)
+        .collect::<Vec<ChangeSetPk>>();
+
+    assert_eq!(3, installed_variants.len());
+
+    let installed_schemas: Vec<Schema> = Schema::list(&new_ctx)
+        .await
+        .expect("get schemas")
+        .into_iter()
+        .filter(|schema| schema.visibility().change_set_pk == new_change_set.pk)
+        .collect();
+
+    let generic_frame = installed_schemas
+        .iter()
+        .find(|schema| schema.name() == "Generic Frame")
+        .expect("able to find generic frame");
+
+    dbg!(generic_frame
+        .ui_menus(&new_ctx)
+        .await
+        .expect("get ui menus for generic frame"));
+}
+
+#[test]
+async fn test_export_funcs_as_module(ctx: &DalContext) {
+    let mut func_ids = vec![];
+    for (name, arg_name) in [("test:upcase", "text"), ("test:joinTags", "tags")] {
+        let mut func = Func::new(
+            ctx,
+            name,
+            FuncBackendKind::JsAttribute,
+            FuncBackendResponseType::String,
+        )
+        .await
+        .expect("create func");
+        func.set_handler(ctx, Some("transform"))
+            .await
+            .expect("set handler");
+        func.set_code_plaintext(ctx, Some("function transform(input) { return input; }"))
+            .await
+            .expect("set code");
+        FuncArgument::new(ctx, arg_name, FuncArgumentKind::String, None, *func.id())
+            .await
+            .expect("create func argument");
+        func_ids.push(*func.id());
+    }
+
+    let pkg = PkgExporter::export_funcs_as_module(ctx, &func_ids, "transforms", "0.1")
+        .await
+        .expect("export funcs");
+    let spec = pkg.to_spec().await.expect("convert to spec");
+    assert_eq!(SiPkgKind::Module, spec.kind);
+    assert!(spec.schemas.is_empty());
+    assert_eq!(2, spec.funcs.len());
+
+    let change_set = ChangeSet::new(ctx, "library", None)
+        .await
+        .expect("create change set");
+    let new_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+    assert!(Func::find_by_name(&new_ctx, "test:upcase")
+        .await
+        .expect("find func")
+        .is_none());
+
+    import_pkg_from_pkg(&new_ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    for (name, arg_name) in [("test:upcase", "text"), ("test:joinTags", "tags")] {
+        let func = Func::find_by_name(&new_ctx, name)
+            .await
+            .expect("find func")
+            .expect("func was imported");
+        assert_eq!(Some("transform"), func.handler());
+        let args = FuncArgument::list_for_func(&new_ctx, *func.id())
+            .await
+            .expect("list func arguments");
+        assert_eq!(
+            vec![arg_name],
+            args.iter().map(|arg| arg.name()).collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+async fn test_install_pkg(ctx: &DalContext) {
+    let qualification_code = "function qualification(_input) { return { result: 'warning', message: 'omit needless words' }; } }";
+    let qualification_b64 = general_purpose::STANDARD_NO_PAD.encode(qualification_code.as_bytes());
+
+    let qualification_func_spec = FuncSpec::builder()
+        .name("si:qualificationWarning")
+        .unique_id("si:qualificationWarning")
+        .data(
+            FuncSpecData::builder()
+                .name("si:qualificationWarning")
+                .display_name("warning")
+                .description("it warns")
+                .handler("qualification")
+                .code_base64(&qualification_b64)
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Qualification)
+                .hidden(false)
+                .build()
+                .expect("able to build func data for qual"),
+        )
+        .build()
+        .expect("build qual func spec");
+
+    let qualification_spec = LeafFunctionSpec::builder()
+        .func_unique_id(&qualification_func_spec.unique_id)
+        .leaf_kind(PkgLeafKind::Qualification)
+        .inputs(vec![
+            PkgLeafInputLocation::Domain,
+            PkgLeafInputLocation::Code,
+        ])
+        .build()
+        .expect("could not build qual spec");
+
+    let scaffold_func_a = "function createAsset() {
+                return new AssetBuilder().build();
+            }";
+    let scaffold_func_spec_a = FuncSpec::builder()
+        .name("si:scaffoldFuncA")
+        .unique_id("si:scaffoldFuncA")
+        .data(
+            FuncSpecData::builder()
+                .name("si:scaffoldFuncA")
+                .code_plaintext(scaffold_func_a)
+                .handler("createAsset")
+                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("could not build schema variant definition spec");
+
+    let schema_a = SchemaSpec::builder()
+        .name("Tyrone Slothrop")
+        .data(
+            SchemaSpecData::builder()
+                .name("Tyrone Slothrop")
+                .category("Banana Puddings")
+                .ui_hidden(false)
+                .build()
+                .expect("you never did the kenosha kid?"),
+        )
+        .variant(
+            SchemaVariantSpec::builder()
+                .name("Pig Bodine")
+                .data(
+                    SchemaVariantSpecData::builder()
+                        .name("Pig Bodine")
+                        .color("baddad")
+                        .component_type(ComponentType::Component)
+                        .func_unique_id(&scaffold_func_spec_a.unique_id)
+                        .build()
+                        .expect("pig bodine"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("ImpolexG")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("able to make prop spec"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("TheZone")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("able to make prop spec"),
+                )
+                .leaf_function(qualification_spec)
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+
+    let scaffold_func_b = "function createAsset() {
+                return new AssetBuilder().build();
+            }";
+    let scaffold_func_spec_b = FuncSpec::builder()
+        .name("si:scaffoldFuncB")
+        .unique_id("si:scaffoldFuncB")
+        .data(
+            FuncSpecData::builder()
+                .name("si:scaffoldFuncB")
+                .code_plaintext(scaffold_func_b)
+                .handler("createAsset")
+                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+                .build()
+                .expect("scaffold b func data"),
+        )
+        .build()
+        .expect("could not build schema variant definition spec");
+
+    let schema_b = SchemaSpec::builder()
+        .name("Roger Mexico")
+        .data(
+            SchemaSpecData::builder()
+                .name("Roger Mexico")
+                .ui_hidden(false)
+                .category("Banana Puddings")
+                .build()
+                .expect("roger mexico data"),
+        )
+        .variant(
+            SchemaVariantSpec::builder()
+                .name("The Light Bulb Conspiracy")
+                .data(
+                    SchemaVariantSpecData::builder()
+                        .name("The Light Bulb Conspiracy")
+                        .color("baddad")
+                        .func_unique_id(&scaffold_func_spec_b.unique_id)
+                        .build()
+                        .expect("light bulb spec data"),
+                )
+                .socket(
+                    SocketSpec::builder()
+                        .name("AC Power")
+                        .data(
+                            SocketSpecData::builder()
+                                .name("AC Power")
+                                .connection_annotations(connection_annotation_string!("ac power"))
+                                .ui_hidden(false)
+                                .kind(SocketSpecKind::Input)
+                                .arity(SocketSpecArity::One)
+                                .build()
+                                .expect("build socket data"),
+                        )
+                        .build()
+                        .expect("able to make input socket"),
+                )
+                .socket(
+                    SocketSpec::builder()
+                        .name("Light")
+                        .data(
+                            SocketSpecData::builder()
+                                .name("Light")
+                                .connection_annotations(connection_annotation_string!("light"))
+                                .kind(SocketSpecKind::Output)
+                                .arity(SocketSpecArity::Many)
+                                .ui_hidden(false)
+                                .build()
+                                .expect("build light socket data"),
+                        )
+                        .build()
+                        .expect("able to make output socket"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("distress_jess")
+                        .kind(PropSpecKind::Number)
+                        .build()
+                        .expect("able to make prop spec"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("sixes_and_sevens")
+                        .kind(PropSpecKind::Number)
+                        .build()
+                        .expect("able to make prop spec"),
+                )
+                .build()
+                .expect("able to make schema variant spec"),
+        )
+        .build()
+        .expect("able to make schema spec");
+
+    let code = "function truth() { return true; }";
+    let code_base64 = general_purpose::STANDARD_NO_PAD.encode(code.as_bytes());
+    let identity_func_spec = IntrinsicFunc::Identity
+        .to_spec()
+        .expect("create identity func spec");
+
+    let func_spec = FuncSpec::builder()
+        .name("si:truthy")
+        .unique_id("si:truthy")
+        .data(
+            FuncSpecData::builder()
+                .name("si:truthy")
+                .display_name("truth")
+                .description("it returns true")
+                .handler("truth")
+                .code_base64(&code_base64)
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Boolean)
+                .hidden(false)
+                .build()
+                .expect("truth func data"),
+        )
+        .build()
+        .expect("build func spec");
+
+    let spec_a = PkgSpec::builder()
+        .name("The White Visitation")
+        .version("0.1")
+        .created_by("Pirate Prentice")
+        .schema(schema_a.clone())
+        .func(func_spec)
+        .func(identity_func_spec)
+        .func(qualification_func_spec)
+        .func(scaffold_func_spec_a.clone())
+        .build()
+        .expect("able to build package spec");
+
+    let pkg_a = SiPkg::load_from_spec(spec_a).expect("able to load from spec");
+
+    let spec_b = PkgSpec::builder()
+        .name("The Kenosha Kid")
+        .version("0.1")
+        .created_by("Pointsman")
+        .func(identity_func_spec)
+        .schema(schema_a)
+        .schema(schema_b)
+        .func(scaffold_func_spec_a)
+        .func(scaffold_func_spec_b)
+        .func(qualification_func_spec)
+        .build()
+        .expect("able to build package spec");
+
+    let pkg_b = SiPkg::load_from_spec(spec_b).expect("able to load pkg from spec");
+
+    import_pkg_from_pkg(ctx, &pkg_a, None, true)
+        .await
+        .expect("able to install pkg");
+
+    // We should refuse to install the same package twice
+    let second_import_result = import_pkg_from_pkg(ctx, &pkg_a, None, true).await;
+    assert!(matches!(
+        second_import_result,
+        Err(PkgError::PackageAlreadyInstalled(_))
+    ));
+
+    // for now just assert that the installed package records are written. we should also verify
+    // the structure of the installed schemas but punting on that for the moment
+    let pkg_a_root_hash = pkg_a.hash().expect("pkg a has a hash").to_string();
+    let installed_pkgs = InstalledPkg::find_by_attr(ctx, "root_hash", &pkg_a_root_hash)
+        .await
+        .expect("find by attr");
+    assert_eq!(1, installed_pkgs.len());
+    let installed_pkg_a = installed_pkgs.get(0).expect("pkg should be there");
+
+    assert_eq!("The White Visitation", installed_pkg_a.name());
+
+    let pkg_a_ipas = InstalledPkgAsset::list_for_installed_pkg_id(ctx, *installed_pkg_a.id())
+        .await
+        .expect("able to fetch installed pkgs for pkg a");
+
+    for ipa in pkg_a_ipas {
+        match ipa.asset_kind() {
+            InstalledPkgAssetKind::Schema => {
+                let typed: InstalledPkgAssetTyped =
+                    ipa.as_installed_schema().expect("get schema ipa type");
+                match typed {
+                    InstalledPkgAssetTyped::Schema { id, .. } => {
+                        let schema = Schema::get_by_id(ctx, &id)
+                            .await
+                            .expect("able to get schema")
+                            .expect("schema is there");
+
+                        assert_eq!("Tyrone Slothrop", schema.name())
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            InstalledPkgAssetKind::SchemaVariant => {
+                let typed: InstalledPkgAssetTyped = ipa
+                    .as_installed_schema_variant()
+                    .expect("get schema ipa type");
+                match typed {
+                    InstalledPkgAssetTyped::SchemaVariant { id, .. } => {
+                        let schema_variant = SchemaVariant::get_by_id(ctx, &id)
+                            .await
+                            .expect("able to get schema variant")
+                            .expect("schema variant is there");
+
+                        assert_eq!("Pig Bodine", schema_variant.name());
+
+                        let qualifications = SchemaVariant::find_leaf_item_functions(
+                            ctx,
+                            *schema_variant.id(),
+                            LeafKind::Qualification,
+                        )
+                        .await
+                        .expect("able to get qualification funcs");
+
+                        assert_eq!(1, qualifications.len())
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            InstalledPkgAssetKind::SchemaVariantDefinition => {}
+            InstalledPkgAssetKind::Func => {
+                let typed: InstalledPkgAssetTyped =
+                    ipa.as_installed_func().expect("get func ipa typed");
+                match typed {
+                    InstalledPkgAssetTyped::Func { id, .. } => {
+                        let _func = Func::get_by_id(ctx, &id)
+                            .await
+                            .expect("able to get func")
+                            .expect("func is there");
+                    }
+                    _ => unreachable!(),
+                }
+            }
+        }
+    }
+
+    import_pkg_from_pkg(ctx, &pkg_b, None, true)
+        .await
+        .expect("install pkg b");
+
+    let installed_pkgs = InstalledPkg::find_by_attr(
+        ctx,
+        "root_hash",
+        &pkg_b.hash().expect("get pkg b hash").to_string(),
+    )
+    .await
+    .expect("find by attr");
+    assert_eq!(1, installed_pkgs.len());
+    let installed_pkg_b = installed_pkgs.get(0).expect("pkg should be there");
+
+    assert_eq!("The Kenosha Kid", installed_pkg_b.name());
+
+    let _pkg_b_ipas = InstalledPkgAsset::list_for_installed_pkg_id(ctx, *installed_pkg_b.id())
+        .await
+        .expect("able to fetch installed pkgs for pkg a");
+
+    // Ensure we did not install the schema that is in both packages twice
+    let schemas = Schema::find_by_attr(ctx, "name", &"Tyrone Slothrop".to_string())
+        .await
+        .expect("get tyrones");
+    assert_eq!(1, schemas.len());
+    let schema_variants = SchemaVariant::find_by_attr(ctx, "name", &"Pig Bodine".to_string())
+        .await
+        .expect("get bodines");
+    assert_eq!(1, schema_variants.len());
+
+    // Ensure the new schemas were installed
+    let schemas = Schema::find_by_attr(ctx, "name", &"Roger Mexico".to_string())
+        .await
+        .expect("roger roger roger roger");
+    assert_eq!(1, schemas.len());
+    let mut schema_variants =
+        SchemaVariant::find_by_attr(ctx, "name", &"The Light Bulb Conspiracy".to_string())
+            .await
+            .expect("above your head like you had an idea");
+    assert_eq!(1, schema_variants.len());
+
+    let light_bulb = schema_variants
+        .pop()
+        .expect("able to get the light bulb conspiracy");
+
+    let _ac_input = InternalProvider::find_explicit_for_schema_variant_and_name(
+        ctx,
+        *light_bulb.id(),
+        "AC Power",
+    )
+    .await
+    .expect("able to search for ac input")
+    .expect("able to find ac input");
+}
+
+fn conflicting_func_names_pkg() -> SiPkg {
+    let func_name = "test:conflictingFunc";
+    let mut builder = PkgSpec::builder();
+    builder
+        .name("conflicting func names")
+        .version("0.1")
+        .created_by("Tchitcherine");
+
+    for (unique_id, code) in [
+        (
+            "test:conflictingFuncA",
+            "function conflict() { return 'a'; }",
+        ),
+        (
+            "test:conflictingFuncB",
+            "function conflict() { return 'b'; }",
+        ),
+    ] {
+        builder.func(
+            FuncSpec::builder()
+                .name(func_name)
+                .unique_id(unique_id)
+                .data(
+                    FuncSpecData::builder()
+                        .name(func_name)
+                        .handler("conflict")
+                        .code_plaintext(code)
+                        .backend_kind(FuncSpecBackendKind::JsAttribute)
+                        .response_type(FuncSpecBackendResponseType::String)
+                        .build()
+                        .expect("build func data"),
+                )
+                .build()
+                .expect("build func spec"),
+        );
+    }
+
+    SiPkg::load_from_spec(builder.build().expect("build pkg spec")).expect("load pkg from spec")
+}
+
+#[test]
+async fn test_import_func_name_conflict_error(ctx: &DalContext) {
+    let result = import_pkg_from_pkg(
+        ctx,
+        &conflicting_func_names_pkg(),
+        Some(ImportOptions {
+            func_name_conflict: FuncNameConflictPolicy::Error,
+            ..Default::default()
+        }),
+        true,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::FuncNameConflict(name)) if name == "test:conflictingFunc"
+    ));
+}
+
+#[test]
+async fn test_import_func_name_conflict_suffix(ctx: &DalContext) {
+    import_pkg_from_pkg(
+        ctx,
+        &conflicting_func_names_pkg(),
+        Some(ImportOptions {
+            func_name_conflict: FuncNameConflictPolicy::Suffix,
+            ..Default::default()
+        }),
+        true,
+    )
+    .await
+    .expect("able to import pkg");
+
+    let funcs = Func::find_by_attr(ctx, "name", &"test:conflictingFunc")
+        .await
+        .expect("find funcs by name");
+    assert_eq!(1, funcs.len());
+
+    let suffixed_funcs = Func::find_by_attr(ctx, "name", &"test:conflictingFunc-2")
+        .await
+        .expect("find funcs by suffixed name");
+    assert_eq!(1, suffixed_funcs.len());
+}
+
+/// Starts a package holding a schema named `schema_name` with a single "v0" variant, defined by an
+/// empty asset func named after the schema (e.g. "test:scaffoldFuncV2" for "V-2"). `build_variant`
+/// adds the variant's props and sockets and any extra variant data; callers add the package's other
+/// funcs before building it.
+fn asset_pkg_spec(
+    schema_name: &str,
+    build_variant: impl FnOnce(&mut SchemaVariantSpecBuilder, &mut SchemaVariantSpecDataBuilder),
+) -> PkgSpecBuilder {
+    let func_name = format!(
+        "test:scaffoldFunc{}",
+        schema_name
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+    );
+    let asset_func_spec = FuncSpec::builder()
+        .name(&func_name)
+        .unique_id(&func_name)
+        .data(
+            FuncSpecData::builder()
+                .name(&func_name)
+                .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+                .handler("createAsset")
+                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+                .response_type(FuncSpecBackendResponseType::SchemaVariantDefinition)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build asset func spec");
+
+    let mut variant_data = SchemaVariantSpecData::builder();
+    variant_data
+        .name("v0")
+        .color("baddad")
+        .func_unique_id(&asset_func_spec.unique_id);
+    let mut variant = SchemaVariantSpec::builder();
+    variant.name("v0");
+    build_variant(&mut variant, &mut variant_data);
+    variant.data(variant_data.build().expect("build variant data"));
+
+    let schema_spec = SchemaSpec::builder()
+        .name(schema_name)
+        .data(
+            SchemaSpecData::builder()
+                .name(schema_name)
+                .category("Banana Puddings")
+                .ui_hidden(false)
+                .build()
+                .expect("build schema data"),
+        )
+        .variant(variant.build().expect("build variant spec"))
+        .build()
+        .expect("build schema spec");
+
+    let mut pkg_spec = PkgSpec::builder();
+    pkg_spec
+        .name(schema_name)
+        .version("0.1")
+        .created_by("Tyrone Slothrop")
+        .func(asset_func_spec)
+        .schema(schema_spec);
+    pkg_spec
+}
+
+#[test]
+async fn test_import_schema_variant_description_and_link(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Gravity's Rainbow", |_, data| {
+            data.description("a screaming comes across the sky")
+                .try_link("https://example.com/gravitys-rainbow")
+                .expect("parse link");
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let schema = Schema::find_by_name(ctx, "Gravity's Rainbow")
+        .await
+        .expect("find schema");
+    let schema_variant_id = schema
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let schema_variant = SchemaVariant::get_by_id(ctx, schema_variant_id)
+        .await
+        .expect("get schema variant")
+        .expect("schema variant exists");
+    assert_eq!(
+        Some("https://example.com/gravitys-rainbow"),
+        schema_variant.link()
+    );
+
+    let definition = SchemaVariantDefinition::get_by_schema_variant_id(ctx, schema_variant.id())
+        .await
+        .expect("get schema variant definition")
+        .expect("schema variant definition exists");
+    assert_eq!(
+        Some("a screaming comes across the sky"),
+        definition.description()
+    );
+    assert_eq!(
+        Some("https://example.com/gravitys-rainbow"),
+        definition.link()
+    );
+}
+
+#[test]
+async fn test_import_schema_variant_color_dark(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Lot 49", |_, data| {
+            data.color_dark("dadbad");
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let schema = Schema::find_by_name(ctx, "Lot 49")
+        .await
+        .expect("find schema");
+    let schema_variant_id = schema
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let mut schema_variant = SchemaVariant::get_by_id(ctx, schema_variant_id)
+        .await
+        .expect("get schema variant")
+        .expect("schema variant exists");
+    assert_eq!(
+        Some("baddad".to_owned()),
+        schema_variant.color(ctx).await.expect("get color")
+    );
+    assert_eq!(
+        Some("dadbad".to_owned()),
+        schema_variant
+            .color_dark(ctx)
+            .await
+            .expect("get dark color")
+    );
+
+    schema_variant
+        .set_default_color_dark(ctx, None)
+        .await
+        .expect("able to unset dark color");
+    assert_eq!(
+        Some("baddad".to_owned()),
+        schema_variant
+            .color_dark(ctx)
+            .await
+            .expect("get dark color")
+    );
+}
+
+#[test]
+async fn test_import_sockets_inherit_default_connection_annotations(ctx: &DalContext) {
+    let socket = |name: &str, kind: SocketSpecKind, connection_annotations: &str| {
+        SocketSpec::builder()
+            .name(name)
+            .data(
+                SocketSpecData::builder()
+                    .name(name)
+                    .connection_annotations(connection_annotations)
+                    .kind(kind)
+                    .build()
+                    .expect("build socket data"),
+            )
+            .build()
+            .expect("build socket spec")
+    };
+
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Byzantium", |variant, data| {
+            data.default_connection_annotations(connection_annotation_string!("power"));
+            variant
+                .socket(socket("mains", SocketSpecKind::Input, ""))
+                .socket(socket("outlet", SocketSpecKind::Output, ""))
+                .socket(socket(
+                    "lamp",
+                    SocketSpecKind::Output,
+                    &connection_annotation_string!("light"),
+                ));
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    let ImportResult {
+        schema_variant_ids, ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    let schema_variant = SchemaVariant::get_by_id(
+        ctx,
+        schema_variant_ids
+            .first()
+            .expect("pkg should have a schema variant"),
+    )
+    .await
+    .expect("get schema variant")
+    .expect("schema variant exists");
+
+    let mut annotations: Vec<(String, Vec<String>)> = vec![];
+    for socket in schema_variant.sockets(ctx).await.expect("list sockets") {
+        if socket.name() == "Frame" {
+            continue;
+        }
+        annotations.push((
+            socket.name().to_owned(),
+            socket
+                .connection_annotation_list()
+                .expect("parse connection annotations"),
+        ));
+    }
+    annotations.sort();
+
+    assert_eq!(
+        vec![
+            ("lamp".to_owned(), vec!["light".to_owned()]),
+            ("mains".to_owned(), vec!["power".to_owned()]),
+            ("outlet".to_owned(), vec!["power".to_owned()]),
+        ],
+        annotations
+    );
+}
+
+fn linked_pkg(func_link: &str, doc_link: &str) -> SiPkg {
+    let mut spec = asset_pkg_spec("Vineland", |variant, _| {
+        variant.domain_prop(
+            PropSpec::builder()
+                .name("thanatoid")
+                .kind(PropKind::String)
+                .try_doc_link(doc_link)
+                .expect("parse doc link")
+                .build()
+                .expect("build prop spec"),
+        );
+    })
+    .build()
+    .expect("build pkg spec");
+    if let Some(data) = spec.funcs[0].data.as_mut() {
+        data.link = Some(func_link.try_into().expect("parse func link"));
+    }
+
+    SiPkg::load_from_spec(spec).expect("load pkg from spec")
+}
+
+async fn imported_links(ctx: &DalContext) -> (Option<String>, Option<String>) {
+    let func = Func::find_by_name(ctx, "test:scaffoldFuncVineland")
+        .await
+        .expect("find func")
+        .expect("func exists");
+    let schema = Schema::find_by_name(ctx, "Vineland")
+        .await
+        .expect("find schema");
+    let schema_variant_id = schema
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let prop = Prop::find_prop_by_path(
+        ctx,
+        *schema_variant_id,
+        &PropPath::new(["root", "domain", "thanatoid"]),
+    )
+    .await
+    .expect("find prop");
+
+    (
+        func.link().map(ToOwned::to_owned),
+        prop.doc_link().map(ToOwned::to_owned),
+    )
+}
+
+#[test]
+async fn test_import_func_and_prop_links(ctx: &DalContext) {
+    let pkg = linked_pkg(
+        "https://example.com/vineland",
+        "http://example.com/thanatoids",
+    );
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    assert_eq!(
+        (
+            Some("https://example.com/vineland".to_owned()),
+            Some("http://example.com/thanatoids".to_owned())
+        ),
+        imported_links(ctx).await
+    );
+}
+
+#[test]
+async fn test_import_rejects_non_web_func_link(ctx: &DalContext) {
+    let pkg = linked_pkg("javascript:alert(1)", "https://example.com/thanatoids");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::InvalidFuncLink { func, link })
+            if func == "test:scaffoldFuncVineland" && link == "javascript:alert(1)"
+    ));
+}
+
+#[test]
+async fn test_import_rejects_non_web_prop_doc_link(ctx: &DalContext) {
+    let pkg = linked_pkg("https://example.com/vineland", "file:///etc/passwd");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::InvalidPropDocLink { prop, link })
+            if prop == "thanatoid" && link == "file:///etc/passwd"
+    ));
+}
+
+#[test]
+async fn test_import_strips_non_web_links(ctx: &DalContext) {
+    let pkg = linked_pkg("javascript:alert(1)", "file:///etc/passwd");
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().strip_invalid_links(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import pkg");
+
+    assert_eq!((None, None), imported_links(ctx).await);
+}
+
+const REGION_FORMAT: &str = r#"{"type":"string","flags":{"only":true},"allow":["us-east-1"]}"#;
+
+fn validated_pkg() -> SiPkg {
+    SiPkg::load_from_spec(
+        asset_pkg_spec("Oedipa", |variant, _| {
+            variant.domain_prop(
+                PropSpec::builder()
+                    .name("region")
+                    .kind(PropKind::String)
+                    .validation_format(REGION_FORMAT)
+                    .build()
+                    .expect("build prop spec"),
+            );
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec")
+}
+
+#[test]
+async fn test_prop_validation_format_round_trips(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    import_pkg_from_pkg(ctx, &validated_pkg(), None, true)
+        .await
+        .expect("able to import pkg");
+
+    let schema = Schema::find_by_name(ctx, "Oedipa")
+        .await
+        .expect("find schema");
+    let schema_variant_id = schema
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let prop = Prop::find_prop_by_path(
+        ctx,
+        *schema_variant_id,
+        &PropPath::new(["root", "domain", "region"]),
+    )
+    .await
+    .expect("find prop");
+    assert_eq!(Some(REGION_FORMAT), prop.validation_format());
+
+    let mut exporter = PkgExporter::new_module_exporter(
+        "oedipa",
+        "0.2",
+        None::<String>,
+        "Oedipa Maas",
+        vec![*schema.id()],
+    );
+    let pkg = SiPkg::load_from_bytes(exporter.export_as_bytes(ctx).await.expect("export"))
+        .expect("load exported pkg");
+    let spec = pkg.to_spec().await.expect("convert to spec");
+
+    let domain = &spec.schemas[0].variants[0].domain;
+    let region = match domain {
+        PropSpec::Object { entries, .. } => entries
+            .iter()
+            .find(|entry| entry.name() == "region")
+            .expect("region prop exported"),
+        _ => panic!("domain should be an object"),
+    };
+    let validation_format = match region {
+        PropSpec::String { data, .. } => data
+            .as_ref()
+            .and_then(|data| data.validation_format.as_deref()),
+        _ => panic!("region should be a string"),
+    };
+    assert_eq!(Some(REGION_FORMAT), validation_format);
+}
+
+fn widget_pkg(widget_kind: PropSpecWidgetKind, widget_options: serde_json::Value) -> SiPkg {
+    SiPkg::load_from_spec(
+        asset_pkg_spec("Zhlubb", |variant, _| {
+            variant.domain_prop(
+                PropSpec::builder()
+                    .name("smegma")
+                    .kind(PropKind::String)
+                    .widget_kind(widget_kind)
+                    .widget_options(widget_options)
+                    .build()
+                    .expect("build prop spec"),
+            );
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec")
+}
+
+#[test]
+async fn test_import_select_widget_options(ctx: &DalContext) {
+    let options = serde_json::json!([
+        { "label": "Hot", "value": "hot" },
+        { "label": "Cold", "value": "cold" },
+    ]);
+    let pkg = widget_pkg(PropSpecWidgetKind::Select, options.clone());
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let schema_variant_id = *Schema::find_by_name(ctx, "Zhlubb")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let prop = Prop::find_prop_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "domain", "smegma"]),
+    )
+    .await
+    .expect("find prop");
+
+    assert_eq!(WidgetKind::Select, *prop.widget_kind());
+    assert_eq!(Some(&options), prop.widget_options());
+}
+
+#[test]
+async fn test_import_rejects_spurious_widget_options(ctx: &DalContext) {
+    let pkg = widget_pkg(
+        PropSpecWidgetKind::Text,
+        serde_json::json!([{ "label": "Hot", "value": "hot" }]),
+    );
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::InvalidWidgetOptions { prop, kind })
+            if prop == "smegma" && kind == WidgetKind::Text
+    ));
+}
+
+fn func_pkg(
+    backend_kind: FuncSpecBackendKind,
+    response_type: FuncSpecBackendResponseType,
+) -> SiPkg {
+    let func_spec = FuncSpec::builder()
+        .name("test:refreshZhlubb")
+        .unique_id("test:refreshZhlubb")
+        .data(
+            FuncSpecData::builder()
+                .name("test:refreshZhlubb")
+                .code_plaintext("async function refresh() { return { status: \"ok\" }; }")
+                .handler("refresh")
+                .backend_kind(backend_kind)
+                .response_type(response_type)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build func spec");
+
+    SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name("zhlubb funcs")
+            .version("0.1")
+            .created_by("Zhlubb")
+            .func(func_spec)
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec")
+}
+
+#[test]
+async fn test_import_func_with_valid_kind_response_pair(ctx: &DalContext) {
+    let pkg = func_pkg(
+        FuncSpecBackendKind::JsAction,
+        FuncSpecBackendResponseType::Action,
+    );
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let func = Func::find_by_name(ctx, "test:refreshZhlubb")
+        .await
+        .expect("find func")
+        .expect("func exists");
+    assert_eq!(FuncBackendKind::JsAction, *func.backend_kind());
+    assert_eq!(
+        FuncBackendResponseType::Action,
+        *func.backend_response_type()
+    );
+}
+
+#[test]
+async fn test_import_rejects_invalid_func_kind_response_pair(ctx: &DalContext) {
+    let pkg = func_pkg(
+        FuncSpecBackendKind::JsAction,
+        FuncSpecBackendResponseType::Qualification,
+    );
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::InvalidFuncKindResponsePair { func, kind, response_type })
+            if func == "test:refreshZhlubb"
+                && kind == FuncBackendKind::JsAction
+                && response_type == FuncBackendResponseType::Qualification
+    ));
+}
+
+#[test]
+async fn test_import_rejects_invalid_func_kind_response_pair_on_builtin_update(ctx: &DalContext) {
+    import_pkg_from_pkg(
+        ctx,
+        &func_pkg(
+            FuncSpecBackendKind::JsAction,
+            FuncSpecBackendResponseType::Action,
+        ),
+        None,
+        true,
+    )
+    .await
+    .expect("able to import pkg");
+
+    // Builtin funcs are matched by name and updated in place, which has to validate the pair
+    // just like creating the func does
+    let mut spec = func_pkg(
+        FuncSpecBackendKind::JsAction,
+        FuncSpecBackendResponseType::Qualification,
+    )
+    .to_spec()
+    .await
+    .expect("convert pkg to spec");
+    for func_spec in &mut spec.funcs {
+        func_spec.is_from_builtin = Some(true);
+    }
+    let pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::InvalidFuncKindResponsePair { func, kind, response_type })
+            if func == "test:refreshZhlubb"
+                && kind == FuncBackendKind::JsAction
+                && response_type == FuncBackendResponseType::Qualification
+    ));
+}
+
+#[test]
+async fn test_import_rejects_output_socket_attr_func_input(ctx: &DalContext) {
+    let identity_func_spec = IntrinsicFunc::Identity
+        .to_spec()
+        .expect("create identity func spec");
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Mason & Dixon", |variant, data| {
+            data.component_type(ComponentType::Component);
+            variant.domain_prop(
+                PropSpec::builder()
+                    .name("line")
+                    .kind(PropSpecKind::String)
+                    .func_unique_id(&identity_func_spec.unique_id)
+                    .input(
+                        AttrFuncInputSpec::builder()
+                            .kind(AttrFuncInputSpecKind::OutputSocket)
+                            .name("identity")
+                            .socket_name("visto")
+                            .build()
+                            .expect("build attr func input spec"),
+                    )
+                    .build()
+                    .expect("build prop spec"),
+            );
+        })
+        .func(identity_func_spec)
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+    assert!(
+        matches!(
+            &result,
+            Err(PkgError::UnsupportedOutputSocketInput {
+                func_argument,
+                socket_name,
+                ..
+            }) if func_argument == "identity" && socket_name == "visto"
+        ),
+        "expected the output socket input to be rejected, got {result:?}"
+    );
+}
+
+#[test]
+async fn test_import_continues_past_broken_func(ctx: &DalContext) {
+    let mut spec = upgrade_preview_pkg(&["imipolex"], Utc::now())
+        .to_spec()
+        .await
+        .expect("convert pkg to spec");
+
+    // Funcs are imported in order, so the broken one sits between two good ones
+    let good_func = func_pkg(
+        FuncSpecBackendKind::JsAction,
+        FuncSpecBackendResponseType::Action,
+    )
+    .to_spec()
+    .await
+    .expect("convert func pkg to spec")
+    .funcs
+    .pop()
+    .expect("func pkg has a func");
+    let broken_func = FuncSpec::builder()
+        .name("test:scaffoldFuncV1")
+        .unique_id("test:scaffoldFuncV1")
+        .data(
+            FuncSpecData::builder()
+                .name("test:scaffoldFuncV1")
+                .code_plaintext("function createAsset() { return new AssetBuilder().build(); }")
+                .handler("createAsset")
+                .backend_kind(FuncSpecBackendKind::JsSchemaVariantDefinition)
+                .response_type(FuncSpecBackendResponseType::Qualification)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build broken func spec");
+    spec.funcs.insert(0, broken_func);
+    spec.funcs.insert(0, good_func);
+
+    // A second schema whose asset func is the broken one
+    let mut broken_schema = spec.schemas[0].clone();
+    broken_schema.name = "V-1".to_owned();
+    if let Some(data) = broken_schema.data.as_mut() {
+        data.name = "V-1".to_owned();
+    }
+    for variant in &mut broken_schema.variants {
+        if let Some(data) = variant.data.as_mut() {
+            data.func_unique_id = "test:scaffoldFuncV1".to_owned();
+        }
+    }
+    spec.schemas.push(broken_schema);
+
+    let pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+
+    let ImportResult {
+        schema_variant_ids,
+        skips,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .continue_on_func_error(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import pkg with a broken func");
+
+    assert_eq!(1, schema_variant_ids.len());
+    let skips = skips
+        .expect("import has skips")
+        .pop()
+        .expect("one change set");
+    assert_eq!(
+        vec!["test:scaffoldFuncV1"],
+        skips
+            .func_errors
+            .iter()
+            .map(|(unique_id, _)| unique_id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![(
+            "V-1".to_owned(),
+            "refers to func test:scaffoldFuncV1, which could not be imported".to_owned()
+        )],
+        skips.schema_skips
+    );
+
+    assert!(Func::find_by_name(ctx, "test:refreshZhlubb")
+        .await
+        .expect("find good func")
+        .is_some());
+    assert!(Func::find_by_name(ctx, "test:scaffoldFuncV2")
+        .await
+        .expect("find good asset func")
+        .is_some());
+    assert!(Func::find_by_name(ctx, "test:scaffoldFuncV1")
+        .await
+        .expect("find broken func")
+        .is_none());
+    Schema::find_by_name(ctx, "V-2")
+        .await
+        .expect("schema with good funcs is imported");
+    assert!(Schema::find_by_name(ctx, "V-1").await.is_err());
+}
+
+#[test]
+async fn test_import_continues_past_func_rejected_by_database(ctx: &DalContext) {
+    let mut spec = mucker_maffick_pkg("0.1", false)
+        .to_spec()
+        .await
+        .expect("convert pkg to spec");
+
+    // Postgres refuses text containing a NUL byte, so this func passes validation and only
+    // fails once it is written, which aborts the transaction unless it is rolled back
+    let rejected_name = "test:nul\u{0}Func";
+    let rejected_func = FuncSpec::builder()
+        .name(rejected_name)
+        .unique_id("test:nulFunc")
+        .data(
+            FuncSpecData::builder()
+                .name(rejected_name)
+                .code_plaintext("function truth() { return true; }")
+                .handler("truth")
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Boolean)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build rejected func spec");
+    spec.funcs.insert(0, rejected_func);
+    let pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+
+    let ImportResult { skips, .. } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .continue_on_func_error(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import pkg with a func the database rejects");
+
+    let skips = skips
+        .expect("import has skips")
+        .pop()
+        .expect("one change set");
+    assert_eq!(
+        vec!["test:nulFunc"],
+        skips
+            .func_errors
+            .iter()
+            .map(|(unique_id, _)| unique_id.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(Func::find_by_name(ctx, "test:muckerMaffick")
+        .await
+        .expect("transaction is still usable")
+        .is_some());
+    ctx.commit().await.expect("able to commit the import");
+}
+
+/// The V-2 package with the "test:refreshZhlubb" action func and an intrinsic func added.
+async fn func_allowlist_pkg() -> SiPkg {
+    let mut spec = upgrade_preview_pkg(&["imipolex"], Utc::now())
+        .to_spec()
+        .await
+        .expect("convert pkg to spec");
+    spec.funcs.extend(
+        func_pkg(
+            FuncSpecBackendKind::JsAction,
+            FuncSpecBackendResponseType::Action,
+        )
+        .to_spec()
+        .await
+        .expect("convert func pkg to spec")
+        .funcs,
+    );
+    spec.funcs.push(
+        IntrinsicFunc::Identity
+            .to_spec()
+            .expect("create identity func spec"),
+    );
+
+    SiPkg::load_from_spec(spec).expect("load pkg from spec")
+}
+
+#[test]
+async fn test_import_only_allowed_funcs(ctx: &DalContext) {
+    let pkg = func_allowlist_pkg().await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .funcs(vec!["test:refreshZhlubb".to_owned()])
+                .schemas(vec![])
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import pkg");
+
+    assert!(Func::find_by_name(ctx, "test:refreshZhlubb")
+        .await
+        .expect("find allowed func")
+        .is_some());
+    assert!(Func::find_by_name(ctx, "test:scaffoldFuncV2")
+        .await
+        .expect("find func left out")
+        .is_none());
+    assert!(Func::find_by_name(ctx, "si:identity")
+        .await
+        .expect("find intrinsic func")
+        .is_some());
+}
+
+#[test]
+async fn test_import_schema_referring_to_func_left_out(ctx: &DalContext) {
+    let pkg = func_allowlist_pkg().await;
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .funcs(vec!["test:refreshZhlubb".to_owned()])
+                .build(),
+        ),
+        true,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::MissingFuncUniqueId(unique_id)) if unique_id == "test:scaffoldFuncV2"
+    ));
+}
+
+fn upgrade_preview_pkg(prop_names: &[&str], created_at: DateTime<Utc>) -> SiPkg {
+    SiPkg::load_from_spec(
+        asset_pkg_spec("V-2", |variant, _| {
+            for prop_name in prop_names {
+                variant.domain_prop(
+                    PropSpec::builder()
+                        .name(*prop_name)
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("build prop spec"),
+                );
+            }
+        })
+        .created_at(created_at)
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec")
+}
+
+#[test]
+async fn test_preview_schema_upgrade_flags_data_loss(ctx: &DalContext) {
+    let installed_pkg = upgrade_preview_pkg(
+        &["imipolex", "schwarzgerat"],
+        Utc::now() - Duration::hours(1),
+    );
+    import_pkg_from_pkg(ctx, &installed_pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let mut bagger = ComponentBagger::new();
+    let component_bag = bagger.create_component(ctx, "00000", "V-2").await;
+
+    let upgrade_pkg = upgrade_preview_pkg(&["imipolex"], Utc::now());
+    let schema_spec = upgrade_pkg
+        .schemas()
+        .expect("get schemas")
+        .pop()
+        .expect("pkg has a schema");
+    let metadata = upgrade_pkg.metadata().expect("get metadata");
+
+    // The removed prop has no value yet, so nothing would be lost
+    let preview = preview_schema_upgrade(ctx, schema_spec.clone(), &metadata)
+        .await
+        .expect("able to preview upgrade");
+    assert_eq!(Some("v0".to_owned()), preview.variant_name);
+    assert_eq!(vec![component_bag.component_id], preview.components);
+    assert!(preview.edges.is_empty());
+    assert_eq!(
+        vec![PropPath::new(["root", "domain", "schwarzgerat"])],
+        preview.removed_props
+    );
+    assert!(!preview.data_loss);
+
+    let schwarzgerat_prop = component_bag
+        .find_prop(ctx, &["root", "domain", "schwarzgerat"])
+        .await;
+    component_bag
+        .update_attribute_value_for_prop(
+            ctx,
+            *schwarzgerat_prop.id(),
+            Some(serde_json::json!("Gottfried")),
+        )
+        .await;
+
+    let preview = preview_schema_upgrade(ctx, schema_spec, &metadata)
+        .await
+        .expect("able to preview upgrade");
+    assert!(preview.data_loss);
+
+    // Previewing must not have touched the installed schema or its components
+    assert!(Component::get_by_id(ctx, &component_bag.component_id)
+        .await
+        .expect("get component")
+        .is_some());
+}
+
+#[test]
+async fn test_dry_run_import_plans_upgrade_without_importing(ctx: &DalContext) {
+    let installed_pkg = upgrade_preview_pkg(
+        &["imipolex", "schwarzgerat"],
+        Utc::now() - Duration::hours(1),
+    );
+    import_pkg_from_pkg(
+        ctx,
+        &installed_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import builtin pkg");
+
+    let schema_variant_id = *Schema::find_by_name(ctx, "V-2")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let (component, _) = Component::new(ctx, "00000", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let upgrade_pkg = upgrade_preview_pkg(&["imipolex"], Utc::now());
+    let ImportResult {
+        installed_pkg_id,
+        schema_variant_ids: variant_ids,
+        plan,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &upgrade_pkg,
+        Some(
+            ImportOptions::builder()
+                .is_builtin(true)
+                .dry_run(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to plan import");
+    let plan = plan.expect("a dry run returns a plan");
+
+    assert!(installed_pkg_id.is_none());
+    assert!(variant_ids.is_empty());
+    assert!(plan.schemas_to_create.is_empty());
+    assert_eq!(1, plan.schemas_to_upgrade.len());
+    let upgrade = &plan.schemas_to_upgrade[0];
+    assert_eq!("V-2", upgrade.schema_name);
+    assert_eq!(vec![*component.id()], upgrade.components);
+    assert!(upgrade.data_loss);
+    assert_eq!(vec![*component.id()], plan.components_to_delete);
+
+    // Nothing was imported, so the component and the installed variant are untouched
+    assert!(Component::get_by_id(ctx, component.id())
+        .await
+        .expect("get component")
+        .is_some());
+    assert!(InstalledPkg::find_by_hash(
+        ctx,
+        &upgrade_pkg.hash().expect("pkg has a hash").to_string()
+    )
+    .await
+    .expect("find installed pkg by hash")
+    .is_none());
+    assert_eq!(
+        Some(schema_variant_id),
+        Schema::find_by_name(ctx, "V-2")
+            .await
+            .expect("find schema")
+            .default_schema_variant_id()
+            .copied()
+    );
+}
+
+#[test]
+async fn test_dry_run_import_plans_funcs(ctx: &DalContext) {
+    let pkg = mucker_maffick_pkg("0.1", false);
+    let dry_run = || Some(ImportOptions::builder().dry_run(true).build());
+
+    let ImportResult { plan, .. } = import_pkg_from_pkg(ctx, &pkg, dry_run(), true)
+        .await
+        .expect("able to plan import");
+    let plan = plan.expect("a dry run returns a plan");
+    assert_eq!(vec!["test:muckerMaffick".to_owned()], plan.funcs_to_create);
+    assert!(plan.funcs_to_update.is_empty());
+    assert!(Func::find_by_name(ctx, "test:muckerMaffick")
+        .await
+        .expect("find func")
+        .is_none());
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    // The installed func is found by hash and reused, so there is nothing left to do
+    let ImportResult { plan, .. } = import_pkg_from_pkg(ctx, &pkg, dry_run(), true)
+        .await
+        .expect("able to plan import");
+    let plan = plan.expect("a dry run returns a plan");
+    assert!(plan.funcs_to_create.is_empty());
+    assert!(plan.funcs_to_update.is_empty());
+}
+
+#[test]
+async fn test_dry_run_import_plans_attribute_and_edge_skips(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let from_fallout = bagger.create_component(ctx, "the divide", "fallout").await;
+    let to_starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        from_fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        to_starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        from_fallout.node_id,
+        *output_socket.id(),
+        to_starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let pkg = export_workspace(ctx).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    let rads_path = PropPath::new(["root", "domain", "rads"]);
+    let missing_path = PropPath::new(["root", "domain", "lonesome road"]);
+    let mut patched_attribute = false;
+    for attribute in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.components.iter_mut())
+        .filter(|component_spec| component_spec.name == "the divide")
+        .flat_map(|component_spec| component_spec.attributes.iter_mut())
+        .filter(|attribute| attribute.path.path() == rads_path.as_str())
+    {
+        attribute.path = AttributeValuePath::Prop {
+            path: missing_path.as_str().to_owned(),
+            key: None,
+            index: None,
+        };
+        patched_attribute = true;
+    }
+    assert!(
+        patched_attribute,
+        "exported component should have a rads attribute"
+    );
+    let mut patched_edge = false;
+    for edge_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.edges.iter_mut())
+        .filter(|edge_spec| edge_spec.from_socket_name == "bethesda")
+    {
+        edge_spec.from_socket_name = "Game Studio".to_owned();
+        patched_edge = true;
+    }
+    assert!(patched_edge, "exported workspace should have the edge");
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load patched spec");
+
+    let ImportResult { plan, .. } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().dry_run(true).build()),
+        true,
+    )
+    .await
+    .expect("able to plan import");
+    let plan = plan.expect("a dry run returns a plan");
+
+    assert_eq!(
+        vec![(
+            "the divide".to_owned(),
+            vec![ImportAttributeSkip::MissingProp { path: missing_path }]
+        )],
+        plan.attribute_skips
+    );
+    assert_eq!(
+        vec![ImportEdgeSkip::MissingOutputSocket {
+            name: "Game Studio".to_owned()
+        }],
+        plan.edge_skips
+    );
+    assert!(plan.edges_to_create.is_empty());
+}
+
+#[test]
+async fn test_dry_run_import_plans_suffixed_component_names(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let pkg = export_workspace_with_name_collision(ctx, "web").await;
+    let mut head_ids: Vec<ComponentId> = Component::list(ctx)
+        .await
+        .expect("list components")
+        .iter()
+        .map(|component| *component.id())
+        .collect();
+    let change_sets = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
+        .len();
+
+    let ImportResult { plan, .. } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_policy(ComponentNamePolicy::SuffixOnCollision)
+                .dry_run(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to plan import");
+    let plan = plan.expect("a dry run returns a plan");
+
+    // The plan is made by the import itself, so it has the names the import would give
+    assert_eq!(
+        vec!["web".to_owned(), "web (2)".to_owned()],
+        plan.components_to_create
+    );
+    let mut components_to_delete = plan.components_to_delete.clone();
+    components_to_delete.sort();
+    head_ids.sort();
+    assert_eq!(head_ids, components_to_delete);
+
+    // The import was rolled back, so neither the workspace nor its change sets changed
+    let mut ids: Vec<ComponentId> = Component::list(ctx)
+        .await
+        .expect("list components")
+        .iter()
+        .map(|component| *component.id())
+        .collect();
+    ids.sort();
+    assert_eq!(head_ids, ids);
+    assert_eq!(
+        change_sets,
+        ChangeSet::list_open(ctx)
+            .await
+            .expect("list open change sets")
+            .len()
+    );
+}
+
+#[test]
+async fn test_failed_schema_upgrade_keeps_components(ctx: &DalContext) {
+    let installed_pkg = upgrade_preview_pkg(&["imipolex"], Utc::now() - Duration::hours(1));
+    import_pkg_from_pkg(
+        ctx,
+        &installed_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import builtin pkg");
+
+    let schema_variant_id = *Schema::find_by_name(ctx, "V-2")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let (component, _) = Component::new(ctx, "00000", schema_variant_id)
+        .await
+        .expect("create component");
+
+    // The new variant refers to an asset func that is not in the package, so importing it
+    // fails after the installed schema and its components have been deleted
+    let mut spec = upgrade_preview_pkg(&["imipolex"], Utc::now())
+        .to_spec()
+        .await
+        .expect("convert pkg to spec");
+    for variant in &mut spec.schemas[0].variants {
+        if let Some(data) = variant.data.as_mut() {
+            data.func_unique_id = "test:missingAssetFunc".to_owned();
+        }
+    }
+    let upgrade_pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &upgrade_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        true,
+    )
+    .await;
+    assert!(matches!(result, Err(PkgError::MissingFuncUniqueId(_))));
+
+    // The upgrade was rolled back without rolling back the rest of the transaction
+    let component = Component::get_by_id(ctx, component.id())
+        .await
+        .expect("get component")
+        .expect("component still exists");
+    assert_eq!(
+        Some(schema_variant_id),
+        component
+            .schema_variant(ctx)
+            .await
+            .expect("get schema variant")
+            .map(|variant| *variant.id())
+    );
+    assert_eq!(
+        Some(schema_variant_id),
+        Schema::find_by_name(ctx, "V-2")
+            .await
+            .expect("find schema")
+            .default_schema_variant_id()
+            .copied()
+    );
+}
+
+#[test]
+async fn test_builtin_upgrade_without_flag_is_refused(ctx: &DalContext) {
+    let installed_pkg = upgrade_preview_pkg(&["imipolex"], Utc::now() - Duration::hours(1));
+    import_pkg_from_pkg(
+        ctx,
+        &installed_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import builtin pkg");
+
+    let upgrade_pkg = upgrade_preview_pkg(&["imipolex", "schwarzgerat"], Utc::now());
+    let result = import_pkg_from_pkg(
+        ctx,
+        &upgrade_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        false,
+    )
+    .await;
+    assert!(
+        matches!(result, Err(PkgError::UpgradeRequiresFlag(ref name)) if name == "V-2"),
+        "expected the upgrade to be refused, got {result:?}"
+    );
+
+    assert_eq!(
+        1,
+        Schema::find_by_attr(ctx, "name", &"V-2")
+            .await
+            .expect("find schemas by name")
+            .len()
+    );
+}
+
+#[test]
+async fn test_import_reports_variant_finalize_failure(ctx: &DalContext) {
+    let pkg = stellarfield_pkg().expect("able to build stellarfield");
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import stellarfield");
+
+    // Break the installed variant, so that finalizing it again fails
+    let schema_variant_id = *Schema::find_by_name(ctx, "stellarfield")
+        .await
+        .expect("get stellarfield")
+        .default_schema_variant_id()
+        .expect("stellarfield has a default variant");
+    let mut type_prop =
+        SchemaVariant::find_prop_in_tree(ctx, schema_variant_id, &["root", "si", "type"])
+            .await
+            .expect("find type prop");
+    type_prop
+        .delete_by_id(ctx)
+        .await
+        .expect("able to delete type prop");
+
+    // A new version of the package shares the installed variant, which is finalized again
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+    spec.version = "0.2".to_owned();
+    let pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+
+    match import_pkg_from_pkg(ctx, &pkg, None, true).await {
+        Err(err @ PkgError::VariantFinalizeFailed { .. }) => {
+            assert!(err
+                .to_string()
+                .starts_with("could not finalize variant v0 of schema stellarfield"));
+            assert!(matches!(
+                err,
+                PkgError::VariantFinalizeFailed {
+                    source: SchemaVariantError::PropNotFound("/root/si/type"),
+                    ..
+                }
+            ));
+        }
+        other => panic!("expected the variant finalize to fail, got {other:?}"),
+    }
+}
+
+#[test]
+async fn test_import_options_builder_defaults(_ctx: &DalContext) {
+    let options = ImportOptions::builder()
+        .schemas(vec!["starfield".into()])
+        .is_builtin(true)
+        .build();
+
+    assert_eq!(Some(vec!["starfield".to_owned()]), options.schemas);
+    assert!(options.is_builtin);
+
+    assert!(options.skip_import_funcs.is_none());
+    assert!(!options.no_record);
+    assert!(options.resume_from.is_none());
+    assert!(options.max_change_sets.is_none());
+    assert_eq!(FuncNameConflictPolicy::Allow, options.func_name_conflict);
+    assert!(!options.strict_type_checking);
+    assert!(!options.enqueue_create_actions);
+    assert!(!options.run_qualifications_after_import);
+    assert!(options.cancellation_token.is_none());
+    assert_eq!(ComponentNamePolicy::AsIs, options.component_name_policy);
+    assert_eq!(SecretsPolicy::RequireReinjection, options.secrets_policy);
+    assert!(options.socket_name_remap.is_empty());
+    assert!(!options.strip_invalid_links);
+    assert_eq!(None, options.max_components);
+    assert!(!options.defer_finalize);
+    assert!(!options.defer_dependent_updates);
+    assert!(options.target_layout.is_none());
+    assert!(options.db_retry.is_none());
+    assert!(!options.skip_attribute_funcs);
+    assert!(options.slow_schema_warn_ms.is_none());
+    assert!(options.only_change_set.is_none());
+    assert!(!options.repair_legacy_variants);
+    assert!(options.as_user.is_none());
+    assert!(!options.dry_run);
+    assert!(!options.continue_on_func_error);
+    assert!(options.component_name_prefix.is_none());
+    assert!(options.components.is_none());
+    assert!(!options.skip_edges);
+    assert!(options.funcs.is_none());
+}
+
+#[test]
+async fn test_import_repairs_legacy_variant(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Palmer Eldritch", |_, _| {})
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    let schema_variant_id = *Schema::find_by_name(ctx, "Palmer Eldritch")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+
+    // Variants created before "/root/resource_value" existed do not have it
+    SchemaVariant::find_prop_in_tree(ctx, schema_variant_id, &["root", "resource_value"])
+        .await
+        .expect("find resource value prop")
+        .delete_by_id(ctx)
+        .await
+        .expect("delete resource value prop");
+
+    // A later version of the package has the same variant, so importing it reuses the legacy one
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Palmer Eldritch", |_, _| {})
+            .version("0.2")
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .repair_legacy_variants(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import pkg");
+
+    assert_eq!(
+        schema_variant_id,
+        *Schema::find_by_name(ctx, "Palmer Eldritch")
+            .await
+            .expect("find schema")
+            .default_schema_variant_id()
+            .expect("schema has a default variant")
+    );
+    let resource_value_prop =
+        SchemaVariant::find_prop_in_tree(ctx, schema_variant_id, &["root", "resource_value"])
+            .await
+            .expect("resource value prop was created");
+    assert_eq!(
+        "si:resourcePayloadToValue",
+        prototype_func_name_for_prop(ctx, *resource_value_prop.id()).await
+    );
+}
+
+#[test]
+async fn test_import_warns_about_slow_schemas(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Pokler", |variant, _| {
+            for index in 0..200 {
+                variant.domain_prop(
+                    PropSpec::builder()
+                        .name(format!("rocket{index}"))
+                        .kind(PropKind::String)
+                        .build()
+                        .expect("build prop spec"),
+                );
+            }
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    let mut log_file = tempfile::tempfile().expect("create log file");
+    let writer = log_file.try_clone().expect("clone log file");
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.try_clone().expect("clone log file"))
+        .finish();
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().slow_schema_warn_ms(1).build()),
+        true,
+    )
+    .with_subscriber(subscriber)
+    .await
+    .expect("able to import pkg");
+
+    let mut logs = String::new();
+    log_file.rewind().expect("rewind log file");
+    log_file.read_to_string(&mut logs).expect("read logs");
+    let warning = logs
+        .lines()
+        .find(|line| line.contains("slow schema import"))
+        .expect("slow schema warning was logged");
+    assert!(warning.contains("schema.name=\"Pokler\""));
+    assert!(warning.contains("variants=1"));
+    assert!(warning.contains("props=200"));
+}
+
+#[test]
+async fn test_import_secret_definition_collision(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Slothrop Credentials", |variant, _| {
+            variant
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("token")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("build domain prop spec"),
+                )
+                .secret_definition_prop(
+                    PropSpec::builder()
+                        .name("token")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("build secret definition prop spec"),
+                );
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::SecretDefinitionCollision(name)) if name == "token"
+    ));
+}
+
+#[test]
+async fn test_import_attr_func_with_secret_definition_input(ctx: &DalContext) {
+    let identity_func_spec = IntrinsicFunc::Identity
+        .to_spec()
+        .expect("create identity func spec");
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("Pointsman Credentials", |variant, _| {
+            variant
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("user")
+                        .kind(PropSpecKind::String)
+                        .func_unique_id(&identity_func_spec.unique_id)
+                        .input(
+                            AttrFuncInputSpec::builder()
+                                .kind(AttrFuncInputSpecKind::Prop)
+                                .name("identity")
+                                .prop_path(PropPath::new(["root", "secret_definition", "username"]))
+                                .build()
+                                .expect("build attr func input spec"),
+                        )
+                        .build()
+                        .expect("build domain prop spec"),
+                )
+                .secret_definition_prop(
+                    PropSpec::builder()
+                        .name("username")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("build secret definition prop spec"),
+                );
+        })
+        .func(identity_func_spec)
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let schema_variant_id = *Schema::find_by_name(ctx, "Pointsman Credentials")
+        .await
+        .expect("get schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let username_prop = Prop::find_prop_by_path(
+        ctx,
+        schema_variant_id,
+        &PropPath::new(["root", "secret_definition", "username"]),
+    )
+    .await
+    .expect("find secret definition prop");
+    assert!(InternalProvider::find_for_prop(ctx, *username_prop.id())
+        .await
+        .expect("find internal provider")
+        .is_some());
+    assert_eq!(
+        "si:identity",
+        prototype_func_name_for_prop(
+            ctx,
+            *Prop::find_prop_by_path(
+                ctx,
+                schema_variant_id,
+                &PropPath::new(["root", "domain", "user"])
+            )
+            .await
+            .expect("find domain prop")
+            .id()
+        )
+        .await
+    );
+}
+
+#[test]
+async fn test_failed_import_rolls_back_installed_pkg(ctx: &DalContext) {
+    let mut spec = asset_pkg_spec("Rocket 00000", |_, _| {})
+        .build()
+        .expect("build pkg spec");
+    // Leave the asset func out of the package, so the import fails after the installed pkg row
+    // has been written
+    spec.funcs.clear();
+    let pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+    let root_hash = pkg.hash().expect("get pkg hash").to_string();
+
+    let result = import_pkg_from_pkg(ctx, &pkg, None, true).await;
+    assert!(result.is_err());
+    assert!(InstalledPkg::find_by_hash(ctx, &root_hash)
+        .await
+        .expect("find installed pkg")
+        .is_some());
+
+    ctx.rollback().await.expect("rollback transactions");
+
+    assert!(InstalledPkg::find_by_hash(ctx, &root_hash)
+        .await
+        .expect("find installed pkg after rollback")
+        .is_none());
+}
+
+#[test]
+async fn test_reimported_components_not_attributed_to_temporary_pkg(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    Component::new(ctx, "mindless pleasures", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let pkg = export_workspace(ctx).await;
+    let root_hash = pkg.hash().expect("get pkg hash").to_string();
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import workspace");
+
+    let installed_pkg = InstalledPkg::find_by_hash(ctx, &root_hash)
+        .await
+        .expect("find installed pkg")
+        .expect("workspace pkg is installed");
+    assert_eq!("workspace", installed_pkg.name());
+
+    let temporary_pkgs = InstalledPkg::find_by_attr(ctx, "name", &TEMPORARY_EXPORTER_NAME)
+        .await
+        .expect("find installed pkgs by name");
+    assert!(temporary_pkgs.is_empty());
+}
+
+#[test]
+async fn test_import_component_with_malformed_resource_payload(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    Component::new(ctx, "gravity's rainbow", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let pkg = export_workspace(ctx).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    let resource_path = PropPath::new(["root", "resource"]).to_string();
+    let mut patched = false;
+    for component_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.components.iter_mut())
+        .filter(|component_spec| component_spec.name == "gravity's rainbow")
+    {
+        for attribute in component_spec
+            .attributes
+            .iter_mut()
+            .filter(|attribute| attribute.path.path() == resource_path)
+        {
+            attribute.implicit_value = Some(serde_json::json!("not an action run result"));
+            patched = true;
+        }
+    }
+    assert!(
+        patched,
+        "exported component should have a resource attribute"
+    );
+
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load patched spec");
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("malformed resource payload should not fail the import");
+
+    let mut found = false;
+    for component in Component::list(ctx).await.expect("list components") {
+        if component.name(ctx).await.expect("get component name") == "gravity's rainbow" {
+            let resource = component.resource(ctx).await.expect("get resource");
+            assert_eq!(None, resource.payload);
+            found = true;
+        }
+    }
+    assert!(found, "imported component exists");
+}
+
+#[test]
+async fn test_import_null_into_integer_prop_strict(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "fallout")
+        .await
+        .expect("get fallout")
+        .default_schema_variant_id()
+        .expect("fallout has a default variant");
+    Component::new(ctx, "new vegas", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let pkg = export_workspace(ctx).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    let rads_path = PropPath::new(["root", "domain", "rads"]);
+    let mut patched = false;
+    for component_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.components.iter_mut())
+        .filter(|component_spec| component_spec.name == "new vegas")
+    {
+        for attribute in component_spec
+            .attributes
+            .iter_mut()
+            .filter(|attribute| attribute.path.path() == rads_path.as_str())
+        {
+            attribute.value = Some(serde_json::Value::Null);
+            patched = true;
+        }
+    }
+    assert!(patched, "exported component should have a rads attribute");
+
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load patched spec");
+    let ImportResult {
+        skips: import_skips,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().strict_type_checking(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let attribute_skips: Vec<ImportAttributeSkip> = import_skips
+        .expect("workspace import returns skips")
+        .into_iter()
+        .flat_map(|skips| skips.attribute_skips)
+        .filter(|(name, _)| name == "new vegas")
+        .flat_map(|(_, skips)| skips)
+        .collect();
+    assert_eq!(
+        vec![ImportAttributeSkip::KindMismatch {
+            path: rads_path,
+            expected_kind: None,
+            variant_kind: PropKind::Integer,
+        }],
+        attribute_skips
+    );
+}
+
+#[test]
+async fn test_find_latest_installed_pkg_by_name(ctx: &DalContext) {
+    let mut root_hashes = vec![];
+    for version in ["2023-05-23", "2023-06-01"] {
+        let spec = PkgSpec::builder()
+            .name("V-2")
+            .version(version)
+            .created_by("Slothrop")
+            .build()
+            .expect("able to build package spec");
+        let pkg = SiPkg::load_from_spec(spec).expect("able to load from spec");
+        root_hashes.push(pkg.hash().expect("pkg has a hash").to_string());
+
+        import_pkg_from_pkg(ctx, &pkg, None, true)
+            .await
+            .expect("able to install pkg");
+    }
+
+    let installed_pkgs = InstalledPkg::find_by_name(ctx, "V-2")
+        .await
+        .expect("find installed pkgs by name");
+    assert_eq!(2, installed_pkgs.len());
+
+    let latest = InstalledPkg::find_latest_by_name(ctx, "V-2")
+        .await
+        .expect("find latest installed pkg by name")
+        .expect("latest installed pkg exists");
+    assert_eq!(root_hashes[1], latest.root_hash());
+
+    assert!(InstalledPkg::find_latest_by_name(ctx, "Schwarzgerät")
+        .await
+        .expect("find latest installed pkg by name")
+        .is_none());
+}
+
+#[test]
+async fn test_installed_pkg_variants_for_version(ctx: &DalContext) {
+    let pkg = upgrade_preview_pkg(&["imipolex"], Utc::now());
+    let ImportResult {
+        schema_variant_ids: mut variant_ids,
+        ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    assert!(!variant_ids.is_empty());
+
+    let mut installed_variant_ids = InstalledPkg::variants_for_version(ctx, "V-2", "0.1")
+        .await
+        .expect("list variants for version");
+    variant_ids.sort();
+    installed_variant_ids.sort();
+    assert_eq!(variant_ids, installed_variant_ids);
+
+    assert!(InstalledPkg::variants_for_version(ctx, "V-2", "0.2")
+        .await
+        .expect("list variants for version")
+        .is_empty());
+}
+
+#[test]
+async fn test_workspace_import_enqueues_create_actions(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+
+    let new_change_set = ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
+
+    Component::new(&cs_ctx, "vineland", schema_variant_id)
+        .await
+        .expect("create component without resource");
+    let (provisioned, _) = Component::new(&cs_ctx, "mason & dixon", schema_variant_id)
+        .await
+        .expect("create component with resource");
+    provisioned
+        .set_resource(
+            &cs_ctx,
+            ActionRunResult {
+                status: Some(ResourceStatus::Ok),
+                payload: Some(serde_json::json!({ "exists": true })),
+                message: None,
+                logs: vec![],
+                last_synced: Default::default(),
+            },
+        )
+        .await
+        .expect("set resource");
+
+    let pkg = export_workspace(ctx).await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .enqueue_create_actions(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let imported_change_set = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
+        .into_iter()
+        .find(|cs| cs.name == "cs1" && cs.pk != new_change_set.pk)
+        .expect("imported change set exists");
+    let imported_ctx =
+        ctx.clone_with_new_visibility(ctx.visibility().to_change_set(imported_change_set.pk));
+
+    let mut enqueued = vec![];
+    for action in Action::find_for_change_set(&imported_ctx)
+        .await
+        .expect("list actions")
+    {
+        let prototype = action
+            .prototype(&imported_ctx)
+            .await
+            .expect("get prototype");
+        assert_eq!(ActionKind::Create, *prototype.kind());
+
+        let component = action
+            .component(&imported_ctx)
+            .await
+            .expect("get component");
+        enqueued.push(component.name(&imported_ctx).await.expect("get name"));
+    }
+    assert_eq!(vec!["vineland".to_owned()], enqueued);
+}
+
+/// A module with a single schema whose variant has a qualification that always succeeds.
+fn qualified_pkg() -> SiPkg {
+    let qualification_code = "function qualification(_input) { return { result: 'success' }; }";
+    let qualification_func_spec = FuncSpec::builder()
+        .name("test:qualificationSuccess")
+        .unique_id("test:qualificationSuccess")
+        .data(
+            FuncSpecData::builder()
+                .name("test:qualificationSuccess")
+                .handler("qualification")
+                .code_plaintext(qualification_code)
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Qualification)
+                .build()
+                .expect("build qualification func data"),
+        )
+        .build()
+        .expect("build qualification func spec");
+
+    let spec = asset_pkg_spec("Gravity's Rainbow", |variant, data| {
+        data.component_type(ComponentType::Component);
+        variant
+            .domain_prop(
+                PropSpec::builder()
+                    .name("rocket")
+                    .kind(PropSpecKind::String)
+                    .build()
+                    .expect("build prop spec"),
+            )
+            .leaf_function(
+                LeafFunctionSpec::builder()
+                    .func_unique_id(&qualification_func_spec.unique_id)
+                    .leaf_kind(PkgLeafKind::Qualification)
+                    .inputs(vec![PkgLeafInputLocation::Domain])
+                    .build()
+                    .expect("build qualification leaf spec"),
+            );
+    })
+    .func(qualification_func_spec)
+    .func(
+        IntrinsicFunc::Identity
+            .to_spec()
+            .expect("create identity func spec"),
+    )
+    .build()
+    .expect("build pkg spec");
+
+    SiPkg::load_from_spec(spec).expect("load pkg from spec")
+}
+
+#[test]
+async fn test_workspace_import_runs_qualifications(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    import_pkg_from_pkg(ctx, &qualified_pkg(), None, true)
+        .await
+        .expect("able to import module");
+    let schema_variant_id = *Schema::find_by_name(ctx, "Gravity's Rainbow")
+        .await
+        .expect("get schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    Component::new(ctx, "rocket 00000", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let pkg = export_workspace(ctx).await;
+
+    let ImportResult { skips, .. } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_prefix("restored ")
+                .run_qualifications_after_import(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+    for skips in skips.unwrap_or_default() {
+        assert!(skips.qualification_errors.is_empty());
+    }
+
+    let mut restored = None;
+    for component in Component::list(ctx).await.expect("list components") {
+        if component.name(ctx).await.expect("get component name") == "restored rocket 00000" {
+            restored = Some(*component.id());
+        }
+    }
+    let component_id = restored.expect("component was restored");
+
+    let qualifications = Component::list_qualifications(ctx, component_id)
+        .await
+        .expect("list qualifications");
+    let qualification = qualifications
+        .iter()
+        .find(|qualification| qualification.qualification_name == "test:qualificationSuccess")
+        .expect("qualification was run");
+    assert_eq!(
+        Some(QualificationSubCheckStatus::Success),
+        qualification.result.as_ref().map(|result| result.status)
+    );
+}
+
+#[test]
+async fn test_conflicting_action_prototypes(ctx: &DalContext) {
+    let starfield_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    let fallout_variant_id = *Schema::find_by_name(ctx, "fallout")
+        .await
+        .expect("get fallout")
+        .default_schema_variant_id()
+        .expect("fallout has a default variant");
+
+    let func = Func::new(
+        ctx,
+        "test:launchRocket",
+        FuncBackendKind::JsAction,
+        FuncBackendResponseType::Action,
+    )
+    .await
+    .expect("create func");
+    let prototype = ActionPrototype::new(
+        ctx,
+        *func.id(),
+        ActionKind::Create,
+        ActionPrototypeContext {
+            schema_variant_id: starfield_variant_id,
+        },
+    )
+    .await
+    .expect("create action prototype");
+
+    let conflicting =
+        conflicting_action_prototypes(ctx, *func.id(), fallout_variant_id, ActionKind::Delete)
+            .await
+            .expect("find conflicting prototypes");
+    assert_eq!(
+        vec![*prototype.id()],
+        conflicting
+            .iter()
+            .map(|prototype| *prototype.id())
+            .collect::<Vec<_>>()
+    );
+
+    // Reusing the func for the same kind of action on another variant is fine
+    assert!(
+        conflicting_action_prototypes(ctx, *func.id(), fallout_variant_id, ActionKind::Create)
+            .await
+            .expect("find conflicting prototypes")
+            .is_empty()
+    );
+
+    // Changing the kind of the prototype on its own variant is an update, not a conflict
+    assert!(conflicting_action_prototypes(
+        ctx,
+        *func.id(),
+        starfield_variant_id,
+        ActionKind::Delete
+    )
+    .await
+    .expect("find conflicting prototypes")
+    .is_empty());
+}
+
+#[test]
+async fn test_import_reports_default_values(ctx: &DalContext) {
+    let pkg = SiPkg::load_from_spec(
+        asset_pkg_spec("A4", |variant, _| {
+            variant
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("fuel")
+                        .kind(PropSpecKind::String)
+                        .default_value(serde_json::json!("alcohol"))
+                        .build()
+                        .expect("build prop spec"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("stages")
+                        .kind(PropSpecKind::Number)
+                        .default_value(serde_json::json!(1))
+                        .build()
+                        .expect("build prop spec"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("guided")
+                        .kind(PropSpecKind::Boolean)
+                        .default_value(serde_json::json!(true))
+                        .build()
+                        .expect("build prop spec"),
+                )
+                .domain_prop(
+                    PropSpec::builder()
+                        .name("serial")
+                        .kind(PropSpecKind::String)
+                        .build()
+                        .expect("build prop spec"),
+                );
+        })
+        .build()
+        .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    let ImportResult { default_values, .. } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    assert_eq!(3, default_values.len());
+    for expected in [
+        (
+            PropPath::new(["root", "domain", "fuel"]),
+            serde_json::json!("alcohol"),
+        ),
+        (
+            PropPath::new(["root", "domain", "stages"]),
+            serde_json::json!(1),
+        ),
+        (
+            PropPath::new(["root", "domain", "guided"]),
+            serde_json::json!(true),
+        ),
+    ] {
+        assert!(
+            default_values.contains(&expected),
+            "missing default value {expected:?} in {default_values:?}"
+        );
+    }
+}
+
+#[test]
+async fn test_cancelled_import_rolls_back(ctx: &DalContext) {
+    let pkg = upgrade_preview_pkg(&["imipolex"], Utc::now());
+    let root_hash = pkg.hash().expect("get pkg hash").to_string();
+
+    let cancellation_token = CancellationToken::new();
+    cancellation_token.cancel();
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .cancellation_token(cancellation_token)
+                .build(),
+        ),
+        true,
+    )
+    .await;
+    assert!(
+        matches!(result, Err(PkgError::Cancelled)),
+        "expected the import to be cancelled, got {result:?}"
+    );
+
+    // The import stops before its first func, but has recorded the package by then
+    assert!(Func::find_by_name(ctx, "test:scaffoldFuncV2")
+        .await
+        .expect("find func")
+        .is_none());
+    assert!(InstalledPkg::find_by_hash(ctx, &root_hash)
+        .await
+        .expect("find installed pkg")
+        .is_some());
+
+    ctx.rollback().await.expect("rollback transactions");
+
+    assert!(InstalledPkg::find_by_hash(ctx, &root_hash)
+        .await
+        .expect("find installed pkg after rollback")
+        .is_none());
+    assert!(Schema::find_by_attr(ctx, "name", &"V-2")
+        .await
+        .expect("find schemas by name")
+        .is_empty());
+}
+
+#[test]
+async fn test_import_records_func_source_unique_id(ctx: &DalContext) {
+    let func_spec = FuncSpec::builder()
+        .name("test:slothropsErection")
+        .unique_id("01HQ1KMSYMFVRMBCVF4M8Y1C4A")
+        .data(
+            FuncSpecData::builder()
+                .name("test:slothropsErection")
+                .code_plaintext("function truth() { return true; }")
+                .handler("truth")
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Boolean)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build func spec");
+
+    let pkg = SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name("Jamf")
+            .version("0.1")
+            .created_by("Laszlo Jamf")
+            .func(func_spec)
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+
+    let func = Func::find_by_name(ctx, "test:slothropsErection")
+        .await
+        .expect("find func")
+        .expect("func was imported");
+    assert_eq!(Some("01HQ1KMSYMFVRMBCVF4M8Y1C4A"), func.source_unique_id());
+}
+
+fn mucker_maffick_pkg(version: &str, deleted: bool) -> SiPkg {
+    let func_spec = FuncSpec::builder()
+        .name("test:muckerMaffick")
+        .unique_id("01HQ1KMSYMFVRMBCVF4M8Y1C4B")
+        .deleted(deleted)
+        .data(
+            FuncSpecData::builder()
+                .name("test:muckerMaffick")
+                .code_plaintext("function truth() { return true; }")
+                .handler("truth")
+                .backend_kind(FuncSpecBackendKind::JsAttribute)
+                .response_type(FuncSpecBackendResponseType::Boolean)
+                .build()
+                .expect("build func data"),
+        )
+        .build()
+        .expect("build func spec");
+
+    SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name("Mucker-Maffick")
+            .version(version)
+            .created_by("Tyrone Slothrop")
+            .func(func_spec)
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec")
+}
+
+#[test]
+async fn test_import_deletes_installed_func_marked_deleted(ctx: &DalContext) {
+    let pkg = mucker_maffick_pkg("0.1", false);
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    let func = Func::find_by_name(ctx, "test:muckerMaffick")
+        .await
+        .expect("find func")
+        .expect("func was imported");
+    let installed_hash = pkg
+        .funcs()
+        .expect("get funcs")
+        .pop()
+        .expect("pkg has the func")
+        .hash()
+        .to_string();
+
+    import_pkg_from_pkg(ctx, &mucker_maffick_pkg("0.2", true), None, true)
+        .await
+        .expect("able to import pkg that deletes the func");
+
+    assert!(Func::get_by_id(ctx, func.id())
+        .await
+        .expect("get func")
+        .is_none());
+    assert!(InstalledPkgAsset::list_for_kind_and_hash(
+        ctx,
+        InstalledPkgAssetKind::Func,
+        &installed_hash
+    )
+    .await
+    .expect("list installed funcs")
+    .is_empty());
+
+    // The records of the deleted func are gone, so installing it again creates it anew
+    import_pkg_from_pkg(ctx, &mucker_maffick_pkg("0.3", false), None, true)
+        .await
+        .expect("able to import pkg again");
+    assert!(Func::find_by_name(ctx, "test:muckerMaffick")
+        .await
+        .expect("find func")
+        .is_some());
+}
+
+#[test]
+async fn test_import_pkg_returns_skips_for_missing_prop(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let schema_variant_id = *Schema::find_by_name(ctx, "fallout")
+        .await
+        .expect("get fallout")
+        .default_schema_variant_id()
+        .expect("fallout has a default variant");
+    Component::new(ctx, "the divide", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let pkg = export_workspace(ctx).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    let rads_path = PropPath::new(["root", "domain", "rads"]);
+    let missing_path = PropPath::new(["root", "domain", "lonesome road"]);
+    let mut patched = false;
+    for component_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.components.iter_mut())
+        .filter(|component_spec| component_spec.name == "the divide")
+    {
+        for attribute in component_spec
+            .attributes
+            .iter_mut()
+            .filter(|attribute| attribute.path.path() == rads_path.as_str())
+        {
+            attribute.path = AttributeValuePath::Prop {
+                path: missing_path.as_str().to_owned(),
+                key: None,
+                index: None,
+            };
+            patched = true;
+        }
+    }
+    assert!(patched, "exported component should have a rads attribute");
+
+    let pkg_dir = tempfile::tempdir().expect("create temp dir");
+    let pkg_file_path = pkg_dir.path().join("backup.sipkg");
+    let pkg_bytes = SiPkg::load_from_spec(spec)
+        .expect("able to load patched spec")
+        .write_to_bytes()
+        .expect("able to write pkg to bytes");
+    std::fs::write(&pkg_file_path, pkg_bytes).expect("write pkg to file");
+
+    let (_, import_skips) = import_pkg(ctx, &pkg_file_path, None, true)
+        .await
+        .expect("able to import workspace from file");
+
+    let import_skips = import_skips.expect("workspace import returns skips");
+    assert!(import_skips.iter().any(|skips| !skips.is_empty()));
+    let attribute_skips: Vec<ImportAttributeSkip> = import_skips
+        .into_iter()
+        .flat_map(|skips| skips.attribute_skips)
+        .filter(|(name, _)| name == "the divide")
+        .flat_map(|(_, skips)| skips)
+        .collect();
+    assert_eq!(
+        vec![ImportAttributeSkip::MissingProp { path: missing_path }],
+        attribute_skips
+    );
+}
+
+#[test]
+async fn test_import_pkg_refuses_unexpected_hash(ctx: &DalContext) {
+    let pkg = linked_pkg(
+        "https://example.com/vineland",
+        "https://example.com/thanatoids",
+    );
+    let actual_hash = pkg.hash().expect("get pkg hash").to_string();
+
+    let pkg_dir = tempfile::tempdir().expect("create temp dir");
+    let pkg_file_path = pkg_dir.path().join("vineland.sipkg");
+    std::fs::write(
+        &pkg_file_path,
+        pkg.write_to_bytes().expect("able to write pkg to bytes"),
+    )
+    .expect("write pkg to file");
+
+    match import_pkg(ctx, &pkg_file_path, Some("not-the-hash".to_owned()), true).await {
+        Err(PkgError::HashMismatch { expected, actual }) => {
+            assert_eq!("not-the-hash", expected);
+            assert_eq!(actual_hash, actual);
+        }
+        other => panic!("expected the import to be refused, got {other:?}"),
+    }
+    assert!(InstalledPkg::find_by_hash(ctx, &actual_hash)
+        .await
+        .expect("able to find installed pkg")
+        .is_none());
+    assert!(Schema::find_by_name(ctx, "Vineland").await.is_err());
+
+    import_pkg(ctx, &pkg_file_path, Some(actual_hash.clone()), true)
+        .await
+        .expect("able to import pkg with the expected hash");
+    assert!(InstalledPkg::find_by_hash(ctx, &actual_hash)
+        .await
+        .expect("able to find installed pkg")
+        .is_some());
+}
+
+#[test]
+async fn test_check_map_item_props_rejects_childless_map(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (schema_variant, root_prop) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("create schema variant");
+
+    check_map_item_props(ctx, *schema_variant.id())
+        .await
+        .expect("builtin map props all have an item prop");
+
+    Prop::new_without_ui_optionals(
+        ctx,
+        "tags",
+        PropKind::Map,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("create map prop");
+
+    match check_map_item_props(ctx, *schema_variant.id()).await {
+        Err(PkgError::MapPropItemPropCount(path, 0)) => {
+            assert_eq!("root/domain/tags", path);
+        }
+        other => panic!("expected a missing map item prop error, got {other:?}"),
+    }
+}
+
+async fn export_workspace_with_component(ctx: &DalContext, component_name: &str) -> SiPkg {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    Component::new(ctx, component_name, schema_variant_id)
+        .await
+        .expect("create component");
+
+    export_workspace(ctx).await
+}
+
+/// Exports the workspace with a component on head and a component with the same name in the
+/// "cs1" change set.
+async fn export_workspace_with_name_collision(ctx: &DalContext, component_name: &str) -> SiPkg {
+    let schema_variant_id = *Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield")
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    let change_set = ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+    Component::new(&cs_ctx, component_name, schema_variant_id)
+        .await
+        .expect("create change set component");
+
+    export_workspace_with_component(ctx, component_name).await
+}
+
+/// Lists the names of the components in the imported "cs1" change set.
+async fn imported_cs1_component_names(ctx: &DalContext) -> Vec<String> {
+    let change_set = ChangeSet::list_open(ctx)
+        .await
+        .expect("list open change sets")
+        .into_iter()
+        .find(|cs| cs.name == "cs1")
+        .expect("cs1 was imported");
+    let cs_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+
+    let mut names = vec![];
+    for component in Component::list(&cs_ctx).await.expect("list components") {
+        names.push(component.name(&cs_ctx).await.expect("get component name"));
+    }
+    names.sort();
+    names
+}
+
+#[test]
+async fn test_import_component_name_suffix_on_collision(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let pkg = export_workspace_with_name_collision(ctx, "web").await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_policy(ComponentNamePolicy::SuffixOnCollision)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let mut head_names = vec![];
+    for component in Component::list(ctx).await.expect("list components") {
+        head_names.push(component.name(ctx).await.expect("get component name"));
+    }
+    assert_eq!(vec!["web".to_owned()], head_names);
+    assert_eq!(
+        vec!["web".to_owned(), "web (2)".to_owned()],
+        imported_cs1_component_names(ctx).await
+    );
+}
+
+/// Exports the workspace with a fallout "vault" connected to a starfield "constellation".
+async fn export_workspace_with_edge(ctx: &DalContext) -> SiPkg {
+    let mut bagger = ComponentBagger::new();
+    let fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        fallout.node_id,
+        *output_socket.id(),
+        starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    export_workspace(ctx).await
+}
+
+#[test]
+async fn test_import_component_name_prefix(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let pkg = export_workspace_with_edge(ctx).await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_prefix("copy of ")
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    let mut prefixed = HashMap::new();
+    for component in Component::list(ctx).await.expect("list components") {
+        let name = component.name(ctx).await.expect("get component name");
+        if name.starts_with("copy of ") {
+            prefixed.insert(name, *component.id());
+        }
+    }
+    let mut names: Vec<&String> = prefixed.keys().collect();
+    names.sort();
+    assert_eq!(vec!["copy of constellation", "copy of vault"], names);
+
+    // The edge between the prefixed components was still resolved by unique id
+    let edges = Edge::list_for_component(ctx, prefixed["copy of constellation"])
+        .await
+        .expect("list edges for component");
+    assert_eq!(1, edges.len());
+    assert_eq!(prefixed["copy of vault"], edges[0].tail_component_id());
+}
+
+#[test]
+async fn test_import_component_name_prefix_is_not_applied_on_upgrade(ctx: &DalContext) {
+    let installed_pkg = upgrade_preview_pkg(&["imipolex"], Utc::now() - Duration::hours(1));
+    import_pkg_from_pkg(
+        ctx,
+        &installed_pkg,
+        Some(ImportOptions::builder().is_builtin(true).build()),
+        true,
+    )
+    .await
+    .expect("able to import builtin pkg");
+
+    let schema_variant_id = *Schema::find_by_name(ctx, "V-2")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    Component::new(ctx, "00000", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let upgrade_pkg = upgrade_preview_pkg(&["imipolex", "schwarzgerat"], Utc::now());
+    import_pkg_from_pkg(
+        ctx,
+        &upgrade_pkg,
+        Some(
+            ImportOptions::builder()
+                .is_builtin(true)
+                .component_name_prefix("copy of ")
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to upgrade builtin pkg");
+
+    // The component was moved to the upgraded variant under its own name
+    let schema = Schema::find_by_name(ctx, "V-2").await.expect("find schema");
+    let mut names = vec![];
+    for component in Component::list_for_schema(ctx, *schema.id())
+        .await
+        .expect("list components for schema")
+    {
+        names.push(component.name(ctx).await.expect("get component name"));
+    }
+    assert_eq!(vec!["00000".to_owned()], names);
+}
+
+/// The names of the components whose name starts with `prefix`, sorted.
+async fn component_names_with_prefix(ctx: &DalContext, prefix: &str) -> Vec<String> {
+    let mut names = vec![];
+    for component in Component::list(ctx).await.expect("list components") {
+        let name = component.name(ctx).await.expect("get component name");
+        if name.starts_with(prefix) {
+            names.push(name);
+        }
+    }
+    names.sort();
+    names
+}
+
+#[test]
+async fn test_import_only_filtered_components(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let pkg = export_workspace_with_edge(ctx).await;
+
+    let ImportResult { skips, .. } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_prefix("restored ")
+                .components(vec!["constellation".to_owned()])
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    assert_eq!(
+        vec!["restored constellation"],
+        component_names_with_prefix(ctx, "restored ").await
+    );
+
+    let edge_skips: Vec<ImportEdgeSkip> = skips
+        .expect("import has skips")
+        .into_iter()
+        .flat_map(|import_skips| import_skips.edge_skips)
+        .collect();
+    assert_eq!(1, edge_skips.len());
+    assert!(matches!(
+        edge_skips[0],
+        ImportEdgeSkip::FilteredComponent { .. }
+    ));
+}
+
+#[test]
+async fn test_import_skip_edges(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let pkg = export_workspace_with_edge(ctx).await;
+
+    import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_prefix("restored ")
+                .skip_edges(true)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    for component in Component::list(ctx).await.expect("list components") {
+        let name = component.name(ctx).await.expect("get component name");
+        if name.starts_with("restored ") {
+            assert!(Edge::list_for_component(ctx, *component.id())
+                .await
+                .expect("list edges for component")
+                .is_empty());
+        }
+    }
+    assert_eq!(
+        vec!["restored constellation", "restored vault"],
+        component_names_with_prefix(ctx, "restored ").await
+    );
+}
+
+#[test]
+async fn test_import_component_name_conflict_error(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let pkg = export_workspace_with_name_collision(ctx, "web").await;
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .component_name_policy(ComponentNamePolicy::Error)
+                .build(),
+        ),
+        true,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(PkgError::ComponentNameConflict(name)) if name == "web"
+    ));
+
+    // The import stopped at the change set component, so only the head component was created
+    assert_eq!(
+        vec!["web".to_owned()],
+        imported_cs1_component_names(ctx).await
+    );
+}
+
+/// Exports the workspace with a component whose backed up value carries a secret at
+/// "/root/secrets/api_key", as if the backup had been taken from a workspace with secrets.
+async fn export_workspace_with_component_secret(ctx: &DalContext, component_name: &str) -> SiPkg {
+    let pkg = export_workspace_with_component(ctx, component_name).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    let secrets_path = PropPath::new(["root", "secrets"]);
+    let name_path = PropPath::new(["root", "si", "name"]);
+    let mut patched = false;
+    for component_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.components.iter_mut())
+        .filter(|component_spec| component_spec.name == component_name)
+    {
+        if let Some(serde_json::Value::Object(root)) =
+            component_spec.attributes[0].implicit_value.as_mut()
+        {
+            root.insert(
+                "secrets".to_owned(),
+                serde_json::json!({ "api_key": "secret from another workspace" }),
+            );
+        }
+
+        let mut secret_attribute = component_spec
+            .attributes
+            .iter()
+            .find(|attribute| attribute.path.path() == name_path.as_str())
+            .expect("exported component should have a name attribute")
+            .clone();
+        secret_attribute.parent_path = Some(AttributeValuePath::Prop {
+            path: secrets_path.as_str().to_owned(),
+            key: None,
+            index: None,
+        });
+        secret_attribute.path = AttributeValuePath::Prop {
+            path: secrets_path
+                .join(&PropPath::new(["api_key"]))
+                .as_str()
+                .to_owned(),
+            key: None,
+            index: None,
+        };
+        secret_attribute.value = Some(serde_json::json!("secret from another workspace"));
+        secret_attribute.implicit_value = None;
+        component_spec.attributes.push(secret_attribute);
+        patched = true;
+    }
+    assert!(patched, "exported workspace should have the component");
+
+    SiPkg::load_from_spec(spec).expect("able to load patched spec")
+}
+
+#[test]
+async fn test_import_does_not_write_backed_up_secrets(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let pkg = export_workspace_with_component_secret(ctx, "jessica").await;
+
+    let ImportResult {
+        skips: import_skips,
+        ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import workspace");
+
+    let attribute_skips: Vec<ImportAttributeSkip> = import_skips
+        .expect("workspace import returns skips")
+        .into_iter()
+        .flat_map(|skips| skips.attribute_skips)
+        .filter(|(name, _)| name == "jessica")
+        .flat_map(|(_, skips)| skips)
+        .collect();
+    assert_eq!(
+        vec![ImportAttributeSkip::SecretNeedsReinjection {
+            path: PropPath::new(["root", "secrets", "api_key"]),
+        }],
+        attribute_skips
+    );
+
+    for component in Component::list(ctx).await.expect("list components") {
+        let view = ComponentView::new(ctx, *component.id())
+            .await
+            .expect("get component view");
+        assert_eq!(None, view.properties.pointer("/secrets/api_key"));
+    }
+}
+
+#[test]
+async fn test_import_skips_backed_up_secrets_silently(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let pkg = export_workspace_with_component_secret(ctx, "jessica").await;
+
+    let ImportResult {
+        skips: import_skips,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .secrets_policy(SecretsPolicy::Skip)
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    assert!(import_skips
+        .expect("workspace import returns skips")
+        .iter()
+        .all(|skips| skips.is_empty()));
+}
+
+#[test]
+async fn test_import_edge_with_remapped_socket_names(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let from_fallout = bagger.create_component(ctx, "vault", "fallout").await;
+    let to_starfield = bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+
+    let output_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationOutput,
+        from_fallout.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let input_socket = Socket::find_by_name_for_edge_kind_and_node(
+        ctx,
+        "bethesda",
+        SocketEdgeKind::ConfigurationInput,
+        to_starfield.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    Connection::new(
+        ctx,
+        from_fallout.node_id,
+        *output_socket.id(),
+        to_starfield.node_id,
+        *input_socket.id(),
+        EdgeKind::Configuration,
+    )
+    .await
+    .expect("could not create connection");
+
+    let pkg = export_workspace(ctx).await;
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+
+    // The package's author named the output socket differently, and the input socket with
+    // different casing
+    let mut patched = false;
+    for edge_spec in spec
+        .change_sets
+        .iter_mut()
+        .flat_map(|change_set| change_set.edges.iter_mut())
+        .filter(|edge_spec| edge_spec.from_socket_name == "bethesda")
+    {
+        edge_spec.from_socket_name = "Game Studio".to_owned();
+        edge_spec.to_socket_name = "Bethesda".to_owned();
+        patched = true;
+    }
+    assert!(patched, "exported workspace should have the edge");
+    let pkg = SiPkg::load_from_spec(spec).expect("able to load patched spec");
+
+    let ImportResult {
+        skips: import_skips,
+        ..
+    } = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(
+            ImportOptions::builder()
+                .socket_name_remap(HashMap::from([(
+                    "Game Studio".to_owned(),
+                    "bethesda".to_owned(),
+                )]))
+                .build(),
+        ),
+        true,
+    )
+    .await
+    .expect("able to import workspace");
+
+    assert!(import_skips
+        .expect("workspace import returns skips")
+        .iter()
+        .all(|skips| skips.edge_skips.is_empty()));
+
+    let mut imported_edge_count = 0;
+    for component in Component::list(ctx).await.expect("list components") {
+        if *component.id() == to_starfield.component_id
+            || component.name(ctx).await.expect("get component name") != "constellation"
+        {
+            continue;
+        }
+        imported_edge_count += Edge::list_for_component(ctx, *component.id())
+            .await
+            .expect("list edges for component")
+            .iter()
+            .filter(|edge| edge.kind() == &EdgeKind::Configuration)
+            .count();
+    }
+    assert_eq!(1, imported_edge_count);
+}
+
+#[test]
+async fn test_import_restores_aggregation_frame_with_members(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    let frame = bagger
+        .create_component(ctx, "shattered space", "starfield")
+        .await;
+    let members = [
+        bagger.create_component(ctx, "new vegas", "fallout").await,
+        bagger.create_component(ctx, "far harbor", "fallout").await,
+    ];
+
+    Component::get_by_id(ctx, &frame.component_id)
+        .await
+        .expect("could not get frame")
+        .expect("frame not found")
+        .set_type(ctx, ComponentType::AggregationFrame)
+        .await
+        .expect("could not set frame type");
+    for member in &members {
+        Connection::new_to_parent(ctx, member.node_id, frame.node_id)
+            .await
+            .expect("could not put member into frame");
+    }
+
+    let pkg = export_workspace(ctx).await;
+
+    import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import workspace");
+
+    let mut imported_frame = None;
+    let mut imported_members = vec![];
+    for component in Component::list(ctx).await.expect("list components") {
+        if *component.id() == frame.component_id
+            || members
+                .iter()
+                .any(|member| *component.id() == member.component_id)
+        {
+            continue;
+        }
+        match component
+            .name(ctx)
+            .await
+            .expect("get component name")
+            .as_str()
+        {
+            "shattered space" => imported_frame = Some(component),
+            "new vegas" | "far harbor" => imported_members.push(component),
+            _ => {}
+        }
+    }
+    let imported_frame = imported_frame.expect("frame should be imported");
+    assert_eq!(
+        ComponentType::AggregationFrame,
+        imported_frame.get_type(ctx).await.expect("get frame type")
+    );
+    assert_eq!(2, imported_members.len());
 
-    let code = "function truth() { return true; }";
-    let code_base64 = general_purpose::STANDARD_NO_PAD.encode(code.as_bytes());
-    let identity_func_spec = IntrinsicFunc::Identity
-        .to_spec()
-        .expect("create identity func spec");
+    for member in imported_members {
+        assert_eq!(
+            ComponentType::Component,
+            member.get_type(ctx).await.expect("get member type")
+        );
+        assert_eq!(
+            Some(*imported_frame.id()),
+            Edge::get_parent_for_component(ctx, *member.id())
+                .await
+                .expect("get parent of member")
+        );
 
-    let func_spec = FuncSpec::builder()
-        .name("si:truthy")
-        .unique_id("si:truthy")
-        .data(
-            FuncSpecData::builder()
-                .name("si:truthy")
-                .display_name("truth")
-                .description("it returns true")
-                .handler("truth")
-                .code_base64(&code_base64)
-                .backend_kind(FuncSpecBackendKind::JsAttribute)
-                .response_type(FuncSpecBackendResponseType::Boolean)
-                .hidden(false)
-                .build()
-                .expect("truth func data"),
-        )
-        .build()
-        .expect("build func spec");
+        // The frame's sockets are linked to the member directly, not through the member's
+        // own sockets like configuration frames do
+        let aggregation_edges = Edge::list_for_component(ctx, *member.id())
+            .await
+            .expect("list edges for member")
+            .into_iter()
+            .filter(|edge| {
+                edge.kind() == &EdgeKind::Configuration
+                    && edge.head_socket_id() == edge.tail_socket_id()
+            })
+            .count();
+        assert!(aggregation_edges > 0);
+    }
+}
 
-    let spec_a = PkgSpec::builder()
-        .name("The White Visitation")
-        .version("0.1")
-        .created_by("Pirate Prentice")
-        .schema(schema_a.clone())
-        .func(func_spec)
-        .func(identity_func_spec.clone())
-        .func(qualification_func_spec.clone())
-        .func(scaffold_func_spec_a.clone())
-        .build()
-        .expect("able to build package spec");
+#[test]
+async fn test_canonical_spec_survives_export_and_import(ctx: &DalContext) {
+    let starfield = Schema::find_by_name(ctx, "starfield")
+        .await
+        .expect("get starfield");
+    let original_variant_id = *starfield
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    let original_spec = SchemaVariant::canonical_spec(ctx, original_variant_id)
+        .await
+        .expect("able to get canonical spec");
+    assert!(original_spec.unique_id.is_none());
 
-    let pkg_a = SiPkg::load_from_spec(spec_a).expect("able to load from spec");
+    let mut exporter = PkgExporter::new_module_exporter(
+        "module",
+        "test-version",
+        None::<String>,
+        "sally@systeminit.com",
+        vec![*starfield.id()],
+    );
+    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
+    let pkg = SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes");
 
-    let spec_b = PkgSpec::builder()
-        .name("The Kenosha Kid")
-        .version("0.1")
-        .created_by("Pointsman")
-        .func(identity_func_spec.clone())
-        .schema(schema_a)
-        .schema(schema_b)
-        .func(scaffold_func_spec_a)
-        .func(scaffold_func_spec_b)
-        .func(qualification_func_spec)
-        .build()
-        .expect("able to build package spec");
+    let new_change_set = ChangeSet::new(ctx, "cs1", None)
+        .await
+        .expect("can create change set");
+    let new_ctx = ctx.clone_with_new_visibility(ctx.visibility().to_change_set(new_change_set.pk));
+    let ImportResult {
+        schema_variant_ids: variant_ids,
+        ..
+    } = import_pkg_from_pkg(&new_ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    let imported_variant_id = variant_ids
+        .into_iter()
+        .find(|id| *id != original_variant_id)
+        .expect("import created a new variant");
 
-    let pkg_b = SiPkg::load_from_spec(spec_b).expect("able to load pkg from spec");
+    let imported_spec = SchemaVariant::canonical_spec(&new_ctx, imported_variant_id)
+        .await
+        .expect("able to get canonical spec");
+    assert_eq!(original_spec, imported_spec);
 
-    import_pkg_from_pkg(ctx, &pkg_a, None, true)
+    let mut incoming_spec = pkg
+        .to_spec()
         .await
-        .expect("able to install pkg");
+        .expect("can convert to spec")
+        .schemas
+        .pop()
+        .expect("package has a schema")
+        .variants
+        .pop()
+        .expect("schema has a variant");
+    incoming_spec.canonicalize();
+    assert_eq!(incoming_spec, imported_spec);
+}
+
+#[test]
+async fn test_import_rejects_backup_with_too_many_components(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    bagger.create_component(ctx, "vault", "fallout").await;
+    bagger
+        .create_component(ctx, "constellation", "starfield")
+        .await;
+
+    let pkg = export_workspace(ctx).await;
+
+    let components_before = Component::list(ctx).await.expect("list components").len();
+
+    let result = import_pkg_from_pkg(
+        ctx,
+        &pkg,
+        Some(ImportOptions::builder().max_components(1).build()),
+        true,
+    )
+    .await;
 
-    // We should refuse to install the same package twice
-    let second_import_result = import_pkg_from_pkg(ctx, &pkg_a, None, true).await;
     assert!(matches!(
-        second_import_result,
-        Err(PkgError::PackageAlreadyInstalled(_))
+        result,
+        Err(PkgError::BackupTooLarge { components, limit: 1 }) if components >= 2
     ));
+    assert_eq!(
+        components_before,
+        Component::list(ctx).await.expect("list components").len()
+    );
+}
 
-    // for now just assert that the installed package records are written. we should also verify
-    // the structure of the installed schemas but punting on that for the moment
-    let pkg_a_root_hash = pkg_a.hash().expect("pkg a has a hash").to_string();
-    let installed_pkgs = InstalledPkg::find_by_attr(ctx, "root_hash", &pkg_a_root_hash)
+#[test]
+async fn test_import_with_deferred_finalize_matches_inline(ctx: &DalContext) {
+    let starfield = Schema::find_by_name(ctx, "starfield")
         .await
-        .expect("find by attr");
-    assert_eq!(1, installed_pkgs.len());
-    let installed_pkg_a = installed_pkgs.get(0).expect("pkg should be there");
-
-    assert_eq!("The White Visitation", installed_pkg_a.name());
+        .expect("get starfield");
+    let mut exporter = PkgExporter::new_module_exporter(
+        "module",
+        "test-version",
+        None::<String>,
+        "sally@systeminit.com",
+        vec![*starfield.id()],
+    );
+    let package_bytes = exporter.export_as_bytes(ctx).await.expect("able to export");
+    let pkg = SiPkg::load_from_bytes(package_bytes).expect("able to load from bytes");
 
-    let pkg_a_ipas = InstalledPkgAsset::list_for_installed_pkg_id(ctx, *installed_pkg_a.id())
+    let mut imported = vec![];
+    for (change_set_name, defer_finalize) in [("inline", false), ("deferred", true)] {
+        let change_set = ChangeSet::new(ctx, change_set_name, None)
+            .await
+            .expect("can create change set");
+        let change_set_ctx =
+            ctx.clone_with_new_visibility(ctx.visibility().to_change_set(change_set.pk));
+        let ImportResult {
+            schema_variant_ids: variant_ids,
+            mut default_values,
+            ..
+        } = import_pkg_from_pkg(
+            &change_set_ctx,
+            &pkg,
+            Some(
+                ImportOptions::builder()
+                    .no_record(true)
+                    .defer_finalize(defer_finalize)
+                    .build(),
+            ),
+            true,
+        )
         .await
-        .expect("able to fetch installed pkgs for pkg a");
+        .expect("able to import pkg");
+        let variant_id = variant_ids
+            .into_iter()
+            .next()
+            .expect("import created a variant");
+        default_values.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
 
-    for ipa in pkg_a_ipas {
-        match ipa.asset_kind() {
-            InstalledPkgAssetKind::Schema => {
-                let typed: InstalledPkgAssetTyped =
-                    ipa.as_installed_schema().expect("get schema ipa type");
-                match typed {
-                    InstalledPkgAssetTyped::Schema { id, .. } => {
-                        let schema = Schema::get_by_id(ctx, &id)
-                            .await
-                            .expect("able to get schema")
-                            .expect("schema is there");
+        imported.push((
+            SchemaVariant::canonical_spec(&change_set_ctx, variant_id)
+                .await
+                .expect("able to get canonical spec"),
+            default_values,
+        ));
+    }
 
-                        assert_eq!("Tyrone Slothrop", schema.name())
-                    }
-                    _ => unreachable!(),
-                }
-            }
-            InstalledPkgAssetKind::SchemaVariant => {
-                let typed: InstalledPkgAssetTyped = ipa
-                    .as_installed_schema_variant()
-                    .expect("get schema ipa type");
-                match typed {
-                    InstalledPkgAssetTyped::SchemaVariant { id, .. } => {
-                        let schema_variant = SchemaVariant::get_by_id(ctx, &id)
-                            .await
-                            .expect("able to get schema variant")
-                            .expect("schema variant is there");
+    let deferred = imported.pop().expect("deferred import");
+    let inline = imported.pop().expect("inline import");
+    assert_eq!(inline, deferred);
+}
 
-                        assert_eq!("Pig Bodine", schema_variant.name());
+#[test]
+async fn test_import_with_deferred_dependent_updates_matches_eager(
+    DalContextHeadRef(ctx): DalContextHeadRef<'_>,
+) {
+    let mut bagger = ComponentBagger::new();
+    for name in ["vault 13", "vault 101", "vault 111"] {
+        bagger.create_component(ctx, name, "fallout").await;
+    }
+    for name in ["constellation", "frontier", "lodge"] {
+        bagger.create_component(ctx, name, "starfield").await;
+    }
 
-                        let qualifications = SchemaVariant::find_leaf_item_functions(
-                            ctx,
-                            *schema_variant.id(),
-                            LeafKind::Qualification,
-                        )
-                        .await
-                        .expect("able to get qualification funcs");
+    let pkg = export_workspace(ctx).await;
 
-                        assert_eq!(1, qualifications.len())
-                    }
-                    _ => unreachable!(),
-                }
-            }
-            InstalledPkgAssetKind::SchemaVariantDefinition => {}
-            InstalledPkgAssetKind::Func => {
-                let typed: InstalledPkgAssetTyped =
-                    ipa.as_installed_func().expect("get func ipa typed");
-                match typed {
-                    InstalledPkgAssetTyped::Func { id, .. } => {
-                        let _func = Func::get_by_id(ctx, &id)
-                            .await
-                            .expect("able to get func")
-                            .expect("func is there");
-                    }
-                    _ => unreachable!(),
-                }
-            }
+    let mut restored = vec![];
+    for defer_dependent_updates in [false, true] {
+        import_pkg_from_pkg(
+            ctx,
+            &pkg,
+            Some(
+                ImportOptions::builder()
+                    .defer_dependent_updates(defer_dependent_updates)
+                    .build(),
+            ),
+            true,
+        )
+        .await
+        .expect("able to import workspace");
+
+        ctx.blocking_commit()
+            .await
+            .expect("run dependent values updates");
+
+        let mut properties = vec![];
+        for component in Component::list(ctx).await.expect("list components") {
+            let view = ComponentView::new(ctx, *component.id())
+                .await
+                .expect("get component view");
+            properties.push((
+                component.name(ctx).await.expect("get component name"),
+                view.properties,
+            ));
         }
+        properties.sort_by(|a, b| a.0.cmp(&b.0));
+        restored.push(properties);
     }
 
-    import_pkg_from_pkg(ctx, &pkg_b, None, true)
+    let deferred = restored.pop().expect("deferred import");
+    let eager = restored.pop().expect("eager import");
+    assert_eq!(6, eager.len());
+    assert_eq!(eager, deferred);
+}
+
+#[test]
+async fn test_import_positions_into_named_layout(DalContextHeadRef(ctx): DalContextHeadRef<'_>) {
+    let bag = ComponentBagger::new()
+        .create_component(ctx, "vault 13", "fallout")
+        .await;
+    bag.node(ctx)
         .await
-        .expect("install pkg b");
+        .set_geometry(ctx, "100", "200", Some("500"), Some("250"))
+        .await
+        .expect("able to set geometry");
 
-    let installed_pkgs = InstalledPkg::find_by_attr(
+    let pkg = export_workspace(ctx).await;
+
+    import_pkg_from_pkg(
         ctx,
-        "root_hash",
-        &pkg_b.hash().expect("get pkg b hash").to_string(),
+        &pkg,
+        Some(ImportOptions::builder().target_layout("by region").build()),
+        true,
     )
     .await
-    .expect("find by attr");
-    assert_eq!(1, installed_pkgs.len());
-    let installed_pkg_b = installed_pkgs.get(0).expect("pkg should be there");
+    .expect("able to import workspace");
 
-    assert_eq!("The Kenosha Kid", installed_pkg_b.name());
+    let mut restored = None;
+    for node in Node::list(ctx).await.expect("list nodes") {
+        let component = node
+            .component(ctx)
+            .await
+            .expect("get component for node")
+            .expect("node has a component");
+        if component.name(ctx).await.expect("get component name") == "vault 13" {
+            restored = Some(node);
+        }
+    }
+    let node = restored.expect("component was restored");
 
-    let _pkg_b_ipas = InstalledPkgAsset::list_for_installed_pkg_id(ctx, *installed_pkg_b.id())
+    assert_eq!(
+        Some(&NodeGeometry {
+            x: "100".to_owned(),
+            y: "200".to_owned(),
+            width: Some("500".to_owned()),
+            height: Some("250".to_owned()),
+        }),
+        node.geometry_for_layout("by region")
+    );
+    assert_eq!(1, node.layouts().len());
+    assert_ne!("100", node.x());
+}
+
+#[test]
+async fn test_verify_installed_pkg_integrity_flags_deleted_func(ctx: &DalContext) {
+    let pkg = linked_pkg(
+        "https://example.com/vineland",
+        "https://example.com/thanatoids",
+    );
+    let ImportResult {
+        installed_pkg_id, ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
         .await
-        .expect("able to fetch installed pkgs for pkg a");
+        .expect("able to import pkg");
+    let installed_pkg_id = installed_pkg_id.expect("import recorded an installed pkg");
 
-    // Ensure we did not install the schema that is in both packages twice
-    let schemas = Schema::find_by_attr(ctx, "name", &"Tyrone Slothrop".to_string())
+    let report = verify_installed_pkg_integrity(ctx, installed_pkg_id)
         .await
-        .expect("get tyrones");
-    assert_eq!(1, schemas.len());
-    let schema_variants = SchemaVariant::find_by_attr(ctx, "name", &"Pig Bodine".to_string())
+        .expect("able to verify integrity");
+    assert!(report.is_healthy());
+    assert!(report.checked_assets > 0);
+
+    let mut func = Func::find_by_name(ctx, "test:scaffoldFuncVineland")
         .await
-        .expect("get bodines");
-    assert_eq!(1, schema_variants.len());
+        .expect("find func")
+        .expect("func exists");
+    func.delete_by_id(ctx).await.expect("able to delete func");
 
-    // Ensure the new schemas were installed
-    let schemas = Schema::find_by_attr(ctx, "name", &"Roger Mexico".to_string())
+    let report = verify_installed_pkg_integrity(ctx, installed_pkg_id)
         .await
-        .expect("roger roger roger roger");
-    assert_eq!(1, schemas.len());
-    let mut schema_variants =
-        SchemaVariant::find_by_attr(ctx, "name", &"The Light Bulb Conspiracy".to_string())
-            .await
-            .expect("above your head like you had an idea");
-    assert_eq!(1, schema_variants.len());
+        .expect("able to verify integrity");
+    assert!(!report.is_healthy());
+    assert_eq!(1, report.dangling_assets.len());
+    assert!(matches!(
+        report.dangling_assets.first(),
+        Some(InstalledPkgAssetTyped::Func { id, .. }) if id == func.id()
+    ));
+}
 
-    let light_bulb = schema_variants
-        .pop()
-        .expect("able to get the light bulb conspiracy");
+#[test]
+async fn test_remove_pkg(ctx: &DalContext) {
+    let pkg = linked_pkg(
+        "https://example.com/vineland",
+        "https://example.com/thanatoids",
+    );
+    let ImportResult {
+        installed_pkg_id, ..
+    } = import_pkg_from_pkg(ctx, &pkg, None, true)
+        .await
+        .expect("able to import pkg");
+    let installed_pkg_id = installed_pkg_id.expect("import recorded an installed pkg");
 
-    let _ac_input = InternalProvider::find_explicit_for_schema_variant_and_name(
-        ctx,
-        *light_bulb.id(),
-        "AC Power",
-    )
-    .await
-    .expect("able to search for ac input")
-    .expect("able to find ac input");
+    // A second package that installs the same asset func and nothing else
+    let mut spec = pkg.to_spec().await.expect("convert pkg to spec");
+    spec.name = "vineland funcs".to_owned();
+    spec.schemas.clear();
+    let funcs_pkg = SiPkg::load_from_spec(spec).expect("load pkg from spec");
+    let ImportResult {
+        installed_pkg_id: funcs_installed_pkg_id,
+        ..
+    } = import_pkg_from_pkg(ctx, &funcs_pkg, None, true)
+        .await
+        .expect("able to import funcs pkg");
+    let funcs_installed_pkg_id = funcs_installed_pkg_id.expect("import recorded an installed pkg");
+
+    let func_id = *Func::find_by_name(ctx, "test:scaffoldFuncVineland")
+        .await
+        .expect("find func")
+        .expect("func exists")
+        .id();
+    let schema_variant_id = *Schema::find_by_name(ctx, "Vineland")
+        .await
+        .expect("find schema")
+        .default_schema_variant_id()
+        .expect("schema has a default variant");
+    let (component, _) = Component::new(ctx, "Zoyd", schema_variant_id)
+        .await
+        .expect("create component");
+
+    let result = remove_pkg(ctx, installed_pkg_id, false).await;
+    assert!(matches!(
+        result,
+        Err(PkgError::InstalledPkgInUse(id, components))
+            if id == installed_pkg_id && components == vec![*component.id()]
+    ));
+
+    let summary = remove_pkg(ctx, installed_pkg_id, true)
+        .await
+        .expect("able to force removal");
+    assert_eq!(vec![*component.id()], summary.removed_components);
+    assert_eq!(vec![schema_variant_id], summary.removed_schema_variants);
+    assert_eq!(1, summary.removed_schemas.len());
+    assert!(summary.removed_funcs.is_empty());
+    assert_eq!(vec![func_id], summary.retained_funcs);
+
+    assert!(InstalledPkg::get_by_id(ctx, &installed_pkg_id)
+        .await
+        .expect("get installed pkg")
+        .is_none());
+    assert!(Schema::find_by_attr(ctx, "name", &"Vineland".to_string())
+        .await
+        .expect("find schemas")
+        .is_empty());
+    assert!(Func::get_by_id(ctx, &func_id)
+        .await
+        .expect("get func")
+        .is_some());
+
+    // Once nothing else installed it, the func goes with the last package
+    let summary = remove_pkg(ctx, funcs_installed_pkg_id, false)
+        .await
+        .expect("able to remove funcs pkg");
+    assert_eq!(vec![func_id], summary.removed_funcs);
+    assert!(Func::get_by_id(ctx, &func_id)
+        .await
+        .expect("get func")
+        .is_none());
 }
@@ -561,3 +561,68 @@ async fn intra_component_custom_func_update_to_external_provider(ctx: &DalContex
             .expect("get value for external provider av")
     );
 }
+
+#[test]
+async fn find_or_create_for_prop_added_after_finalize(ctx: &DalContext) {
+    let mut schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = create_schema_variant_with_root(ctx, *schema.id()).await;
+    schema
+        .set_default_schema_variant_id(ctx, Some(*schema_variant.id()))
+        .await
+        .expect("cannot set default schema variant");
+    let schema_variant_id = *schema_variant.id();
+
+    let map_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "map",
+        PropKind::Map,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await;
+    let map_item_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "item",
+        PropKind::String,
+        schema_variant_id,
+        Some(*map_prop.id()),
+    )
+    .await;
+
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize SchemaVariant");
+
+    // Added once the implicit internal providers of the variant have been created
+    let late_prop = dal_test::test_harness::create_prop_without_ui_optionals(
+        ctx,
+        "late",
+        PropKind::String,
+        schema_variant_id,
+        Some(root_prop.domain_prop_id),
+    )
+    .await;
+    assert!(InternalProvider::find_for_prop(ctx, *late_prop.id())
+        .await
+        .expect("could not find internal provider")
+        .is_none());
+
+    let created = InternalProvider::find_or_create_for_prop(ctx, *late_prop.id())
+        .await
+        .expect("could not find or create internal provider")
+        .expect("internal provider was created");
+    assert_eq!(*late_prop.id(), *created.prop_id());
+    let found = InternalProvider::find_or_create_for_prop(ctx, *late_prop.id())
+        .await
+        .expect("could not find or create internal provider")
+        .expect("internal provider was found");
+    assert_eq!(created.id(), found.id());
+
+    assert!(
+        InternalProvider::find_or_create_for_prop(ctx, *map_item_prop.id())
+            .await
+            .expect("could not find or create internal provider")
+            .is_none()
+    );
+}
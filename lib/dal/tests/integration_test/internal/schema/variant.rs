@@ -1,9 +1,20 @@
 use dal::{
-    schema::{variant::leaves::LeafKind, SchemaVariant},
-    DalContext, InternalProvider, Prop, PropId, RootPropChild, Schema, StandardModel,
+    authentication_prototype::{AuthenticationPrototype, AuthenticationPrototypeContext},
+    schema::{
+        variant::leaves::{LeafInputLocation, LeafKind},
+        SchemaVariant,
+    },
+    socket::SocketEdgeKind,
+    ActionKind, ActionPrototype, ActionPrototypeContext, AttributeReadContext, AttributeValue,
+    DalContext, Func, FuncBackendKind, FuncBackendResponseType, InternalProvider, Prop, PropId,
+    PropKind, RootPropChild, Schema, StandardModel,
 };
 use dal_test::{test, test_harness::create_schema};
 use pretty_assertions_sorted::assert_eq;
+use si_pkg::{
+    PkgSpec, SchemaSpec, SchemaVariantSpec, SiPkg, SocketSpec, SocketSpecArity, SocketSpecData,
+    SocketSpecKind,
+};
 
 #[test]
 async fn new(ctx: &DalContext) {
@@ -248,3 +259,541 @@ async fn list_implicit_internal_providers_for_root_children(ctx: &DalContext) {
         );
     }
 }
+
+#[test]
+async fn sockets_with_providers(ctx: &DalContext) {
+    for (schema_name, socket_name, expected_edge_kind) in [
+        ("starfield", "bethesda", SocketEdgeKind::ConfigurationInput),
+        ("fallout", "bethesda", SocketEdgeKind::ConfigurationOutput),
+    ] {
+        let schema_variant = Schema::find_by_name(ctx, schema_name)
+            .await
+            .expect("could not find schema")
+            .default_variant(ctx)
+            .await
+            .expect("could not get default variant");
+
+        let sockets_with_providers =
+            SchemaVariant::sockets_with_providers(ctx, *schema_variant.id())
+                .await
+                .expect("could not list sockets with providers");
+
+        for (socket, internal_provider, external_provider) in &sockets_with_providers {
+            match socket.edge_kind() {
+                SocketEdgeKind::ConfigurationInput => {
+                    assert!(internal_provider.is_some());
+                    assert!(external_provider.is_none());
+                }
+                SocketEdgeKind::ConfigurationOutput => {
+                    assert!(internal_provider.is_none());
+                    assert!(external_provider.is_some());
+                }
+            }
+        }
+
+        let (socket, _, _) = sockets_with_providers
+            .iter()
+            .find(|(socket, _, _)| socket.name() == socket_name)
+            .expect("could not find socket");
+        assert_eq!(&expected_edge_kind, socket.edge_kind());
+    }
+}
+
+#[test]
+async fn create_sockets_batch(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (variant, _) = SchemaVariant::new(ctx, *schema.id(), "ringo")
+        .await
+        .expect("cannot create schema variant");
+
+    let socket_spec = |name: &str, kind: SocketSpecKind| {
+        SocketSpec::builder()
+            .name(name)
+            .data(
+                SocketSpecData::builder()
+                    .name(name)
+                    .kind(kind)
+                    .arity(SocketSpecArity::One)
+                    .connection_annotations(
+                        serde_json::to_string(&vec![name]).expect("serialize annotations"),
+                    )
+                    .build()
+                    .expect("build socket data"),
+            )
+            .build()
+            .expect("build socket spec")
+    };
+    let pkg = SiPkg::load_from_spec(
+        PkgSpec::builder()
+            .name("beatles")
+            .version("0.1")
+            .created_by("george")
+            .schema(
+                SchemaSpec::builder()
+                    .name("beatles")
+                    .variant(
+                        SchemaVariantSpec::builder()
+                            .name("ringo")
+                            .socket(socket_spec("drums", SocketSpecKind::Input))
+                            .socket(socket_spec("sticks", SocketSpecKind::Input))
+                            .socket(socket_spec("beat", SocketSpecKind::Output))
+                            .build()
+                            .expect("build variant spec"),
+                    )
+                    .build()
+                    .expect("build schema spec"),
+            )
+            .build()
+            .expect("build pkg spec"),
+    )
+    .expect("load pkg from spec");
+    let socket_data: Vec<_> = pkg
+        .schemas()
+        .expect("get schemas")
+        .pop()
+        .expect("pkg has a schema")
+        .variants()
+        .expect("get variants")
+        .pop()
+        .expect("schema has a variant")
+        .sockets()
+        .expect("get sockets")
+        .iter()
+        .map(|socket| socket.data().expect("socket has data").to_owned())
+        .collect();
+
+    let sockets =
+        SchemaVariant::create_sockets_batch(ctx, *variant.id(), *schema.id(), &socket_data)
+            .await
+            .expect("create sockets");
+
+    let created: Vec<(String, SocketEdgeKind, bool, bool)> = sockets
+        .iter()
+        .map(|(socket, internal_provider, external_provider)| {
+            (
+                socket.name().to_owned(),
+                *socket.edge_kind(),
+                internal_provider.is_some(),
+                external_provider.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                "drums".to_owned(),
+                SocketEdgeKind::ConfigurationInput,
+                true,
+                false
+            ),
+            (
+                "sticks".to_owned(),
+                SocketEdgeKind::ConfigurationInput,
+                true,
+                false
+            ),
+            (
+                "beat".to_owned(),
+                SocketEdgeKind::ConfigurationOutput,
+                false,
+                true
+            ),
+        ],
+        created
+    );
+
+    assert!(
+        SchemaVariant::create_sockets_batch(ctx, *variant.id(), *schema.id(), &[])
+            .await
+            .expect("create no sockets")
+            .is_empty()
+    );
+}
+
+#[test]
+async fn export_json_schema(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+
+    Prop::new(
+        ctx,
+        "album",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+        None,
+        Some("the album title".to_string()),
+        Some(r#"{"type":"string","flags":{"presence":"required"}}"#.to_string()),
+    )
+    .await
+    .expect("could not create prop");
+    let year_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "year",
+        PropKind::Integer,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    let tracks_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "tracks",
+        PropKind::Array,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new_without_ui_optionals(
+        ctx,
+        "track",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*tracks_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    let credits_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "credits",
+        PropKind::Map,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new_without_ui_optionals(
+        ctx,
+        "credit",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*credits_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+    year_prop
+        .set_default_value(ctx, 1969)
+        .await
+        .expect("could not set default value");
+
+    let json_schema = SchemaVariant::export_json_schema(ctx, *schema_variant.id())
+        .await
+        .expect("could not export json schema");
+
+    assert_eq!(
+        Some(&serde_json::json!(["album"])),
+        json_schema.get("required")
+    );
+    assert_eq!(
+        Some(&serde_json::json!("the album title")),
+        json_schema.pointer("/properties/album/description")
+    );
+    assert_eq!(
+        Some(&serde_json::json!(1969)),
+        json_schema.pointer("/properties/year/default")
+    );
+
+    let compiled = jsonschema::JSONSchema::compile(&json_schema).expect("invalid json schema");
+    assert!(compiled.is_valid(&serde_json::json!({
+        "album": "Abbey Road",
+        "year": 1969,
+        "tracks": ["Come Together", "Something"],
+        "credits": { "drums": "Ringo Starr" },
+    })));
+    assert!(!compiled.is_valid(&serde_json::json!({
+        "year": "1969",
+        "tracks": "Come Together",
+    })));
+}
+
+#[test]
+async fn build_empty_domain_skeleton(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, root_prop) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+
+    let album_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "album",
+        PropKind::Object,
+        *schema_variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new_without_ui_optionals(
+        ctx,
+        "title",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*album_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    let tracks_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "tracks",
+        PropKind::Array,
+        *schema_variant.id(),
+        Some(*album_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new_without_ui_optionals(
+        ctx,
+        "track",
+        PropKind::Object,
+        *schema_variant.id(),
+        Some(*tracks_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    let credits_prop = Prop::new_without_ui_optionals(
+        ctx,
+        "credits",
+        PropKind::Map,
+        *schema_variant.id(),
+        Some(*album_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    Prop::new_without_ui_optionals(
+        ctx,
+        "credit",
+        PropKind::String,
+        *schema_variant.id(),
+        Some(*credits_prop.id()),
+    )
+    .await
+    .expect("could not create prop");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+
+    let skeleton = SchemaVariant::build_empty_domain_skeleton(ctx, *schema_variant.id())
+        .await
+        .expect("could not build skeleton");
+
+    assert_eq!(
+        Some(&serde_json::json!({
+            "album": {
+                "title": null,
+                "tracks": [],
+                "credits": {},
+            },
+        })),
+        skeleton.get("domain")
+    );
+    assert_eq!(Some(&serde_json::Value::Null), skeleton.pointer("/si/name"));
+}
+
+#[test]
+async fn ensure_resource_value_prop(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, _) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+
+    assert!(
+        !SchemaVariant::ensure_resource_value_prop(ctx, *schema_variant.id())
+            .await
+            .expect("could not ensure resource value prop")
+    );
+
+    // Variants created before "/root/resource_value" existed do not have it
+    let mut resource_value_prop =
+        SchemaVariant::find_prop_in_tree(ctx, *schema_variant.id(), &["root", "resource_value"])
+            .await
+            .expect("could not find resource value prop");
+    resource_value_prop
+        .delete_by_id(ctx)
+        .await
+        .expect("could not delete resource value prop");
+    assert!(SchemaVariant::find_prop_in_tree(
+        ctx,
+        *schema_variant.id(),
+        &["root", "resource_value"]
+    )
+    .await
+    .is_err());
+
+    assert!(
+        SchemaVariant::ensure_resource_value_prop(ctx, *schema_variant.id())
+            .await
+            .expect("could not ensure resource value prop")
+    );
+    SchemaVariant::find_prop_in_tree(ctx, *schema_variant.id(), &["root", "resource_value"])
+        .await
+        .expect("resource value prop was created");
+
+    assert!(
+        !SchemaVariant::ensure_resource_value_prop(ctx, *schema_variant.id())
+            .await
+            .expect("could not ensure resource value prop")
+    );
+}
+
+async fn new_func_with_code(
+    ctx: &DalContext,
+    name: &str,
+    backend_kind: FuncBackendKind,
+    backend_response_type: FuncBackendResponseType,
+) -> Func {
+    let mut func = Func::new(ctx, name, backend_kind, backend_response_type)
+        .await
+        .expect("could not create func");
+    func.set_code_plaintext(ctx, Some("function main() { return null; }"))
+        .await
+        .expect("could not set code");
+    func.set_handler(ctx, Some("main"))
+        .await
+        .expect("could not set handler");
+    func
+}
+
+#[test]
+async fn bound_funcs(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut variant, root_prop) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+    let prop = Prop::new_without_ui_optionals(
+        ctx,
+        "trystero",
+        PropKind::String,
+        *variant.id(),
+        Some(root_prop.domain_prop_id),
+    )
+    .await
+    .expect("could not create prop");
+    variant
+        .finalize(ctx, None)
+        .await
+        .expect("unable to finalize schema variant");
+
+    let action_func = new_func_with_code(
+        ctx,
+        "test:boundAction",
+        FuncBackendKind::JsAction,
+        FuncBackendResponseType::Action,
+    )
+    .await;
+    ActionPrototype::new(
+        ctx,
+        *action_func.id(),
+        ActionKind::Create,
+        ActionPrototypeContext {
+            schema_variant_id: *variant.id(),
+        },
+    )
+    .await
+    .expect("could not create action prototype");
+
+    let auth_func = new_func_with_code(
+        ctx,
+        "test:boundAuth",
+        FuncBackendKind::JsAuthentication,
+        FuncBackendResponseType::Void,
+    )
+    .await;
+    AuthenticationPrototype::new(
+        ctx,
+        *auth_func.id(),
+        AuthenticationPrototypeContext {
+            schema_variant_id: *variant.id(),
+        },
+    )
+    .await
+    .expect("could not create authentication prototype");
+
+    let leaf_func = new_func_with_code(
+        ctx,
+        "test:boundQualification",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::Qualification,
+    )
+    .await;
+    SchemaVariant::upsert_leaf_function(
+        ctx,
+        *variant.id(),
+        None,
+        LeafKind::Qualification,
+        &[LeafInputLocation::Domain],
+        &leaf_func,
+    )
+    .await
+    .expect("could not upsert leaf function");
+
+    let attribute_func = new_func_with_code(
+        ctx,
+        "test:boundAttribute",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await;
+    let mut attribute_prototype =
+        AttributeValue::find_for_context(ctx, AttributeReadContext::default_with_prop(*prop.id()))
+            .await
+            .expect("cannot find attribute value")
+            .expect("attribute value not found")
+            .attribute_prototype(ctx)
+            .await
+            .expect("cannot find attribute prototype")
+            .expect("attribute prototype not found");
+    attribute_prototype
+        .set_func_id(ctx, *attribute_func.id())
+        .await
+        .expect("could not set func id on attribute prototype");
+
+    // Validation prototypes can no longer be created through the dal, but existing rows still
+    // bind funcs to variants
+    let validation_func = new_func_with_code(
+        ctx,
+        "test:boundValidation",
+        FuncBackendKind::JsValidation,
+        FuncBackendResponseType::Validation,
+    )
+    .await;
+    ctx.txns()
+        .await
+        .expect("could not get transactions")
+        .pg()
+        .query_one(
+            "SELECT object FROM validation_prototype_create_v1($1, $2, $3, $4, $5, $6, $7)",
+            &[
+                ctx.tenancy(),
+                ctx.visibility(),
+                validation_func.id(),
+                &serde_json::json!({}),
+                prop.id(),
+                schema.id(),
+                variant.id(),
+            ],
+        )
+        .await
+        .expect("could not create validation prototype");
+
+    let bound_funcs = SchemaVariant::bound_funcs(ctx, *variant.id())
+        .await
+        .expect("could not find bound funcs");
+
+    assert_eq!(vec![*action_func.id()], bound_funcs.actions);
+    assert_eq!(vec![*auth_func.id()], bound_funcs.auths);
+    assert_eq!(vec![*leaf_func.id()], bound_funcs.leaves);
+    assert_eq!(vec![*attribute_func.id()], bound_funcs.attributes);
+    assert_eq!(vec![*validation_func.id()], bound_funcs.validations);
+}
@@ -245,3 +245,74 @@ async fn find_by_name_for_edge_kind_and_node(ctx: &DalContext) {
         *found_input_socket.id(), // actual
     );
 }
+
+#[test]
+async fn find_by_name_for_edge_kind_and_node_compatible_with(ctx: &DalContext) {
+    let schema = create_schema(ctx).await;
+    let (mut schema_variant, _) = SchemaVariant::new(ctx, *schema.id(), "v0")
+        .await
+        .expect("cannot create schema variant");
+
+    // Create two output sockets that share a name but not their annotations.
+    let _fallout_output_socket = Socket::new(
+        ctx,
+        "output",
+        connection_annotation_string!("fallout"),
+        SocketKind::Standalone,
+        &SocketEdgeKind::ConfigurationOutput,
+        &SocketArity::Many,
+        &DiagramKind::Configuration,
+        Some(*schema_variant.id()),
+    )
+    .await
+    .expect("could not create socket");
+    let starfield_output_socket = Socket::new(
+        ctx,
+        "output",
+        connection_annotation_string!("starfield"),
+        SocketKind::Standalone,
+        &SocketEdgeKind::ConfigurationOutput,
+        &SocketArity::Many,
+        &DiagramKind::Configuration,
+        Some(*schema_variant.id()),
+    )
+    .await
+    .expect("could not create socket");
+    let input_socket = Socket::new(
+        ctx,
+        "input",
+        connection_annotation_string!("starfield"),
+        SocketKind::Standalone,
+        &SocketEdgeKind::ConfigurationInput,
+        &SocketArity::Many,
+        &DiagramKind::Configuration,
+        None,
+    )
+    .await
+    .expect("could not create socket");
+
+    // Finalize the schema variant and create the component.
+    schema_variant
+        .finalize(ctx, None)
+        .await
+        .expect("cannot finalize schema variant");
+    let (_component, node) = Component::new(ctx, "Hog Island", *schema_variant.id())
+        .await
+        .expect("could not create component");
+
+    // Test our query.
+    let found_output_socket = Socket::find_by_name_for_edge_kind_and_node_compatible_with(
+        ctx,
+        "output",
+        SocketEdgeKind::ConfigurationOutput,
+        *node.id(),
+        &input_socket,
+    )
+    .await
+    .expect("could not perform query")
+    .expect("socket not found");
+    assert_eq!(
+        *starfield_output_socket.id(), // expected
+        *found_output_socket.id(),     // actual
+    );
+}
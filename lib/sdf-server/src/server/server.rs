@@ -38,6 +38,7 @@ use tokio::{
     task::{JoinError, JoinSet},
     time::{self, Instant},
 };
+use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
 use ulid::Ulid;
 use veritech_client::{Client as VeritechClient, CycloneEncryptionKey, CycloneEncryptionKeyError};
@@ -381,13 +382,27 @@ pub async fn migrate_builtins_from_module_index(services_context: &ServicesConte
     let module_index_client =
         IndexClient::unauthenticated_client(module_index_url.clone().as_str().try_into()?);
     let module_list = module_index_client.list_builtins().await?;
-    let install_builtins = install_builtins(ctx, module_list, module_index_client);
+
+    // The server's own shutdown handling is not set up yet, so stop the install on SIGTERM here
+    let mut sigterm_watcher =
+        signal::unix::signal(signal::unix::SignalKind::terminate()).map_err(ServerError::Signal)?;
+    let cancellation_token = CancellationToken::new();
+    let install_builtins = install_builtins(
+        ctx,
+        module_list,
+        module_index_client,
+        cancellation_token.clone(),
+    );
     tokio::pin!(install_builtins);
     loop {
         tokio::select! {
             _ = interval.tick() => {
                 info!(elapsed = instant.elapsed().as_secs_f32(), "migrating");
             }
+            _ = sigterm_watcher.recv(), if !cancellation_token.is_cancelled() => {
+                info!("received SIGTERM signal, cancelling the builtin install");
+                cancellation_token.cancel();
+            }
             result = &mut install_builtins  => match result {
                 Ok(_) => {
                     info!(elapsed = instant.elapsed().as_secs_f32(), "migrating completed");
@@ -405,6 +420,7 @@ async fn install_builtins(
     ctx: DalContext,
     module_list: BuiltinsDetailsResponse,
     module_index_client: IndexClient,
+    cancellation_token: CancellationToken,
 ) -> Result<()> {
     let dal = &ctx;
     let client = &module_index_client.clone();
@@ -427,22 +443,20 @@ async fn install_builtins(
     while let Some(res) = join_set.join_next().await {
         let (pkg_name, res) = res?;
         match res {
-            Ok(pkg) => {
-                if let Err(err) = import_pkg_from_pkg(
-                    &ctx,
-                    &pkg,
-                    Some(ImportOptions {
-                        schemas: None,
-                        skip_import_funcs: None,
-                        no_record: false,
-                        is_builtin: true,
-                    }),
-                    true,
-                )
-                .await
-                {
-                    println!("Pkg {pkg_name} Install failed, {err}");
-                } else {
+            Ok(pkg) => match import_pkg_from_pkg(
+                &ctx,
+                &pkg,
+                Some(
+                    ImportOptions::builder()
+                        .is_builtin(true)
+                        .cancellation_token(cancellation_token.clone())
+                        .build(),
+                ),
+                true,
+            )
+            .await
+            {
+                Ok(_) => {
                     ctx.commit().await?;
 
                     count += 1;
@@ -450,7 +464,15 @@ async fn install_builtins(
                         "Pkg {pkg_name} Install finished successfully. {count} of {total} installed.",
                     );
                 }
-            }
+                // Stop without committing the part of the package that was imported
+                Err(PkgError::Cancelled) => {
+                    ctx.rollback().await?;
+                    return Err(PkgError::Cancelled.into());
+                }
+                Err(err) => {
+                    println!("Pkg {pkg_name} Install failed, {err}");
+                }
+            },
             Err(err) => {
                 println!("Pkg {pkg_name} Install failed, {err}");
             }
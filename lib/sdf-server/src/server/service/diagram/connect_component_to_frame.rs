@@ -5,13 +5,12 @@ use axum::{response::IntoResponse, Json};
 use hyper::http::Uri;
 use serde::{Deserialize, Serialize};
 
-use dal::edge::{EdgeKind, EdgeObjectId, VertexObjectKind};
+use dal::edge::EdgeKind;
 use dal::job::definition::DependentValuesUpdate;
 use dal::socket::{SocketEdgeKind, SocketKind};
 use dal::{
     node::NodeId, AttributeReadContext, AttributeValue, ChangeSet, Component, ComponentError,
-    Connection, DalContext, Edge, EdgeError, ExternalProvider, InternalProvider, Node, SocketId,
-    StandardModel, Visibility,
+    Connection, DalContext, Edge, InternalProvider, Node, SocketId, StandardModel, Visibility,
 };
 use dal::{ComponentType, Socket};
 
@@ -166,108 +165,15 @@ async fn connect_component_sockets_to_frame_inner_work(
                 return Err(DiagramError::InvalidComponentTypeForFrame(component_type));
             }
             ComponentType::AggregationFrame => {
-                match *parent_socket.edge_kind() {
-                    SocketEdgeKind::ConfigurationInput => {
-                        let provider =
-                            InternalProvider::find_explicit_for_socket(ctx, *parent_socket.id())
-                                .await?
-                                .ok_or(EdgeError::InternalProviderNotFoundForSocket(
-                                    *parent_socket.id(),
-                                ))?;
-
-                        // We don't want to connect the provider when we are not using configuration edge kind
-                        Edge::connect_internal_providers_for_components(
-                            ctx,
-                            *provider.id(),
-                            *child_component.id(),
-                            *parent_component.id(),
-                        )
-                        .await?;
-
-                        Edge::new(
-                            ctx,
-                            EdgeKind::Configuration,
-                            child_node_id,
-                            VertexObjectKind::Configuration,
-                            EdgeObjectId::from(*child_component.id()),
-                            *parent_socket.id(),
-                            parent_node_id,
-                            VertexObjectKind::Configuration,
-                            EdgeObjectId::from(*parent_component.id()),
-                            *parent_socket.id(),
-                        )
-                        .await?;
-
-                        let attribute_value_context = AttributeReadContext {
-                            component_id: Some(*parent_component.id()),
-                            internal_provider_id: Some(*provider.id()),
-                            ..Default::default()
-                        };
-
-                        let attribute_value =
-                            AttributeValue::find_for_context(ctx, attribute_value_context)
-                                .await?
-                                .ok_or(DiagramError::AttributeValueNotFoundForContext(
-                                    attribute_value_context,
-                                ))?;
-
-                        ctx.enqueue_job(DependentValuesUpdate::new(
-                            ctx.access_builder(),
-                            *ctx.visibility(),
-                            vec![*attribute_value.id()],
-                        ))
-                        .await?;
-                    }
-                    SocketEdgeKind::ConfigurationOutput => {
-                        let provider = ExternalProvider::find_for_socket(ctx, *parent_socket.id())
-                            .await?
-                            .ok_or(EdgeError::ExternalProviderNotFoundForSocket(
-                                *parent_socket.id(),
-                            ))?;
-
-                        Edge::connect_external_providers_for_components(
-                            ctx,
-                            *provider.id(),
-                            *parent_component.id(),
-                            *child_component.id(),
-                        )
-                        .await?;
-
-                        Edge::new(
-                            ctx,
-                            EdgeKind::Configuration,
-                            parent_node_id,
-                            VertexObjectKind::Configuration,
-                            EdgeObjectId::from(*parent_component.id()),
-                            *parent_socket.id(),
-                            child_node_id,
-                            VertexObjectKind::Configuration,
-                            EdgeObjectId::from(*child_component.id()),
-                            *parent_socket.id(),
-                        )
-                        .await?;
-
-                        let attribute_value_context = AttributeReadContext {
-                            component_id: Some(*child_component.id()),
-                            external_provider_id: Some(*provider.id()),
-                            ..Default::default()
-                        };
-
-                        let attribute_value =
-                            AttributeValue::find_for_context(ctx, attribute_value_context)
-                                .await?
-                                .ok_or(DiagramError::AttributeValueNotFoundForContext(
-                                    attribute_value_context,
-                                ))?;
-
-                        ctx.enqueue_job(DependentValuesUpdate::new(
-                            ctx.access_builder(),
-                            *ctx.visibility(),
-                            vec![*attribute_value.id()],
-                        ))
-                        .await?;
-                    }
-                }
+                Edge::connect_aggregation_frame_socket(
+                    ctx,
+                    parent_socket,
+                    *parent_component.id(),
+                    parent_node_id,
+                    *child_component.id(),
+                    child_node_id,
+                )
+                .await?;
             }
             component_type @ (ComponentType::ConfigurationFrameDown
             | ComponentType::ConfigurationFrameUp) => {
@@ -1,6 +1,6 @@
 use axum::extract::OriginalUri;
 use axum::{response::IntoResponse, Json};
-use dal::edge::EdgeKind;
+use dal::edge::{EdgeId, EdgeKind};
 use dal::{
     job::definition::DependentValuesUpdate, node::NodeId, socket::SocketId, AttributeReadContext,
     AttributeValue, ChangeSet, Connection, InternalProvider, Node, Socket, StandardModel,
@@ -27,6 +27,12 @@ pub struct CreateConnectionRequest {
 #[serde(rename_all = "camelCase")]
 pub struct CreateConnectionResponse {
     pub connection: Connection,
+    pub edge_id: EdgeId,
+    pub from_socket_id: SocketId,
+    pub to_socket_id: SocketId,
+    /// Whether the connection replaced the existing connection of a _to_
+    /// [`Socket`](dal::Socket) with an arity of one
+    pub replaced_existing: bool,
 }
 
 /// Create a [`Connection`](dal::Connection) with a _to_ [`Socket`](dal::Socket) and
@@ -57,7 +63,7 @@ pub async fn create_connection(
             .await?;
     };
 
-    let connection = Connection::new(
+    let (connection, replaced_edge_ids) = Connection::new_with_replaced_edges(
         &ctx,
         request.from_node_id,
         request.from_socket_id,
@@ -128,6 +134,12 @@ pub async fn create_connection(
     ))
     .await?;
 
+    let replaced_existing = !replaced_edge_ids.is_empty();
+    WsEvent::connection_created(&ctx, &connection, replaced_existing)
+        .await?
+        .publish_on_commit(&ctx)
+        .await?;
+
     track(
         &posthog_client,
         &ctx,
@@ -154,6 +166,10 @@ pub async fn create_connection(
     Ok(response
         .header("content-type", "application/json")
         .body(serde_json::to_string(&CreateConnectionResponse {
+            edge_id: connection.id,
+            from_socket_id: connection.source.socket_id,
+            to_socket_id: connection.destination.socket_id,
+            replaced_existing,
             connection,
         })?)?)
 }
@@ -42,6 +42,7 @@ pub mod list_input_sources;
 pub mod revert_func;
 pub mod save_and_exec;
 pub mod save_func;
+pub mod variant_prop_types;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -106,6 +107,12 @@ pub enum FuncError {
     FuncArgumentAlreadyExists,
     #[error("func argument {0} missing attribute prototype argument for prototype {1}")]
     FuncArgumentMissingPrototypeArgument(FuncArgumentId, AttributePrototypeId),
+    #[error("func {func_id} is a {variant:?} func but has {association} prototypes")]
+    FuncAssociationsContradictVariant {
+        func_id: FuncId,
+        variant: FuncVariant,
+        association: &'static str,
+    },
     #[error("func binding error: {0}")]
     FuncBinding(#[from] FuncBindingError),
     #[error("func binding return value error: {0}")]
@@ -443,6 +450,58 @@ async fn action_prototypes_into_schema_variants_and_components(
     Ok((action_kind, variant_ids))
 }
 
+/// Checks that the prototypes stored for a func agree with the [`FuncVariant`] derived from its
+/// backend kind and response type, e.g. that an attribute func has no action prototypes. A
+/// contradiction means the func's data is corrupt, usually because of a bad import.
+pub async fn validate_associations_match_variant(
+    ctx: &DalContext,
+    func_id: FuncId,
+) -> FuncResult<()> {
+    let func = Func::get_by_id(ctx, &func_id)
+        .await?
+        .ok_or(FuncError::FuncNotFound)?;
+    let variant = FuncVariant::try_from(&func)?;
+
+    let has_action_prototypes = !ActionPrototype::find_for_func(ctx, func_id)
+        .await?
+        .is_empty();
+    let has_attribute_prototypes = !AttributePrototype::find_for_func(ctx, &func_id)
+        .await?
+        .is_empty();
+    let has_authentication_prototypes = !AuthenticationPrototype::find_for_func(ctx, func_id)
+        .await?
+        .is_empty();
+
+    let (allows_action, allows_attribute, allows_authentication) = match variant {
+        FuncVariant::Action => (true, false, false),
+        FuncVariant::Attribute | FuncVariant::CodeGeneration | FuncVariant::Qualification => {
+            (false, true, false)
+        }
+        FuncVariant::Authentication => (false, false, true),
+        FuncVariant::Reconciliation | FuncVariant::Validation => (false, false, false),
+    };
+
+    for (association, present, allowed) in [
+        ("action", has_action_prototypes, allows_action),
+        ("attribute", has_attribute_prototypes, allows_attribute),
+        (
+            "authentication",
+            has_authentication_prototypes,
+            allows_authentication,
+        ),
+    ] {
+        if present && !allowed {
+            return Err(FuncError::FuncAssociationsContradictVariant {
+                func_id,
+                variant,
+                association,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 async fn attribute_prototypes_into_schema_variants_and_components(
     ctx: &DalContext,
     func_id: FuncId,
@@ -593,7 +652,7 @@ pub async fn get_func_view(ctx: &DalContext, func: &Func) -> FuncResult<GetFuncR
     let types = [
         compile_return_types(*func.backend_response_type(), *func.backend_kind()),
         &input_type,
-        langjs_types(),
+        &langjs_types(),
     ]
     .join("\n");
 
@@ -611,71 +670,99 @@ pub async fn get_func_view(ctx: &DalContext, func: &Func) -> FuncResult<GetFuncR
     })
 }
 
-pub fn compile_return_types(ty: FuncBackendResponseType, kind: FuncBackendKind) -> &'static str {
-    if matches!(kind, FuncBackendKind::JsAttribute)
-        && !matches!(
-            ty,
-            FuncBackendResponseType::CodeGeneration | FuncBackendResponseType::Qualification
-        )
-    {
-        return ""; // attribute functions have their output compiled dynamically
-    }
-
-    match ty {
-        FuncBackendResponseType::Boolean => "type Output = boolean | null;",
-        FuncBackendResponseType::String => "type Output = string | null;",
-        FuncBackendResponseType::Integer => "type Output = number | null;",
-        FuncBackendResponseType::Qualification => {
-            "type Output {
+/// The TypeScript `Output` type shown in the func editor for each [`FuncBackendResponseType`].
+/// [`FuncBackendResponseType::SchemaVariantDefinition`] is not listed here because its
+/// definition depends on which asset builder types are in use.
+const RETURN_TYPES: &[(FuncBackendResponseType, &str)] = &[
+    (
+        FuncBackendResponseType::Boolean,
+        "type Output = boolean | null;",
+    ),
+    (
+        FuncBackendResponseType::String,
+        "type Output = string | null;",
+    ),
+    (
+        FuncBackendResponseType::Integer,
+        "type Output = number | null;",
+    ),
+    (
+        FuncBackendResponseType::Qualification,
+        "type Output {
   result: 'success' | 'warning' | 'failure';
   message?: string | null;
-}"
-        }
-        FuncBackendResponseType::CodeGeneration => {
-            "type Output {
+}",
+    ),
+    (
+        FuncBackendResponseType::CodeGeneration,
+        "type Output {
   format: string;
   code: string;
-}"
-        }
-        FuncBackendResponseType::Validation => {
-            "type Output {
+}",
+    ),
+    (
+        FuncBackendResponseType::Validation,
+        "type Output {
   valid: boolean;
   message: string;
-}"
-        }
-        FuncBackendResponseType::Reconciliation => {
-            "type Output {
+}",
+    ),
+    (
+        FuncBackendResponseType::Reconciliation,
+        "type Output {
   updates: { [key: string]: unknown };
   actions: string[];
   message: string | null;
-}"
-        }
-        FuncBackendResponseType::Action => {
-            "type Output {
+}",
+    ),
+    (
+        FuncBackendResponseType::Action,
+        "type Output {
     status: 'ok' | 'warning' | 'error';
     payload?: { [key: string]: unknown } | null;
     message?: string | null;
-}"
-        }
-        FuncBackendResponseType::Json => "type Output = any;",
-        // Note: there is no ts function returning those
-        FuncBackendResponseType::Identity => "interface Output extends Input {}",
-        FuncBackendResponseType::Array => "type Output = any[];",
-        FuncBackendResponseType::Map => "type Output = Record<string, any>;",
-        FuncBackendResponseType::Object => "type Output = any;",
-        FuncBackendResponseType::Unset => "type Output = undefined | null;",
-        FuncBackendResponseType::Void => "type Output = void;",
-        FuncBackendResponseType::SchemaVariantDefinition => concat!(
-            include_str!("./ts_types/asset_builder.d.ts"),
-            "\n",
-            include_str!("./ts_types/joi.d.ts"),
-            "\n",
-            "type Output = any;"
-        ),
-    }
-}
-
-pub fn compile_return_types_2(ty: FuncBackendResponseType, kind: FuncBackendKind) -> &'static str {
+}",
+    ),
+    (FuncBackendResponseType::Json, "type Output = any;"),
+    // Note: there is no ts function returning those
+    (
+        FuncBackendResponseType::Identity,
+        "interface Output extends Input {}",
+    ),
+    (FuncBackendResponseType::Array, "type Output = any[];"),
+    (
+        FuncBackendResponseType::Map,
+        "type Output = Record<string, any>;",
+    ),
+    (FuncBackendResponseType::Object, "type Output = any;"),
+    (
+        FuncBackendResponseType::Unset,
+        "type Output = undefined | null;",
+    ),
+    (FuncBackendResponseType::Void, "type Output = void;"),
+];
+
+const SCHEMA_VARIANT_DEFINITION_RETURN_TYPE: &str = concat!(
+    include_str!("./ts_types/asset_builder.d.ts"),
+    "\n",
+    include_str!("./ts_types/joi.d.ts"),
+    "\n",
+    "type Output = any;"
+);
+
+const SCHEMA_VARIANT_DEFINITION_RETURN_TYPE_2: &str = concat!(
+    include_str!("./ts_types/asset_types_with_secrets.d.ts"),
+    "\n",
+    include_str!("./ts_types/joi.d.ts"),
+    "\n",
+    "type Output = any;"
+);
+
+fn return_type_definition(
+    ty: FuncBackendResponseType,
+    kind: FuncBackendKind,
+    schema_variant_definition: &'static str,
+) -> &'static str {
     if matches!(kind, FuncBackendKind::JsAttribute)
         && !matches!(
             ty,
@@ -685,58 +772,23 @@ pub fn compile_return_types_2(ty: FuncBackendResponseType, kind: FuncBackendKind
         return ""; // attribute functions have their output compiled dynamically
     }
 
-    match ty {
-        FuncBackendResponseType::Boolean => "type Output = boolean | null;",
-        FuncBackendResponseType::String => "type Output = string | null;",
-        FuncBackendResponseType::Integer => "type Output = number | null;",
-        FuncBackendResponseType::Qualification => {
-            "type Output {
-  result: 'success' | 'warning' | 'failure';
-  message?: string | null;
-}"
-        }
-        FuncBackendResponseType::CodeGeneration => {
-            "type Output {
-  format: string;
-  code: string;
-}"
-        }
-        FuncBackendResponseType::Validation => {
-            "type Output {
-  valid: boolean;
-  message: string;
-}"
-        }
-        FuncBackendResponseType::Reconciliation => {
-            "type Output {
-  updates: { [key: string]: unknown };
-  actions: string[];
-  message: string | null;
-}"
-        }
-        FuncBackendResponseType::Action => {
-            "type Output {
-    status: 'ok' | 'warning' | 'error';
-    payload?: { [key: string]: unknown } | null;
-    message?: string | null;
-}"
-        }
-        FuncBackendResponseType::Json => "type Output = any;",
-        // Note: there is no ts function returning those
-        FuncBackendResponseType::Identity => "interface Output extends Input {}",
-        FuncBackendResponseType::Array => "type Output = any[];",
-        FuncBackendResponseType::Map => "type Output = Record<string, any>;",
-        FuncBackendResponseType::Object => "type Output = any;",
-        FuncBackendResponseType::Unset => "type Output = undefined | null;",
-        FuncBackendResponseType::Void => "type Output = void;",
-        FuncBackendResponseType::SchemaVariantDefinition => concat!(
-            include_str!("./ts_types/asset_types_with_secrets.d.ts"),
-            "\n",
-            include_str!("./ts_types/joi.d.ts"),
-            "\n",
-            "type Output = any;"
-        ),
+    if ty == FuncBackendResponseType::SchemaVariantDefinition {
+        return schema_variant_definition;
     }
+
+    RETURN_TYPES
+        .iter()
+        .find(|(response_type, _)| *response_type == ty)
+        .map(|(_, definition)| *definition)
+        .unwrap_or_default()
+}
+
+pub fn compile_return_types(ty: FuncBackendResponseType, kind: FuncBackendKind) -> &'static str {
+    return_type_definition(ty, kind, SCHEMA_VARIANT_DEFINITION_RETURN_TYPE)
+}
+
+pub fn compile_return_types_2(ty: FuncBackendResponseType, kind: FuncBackendKind) -> &'static str {
+    return_type_definition(ty, kind, SCHEMA_VARIANT_DEFINITION_RETURN_TYPE_2)
 }
 
 async fn get_per_variant_types_for_prop_path(
@@ -877,26 +929,42 @@ async fn compile_action_types(
     ))
 }
 
+/// The ambient TypeScript declaration for one of the globals that lang-js makes available to
+/// every func, shown in the func editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AmbientModule {
+    pub name: &'static str,
+    pub declaration: &'static str,
+}
+
 // TODO: stop duplicating definition
 // TODO: use execa types instead of any
-// TODO: add os, fs and path types (possibly fetch but I think it comes with DOM)
-fn langjs_types() -> &'static str {
-    "declare namespace YAML {
+// TODO: add fetch types (I think it comes with DOM)
+const LANGJS_AMBIENT_MODULES: &[AmbientModule] = &[
+    AmbientModule {
+        name: "YAML",
+        declaration: "declare namespace YAML {
     function stringify(obj: unknown): string;
-}
-
-    declare namespace zlib {
-        function gzip(inputstr: string, callback: any);
-    }
-
-    declare namespace requestStorage {
-        function getEnv(key: string): string;
-        function getItem(key: string): any;
-        function getEnvKeys(): string[];
-        function getKeys(): string[];
-    }
-
-    declare namespace siExec {
+}",
+    },
+    AmbientModule {
+        name: "zlib",
+        declaration: "declare namespace zlib {
+    function gzip(inputstr: string, callback: any);
+}",
+    },
+    AmbientModule {
+        name: "requestStorage",
+        declaration: "declare namespace requestStorage {
+    function getEnv(key: string): string;
+    function getItem(key: string): any;
+    function getEnvKeys(): string[];
+    function getKeys(): string[];
+}",
+    },
+    AmbientModule {
+        name: "siExec",
+        declaration: "declare namespace siExec {
 
     interface WatchArgs {
         cmd: string,
@@ -916,7 +984,60 @@ fn langjs_types() -> &'static str {
 
     async function waitUntilEnd(execaFile: string, execaArgs?: string[], execaOptions?: any): Promise<any>;
     async function watch(options: WatchArgs, deadlineCount?: number): Promise<WatchResult>;
-}"
+}",
+    },
+    AmbientModule {
+        name: "os",
+        declaration: "declare namespace os {
+    const EOL: string;
+    function arch(): string;
+    function homedir(): string;
+    function hostname(): string;
+    function platform(): string;
+    function tmpdir(): string;
+}",
+    },
+    AmbientModule {
+        name: "fs",
+        declaration: "declare namespace fs {
+    function existsSync(path: string): boolean;
+    function mkdtempSync(prefix: string): string;
+    function readFileSync(path: string, encoding: string): string;
+    function rmSync(path: string, options?: { recursive?: boolean, force?: boolean }): void;
+    function writeFileSync(path: string, data: string): void;
+    namespace promises {
+        function mkdtemp(prefix: string): Promise<string>;
+        function readFile(path: string, encoding: string): Promise<string>;
+        function rm(path: string, options?: { recursive?: boolean, force?: boolean }): Promise<void>;
+        function writeFile(path: string, data: string): Promise<void>;
+    }
+}",
+    },
+    AmbientModule {
+        name: "path",
+        declaration: "declare namespace path {
+    const sep: string;
+    function basename(path: string, ext?: string): string;
+    function dirname(path: string): string;
+    function extname(path: string): string;
+    function join(...paths: string[]): string;
+    function resolve(...paths: string[]): string;
+}",
+    },
+];
+
+/// The ambient declarations for every global lang-js provides to funcs, so the editor can load
+/// them selectively.
+pub fn langjs_ambient_modules() -> &'static [AmbientModule] {
+    LANGJS_AMBIENT_MODULES
+}
+
+fn langjs_types() -> String {
+    LANGJS_AMBIENT_MODULES
+        .iter()
+        .map(|module| module.declaration)
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
 
 pub fn routes() -> Router<AppState> {
@@ -937,4 +1058,8 @@ pub fn routes() -> Router<AppState> {
             "/list_input_sources",
             get(list_input_sources::list_input_sources),
         )
+        .route(
+            "/variant_prop_types",
+            get(variant_prop_types::variant_prop_types),
+        )
 }
@@ -648,6 +648,8 @@ pub async fn do_save_func(
         | FuncBackendKind::JsValidation => return Err(FuncError::NotWritable),
     }
 
+    super::validate_associations_match_variant(ctx, *func.id()).await?;
+
     let is_revertible = super::is_func_revertible(ctx, &func).await?;
     let view = super::get_func_view(ctx, &func).await?;
     let associations = view.associations;
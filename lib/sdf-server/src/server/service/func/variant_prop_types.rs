@@ -0,0 +1,38 @@
+use super::{get_per_variant_types_for_prop_path, FuncResult};
+use crate::server::extract::{AccessBuilder, HandlerContext};
+use axum::{extract::Query, Json};
+use dal::{SchemaVariantId, Visibility};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantPropTypesRequest {
+    pub schema_variant_id: SchemaVariantId,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantPropTypesResponse {
+    pub types: String,
+}
+
+pub async fn variant_prop_types(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    Query(request): Query<VariantPropTypesRequest>,
+) -> FuncResult<Json<VariantPropTypesResponse>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let domain_types = get_per_variant_types_for_prop_path(
+        &ctx,
+        &[request.schema_variant_id],
+        &["root", "domain"],
+    )
+    .await?;
+
+    Ok(Json(VariantPropTypesResponse {
+        types: format!("interface Domain {domain_types}"),
+    }))
+}
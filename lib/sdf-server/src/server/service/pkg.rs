@@ -30,6 +30,7 @@ pub mod install_pkg;
 pub mod list_pkgs;
 mod reject_pkg;
 pub mod remote_module_spec;
+pub mod remove_pkg;
 
 #[remain::sorted]
 #[derive(Error, Debug)]
@@ -220,6 +221,7 @@ pub fn routes() -> Router<AppState> {
             post(builtin_module_spec::promote_to_builtin),
         )
         .route("/reject_pkg", post(reject_pkg::reject_pkg))
+        .route("/remove_pkg", post(remove_pkg::remove_pkg))
         .route(
             "/begin_approval_process",
             post(approval_process::begin_approval_process),
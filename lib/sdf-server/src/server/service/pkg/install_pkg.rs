@@ -2,20 +2,25 @@ use std::str::FromStr;
 
 use super::PkgResult;
 use crate::server::extract::RawAccessToken;
+use crate::server::state::ShutdownBroadcast;
 use crate::server::tracking::track;
 use crate::{
     server::extract::{AccessBuilder, HandlerContext, PosthogClient},
     service::pkg::PkgError,
 };
-use axum::extract::OriginalUri;
+use axum::extract::{OriginalUri, State};
 use axum::http::uri::Uri;
 use axum::{response::IntoResponse, Json};
-use dal::{pkg::import_pkg_from_pkg, ChangeSet, Visibility, WsEvent};
+use dal::{
+    pkg::{import_pkg_from_pkg, ImportOptions, ImportResult},
+    ChangeSet, Visibility, WsEvent,
+};
 use dal::{DalContext, HistoryActor, User, WorkspacePk};
 use module_index_client::IndexClient;
 use serde::{Deserialize, Serialize};
 use si_pkg::{SiPkg, SiPkgKind};
 use telemetry::prelude::*;
+use tokio_util::sync::CancellationToken;
 use ulid::Ulid;
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -39,20 +44,26 @@ pub async fn install_pkg(
     RawAccessToken(raw_access_token): RawAccessToken,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    State(shutdown_broadcast): State<ShutdownBroadcast>,
     Json(request): Json<InstallPkgRequest>,
 ) -> PkgResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
 
     let force_changeset_pk = ChangeSet::force_new(&mut ctx).await?;
 
+    // The install outlives the request, so stop it if the server shuts down in the meantime
+    let cancellation_token = shutdown_broadcast.cancellation_token();
+
     let id = Ulid::new();
     tokio::task::spawn(async move {
+        let _stop_watching_shutdown = cancellation_token.clone().drop_guard();
         if let Err(err) = install_pkg_inner(
             &ctx,
             request,
             &original_uri,
             PosthogClient(posthog_client),
             raw_access_token,
+            cancellation_token,
         )
         .await
         {
@@ -106,6 +117,7 @@ async fn install_pkg_inner(
     original_uri: &Uri,
     PosthogClient(posthog_client): PosthogClient,
     raw_access_token: String,
+    cancellation_token: CancellationToken,
 ) -> PkgResult<()> {
     let module_index_url = match ctx.module_index_url() {
         Some(url) => url,
@@ -117,10 +129,18 @@ async fn install_pkg_inner(
 
     let pkg = SiPkg::load_from_bytes(pkg_data)?;
     let metadata = pkg.metadata()?;
-    let (_, svs, _import_skips) = import_pkg_from_pkg(
+    let ImportResult {
+        schema_variant_ids: svs,
+        ..
+    } = import_pkg_from_pkg(
         ctx,
         &pkg,
-        None, // TODO: add is_builtin option
+        // TODO: add is_builtin option
+        Some(
+            ImportOptions::builder()
+                .cancellation_token(cancellation_token)
+                .build(),
+        ),
         request.override_builtin_schema_feature_flag,
     )
     .await?;
@@ -0,0 +1,58 @@
+use axum::{extract::OriginalUri, Json};
+use dal::{
+    installed_pkg::InstalledPkgId,
+    pkg::{remove_pkg as dal_remove_pkg, RemovedPkgSummary},
+    Visibility, WsEvent,
+};
+use serde::{Deserialize, Serialize};
+
+use super::PkgResult;
+use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::tracking::track;
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovePkgRequest {
+    pub installed_pkg_id: InstalledPkgId,
+    /// Delete any components of the package's schema variants instead of refusing the removal
+    #[serde(default)]
+    pub force: bool,
+    #[serde(flatten)]
+    pub visibility: Visibility,
+}
+
+pub async fn remove_pkg(
+    HandlerContext(builder): HandlerContext,
+    AccessBuilder(request_ctx): AccessBuilder,
+    PosthogClient(posthog_client): PosthogClient,
+    OriginalUri(original_uri): OriginalUri,
+    Json(request): Json<RemovePkgRequest>,
+) -> PkgResult<Json<RemovedPkgSummary>> {
+    let ctx = builder.build(request_ctx.build(request.visibility)).await?;
+
+    let summary = dal_remove_pkg(&ctx, request.installed_pkg_id, request.force).await?;
+
+    WsEvent::module_removed(
+        &ctx,
+        request.installed_pkg_id,
+        summary.removed_schema_variants.clone(),
+    )
+    .await?
+    .publish_on_commit(&ctx)
+    .await?;
+
+    track(
+        &posthog_client,
+        &ctx,
+        &original_uri,
+        "remove_pkg",
+        serde_json::json!({
+            "installed_pkg_id": request.installed_pkg_id,
+            "force": request.force,
+        }),
+    );
+
+    ctx.commit().await?;
+
+    Ok(Json(summary))
+}
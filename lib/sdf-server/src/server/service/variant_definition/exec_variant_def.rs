@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use axum::extract::OriginalUri;
+use axum::extract::{OriginalUri, State};
 use axum::{response::IntoResponse, Json};
 use chrono::Utc;
 use convert_case::{Case, Casing};
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 
 use dal::{
     func::intrinsics::IntrinsicFunc,
-    pkg::{attach_resource_payload_to_value, import_pkg_from_pkg},
+    pkg::{attach_resource_payload_to_value, import_pkg_from_pkg, ImportResult},
     schema::variant::definition::{
         SchemaVariantDefinition, SchemaVariantDefinitionJson, SchemaVariantDefinitionMetadataJson,
     },
@@ -20,6 +20,7 @@ use si_pkg::{
 };
 
 use crate::server::extract::{AccessBuilder, HandlerContext, PosthogClient};
+use crate::server::state::ShutdownBroadcast;
 use crate::server::tracking::track;
 use crate::service::variant_definition::migrate_authentication_funcs_to_new_schema_variant;
 
@@ -57,6 +58,7 @@ pub async fn exec_variant_def(
     AccessBuilder(request_ctx): AccessBuilder,
     PosthogClient(posthog_client): PosthogClient,
     OriginalUri(original_uri): OriginalUri,
+    State(shutdown_broadcast): State<ShutdownBroadcast>,
     Json(request): Json<ExecVariantDefRequest>,
 ) -> SchemaVariantDefinitionResult<impl IntoResponse> {
     let mut ctx = builder.build(request_ctx.build(request.visibility)).await?;
@@ -187,18 +189,23 @@ pub async fn exec_variant_def(
 
     let pkg = SiPkg::load_from_spec(pkg_spec.clone())?;
 
-    let (_, schema_variant_ids, _) = import_pkg_from_pkg(
+    let cancellation_token = shutdown_broadcast.cancellation_token();
+    let _stop_watching_shutdown = cancellation_token.clone().drop_guard();
+    let ImportResult {
+        schema_variant_ids, ..
+    } = import_pkg_from_pkg(
         &ctx,
         &pkg,
-        Some(dal::pkg::ImportOptions {
-            schemas: None,
-            skip_import_funcs: Some(HashMap::from_iter([(
-                asset_func_built.unique_id.to_owned(),
-                asset_func.clone(),
-            )])),
-            no_record: true,
-            is_builtin: false,
-        }),
+        Some(
+            dal::pkg::ImportOptions::builder()
+                .skip_import_funcs(HashMap::from_iter([(
+                    asset_func_built.unique_id.to_owned(),
+                    asset_func.clone(),
+                )]))
+                .no_record(true)
+                .cancellation_token(cancellation_token)
+                .build(),
+        ),
         request.override_builtin_schema_feature_flag,
     )
     .await?;
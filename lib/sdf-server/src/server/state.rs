@@ -4,6 +4,7 @@ use nats_multiplexer_client::MultiplexerClient;
 use si_std::SensitiveString;
 use std::{ops::Deref, sync::Arc};
 use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
 
 use super::server::ShutdownSource;
 use crate::server::nats_multiplexer::NatsMultiplexerClients;
@@ -154,6 +155,23 @@ impl ShutdownBroadcast {
     pub fn subscribe(&self) -> broadcast::Receiver<()> {
         self.0.subscribe()
     }
+
+    /// Returns a token that is cancelled once the server starts shutting down, so that long
+    /// running work such as a package import can stop early. Cancelling the token (e.g. through
+    /// its drop guard once the work is done) stops watching for the shutdown.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        let cancellation_token = CancellationToken::new();
+        let mut shutdown_broadcast_rx = self.subscribe();
+        let watched_token = cancellation_token.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = shutdown_broadcast_rx.recv() => watched_token.cancel(),
+                _ = watched_token.cancelled() => {}
+            }
+        });
+
+        cancellation_token
+    }
 }
 
 impl From<broadcast::Sender<()>> for ShutdownBroadcast {
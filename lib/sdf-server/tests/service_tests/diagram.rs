@@ -0,0 +1,71 @@
+use axum::{http::Method, Router};
+use dal::{socket::SocketEdgeKind, Socket, StandardModel, Visibility};
+use dal_test::{
+    helpers::component_bag::ComponentBagger, sdf_test,
+    test_harness::create_change_set as dal_create_change_set, AuthTokenRef, DalContextHead,
+};
+use sdf_server::service::diagram::create_connection::{
+    CreateConnectionRequest, CreateConnectionResponse,
+};
+
+use crate::service_tests::api_request_auth_json_body;
+
+#[sdf_test]
+async fn create_connection(
+    DalContextHead(mut ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let change_set = dal_create_change_set(&ctx).await;
+    let visibility = Visibility::new(change_set.pk, None);
+    ctx.update_visibility(visibility);
+
+    let mut bagger = ComponentBagger::new();
+    let fallout_bag = bagger.create_component(&ctx, "source", "fallout").await;
+    let starfield_bag = bagger
+        .create_component(&ctx, "destination", "starfield")
+        .await;
+
+    let from_socket = Socket::find_by_name_for_edge_kind_and_node(
+        &ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationOutput,
+        fallout_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    let to_socket = Socket::find_by_name_for_edge_kind_and_node(
+        &ctx,
+        "fallout",
+        SocketEdgeKind::ConfigurationInput,
+        starfield_bag.node_id,
+    )
+    .await
+    .expect("could not perform socket find")
+    .expect("could not find socket");
+    ctx.blocking_commit()
+        .await
+        .expect("cannot commit transaction");
+
+    let request = CreateConnectionRequest {
+        from_node_id: fallout_bag.node_id,
+        from_socket_id: *from_socket.id(),
+        to_node_id: starfield_bag.node_id,
+        to_socket_id: *to_socket.id(),
+        visibility,
+    };
+    let response: CreateConnectionResponse = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/diagram/create_connection",
+        auth_token,
+        &request,
+    )
+    .await;
+
+    assert_eq!(response.connection.id, response.edge_id);
+    assert_eq!(*from_socket.id(), response.from_socket_id);
+    assert_eq!(*to_socket.id(), response.to_socket_id);
+    assert!(!response.replaced_existing);
+}
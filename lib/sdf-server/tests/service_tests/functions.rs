@@ -1,11 +1,21 @@
 use axum::{http::Method, Router};
 
-use dal::{ComponentId, Func, FuncBackendKind, FuncBackendResponseType, StandardModel};
+use dal::{
+    ActionKind, ActionPrototype, ActionPrototypeContext, ComponentId, Func, FuncBackendKind,
+    FuncBackendResponseType, Schema, StandardModel,
+};
 use dal_test::{sdf_test, AuthTokenRef, DalContextHead};
 
-use sdf_server::service::func::execute::{ExecuteRequest, ExecuteResponse};
+use sdf_server::service::func::{
+    compile_return_types, compile_return_types_2,
+    execute::{ExecuteRequest, ExecuteResponse},
+    langjs_ambient_modules, validate_associations_match_variant,
+    variant_prop_types::{VariantPropTypesRequest, VariantPropTypesResponse},
+    FuncError, FuncVariant,
+};
+use strum::IntoEnumIterator;
 
-use crate::service_tests::api_request_auth_json_body;
+use crate::service_tests::{api_request_auth_json_body, api_request_auth_query};
 
 #[sdf_test]
 async fn test_execution_endpoint_qualification_function(
@@ -55,3 +65,119 @@ async fn test_execution_endpoint_qualification_function(
         serde_json::json!({"result": "success", "message": "info"})
     );
 }
+
+#[sdf_test]
+async fn test_validate_associations_match_variant(DalContextHead(ctx): DalContextHead) {
+    let func = Func::new(
+        &ctx,
+        "test:attributeWithActions",
+        FuncBackendKind::JsAttribute,
+        FuncBackendResponseType::String,
+    )
+    .await
+    .expect("cannot create new function");
+
+    validate_associations_match_variant(&ctx, *func.id())
+        .await
+        .expect("func without prototypes has no contradictions");
+
+    let schema = Schema::find_by_name(&ctx, "starfield")
+        .await
+        .expect("find starfield");
+    let schema_variant_id = *schema
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+    ActionPrototype::new(
+        &ctx,
+        *func.id(),
+        ActionKind::Create,
+        ActionPrototypeContext { schema_variant_id },
+    )
+    .await
+    .expect("create action prototype");
+
+    let result = validate_associations_match_variant(&ctx, *func.id()).await;
+    assert!(matches!(
+        result,
+        Err(FuncError::FuncAssociationsContradictVariant {
+            func_id,
+            variant: FuncVariant::Attribute,
+            association: "action",
+        }) if func_id == *func.id()
+    ));
+}
+
+#[sdf_test]
+async fn test_variant_prop_types_endpoint(
+    DalContextHead(ctx): DalContextHead,
+    app: Router,
+    AuthTokenRef(auth_token): AuthTokenRef<'_>,
+) {
+    let schema = Schema::find_by_name(&ctx, "starfield")
+        .await
+        .expect("find starfield");
+    let schema_variant_id = *schema
+        .default_schema_variant_id()
+        .expect("starfield has a default variant");
+
+    ctx.commit().await.expect("cannot commit");
+
+    let request = VariantPropTypesRequest {
+        schema_variant_id,
+        visibility: *ctx.visibility(),
+    };
+    let response: VariantPropTypesResponse =
+        api_request_auth_query(app, "/api/func/variant_prop_types", auth_token, &request).await;
+
+    assert!(response.types.starts_with("interface Domain {"));
+    // Each galaxy's fields are nested in the element type of the array under "universe"
+    for fields in [
+        "\"name\": string | null | undefined;",
+        concat!(
+            "\"universe\": {\n",
+            "\"galaxies\": {\n",
+            "\"sun\": string | null | undefined;\n",
+            "\"planets\": number | null | undefined;\n",
+            "}[] | null | undefined;\n",
+            "} | null | undefined;",
+        ),
+    ] {
+        assert!(
+            response.types.contains(fields),
+            "missing {fields} in {}",
+            response.types
+        );
+    }
+}
+
+#[test]
+fn compile_return_types_covers_every_response_type() {
+    for response_type in FuncBackendResponseType::iter() {
+        assert!(
+            !compile_return_types(response_type, FuncBackendKind::JsAction).is_empty(),
+            "missing return type definition for {response_type}"
+        );
+        assert!(
+            !compile_return_types_2(response_type, FuncBackendKind::JsAction).is_empty(),
+            "missing return type definition for {response_type}"
+        );
+    }
+}
+
+#[test]
+fn langjs_ambient_modules_declare_runtime_globals() {
+    let modules = langjs_ambient_modules();
+
+    for name in ["YAML", "siExec", "os", "fs", "path"] {
+        let module = modules
+            .iter()
+            .find(|module| module.name == name)
+            .unwrap_or_else(|| panic!("missing ambient module {name}"));
+        assert!(
+            module
+                .declaration
+                .starts_with(&format!("declare namespace {name} {{")),
+            "ambient module {name} does not declare its namespace"
+        );
+    }
+}
@@ -9,7 +9,9 @@ use tower::ServiceExt;
 mod change_set;
 mod component;
 mod crdt;
+mod diagram;
 mod functions;
+mod pkg;
 mod scenario;
 mod schema;
 mod secret;
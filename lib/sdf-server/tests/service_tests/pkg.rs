@@ -0,0 +1,86 @@
+use axum::{http::Method, Router};
+use dal::{
+    installed_pkg::{InstalledPkg, InstalledPkgAsset, InstalledPkgAssetTyped},
+    pkg::RemovedPkgSummary,
+    DalContext, StandardModel,
+};
+use dal_test::{
+    sdf_test,
+    test_harness::{create_schema, create_schema_variant},
+    AuthTokenRef,
+};
+use futures::StreamExt;
+use sdf_server::service::pkg::remove_pkg::RemovePkgRequest;
+
+use crate::service_tests::api_request_auth_json_body;
+
+#[sdf_test]
+async fn remove_pkg(ctx: DalContext, app: Router, AuthTokenRef(auth_token): AuthTokenRef<'_>) {
+    let schema = create_schema(&ctx).await;
+    let schema_variant = create_schema_variant(&ctx, *schema.id()).await;
+    let installed_pkg = InstalledPkg::new(&ctx, "ubik", "ubik-root-hash")
+        .await
+        .expect("cannot create installed pkg");
+    for asset in [
+        InstalledPkgAssetTyped::new_for_schema(
+            *schema.id(),
+            *installed_pkg.id(),
+            "ubik-schema-hash".to_owned(),
+        ),
+        InstalledPkgAssetTyped::new_for_schema_variant(
+            *schema_variant.id(),
+            *installed_pkg.id(),
+            "ubik-variant-hash".to_owned(),
+        ),
+    ] {
+        InstalledPkgAsset::new(&ctx, asset)
+            .await
+            .expect("cannot create installed pkg asset");
+    }
+
+    let visibility = *ctx.visibility();
+    ctx.commit().await.expect("cannot commit txn");
+
+    let workspace_pk = ctx.tenancy().workspace_pk().expect("ctx has a workspace");
+    let mut events = ctx
+        .nats_conn()
+        .subscribe(format!("si.workspace_pk.{workspace_pk}.event"))
+        .await
+        .expect("cannot subscribe to ws events");
+
+    let request = RemovePkgRequest {
+        installed_pkg_id: *installed_pkg.id(),
+        force: false,
+        visibility,
+    };
+    let summary: RemovedPkgSummary = api_request_auth_json_body(
+        app,
+        Method::POST,
+        "/api/pkg/remove_pkg",
+        auth_token,
+        &request,
+    )
+    .await;
+    assert_eq!(vec![*schema.id()], summary.removed_schemas);
+    assert_eq!(vec![*schema_variant.id()], summary.removed_schema_variants);
+
+    let payload = loop {
+        let message = tokio::time::timeout(std::time::Duration::from_secs(10), events.next())
+            .await
+            .expect("module removed event was published")
+            .expect("ws event subscription is open");
+        let event: serde_json::Value =
+            serde_json::from_slice(message.payload()).expect("ws event is json");
+        if event["payload"]["kind"] == "ModuleRemoved" {
+            break event["payload"]["data"].clone();
+        }
+    };
+    assert_eq!(
+        serde_json::json!(installed_pkg.id()),
+        payload["installedPkgId"]
+    );
+    assert_eq!(
+        serde_json::json!([schema_variant.id()]),
+        payload["schemaVariantIds"]
+    );
+}
@@ -9,3 +9,6 @@ mod start;
 mod status;
 mod stop;
 mod update;
+mod validate;
+
+pub use validate::{validate_pkg, ValidationReport};
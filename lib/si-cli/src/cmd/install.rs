@@ -3,17 +3,17 @@ use crate::state::AppState;
 use crate::CliResult;
 
 impl AppState {
-    pub async fn install(&self) -> CliResult<()> {
+    pub async fn install(&self, verbose: bool) -> CliResult<()> {
         self.track(
             get_user_email().await?,
             serde_json::json!({"command-name": "install"}),
         );
-        invoke(self, self.is_preview()).await?;
+        invoke(self, self.is_preview(), verbose).await?;
         Ok(())
     }
 }
 
-async fn invoke(app: &AppState, is_preview: bool) -> CliResult<()> {
+async fn invoke(app: &AppState, is_preview: bool, verbose: bool) -> CliResult<()> {
     let missing_containers = app.container_engine().missing_containers().await?;
     if missing_containers.is_empty() {
         println!("All containers downloaded\n");
@@ -30,7 +30,7 @@ async fn invoke(app: &AppState, is_preview: bool) -> CliResult<()> {
 
     println!("Downloading the containers required to run System Initiative");
     app.container_engine()
-        .download_missing_containers(missing_containers)
+        .download_missing_containers(missing_containers, verbose)
         .await?;
 
     Ok(())
@@ -6,20 +6,20 @@ use crate::state::AppState;
 use crate::{CliResult, CONTAINER_NAMES};
 
 impl AppState {
-    pub async fn start(&self) -> CliResult<()> {
+    pub async fn start(&self, verbose: bool) -> CliResult<()> {
         self.track(
             get_user_email().await?,
             serde_json::json!({"command-name": "start-system"}),
         );
-        invoke(self, self.is_preview()).await?;
+        invoke(self, self.is_preview(), verbose).await?;
         Ok(())
     }
 }
 
-async fn invoke(app: &AppState, is_preview: bool) -> CliResult<()> {
+async fn invoke(app: &AppState, is_preview: bool, verbose: bool) -> CliResult<()> {
     app.configure(false).await?;
     app.check(false).await?;
-    app.install().await?;
+    app.install(verbose).await?;
 
     if is_preview {
         println!("Started the following containers:");
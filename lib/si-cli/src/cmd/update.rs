@@ -236,7 +236,7 @@ async fn invoke(
                     }
                 }
 
-                app.start().await?;
+                app.start(false).await?;
 
                 app.track(
                     get_user_email().await?,
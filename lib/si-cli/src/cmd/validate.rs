@@ -0,0 +1,106 @@
+use std::fmt;
+use std::path::Path;
+
+use si_pkg::{validate_pkg_spec, PkgSpecProblem, SiPkg};
+
+use crate::CliResult;
+
+/// The result of [`validate_pkg`].
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    pub pkg_name: String,
+    pub problems: Vec<PkgSpecProblem>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// The code `si validate` exits with.
+    pub fn exit_code(&self) -> u8 {
+        if self.is_valid() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return writeln!(f, "Package \"{}\" has no problems", self.pkg_name);
+        }
+
+        writeln!(
+            f,
+            "Package \"{}\" has {} problem(s):",
+            self.pkg_name,
+            self.problems.len()
+        )?;
+        for problem in &self.problems {
+            writeln!(f, "  - {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Loads the package at `path` and runs the static checks on it. This does not need a running
+/// System Initiative, so package authors can check a package before publishing it.
+pub async fn validate_pkg(path: impl AsRef<Path>) -> CliResult<ValidationReport> {
+    let pkg = SiPkg::load_from_file(path).await?;
+    let spec = pkg.to_spec().await?;
+
+    Ok(ValidationReport {
+        pkg_name: spec.name.to_owned(),
+        problems: validate_pkg_spec(&spec),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use si_pkg::PkgSpec;
+
+    use super::*;
+
+    const INVALID_PKG_JSON: &str = include_str!("../../pkg-invalid.json");
+
+    #[tokio::test]
+    async fn reports_problems_of_invalid_pkg() {
+        let spec: PkgSpec = serde_json::from_str(INVALID_PKG_JSON).expect("parse fixture spec");
+        let pkg = SiPkg::load_from_spec(spec).expect("load fixture spec");
+        let file = tempfile::NamedTempFile::new().expect("create temp file");
+        std::fs::write(
+            file.path(),
+            pkg.write_to_bytes().expect("serialize fixture pkg"),
+        )
+        .expect("write fixture pkg");
+
+        let report = validate_pkg(file.path())
+            .await
+            .expect("validate fixture pkg");
+
+        assert_eq!(1, report.exit_code());
+        assert_eq!(
+            vec![
+                PkgSpecProblem::DanglingFuncReference {
+                    schema: "Mucker".to_owned(),
+                    func_unique_id: "si:missingQualification".to_owned(),
+                },
+                PkgSpecProblem::DuplicateUniqueId {
+                    kind: "func",
+                    unique_id: "si:scaffoldMucker".to_owned(),
+                    change_set: None,
+                },
+            ],
+            report.problems
+        );
+        assert_eq!(
+            "Package \"invalid\" has 2 problem(s):\n  \
+             - schema \"Mucker\" refers to func si:missingQualification, which is not in the package\n  \
+             - more than one func has unique id si:scaffoldMucker\n",
+            report.to_string()
+        );
+    }
+}
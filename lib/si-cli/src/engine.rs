@@ -1,4 +1,4 @@
-use crate::{CliResult, SiCliError};
+use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 use async_trait::async_trait;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -11,7 +11,11 @@ pub trait ContainerEngine {
     fn get_engine_identifier(&self) -> String;
     async fn ping(&self) -> CliResult<()>;
     async fn missing_containers(&self) -> Result<Vec<String>, SiCliError>;
-    async fn download_missing_containers(&self, missing_containers: Vec<String>) -> CliResult<()>;
+    async fn download_missing_containers(
+        &self,
+        missing_containers: Vec<String>,
+        verbose: bool,
+    ) -> CliResult<()>;
     async fn get_container_details(&self) -> CliResult<Vec<ContainerReleaseInfo>>;
     async fn cleanup_image(&self, name: String) -> CliResult<()>;
     async fn get_container_logs(&self, name: String, log_lines: usize) -> CliResult<bool>;
@@ -53,6 +57,239 @@ pub trait ContainerEngine {
         host_ip: String,
         host_port: u32,
     ) -> CliResult<()>;
+    /// Takes a single stats sample for the container with the given id. Returns `None` if the
+    /// container no longer exists or the engine did not report usable stats.
+    async fn get_container_stats(
+        &self,
+        id: String,
+        name: String,
+    ) -> CliResult<Option<ContainerStats>>;
+
+    /// Reports a snapshot of resource usage for each running System Initiative container.
+    async fn container_stats(&self) -> CliResult<Vec<ContainerStats>> {
+        let mut stats = Vec::new();
+        for name in CONTAINER_NAMES.iter() {
+            let container = self
+                .get_existing_container(format!("local-{0}-1", name))
+                .await?;
+            let id = match container {
+                Some(SiContainerSummary {
+                    id: Some(id),
+                    state: Some(state),
+                    ..
+                }) if state == "running" => id,
+                _ => continue,
+            };
+
+            if let Some(container_stats) = self.get_container_stats(id, name.to_string()).await? {
+                stats.push(container_stats);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Lists the System Initiative containers that are not running, along with the reason why.
+    async fn get_non_running_container_details(&self) -> CliResult<Vec<NonRunningContainer>> {
+        let images = self.downloaded_systeminit_containers_list().await?;
+
+        let mut non_running = Vec::new();
+        for name in CONTAINER_NAMES.iter() {
+            let container = self
+                .get_existing_container(format!("local-{0}-1", name))
+                .await?;
+            if let Some(reason) = non_running_reason(name, &images, container.as_ref()) {
+                non_running.push(NonRunningContainer {
+                    name: name.to_string(),
+                    reason,
+                });
+            }
+        }
+
+        Ok(non_running)
+    }
+
+    async fn get_non_running_containers(&self) -> CliResult<Vec<String>> {
+        Ok(self
+            .get_non_running_container_details()
+            .await?
+            .into_iter()
+            .map(|container| container.name)
+            .collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonRunningContainer {
+    pub name: String,
+    pub reason: NonRunningReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonRunningReason {
+    /// The image for the container has never been downloaded
+    NotDownloaded,
+    /// The image is downloaded but the container is not running (or was never created)
+    Stopped,
+    /// The container exited with a non-zero exit code
+    ExitedWithError(i64),
+}
+
+fn non_running_reason(
+    name: &str,
+    images: &[SiImageSummary],
+    container: Option<&SiContainerSummary>,
+) -> Option<NonRunningReason> {
+    if let Some(container) = container {
+        if container.state.as_deref() == Some("running") {
+            return None;
+        }
+        if let Some(exit_code) = container.status.as_deref().and_then(exit_code_from_status) {
+            if exit_code != 0 {
+                return Some(NonRunningReason::ExitedWithError(exit_code));
+            }
+        }
+        return Some(NonRunningReason::Stopped);
+    }
+
+    let image_name = format!("systeminit/{0}:stable", name);
+    if images
+        .iter()
+        .any(|image| image.repo_tags.iter().any(|tag| *tag == image_name))
+    {
+        Some(NonRunningReason::Stopped)
+    } else {
+        Some(NonRunningReason::NotDownloaded)
+    }
+}
+
+/// Parses the exit code out of a container status such as `Exited (1) 5 minutes ago`.
+fn exit_code_from_status(status: &str) -> Option<i64> {
+    let (_, rest) = status.split_once("Exited (")?;
+    let (code, _) = rest.split_once(')')?;
+    code.parse().ok()
+}
+
+/// Tracks the last status reported for each image layer during a pull, so that verbose
+/// downloads print one line per status transition rather than one per progress chunk.
+#[derive(Debug, Default)]
+pub struct LayerStatusTracker {
+    statuses: HashMap<String, String>,
+}
+
+impl LayerStatusTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the status for a layer and returns the line to print if it changed.
+    pub fn update(&mut self, image: &str, id: Option<&str>, status: &str) -> Option<String> {
+        let key = id.unwrap_or(image);
+        if self.statuses.get(key).map(String::as_str) == Some(status) {
+            return None;
+        }
+        self.statuses.insert(key.to_owned(), status.to_owned());
+
+        Some(match id {
+            Some(id) => format!("{image} [{id}]: {status}"),
+            None => format!("{image}: {status}"),
+        })
+    }
+}
+
+/// A snapshot of the resources used by a single container.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerStats {
+    pub name: String,
+    pub cpu_percent: f64,
+    pub memory_usage: u64,
+    pub memory_limit: u64,
+    pub network_rx: u64,
+    pub network_tx: u64,
+}
+
+impl ContainerStats {
+    /// Builds the stats from a Docker stats sample, computing the CPU percentage the same way
+    /// `docker stats` does.
+    pub fn from_docker_stats(name: String, stats: &serde_json::Value) -> Option<Self> {
+        let cpu_delta = stats
+            .pointer("/cpu_stats/cpu_usage/total_usage")?
+            .as_u64()?
+            .saturating_sub(
+                stats
+                    .pointer("/precpu_stats/cpu_usage/total_usage")
+                    .and_then(|usage| usage.as_u64())
+                    .unwrap_or_default(),
+            );
+        let system_delta = stats
+            .pointer("/cpu_stats/system_cpu_usage")
+            .and_then(|usage| usage.as_u64())
+            .unwrap_or_default()
+            .saturating_sub(
+                stats
+                    .pointer("/precpu_stats/system_cpu_usage")
+                    .and_then(|usage| usage.as_u64())
+                    .unwrap_or_default(),
+            );
+        let online_cpus = stats
+            .pointer("/cpu_stats/online_cpus")
+            .and_then(|cpus| cpus.as_u64())
+            .unwrap_or(1);
+        let cpu_percent = if system_delta > 0 {
+            cpu_delta as f64 / system_delta as f64 * online_cpus as f64 * 100.0
+        } else {
+            0.0
+        };
+
+        let (network_rx, network_tx) = stats
+            .get("networks")
+            .and_then(|networks| networks.as_object())
+            .map(|networks| {
+                networks.values().fold((0, 0), |(rx, tx), network| {
+                    (
+                        rx + network["rx_bytes"].as_u64().unwrap_or_default(),
+                        tx + network["tx_bytes"].as_u64().unwrap_or_default(),
+                    )
+                })
+            })
+            .unwrap_or_default();
+
+        Some(Self {
+            name,
+            cpu_percent,
+            memory_usage: stats.pointer("/memory_stats/usage")?.as_u64()?,
+            memory_limit: stats
+                .pointer("/memory_stats/limit")
+                .and_then(|limit| limit.as_u64())
+                .unwrap_or_default(),
+            network_rx,
+            network_tx,
+        })
+    }
+
+    /// Builds the stats from a Podman stats report, which carries one entry per requested
+    /// container with the CPU percentage already computed.
+    pub fn from_podman_stats(name: String, stats: &serde_json::Value) -> Option<Self> {
+        let entry = stats.get("Stats")?.as_array()?.first()?;
+
+        Some(Self {
+            name,
+            cpu_percent: entry.get("CPU")?.as_f64()?,
+            memory_usage: entry.get("MemUsage")?.as_u64()?,
+            memory_limit: entry
+                .get("MemLimit")
+                .and_then(|limit| limit.as_u64())
+                .unwrap_or_default(),
+            network_rx: entry
+                .get("NetInput")
+                .and_then(|rx| rx.as_u64())
+                .unwrap_or_default(),
+            network_tx: entry
+                .get("NetOutput")
+                .and_then(|tx| tx.as_u64())
+                .unwrap_or_default(),
+        })
+    }
 }
 
 #[derive(Debug)]
@@ -198,3 +435,172 @@ impl From<podman_api::models::ListContainer> for SiContainerSummary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(state: &str, status: &str) -> SiContainerSummary {
+        SiContainerSummary {
+            created: None,
+            id: None,
+            image: None,
+            labels: None,
+            status: Some(status.to_owned()),
+            state: Some(state.to_owned()),
+            ports: None,
+        }
+    }
+
+    fn image(name: &str) -> SiImageSummary {
+        SiImageSummary {
+            containers: 0,
+            created: 0,
+            id: "".to_owned(),
+            labels: HashMap::new(),
+            repo_tags: vec![format!("systeminit/{0}:stable", name)],
+        }
+    }
+
+    #[test]
+    fn layer_status_transitions() {
+        let chunks = [
+            (Some("a1"), "Pulling fs layer"),
+            (Some("b2"), "Pulling fs layer"),
+            (Some("a1"), "Downloading"),
+            (Some("a1"), "Downloading"),
+            (Some("a1"), "Extracting"),
+            (Some("a1"), "Extracting"),
+            (Some("b2"), "Pull complete"),
+            (Some("a1"), "Pull complete"),
+            (None, "Status: Downloaded newer image"),
+        ];
+
+        let mut tracker = LayerStatusTracker::new();
+        let lines: Vec<String> = chunks
+            .iter()
+            .filter_map(|(id, status)| tracker.update("systeminit/sdf", *id, status))
+            .collect();
+
+        assert_eq!(
+            vec![
+                "systeminit/sdf [a1]: Pulling fs layer",
+                "systeminit/sdf [b2]: Pulling fs layer",
+                "systeminit/sdf [a1]: Downloading",
+                "systeminit/sdf [a1]: Extracting",
+                "systeminit/sdf [b2]: Pull complete",
+                "systeminit/sdf [a1]: Pull complete",
+                "systeminit/sdf: Status: Downloaded newer image",
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn docker_container_stats() {
+        let sample = serde_json::json!({
+            "cpu_stats": {
+                "cpu_usage": { "total_usage": 400_000_000u64 },
+                "system_cpu_usage": 20_000_000_000u64,
+                "online_cpus": 4
+            },
+            "precpu_stats": {
+                "cpu_usage": { "total_usage": 300_000_000u64 },
+                "system_cpu_usage": 18_000_000_000u64
+            },
+            "memory_stats": { "usage": 104_857_600u64, "limit": 2_147_483_648u64 },
+            "networks": {
+                "eth0": { "rx_bytes": 1000, "tx_bytes": 200 },
+                "eth1": { "rx_bytes": 24, "tx_bytes": 6 }
+            }
+        });
+
+        let stats =
+            ContainerStats::from_docker_stats("sdf".to_owned(), &sample).expect("usable stats");
+        assert_eq!("sdf", stats.name);
+        assert!((stats.cpu_percent - 20.0).abs() < 1e-9);
+        assert_eq!(104_857_600, stats.memory_usage);
+        assert_eq!(2_147_483_648, stats.memory_limit);
+        assert_eq!(1024, stats.network_rx);
+        assert_eq!(206, stats.network_tx);
+
+        // A container that stopped between listing and sampling reports no memory usage
+        let stopped = serde_json::json!({
+            "cpu_stats": { "cpu_usage": { "total_usage": 0 } },
+            "precpu_stats": {},
+            "memory_stats": {}
+        });
+        assert_eq!(
+            None,
+            ContainerStats::from_docker_stats("sdf".to_owned(), &stopped)
+        );
+    }
+
+    #[test]
+    fn podman_container_stats() {
+        let report = serde_json::json!({
+            "Error": null,
+            "Stats": [{
+                "Name": "local-web-1",
+                "CPU": 1.5,
+                "MemUsage": 5_242_880u64,
+                "MemLimit": 1_073_741_824u64,
+                "NetInput": 300,
+                "NetOutput": 100
+            }]
+        });
+
+        assert_eq!(
+            Some(ContainerStats {
+                name: "web".to_owned(),
+                cpu_percent: 1.5,
+                memory_usage: 5_242_880,
+                memory_limit: 1_073_741_824,
+                network_rx: 300,
+                network_tx: 100,
+            }),
+            ContainerStats::from_podman_stats("web".to_owned(), &report)
+        );
+        assert_eq!(
+            None,
+            ContainerStats::from_podman_stats(
+                "web".to_owned(),
+                &serde_json::json!({ "Stats": [] })
+            )
+        );
+    }
+
+    #[test]
+    fn non_running_reasons() {
+        let images = vec![image("sdf"), image("web")];
+
+        assert_eq!(
+            None,
+            non_running_reason("sdf", &images, Some(&container("running", "Up 2 hours")))
+        );
+        assert_eq!(
+            Some(NonRunningReason::NotDownloaded),
+            non_running_reason("pinga", &images, None)
+        );
+        assert_eq!(
+            Some(NonRunningReason::Stopped),
+            non_running_reason("web", &images, None)
+        );
+        assert_eq!(
+            Some(NonRunningReason::Stopped),
+            non_running_reason(
+                "web",
+                &images,
+                Some(&container("exited", "Exited (0) 1 minute ago"))
+            )
+        );
+        assert_eq!(
+            Some(NonRunningReason::ExitedWithError(137)),
+            non_running_reason(
+                "sdf",
+                &images,
+                Some(&container("exited", "Exited (137) 3 seconds ago"))
+            )
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::engine::{ContainerEngine, ContainerReleaseInfo, SiContainerSummary, SiImageSummary};
+use crate::engine::{
+    ContainerEngine, ContainerReleaseInfo, ContainerStats, LayerStatusTracker, SiContainerSummary,
+    SiImageSummary,
+};
 use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 use async_trait::async_trait;
 use color_eyre::eyre::eyre;
@@ -85,7 +88,11 @@ impl ContainerEngine for DockerEngine {
         Ok(missing_containers)
     }
 
-    async fn download_missing_containers(&self, missing_containers: Vec<String>) -> CliResult<()> {
+    async fn download_missing_containers(
+        &self,
+        missing_containers: Vec<String>,
+        verbose: bool,
+    ) -> CliResult<()> {
         let m = MultiProgress::new();
         let sty = ProgressStyle::with_template(
             "{spinner:.red} [{elapsed_precise}] [{wide_bar:.yellow/blue}]",
@@ -99,7 +106,11 @@ impl ContainerEngine for DockerEngine {
 
         let mut spawned = Vec::new();
         for missing_container in missing_containers {
-            let pb = m.add(ProgressBar::new(total_size));
+            let pb = if verbose {
+                ProgressBar::hidden()
+            } else {
+                m.add(ProgressBar::new(total_size))
+            };
             pb.set_style(sty.clone());
 
             let mut message = "Downloading ".to_owned();
@@ -109,6 +120,8 @@ impl ContainerEngine for DockerEngine {
 
             let h1 = tokio::spawn(async move {
                 let mut downloaded = 0;
+                let mut layers = LayerStatusTracker::new();
+                let image = missing_container.clone();
 
                 let pull_opts = PullOpts::builder()
                     .image(missing_container)
@@ -119,10 +132,16 @@ impl ContainerEngine for DockerEngine {
                 while let Some(pull_result) = stream.next().await {
                     match pull_result {
                         Ok(docker_api::models::ImageBuildChunk::PullStatus {
+                            status,
+                            id,
                             progress_detail,
                             ..
                         }) => {
-                            if let Some(progress_detail) = progress_detail {
+                            if verbose {
+                                if let Some(line) = layers.update(&image, id.as_deref(), &status) {
+                                    println!("{line}");
+                                }
+                            } else if let Some(progress_detail) = progress_detail {
                                 let new = min(
                                     downloaded + progress_detail.current.unwrap_or(0),
                                     total_size,
@@ -240,6 +259,28 @@ impl ContainerEngine for DockerEngine {
         Ok(false)
     }
 
+    async fn get_container_stats(
+        &self,
+        id: String,
+        name: String,
+    ) -> CliResult<Option<ContainerStats>> {
+        let container = self.docker.containers().get(id);
+        let mut stats_stream = container.stats();
+
+        // The first sample has no previous CPU reading to compare against, so the CPU
+        // percentage is only meaningful from the second sample onwards
+        let mut sample = None;
+        for _ in 0..2 {
+            match stats_stream.next().await {
+                Some(Ok(stats)) => sample = Some(stats),
+                // The container went away after we listed it
+                Some(Err(_)) | None => break,
+            }
+        }
+
+        Ok(sample.and_then(|stats| ContainerStats::from_docker_stats(name, &stats)))
+    }
+
     async fn get_existing_container(&self, name: String) -> CliResult<Option<SiContainerSummary>> {
         let filter = ContainerFilter::Name(name.clone());
         let list_opts = ContainerListOpts::builder()
@@ -1,4 +1,7 @@
-use crate::engine::{ContainerEngine, ContainerReleaseInfo, SiContainerSummary, SiImageSummary};
+use crate::engine::{
+    ContainerEngine, ContainerReleaseInfo, ContainerStats, LayerStatusTracker, SiContainerSummary,
+    SiImageSummary,
+};
 use crate::{CliResult, SiCliError, CONTAINER_NAMES};
 use async_trait::async_trait;
 use color_eyre::eyre::eyre;
@@ -115,7 +118,11 @@ impl ContainerEngine for PodmanEngine {
         Ok(missing_containers)
     }
 
-    async fn download_missing_containers(&self, missing_containers: Vec<String>) -> CliResult<()> {
+    async fn download_missing_containers(
+        &self,
+        missing_containers: Vec<String>,
+        verbose: bool,
+    ) -> CliResult<()> {
         let m = MultiProgress::new();
         let sty = ProgressStyle::with_template(
             "{spinner:.red} [{elapsed_precise}] [{wide_msg:.yellow/blue}]",
@@ -129,7 +136,11 @@ impl ContainerEngine for PodmanEngine {
 
         let mut spawned = Vec::new();
         for missing_container in missing_containers {
-            let pb = m.add(ProgressBar::new(total_size));
+            let pb = if verbose {
+                ProgressBar::hidden()
+            } else {
+                m.add(ProgressBar::new(total_size))
+            };
             pb.set_style(sty.clone());
 
             let mut message = "Downloading ".to_owned();
@@ -138,6 +149,7 @@ impl ContainerEngine for PodmanEngine {
             let podman = self.podman.clone();
 
             let h1 = tokio::spawn(async move {
+                let mut layers = LayerStatusTracker::new();
                 let pull_opts = PullOpts::builder()
                     // TODO: Can the docker.io/ prefix be omitted?
                     .reference(format!("docker.io/{}:stable", missing_container))
@@ -148,7 +160,15 @@ impl ContainerEngine for PodmanEngine {
                     match pull_report {
                         Ok(pull_report) => {
                             if let Some(stream) = pull_report.stream {
-                                pb.set_message(stream.trim().to_owned());
+                                if verbose {
+                                    if let Some(line) =
+                                        layers.update(&missing_container, None, stream.trim())
+                                    {
+                                        println!("{line}");
+                                    }
+                                } else {
+                                    pb.set_message(stream.trim().to_owned());
+                                }
                             }
                         }
                         Err(e) => eprintln!("{e}"),
@@ -254,6 +274,18 @@ impl ContainerEngine for PodmanEngine {
         Ok(false)
     }
 
+    async fn get_container_stats(
+        &self,
+        id: String,
+        name: String,
+    ) -> CliResult<Option<ContainerStats>> {
+        match self.podman.containers().get(id).stats().await {
+            Ok(stats) => Ok(ContainerStats::from_podman_stats(name, &stats)),
+            // The container went away after we listed it
+            Err(_) => Ok(None),
+        }
+    }
+
     async fn get_existing_container(&self, name: String) -> CliResult<Option<SiContainerSummary>> {
         let list_opts = ContainerListOpts::builder()
             .all(true)
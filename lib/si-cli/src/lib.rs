@@ -37,6 +37,8 @@ pub enum SiCliError {
     Join(#[from] tokio::task::JoinError),
     #[error("Unable to find local data dir. Expected format `$HOME/.local/share` or `$HOME/Library/Application Support`")]
     MissingDataDir(),
+    #[error("pkg error: {0}")]
+    Pkg(#[from] si_pkg::SiPkgError),
     #[error("podman api: {0}")]
     Podman(#[from] podman_api::Error),
     #[error("reqwest: {0}")]
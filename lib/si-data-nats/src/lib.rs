@@ -1217,6 +1217,17 @@ impl NatsTxn {
     pub fn metadata(&self) -> &ConnectionMetadata {
         self.metadata.as_ref()
     }
+
+    /// Returns how many messages are waiting to be published on commit.
+    pub async fn pending_len(&self) -> usize {
+        self.pending_publish.lock().await.len()
+    }
+
+    /// Drops the messages waiting to be published on commit, keeping only the first `len` of
+    /// them.
+    pub async fn truncate_pending(&self, len: usize) {
+        self.pending_publish.lock().await.truncate(len);
+    }
 }
 
 /// Used for building customized requests.
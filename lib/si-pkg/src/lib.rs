@@ -7,6 +7,8 @@ pub use spec::*;
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use petgraph::dot::Dot;
     use tokio::sync::Mutex;
 
@@ -154,4 +156,119 @@ mod tests {
 
         let _ = dbg!(props.lock().await);
     }
+
+    #[test]
+    fn func_dependencies_of_schema_collects_every_func_kind() {
+        let variant = SchemaVariantSpec::builder()
+            .name("v0")
+            .data(
+                SchemaVariantSpecData::builder()
+                    .name("v0")
+                    .func_unique_id("asset")
+                    .build()
+                    .expect("build variant data"),
+            )
+            .action_func(
+                ActionFuncSpec::builder()
+                    .kind(ActionFuncSpecKind::Create)
+                    .func_unique_id("action")
+                    .build()
+                    .expect("build action func"),
+            )
+            .auth_func(
+                AuthenticationFuncSpec::builder()
+                    .func_unique_id("auth")
+                    .build()
+                    .expect("build auth func"),
+            )
+            .leaf_function(
+                LeafFunctionSpec::builder()
+                    .leaf_kind(LeafKind::Qualification)
+                    .func_unique_id("qualification")
+                    .build()
+                    .expect("build leaf function"),
+            )
+            .socket(
+                SocketSpec::builder()
+                    .name("output")
+                    .data(
+                        SocketSpecData::builder()
+                            .name("output")
+                            .kind(SocketSpecKind::Output)
+                            .func_unique_id("socket")
+                            .build()
+                            .expect("build socket data"),
+                    )
+                    .build()
+                    .expect("build socket"),
+            )
+            .si_prop_func(
+                SiPropFuncSpec::builder()
+                    .kind(SiPropFuncSpecKind::Name)
+                    .func_unique_id("si_prop")
+                    .build()
+                    .expect("build si prop func"),
+            )
+            .root_prop_func(
+                RootPropFuncSpec::builder()
+                    .prop(SchemaVariantSpecPropRoot::ResourceValue)
+                    .func_unique_id("root_prop")
+                    .build()
+                    .expect("build root prop func"),
+            )
+            .domain_prop(
+                PropSpec::builder()
+                    .name("name")
+                    .kind(PropSpecKind::String)
+                    .func_unique_id("attribute")
+                    .build()
+                    .expect("build string prop"),
+            )
+            .domain_prop(
+                PropSpec::builder()
+                    .name("tags")
+                    .kind(PropSpecKind::Map)
+                    .type_prop(
+                        PropSpec::builder()
+                            .name("tag")
+                            .kind(PropSpecKind::String)
+                            .build()
+                            .expect("build map type prop"),
+                    )
+                    .map_key_func(
+                        MapKeyFuncSpec::builder()
+                            .key("owner")
+                            .func_unique_id("map_key")
+                            .build()
+                            .expect("build map key func"),
+                    )
+                    .build()
+                    .expect("build map prop"),
+            )
+            .build()
+            .expect("build variant");
+
+        let schema = SchemaSpec::builder()
+            .name("schema")
+            .variant(variant)
+            .build()
+            .expect("build schema");
+
+        let expected: HashSet<String> = [
+            "asset",
+            "action",
+            "auth",
+            "qualification",
+            "socket",
+            "si_prop",
+            "root_prop",
+            "attribute",
+            "map_key",
+        ]
+        .into_iter()
+        .map(ToString::to_string)
+        .collect();
+
+        assert_eq!(expected, func_dependencies_of_schema(&schema));
+    }
 }
@@ -4,8 +4,8 @@ use std::{
 };
 
 use object_tree::{
-    read_key_value_line, read_key_value_line_opt, write_key_value_line, GraphError, NameStr,
-    NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
+    read_key_value_line, read_key_value_line_opt, write_key_value_line, write_key_value_line_opt,
+    GraphError, NameStr, NodeChild, NodeKind, NodeWithChildren, ReadBytes, WriteBytes,
 };
 use url::Url;
 
@@ -14,18 +14,24 @@ use crate::{node::SchemaVariantChild, SchemaVariantSpec, SchemaVariantSpecCompon
 use super::{read_common_fields, write_common_fields, PkgNode};
 
 const KEY_COLOR_STR: &str = "color";
+const KEY_COLOR_DARK_STR: &str = "color_dark";
+const KEY_DESCRIPTION_STR: &str = "description";
 const KEY_LINK_STR: &str = "link";
 const KEY_NAME_STR: &str = "name";
 const KEY_COMPONENT_TYPE_STR: &str = "component_type";
 const KEY_FUNC_UNIQUE_ID_STR: &str = "func_unique_id";
+const KEY_DEFAULT_CONNECTION_ANNOTATIONS_STR: &str = "default_connection_annotations";
 
 #[derive(Clone, Debug)]
 pub struct SchemaVariantData {
     pub name: String,
     pub link: Option<Url>,
     pub color: Option<String>,
+    pub color_dark: Option<String>,
     pub component_type: SchemaVariantSpecComponentType,
     pub func_unique_id: String,
+    pub description: Option<String>,
+    pub default_connection_annotations: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -58,6 +64,13 @@ impl WriteBytes for SchemaVariantNode {
                 KEY_FUNC_UNIQUE_ID_STR,
                 data.func_unique_id.to_string(),
             )?;
+            write_key_value_line_opt(writer, KEY_DESCRIPTION_STR, data.description.as_deref())?;
+            write_key_value_line_opt(
+                writer,
+                KEY_DEFAULT_CONNECTION_ANNOTATIONS_STR,
+                data.default_connection_annotations.as_deref(),
+            )?;
+            write_key_value_line_opt(writer, KEY_COLOR_DARK_STR, data.color_dark.as_deref())?;
         }
 
         write_common_fields(writer, self.unique_id.as_deref(), self.deleted)?;
@@ -90,13 +103,20 @@ impl ReadBytes for SchemaVariantNode {
                     .map_err(GraphError::parse)?;
 
                 let func_unique_id = read_key_value_line(reader, KEY_FUNC_UNIQUE_ID_STR)?;
+                let description = read_key_value_line_opt(reader, KEY_DESCRIPTION_STR)?;
+                let default_connection_annotations =
+                    read_key_value_line_opt(reader, KEY_DEFAULT_CONNECTION_ANNOTATIONS_STR)?;
+                let color_dark = read_key_value_line_opt(reader, KEY_COLOR_DARK_STR)?;
 
                 Some(SchemaVariantData {
                     name: name.to_owned(),
                     link,
                     color,
+                    color_dark,
                     component_type,
                     func_unique_id,
+                    description,
+                    default_connection_annotations,
                 })
             }
             None => None,
@@ -156,8 +176,14 @@ impl NodeChild for SchemaVariantSpec {
                     name: self.name.to_owned(),
                     link: data.link.as_ref().cloned(),
                     color: data.color.as_ref().cloned(),
+                    color_dark: data.color_dark.as_ref().cloned(),
                     component_type: data.component_type,
                     func_unique_id: data.func_unique_id.to_owned(),
+                    description: data.description.as_ref().cloned(),
+                    default_connection_annotations: data
+                        .default_connection_annotations
+                        .as_ref()
+                        .cloned(),
                 }),
                 unique_id: self.unique_id.to_owned(),
                 deleted: self.deleted,
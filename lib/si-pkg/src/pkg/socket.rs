@@ -38,6 +38,13 @@ impl SiPkgSocketData {
     pub fn ui_hidden(&self) -> bool {
         self.ui_hidden
     }
+
+    /// Uses `connection_annotations` if the socket doesn't have any of its own.
+    pub fn inherit_connection_annotations(&mut self, connection_annotations: &str) {
+        if self.connection_annotations.is_empty() {
+            self.connection_annotations = connection_annotations.to_owned();
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
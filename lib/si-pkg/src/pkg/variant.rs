@@ -23,8 +23,11 @@ pub struct SiPkgSchemaVariantData {
     name: String,
     link: Option<Url>,
     color: Option<String>,
+    color_dark: Option<String>,
+    description: Option<String>,
     component_type: SchemaVariantSpecComponentType,
     func_unique_id: String,
+    default_connection_annotations: Option<String>,
 }
 
 impl SiPkgSchemaVariantData {
@@ -40,6 +43,14 @@ impl SiPkgSchemaVariantData {
         self.color.as_deref()
     }
 
+    pub fn color_dark(&self) -> Option<&str> {
+        self.color_dark.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
     pub fn component_type(&self) -> SchemaVariantSpecComponentType {
         self.component_type
     }
@@ -47,6 +58,10 @@ impl SiPkgSchemaVariantData {
     pub fn func_unique_id(&self) -> &str {
         self.func_unique_id.as_str()
     }
+
+    pub fn default_connection_annotations(&self) -> Option<&str> {
+        self.default_connection_annotations.as_deref()
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -114,8 +129,11 @@ impl<'a> SiPkgSchemaVariant<'a> {
                 name: schema_variant_node.name,
                 link: data.link,
                 color: data.color,
+                color_dark: data.color_dark,
+                description: data.description,
                 component_type: data.component_type,
                 func_unique_id: data.func_unique_id,
+                default_connection_annotations: data.default_connection_annotations,
             }),
             unique_id: schema_variant_node.unique_id,
             deleted: schema_variant_node.deleted,
@@ -409,6 +427,18 @@ impl<'a> SiPkgSchemaVariant<'a> {
             if let Some(color) = data.color() {
                 data_builder.color(color);
             }
+
+            if let Some(color_dark) = data.color_dark() {
+                data_builder.color_dark(color_dark);
+            }
+
+            if let Some(default_connection_annotations) = data.default_connection_annotations() {
+                data_builder.default_connection_annotations(default_connection_annotations);
+            }
+
+            if let Some(description) = data.description() {
+                data_builder.description(description);
+            }
             data_builder.func_unique_id(data.func_unique_id());
             builder.data(data_builder.build()?);
         }
@@ -25,7 +25,7 @@ pub enum ActionFuncSpecKind {
     Delete,
 }
 
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct ActionFuncSpec {
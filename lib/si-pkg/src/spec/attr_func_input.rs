@@ -26,7 +26,7 @@ pub enum AttrFuncInputSpecKind {
 }
 
 #[remain::sorted]
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum AttrFuncInputSpec {
     InputSocket {
@@ -77,6 +77,19 @@ impl AttrFuncInputSpec {
             Self::Prop { name, .. } => name.as_str(),
         }
     }
+
+    /// Clears the unique ids of `inputs` and sorts them by name, so that two input lists
+    /// reading from the same sources compare equal.
+    pub fn canonicalize_all(inputs: &mut [AttrFuncInputSpec]) {
+        for input in inputs.iter_mut() {
+            match input {
+                Self::InputSocket { unique_id, .. }
+                | Self::OutputSocket { unique_id, .. }
+                | Self::Prop { unique_id, .. } => *unique_id = None,
+            }
+        }
+        inputs.sort_by(|a, b| a.name().cmp(b.name()));
+    }
 }
 
 impl AttrFuncInputSpecBuilder {
@@ -3,7 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use super::SpecError;
 
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct AuthenticationFuncSpec {
@@ -47,7 +47,7 @@ pub enum LeafInputLocation {
     Secrets,
 }
 
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct LeafFunctionSpec {
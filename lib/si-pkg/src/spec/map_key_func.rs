@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use super::{AttrFuncInputSpec, SpecError};
 
 /// MapKeyFuncSpecs track custom functions set on keys to a map
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct MapKeyFuncSpec {
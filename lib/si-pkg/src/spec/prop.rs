@@ -48,7 +48,7 @@ impl From<&PropSpec> for PropSpecWidgetKind {
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PropSpecData {
     pub name: String,
@@ -64,7 +64,7 @@ pub struct PropSpecData {
 }
 
 #[remain::sorted]
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(tag = "kind", rename_all = "camelCase")]
 pub enum PropSpec {
     #[serde(rename_all = "camelCase")]
@@ -113,6 +113,77 @@ impl PropSpec {
     pub fn builder() -> PropSpecBuilder {
         PropSpecBuilder::default()
     }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Array { name, .. }
+            | Self::Boolean { name, .. }
+            | Self::Map { name, .. }
+            | Self::Number { name, .. }
+            | Self::Object { name, .. }
+            | Self::String { name, .. } => name,
+        }
+    }
+
+    /// Clears the unique ids in this prop tree and sorts object entries, map key funcs and
+    /// attribute function inputs into a fixed order.
+    pub fn canonicalize(&mut self) {
+        let (data, unique_id) = match self {
+            Self::Array {
+                data,
+                unique_id,
+                type_prop,
+                ..
+            } => {
+                type_prop.canonicalize();
+                (data, unique_id)
+            }
+            Self::Map {
+                data,
+                unique_id,
+                type_prop,
+                map_key_funcs,
+                ..
+            } => {
+                type_prop.canonicalize();
+                if let Some(map_key_funcs) = map_key_funcs {
+                    for map_key_func in map_key_funcs.iter_mut() {
+                        AttrFuncInputSpec::canonicalize_all(&mut map_key_func.inputs);
+                    }
+                    map_key_funcs.sort_by(|a, b| a.key.cmp(&b.key));
+                }
+                (data, unique_id)
+            }
+            Self::Object {
+                data,
+                unique_id,
+                entries,
+                ..
+            } => {
+                entries.iter_mut().for_each(Self::canonicalize);
+                entries.sort_by(|a, b| a.name().cmp(b.name()));
+                (data, unique_id)
+            }
+            Self::Boolean {
+                data, unique_id, ..
+            }
+            | Self::Number {
+                data, unique_id, ..
+            }
+            | Self::String {
+                data, unique_id, ..
+            } => (data, unique_id),
+        };
+
+        *unique_id = None;
+        if let Some(PropSpecData {
+            inputs: Some(inputs),
+            ..
+        }) = data
+        {
+            AttrFuncInputSpec::canonicalize_all(inputs);
+        }
+    }
 }
 
 #[remain::sorted]
@@ -6,7 +6,7 @@ use crate::SchemaVariantSpecPropRoot;
 use super::{AttrFuncInputSpec, SpecError};
 
 /// RootPropFuncs track custom functions for for props that are immediate children of the root.
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct RootPropFuncSpec {
@@ -1,7 +1,9 @@
+use std::collections::HashSet;
+
 use derive_builder::Builder;
 use serde::{Deserialize, Serialize};
 
-use super::{SchemaVariantSpec, SpecError};
+use super::{PropSpec, SchemaVariantSpec, SpecError};
 
 #[derive(Builder, Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,6 +54,104 @@ impl SchemaSpec {
     }
 }
 
+/// Returns the unique ids of every func referenced by the variants of `schema_spec`: asset,
+/// action, auth, leaf, socket, si prop, root prop, attribute and map key funcs.
+pub fn func_dependencies_of_schema(schema_spec: &SchemaSpec) -> HashSet<String> {
+    let mut func_unique_ids = HashSet::new();
+
+    for variant in &schema_spec.variants {
+        if let Some(data) = &variant.data {
+            func_unique_ids.insert(data.func_unique_id.to_owned());
+        }
+
+        func_unique_ids.extend(
+            variant
+                .action_funcs
+                .iter()
+                .map(|func| func.func_unique_id.to_owned()),
+        );
+        func_unique_ids.extend(
+            variant
+                .auth_funcs
+                .iter()
+                .map(|func| func.func_unique_id.to_owned()),
+        );
+        func_unique_ids.extend(
+            variant
+                .leaf_functions
+                .iter()
+                .map(|func| func.func_unique_id.to_owned()),
+        );
+        func_unique_ids.extend(
+            variant
+                .si_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.to_owned()),
+        );
+        func_unique_ids.extend(
+            variant
+                .root_prop_funcs
+                .iter()
+                .map(|func| func.func_unique_id.to_owned()),
+        );
+        func_unique_ids.extend(
+            variant
+                .sockets
+                .iter()
+                .filter_map(|socket| socket.data.as_ref())
+                .filter_map(|data| data.func_unique_id.to_owned()),
+        );
+
+        for prop_root in [
+            Some(&variant.domain),
+            Some(&variant.secrets),
+            variant.secret_definition.as_ref(),
+            Some(&variant.resource_value),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            collect_prop_func_dependencies(prop_root, &mut func_unique_ids);
+        }
+    }
+
+    func_unique_ids
+}
+
+fn collect_prop_func_dependencies(prop_spec: &PropSpec, func_unique_ids: &mut HashSet<String>) {
+    let (data, children) = match prop_spec {
+        PropSpec::Array {
+            data, type_prop, ..
+        } => (data, vec![type_prop.as_ref()]),
+        PropSpec::Map {
+            data,
+            type_prop,
+            map_key_funcs,
+            ..
+        } => {
+            func_unique_ids.extend(
+                map_key_funcs
+                    .iter()
+                    .flatten()
+                    .map(|func| func.func_unique_id.to_owned()),
+            );
+            (data, vec![type_prop.as_ref()])
+        }
+        PropSpec::Object { data, entries, .. } => (data, entries.iter().collect()),
+        PropSpec::Boolean { data, .. }
+        | PropSpec::Number { data, .. }
+        | PropSpec::String { data, .. } => (data, vec![]),
+    };
+
+    if let Some(func_unique_id) = data.as_ref().and_then(|data| data.func_unique_id.as_ref()) {
+        func_unique_ids.insert(func_unique_id.to_owned());
+    }
+
+    for child in children {
+        collect_prop_func_dependencies(child, func_unique_ids);
+    }
+}
+
 impl TryFrom<SchemaSpecBuilder> for SchemaSpec {
     type Error = SpecError;
 
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use super::{func_dependencies_of_schema, ChangeSetSpec, ComponentSpecVariant, PkgSpec};
+use crate::SiPkgKind;
+
+/// A problem found by the static checks on a [`PkgSpec`]. None of the checks need a running
+/// backend, so packages can be checked before they are published or imported.
+#[remain::sorted]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PkgSpecProblem {
+    /// A component is based on a workspace schema variant that isn't in the package
+    ComponentMissingVariant {
+        change_set: String,
+        component: String,
+        variant_unique_id: String,
+    },
+    /// A schema refers to a func that isn't in the package
+    DanglingFuncReference {
+        schema: String,
+        func_unique_id: String,
+    },
+    /// More than one item of the same kind has the same unique id
+    DuplicateUniqueId {
+        kind: &'static str,
+        unique_id: String,
+        change_set: Option<String>,
+    },
+    /// An edge connects to a component that isn't in the package
+    EdgeMissingComponent {
+        change_set: String,
+        edge_unique_id: String,
+        component_unique_id: String,
+    },
+}
+
+impl fmt::Display for PkgSpecProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ComponentMissingVariant {
+                change_set,
+                component,
+                variant_unique_id,
+            } => write!(
+                f,
+                "component \"{component}\" in change set \"{change_set}\" uses schema variant {variant_unique_id}, which is not in the package"
+            ),
+            Self::DanglingFuncReference {
+                schema,
+                func_unique_id,
+            } => write!(
+                f,
+                "schema \"{schema}\" refers to func {func_unique_id}, which is not in the package"
+            ),
+            Self::DuplicateUniqueId {
+                kind,
+                unique_id,
+                change_set: Some(change_set),
+            } => write!(
+                f,
+                "more than one {kind} in change set \"{change_set}\" has unique id {unique_id}"
+            ),
+            Self::DuplicateUniqueId {
+                kind,
+                unique_id,
+                change_set: None,
+            } => write!(f, "more than one {kind} has unique id {unique_id}"),
+            Self::EdgeMissingComponent {
+                change_set,
+                edge_unique_id,
+                component_unique_id,
+            } => write!(
+                f,
+                "edge {edge_unique_id} in change set \"{change_set}\" connects to component {component_unique_id}, which is not in the package"
+            ),
+        }
+    }
+}
+
+/// Runs every static check on `spec`: [`dangling_func_references`],
+/// [`validate_workspace_backup`] and [`duplicate_unique_ids`].
+pub fn validate_pkg_spec(spec: &PkgSpec) -> Vec<PkgSpecProblem> {
+    let mut problems = dangling_func_references(spec);
+    problems.extend(validate_workspace_backup(spec));
+    problems.extend(duplicate_unique_ids(spec));
+    problems
+}
+
+/// Finds the funcs that schemas of the package refer to (see [`func_dependencies_of_schema`])
+/// but that the package doesn't have.
+pub fn dangling_func_references(spec: &PkgSpec) -> Vec<PkgSpecProblem> {
+    let func_unique_ids: HashSet<&str> = spec
+        .funcs
+        .iter()
+        .chain(spec.change_sets.iter().flat_map(|cs| cs.funcs.iter()))
+        .map(|func| func.unique_id.as_str())
+        .collect();
+
+    let mut problems = vec![];
+    for schema in spec
+        .schemas
+        .iter()
+        .chain(spec.change_sets.iter().flat_map(|cs| cs.schemas.iter()))
+    {
+        let mut missing: Vec<String> = func_dependencies_of_schema(schema)
+            .into_iter()
+            .filter(|func_unique_id| !func_unique_ids.contains(func_unique_id.as_str()))
+            .collect();
+        missing.sort();
+
+        problems.extend(missing.into_iter().map(|func_unique_id| {
+            PkgSpecProblem::DanglingFuncReference {
+                schema: schema.name.to_owned(),
+                func_unique_id,
+            }
+        }));
+    }
+
+    problems
+}
+
+/// Checks that the components and edges of a workspace backup only refer to schema variants
+/// and components that are in the backup. Packages that are not workspace backups have no
+/// problems.
+pub fn validate_workspace_backup(spec: &PkgSpec) -> Vec<PkgSpecProblem> {
+    if spec.kind != SiPkgKind::WorkspaceBackup {
+        return vec![];
+    }
+
+    // Change sets build on each other, so anything in one of them can be referred to by another
+    let variant_unique_ids: HashSet<&str> = spec
+        .schemas
+        .iter()
+        .chain(spec.change_sets.iter().flat_map(|cs| cs.schemas.iter()))
+        .flat_map(|schema| schema.variants.iter())
+        .filter_map(|variant| variant.unique_id.as_deref())
+        .collect();
+    let component_unique_ids: HashSet<&str> = spec
+        .change_sets
+        .iter()
+        .flat_map(|cs| cs.components.iter())
+        .map(|component| component.unique_id.as_str())
+        .collect();
+
+    let mut problems = vec![];
+    for change_set in &spec.change_sets {
+        for component in &change_set.components {
+            if let ComponentSpecVariant::WorkspaceVariant { variant_unique_id } = &component.variant
+            {
+                if !variant_unique_ids.contains(variant_unique_id.as_str()) {
+                    problems.push(PkgSpecProblem::ComponentMissingVariant {
+                        change_set: change_set.name.to_owned(),
+                        component: component.name.to_owned(),
+                        variant_unique_id: variant_unique_id.to_owned(),
+                    });
+                }
+            }
+        }
+
+        // Deleted edges are never connected on import
+        for edge in change_set.edges.iter().filter(|edge| !edge.deleted) {
+            for component_unique_id in
+                [&edge.from_component_unique_id, &edge.to_component_unique_id]
+            {
+                if !component_unique_ids.contains(component_unique_id.as_str()) {
+                    problems.push(PkgSpecProblem::EdgeMissingComponent {
+                        change_set: change_set.name.to_owned(),
+                        edge_unique_id: edge.unique_id.to_owned(),
+                        component_unique_id: component_unique_id.to_owned(),
+                    });
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// Finds funcs, schemas, schema variants, components and edges that share a unique id with
+/// another item of the same kind. Change sets are checked on their own, since the same item is
+/// expected to show up in more than one of them.
+pub fn duplicate_unique_ids(spec: &PkgSpec) -> Vec<PkgSpecProblem> {
+    let mut problems = vec![];
+
+    collect_duplicates(
+        "func",
+        spec.funcs.iter().map(|func| func.unique_id.as_str()),
+        None,
+        &mut problems,
+    );
+    collect_duplicates(
+        "schema",
+        spec.schemas
+            .iter()
+            .filter_map(|schema| schema.unique_id.as_deref()),
+        None,
+        &mut problems,
+    );
+    collect_duplicates(
+        "schema variant",
+        spec.schemas
+            .iter()
+            .flat_map(|schema| schema.variants.iter())
+            .filter_map(|variant| variant.unique_id.as_deref()),
+        None,
+        &mut problems,
+    );
+
+    for change_set in &spec.change_sets {
+        collect_change_set_duplicates(change_set, &mut problems);
+    }
+
+    problems
+}
+
+fn collect_change_set_duplicates(change_set: &ChangeSetSpec, problems: &mut Vec<PkgSpecProblem>) {
+    let name = Some(change_set.name.as_str());
+
+    collect_duplicates(
+        "func",
+        change_set.funcs.iter().map(|func| func.unique_id.as_str()),
+        name,
+        problems,
+    );
+    collect_duplicates(
+        "schema",
+        change_set
+            .schemas
+            .iter()
+            .filter_map(|schema| schema.unique_id.as_deref()),
+        name,
+        problems,
+    );
+    collect_duplicates(
+        "schema variant",
+        change_set
+            .schemas
+            .iter()
+            .flat_map(|schema| schema.variants.iter())
+            .filter_map(|variant| variant.unique_id.as_deref()),
+        name,
+        problems,
+    );
+    collect_duplicates(
+        "component",
+        change_set
+            .components
+            .iter()
+            .map(|component| component.unique_id.as_str()),
+        name,
+        problems,
+    );
+    collect_duplicates(
+        "edge",
+        change_set.edges.iter().map(|edge| edge.unique_id.as_str()),
+        name,
+        problems,
+    );
+}
+
+fn collect_duplicates<'a>(
+    kind: &'static str,
+    unique_ids: impl Iterator<Item = &'a str>,
+    change_set: Option<&str>,
+    problems: &mut Vec<PkgSpecProblem>,
+) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut duplicates = vec![];
+    for unique_id in unique_ids {
+        let count = counts.entry(unique_id).or_default();
+        *count += 1;
+        if *count == 2 {
+            duplicates.push(unique_id);
+        }
+    }
+
+    problems.extend(
+        duplicates
+            .into_iter()
+            .map(|unique_id| PkgSpecProblem::DuplicateUniqueId {
+                kind,
+                unique_id: unique_id.to_owned(),
+                change_set: change_set.map(ToOwned::to_owned),
+            }),
+    );
+}
@@ -6,8 +6,8 @@ use url::Url;
 use crate::spec::authentication_func::AuthenticationFuncSpec;
 
 use super::{
-    ActionFuncSpec, LeafFunctionSpec, PropSpec, PropSpecData, PropSpecWidgetKind, RootPropFuncSpec,
-    SiPropFuncSpec, SocketSpec, SpecError,
+    ActionFuncSpec, AttrFuncInputSpec, LeafFunctionSpec, PropSpec, PropSpecData,
+    PropSpecWidgetKind, RootPropFuncSpec, SiPropFuncSpec, SocketSpec, SpecError,
 };
 
 #[remain::sorted]
@@ -78,7 +78,7 @@ impl SchemaVariantSpecPropRoot {
     }
 }
 
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct SchemaVariantSpecData {
@@ -88,11 +88,21 @@ pub struct SchemaVariantSpecData {
     pub link: Option<Url>,
     #[builder(setter(into, strip_option), default)]
     pub color: Option<String>,
+    /// The color of the variant in dark mode, if it differs from `color`
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub color_dark: Option<String>,
+    #[builder(setter(into, strip_option), default)]
+    pub description: Option<String>,
 
     #[builder(setter(into), default)]
     pub component_type: SchemaVariantSpecComponentType,
     #[builder(setter(into))]
     pub func_unique_id: String,
+    /// Connection annotations for the sockets of the variant that don't have their own
+    #[builder(setter(into, strip_option), default)]
+    #[serde(default)]
+    pub default_connection_annotations: Option<String>,
 }
 
 impl SchemaVariantSpecData {
@@ -112,7 +122,7 @@ impl SchemaVariantSpecDataBuilder {
     }
 }
 
-#[derive(Builder, Clone, Debug, Deserialize, Serialize)]
+#[derive(Builder, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[builder(build_fn(error = "SpecError"))]
 pub struct SchemaVariantSpec {
@@ -166,6 +176,68 @@ impl SchemaVariantSpec {
     pub fn builder() -> SchemaVariantSpecBuilder {
         SchemaVariantSpecBuilder::default()
     }
+
+    /// Normalizes this spec so it can be compared for equality with another spec of the same
+    /// variant: unique ids are cleared and props, sockets and funcs are sorted into a fixed
+    /// order. Func unique ids are kept, since they identify the function contents.
+    pub fn canonicalize(&mut self) {
+        self.unique_id = None;
+
+        self.domain.canonicalize();
+        self.secrets.canonicalize();
+        self.resource_value.canonicalize();
+        if let Some(secret_definition) = &mut self.secret_definition {
+            secret_definition.canonicalize();
+        }
+
+        for action_func in self.action_funcs.iter_mut() {
+            action_func.unique_id = None;
+        }
+        self.action_funcs.sort_by(|a, b| {
+            (a.kind.as_ref(), &a.func_unique_id).cmp(&(b.kind.as_ref(), &b.func_unique_id))
+        });
+
+        for auth_func in self.auth_funcs.iter_mut() {
+            auth_func.unique_id = None;
+        }
+        self.auth_funcs
+            .sort_by(|a, b| a.func_unique_id.cmp(&b.func_unique_id));
+
+        for leaf_function in self.leaf_functions.iter_mut() {
+            leaf_function.unique_id = None;
+            leaf_function
+                .inputs
+                .sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+        }
+        self.leaf_functions.sort_by(|a, b| {
+            (a.leaf_kind.as_ref(), &a.func_unique_id)
+                .cmp(&(b.leaf_kind.as_ref(), &b.func_unique_id))
+        });
+
+        for socket in self.sockets.iter_mut() {
+            socket.unique_id = None;
+            AttrFuncInputSpec::canonicalize_all(&mut socket.inputs);
+        }
+        self.sockets.sort_by(|a, b| {
+            let a_kind = a.data.as_ref().map(|data| data.kind.as_ref());
+            let b_kind = b.data.as_ref().map(|data| data.kind.as_ref());
+            (&a.name, a_kind).cmp(&(&b.name, b_kind))
+        });
+
+        for si_prop_func in self.si_prop_funcs.iter_mut() {
+            si_prop_func.unique_id = None;
+            AttrFuncInputSpec::canonicalize_all(&mut si_prop_func.inputs);
+        }
+        self.si_prop_funcs
+            .sort_by(|a, b| a.kind.as_ref().cmp(b.kind.as_ref()));
+
+        for root_prop_func in self.root_prop_funcs.iter_mut() {
+            root_prop_func.unique_id = None;
+            AttrFuncInputSpec::canonicalize_all(&mut root_prop_func.inputs);
+        }
+        self.root_prop_funcs
+            .sort_by(|a, b| a.prop.as_ref().cmp(b.prop.as_ref()));
+    }
 }
 
 impl SchemaVariantSpecBuilder {